@@ -287,13 +287,32 @@ pub struct RunCmd {
 	#[clap(long, conflicts_with = "validator")]
 	pub collator: bool,
 
-	/// EXPERIMENTAL: Specify an URL to a relay chain full node to communicate with.
+	/// EXPERIMENTAL: Specify one or more URLs of relay chain full nodes to communicate with.
+	///
+	/// The nodes are tried in the given order. If the active one becomes unreachable or
+	/// unhealthy, the collator switches to the next one.
 	#[clap(
 		long,
+		alias = "relay-chain-rpc-url",
 		value_parser = validate_relay_chain_url,
+		multiple_values = true,
 		conflicts_with_all = &["alice", "bob", "charlie", "dave", "eve", "ferdie", "one", "two"]	)
 	]
-	pub relay_chain_rpc_url: Option<Url>,
+	pub relay_chain_rpc_urls: Vec<Url>,
+
+	/// EXPERIMENTAL: Embed a minimal relay chain node instead of a full one.
+	///
+	/// The minimal node only runs the subsystems needed for collating, syncs the relay chain
+	/// headers and fetches the relay chain state it does not keep as storage proofs from peers.
+	#[clap(long, conflicts_with = "relay-chain-rpc-urls")]
+	pub relay_chain_minimal_node: bool,
+
+	/// Build collations on the relay chain block this many blocks behind the tip.
+	///
+	/// The relay chain only accepts the candidates if its `allowed_ancestry_len` is at least the
+	/// offset. `0` builds on the tip.
+	#[clap(long, default_value = "0")]
+	pub relay_parent_offset: u32,
 }
 
 impl RunCmd {
@@ -308,15 +327,23 @@ impl RunCmd {
 
 	/// Create [`CollatorOptions`] representing options only relevant to parachain collator nodes
 	pub fn collator_options(&self) -> CollatorOptions {
-		CollatorOptions { relay_chain_rpc_url: self.relay_chain_rpc_url.clone() }
+		CollatorOptions {
+			relay_chain_rpc_urls: self.relay_chain_rpc_urls.clone(),
+			relay_chain_minimal_node: self.relay_chain_minimal_node,
+			relay_parent_offset: self.relay_parent_offset,
+		}
 	}
 }
 
 /// Options only relevant for collator nodes
 #[derive(Clone, Debug)]
 pub struct CollatorOptions {
-	/// Location of relay chain full node
-	pub relay_chain_rpc_url: Option<Url>,
+	/// Locations of relay chain full nodes, in order of preference
+	pub relay_chain_rpc_urls: Vec<Url>,
+	/// Embed a minimal relay chain node instead of a full one
+	pub relay_chain_minimal_node: bool,
+	/// How many blocks behind the relay chain tip collations are built on
+	pub relay_parent_offset: u32,
 }
 
 /// A non-redundant version of the `RunCmd` that sets the `validator` field when the
@@ -305,6 +305,8 @@ pub struct StartCollatorParams<Block: BlockT, RA, BS, Spawner> {
 	pub spawner: Spawner,
 	pub key: CollatorPair,
 	pub parachain_consensus: Box<dyn ParachainConsensus<Block>>,
+	/// How many blocks behind the relay chain tip the collator builds on.
+	pub relay_parent_offset: u32,
 }
 
 /// Start the collator.
@@ -318,6 +320,7 @@ pub async fn start_collator<Block, RA, BS, Spawner>(
 		key,
 		parachain_consensus,
 		runtime_api,
+		relay_parent_offset,
 	}: StartCollatorParams<Block, RA, BS, Spawner>,
 ) where
 	Block: BlockT,
@@ -345,6 +348,7 @@ pub async fn start_collator<Block, RA, BS, Spawner>(
 				.instrument(span.clone())
 				.boxed()
 		}),
+		relay_parent_offset,
 	};
 
 	overseer_handle
@@ -444,6 +448,7 @@ mod tests {
 			para_id,
 			key: CollatorPair::generate().0,
 			parachain_consensus: Box::new(DummyParachainConsensus { client: client.clone() }),
+			relay_parent_offset: 0,
 		});
 		block_on(collator_start);
 
@@ -19,6 +19,10 @@
 //! Provides a custom block announcement implementation for parachains
 //! that use the relay chain provided consensus. See [`BlockAnnounceValidator`]
 //! and [`WaitToAnnounce`] for more information about this implementation.
+//!
+//! The [`BlockAnnounceValidator`] also detects collators that announce conflicting blocks at the
+//! same height, rejects these announcements and can hand out a [`CollatorEquivocationProof`] for
+//! them.
 
 use sp_consensus::block_validation::{
 	BlockAnnounceValidator as BlockAnnounceValidatorT, Validation,
@@ -26,24 +30,36 @@ use sp_consensus::block_validation::{
 use sp_core::traits::SpawnNamed;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
+use forests_primitives_core::{CollatorEquivocationProof, SignedCandidate};
 use forests_relay_chain_interface::RelayChainInterface;
 use selendra_node_primitives::{CollationSecondedSignal, Statement};
 use selendra_parachain::primitives::HeadData;
 use selendra_primitives::v2::{
-	CandidateReceipt, CompactStatement, Hash as PHash, Id as ParaId, OccupiedCoreAssumption,
-	SigningContext, UncheckedSigned,
+	CandidateDescriptor, CandidateReceipt, CollatorId, CompactStatement, Hash as PHash,
+	Id as ParaId, OccupiedCoreAssumption, SigningContext, UncheckedSigned,
 };
 
 use codec::{Decode, DecodeAll, Encode};
 use futures::{channel::oneshot, future::FutureExt, Future};
-
-use std::{convert::TryFrom, fmt, marker::PhantomData, pin::Pin, sync::Arc};
+use parking_lot::Mutex;
+
+use std::{
+	collections::{HashMap, VecDeque},
+	convert::TryFrom,
+	fmt,
+	marker::PhantomData,
+	pin::Pin,
+	sync::Arc,
+};
 
 #[cfg(test)]
 mod tests;
 
 const LOG_TARGET: &str = "sync::forests";
 
+/// The maximum number of announced candidates that are remembered for equivocation detection.
+const MAX_SEEN_CANDIDATES: usize = 1024;
+
 type BoxedError = Box<dyn std::error::Error + Send>;
 
 #[derive(Debug)]
@@ -115,6 +131,11 @@ impl BlockAnnounceData {
 			return Err(Validation::Failure { disconnect: true })
 		}
 
+		if self.receipt.descriptor.check_collator_signature().is_err() {
+			tracing::debug!(target: LOG_TARGET, "Receipt collator signature is invalid",);
+			return Err(Validation::Failure { disconnect: true })
+		}
+
 		Ok(())
 	}
 
@@ -214,20 +235,119 @@ impl TryFrom<&'_ CollationSecondedSignal> for BlockAnnounceData {
 /// chain. If it is at the tip, it is required to provide a justification or otherwise we reject
 /// it. However, if the announcement is for a block below the tip the announcement is accepted
 /// as it probably comes from a node that is currently syncing the chain.
+///
+/// Announcements with a justification are remembered per collator, parent block and block number.
+/// If a collator signed a candidate for a different block at the same height, every announcement
+/// of that block is rejected, which makes the network lower the reputation of the peer relaying it.
+/// The peer is not disconnected, as it is not necessarily the collator. The
+/// [`CollatorEquivocationProof`] is passed once to the handler registered with
+/// [`BlockAnnounceValidator::with_equivocation_handler`].
 #[derive(Clone)]
-pub struct BlockAnnounceValidator<Block, RCInterface> {
+pub struct BlockAnnounceValidator<Block: BlockT, RCInterface> {
 	phantom: PhantomData<Block>,
 	relay_chain_interface: RCInterface,
 	para_id: ParaId,
+	seen_candidates: Arc<Mutex<SeenCandidates<Block::Header>>>,
+	equivocation_handler: Option<EquivocationHandler<Block::Header>>,
 }
 
-impl<Block, RCInterface> BlockAnnounceValidator<Block, RCInterface>
+/// Something that is called with every detected [`CollatorEquivocationProof`].
+pub type EquivocationHandler<Header> = Arc<dyn Fn(CollatorEquivocationProof<Header>) + Send + Sync>;
+
+impl<Block: BlockT, RCInterface> BlockAnnounceValidator<Block, RCInterface>
 where
 	RCInterface: Clone,
 {
 	/// Create a new [`BlockAnnounceValidator`].
 	pub fn new(relay_chain_interface: RCInterface, para_id: ParaId) -> Self {
-		Self { phantom: Default::default(), relay_chain_interface, para_id }
+		Self {
+			phantom: Default::default(),
+			relay_chain_interface,
+			para_id,
+			seen_candidates: Default::default(),
+			equivocation_handler: None,
+		}
+	}
+
+	/// Call the given `handler` with the proof of every detected collator equivocation.
+	///
+	/// This can be used to report the collator to the runtime.
+	pub fn with_equivocation_handler(
+		mut self,
+		handler: EquivocationHandler<Block::Header>,
+	) -> Self {
+		self.equivocation_handler = Some(handler);
+		self
+	}
+
+	/// Remember the candidate that was announced for `header`.
+	///
+	/// See [`SeenCandidates::note`] for the result.
+	fn note_seen_candidate(
+		&self,
+		header: Block::Header,
+		descriptor: CandidateDescriptor,
+	) -> Result<(), Option<CollatorEquivocationProof<Block::Header>>> {
+		self.seen_candidates.lock().note(SignedCandidate { header, descriptor })
+	}
+}
+
+/// The key under which announced candidates are remembered: the block number, the parent hash and
+/// the collator.
+type SeenCandidateKey<Header> =
+	(<Header as HeaderT>::Number, <Header as HeaderT>::Hash, CollatorId);
+
+/// The candidates that were announced recently, used to detect collator equivocations.
+struct SeenCandidates<Header: HeaderT> {
+	/// The first candidate announced per key and whether an equivocation was reported for it.
+	candidates: HashMap<SeenCandidateKey<Header>, (SignedCandidate<Header>, bool)>,
+	/// The keys of `candidates` in the order they were inserted.
+	order: VecDeque<SeenCandidateKey<Header>>,
+}
+
+impl<Header: HeaderT> Default for SeenCandidates<Header> {
+	fn default() -> Self {
+		Self { candidates: Default::default(), order: Default::default() }
+	}
+}
+
+impl<Header: HeaderT> SeenCandidates<Header> {
+	/// Note the given `candidate`.
+	///
+	/// Returns an error if the collator already announced a different block at the same height
+	/// building on the same parent. The error contains the proof if this is the first conflict
+	/// with that block.
+	fn note(
+		&mut self,
+		candidate: SignedCandidate<Header>,
+	) -> Result<(), Option<CollatorEquivocationProof<Header>>> {
+		let key = (
+			*candidate.header.number(),
+			*candidate.header.parent_hash(),
+			candidate.descriptor.collator.clone(),
+		);
+
+		if let Some((seen, reported)) = self.candidates.get_mut(&key) {
+			return if seen.descriptor.para_head == candidate.descriptor.para_head {
+				Ok(())
+			} else if *reported {
+				Err(None)
+			} else {
+				*reported = true;
+				Err(Some(CollatorEquivocationProof { first: seen.clone(), second: candidate }))
+			}
+		}
+
+		if self.order.len() >= MAX_SEEN_CANDIDATES {
+			if let Some(oldest) = self.order.pop_front() {
+				self.candidates.remove(&oldest);
+			}
+		}
+
+		self.order.push_back(key.clone());
+		self.candidates.insert(key, (candidate, false));
+
+		Ok(())
 	}
 }
 
@@ -361,17 +481,42 @@ where
 				return Ok(e)
 			}
 
-			let relay_parent = block_announce_data.receipt.descriptor.relay_parent;
+			let descriptor = block_announce_data.receipt.descriptor.clone();
 
 			relay_chain_interface
-				.wait_for_block(relay_parent)
+				.wait_for_block(descriptor.relay_parent)
 				.await
 				.map_err(|e| Box::new(BlockAnnounceError(e.to_string())) as Box<_>)?;
 
-			block_announce_data
+			let validation = block_announce_data
 				.check_signature(&relay_chain_interface)
 				.await
-				.map_err(|e| Box::new(e) as Box<_>)
+				.map_err(|e| Box::new(e) as Box<_>)?;
+
+			if !matches!(validation, Validation::Success { .. }) {
+				return Ok(validation)
+			}
+
+			if let Err(proof) = block_announce_validator.note_seen_candidate(header, descriptor) {
+				if let Some(proof) = proof {
+					tracing::warn!(
+						target: LOG_TARGET,
+						collator = ?proof.collator(),
+						block_number = ?proof.first.header.number(),
+						"Collator announced conflicting blocks at the same height.",
+					);
+
+					if let Some(handler) = &block_announce_validator.equivocation_handler {
+						handler(proof);
+					}
+				}
+
+				// The network lowers the reputation of the peer relaying the announcement. It is
+				// not necessarily the collator, so it is not disconnected.
+				return Ok(Validation::Failure { disconnect: false })
+			}
+
+			Ok(validation)
 		}
 		.boxed()
 	}
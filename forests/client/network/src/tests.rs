@@ -24,10 +24,10 @@ use parking_lot::Mutex;
 use sc_client_api::{Backend, BlockchainEvents};
 use selendra_node_primitives::{SignedFullStatement, Statement};
 use selendra_primitives::v2::{
-	CandidateCommitments, CandidateDescriptor, CollatorPair, CommittedCandidateReceipt,
-	Hash as PHash, HeadData, Header as PHeader, Id as ParaId, InboundDownwardMessage,
-	InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData, SessionIndex,
-	SigningContext, ValidationCodeHash, ValidatorId,
+	collator_signature_payload, CandidateCommitments, CandidateDescriptor, CollatorPair,
+	CommittedCandidateReceipt, Hash as PHash, HeadData, Header as PHeader, Id as ParaId,
+	InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData,
+	SessionIndex, SigningContext, ValidationCodeHash, ValidatorId,
 };
 use selendra_service::Handle;
 use sp_blockchain::HeaderBackend;
@@ -270,6 +270,29 @@ async fn make_gossip_message_and_header(
 	relay_parent: H256,
 	validator_index: u32,
 ) -> (CollationSecondedSignal, Header) {
+	let header = default_header();
+	let signal = make_gossip_message_for_header(
+		relay_chain_interface,
+		relay_parent,
+		validator_index,
+		&header,
+		&CollatorPair::generate().0,
+	)
+	.await;
+
+	(signal, header)
+}
+
+/// Create the gossip message of a candidate for `header` that is signed by `collator`.
+///
+/// All candidates created by this function share the same persisted validation data.
+async fn make_gossip_message_for_header(
+	relay_chain_interface: Arc<DummyRelayChainInterface>,
+	relay_parent: H256,
+	validator_index: u32,
+	header: &Header,
+	collator: &CollatorPair,
+) -> CollationSecondedSignal {
 	let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
 	let alice_public = SyncCryptoStore::sr25519_generate_new(
 		&*keystore,
@@ -280,22 +303,33 @@ async fn make_gossip_message_and_header(
 	let session_index = relay_chain_interface.session_index_for_child(relay_parent).await.unwrap();
 	let signing_context = SigningContext { parent_hash: relay_parent, session_index };
 
-	let header = default_header();
+	let para_id = 0u32.into();
+	let persisted_validation_data_hash = PHash::repeat_byte(1);
+	let pov_hash = PHash::from(sp_core::blake2_256(&header.encode()));
+	let validation_code_hash = ValidationCodeHash::from(PHash::zero());
+	let signature = collator.sign(&collator_signature_payload(
+		&relay_parent,
+		&para_id,
+		&persisted_validation_data_hash,
+		&pov_hash,
+		&validation_code_hash,
+	));
+
 	let candidate_receipt = CommittedCandidateReceipt {
 		commitments: CandidateCommitments {
 			head_data: header.encode().into(),
 			..Default::default()
 		},
 		descriptor: CandidateDescriptor {
-			para_id: 0u32.into(),
+			para_id,
 			relay_parent,
-			collator: CollatorPair::generate().0.public(),
-			persisted_validation_data_hash: PHash::random().into(),
-			pov_hash: PHash::random(),
+			collator: collator.public(),
+			persisted_validation_data_hash,
+			pov_hash,
 			erasure_root: PHash::random(),
-			signature: sp_core::sr25519::Signature([0u8; 64]).into(),
+			signature,
 			para_head: selendra_parachain::primitives::HeadData(header.encode()).hash(),
-			validation_code_hash: ValidationCodeHash::from(PHash::random()),
+			validation_code_hash,
 		},
 	};
 	let statement = Statement::Seconded(candidate_receipt);
@@ -311,7 +345,7 @@ async fn make_gossip_message_and_header(
 	.flatten()
 	.expect("Signing statement");
 
-	(CollationSecondedSignal { statement: signed, relay_parent }, header)
+	CollationSecondedSignal { statement: signed, relay_parent }
 }
 
 #[test]
@@ -510,6 +544,98 @@ fn check_header_match_candidate_receipt_header() {
 	assert_eq!(Validation::Failure { disconnect: true }, res.unwrap());
 }
 
+#[test]
+fn check_collator_signature() {
+	let (mut validator, api) = make_validator_and_api();
+
+	let (signal, header) = block_on(make_gossip_message_and_header_using_genesis(api, 0));
+	let mut data = BlockAnnounceData::try_from(&signal).unwrap();
+	data.receipt.descriptor.signature = sp_core::sr25519::Signature([0u8; 64]).into();
+
+	let res = block_on(validator.validate(&header, &data.encode()));
+	assert_eq!(Validation::Failure { disconnect: true }, res.unwrap());
+}
+
+#[test]
+fn announcing_the_same_block_twice_is_not_an_equivocation() {
+	let (mut validator, api) = make_validator_and_api();
+
+	let (signal, header) = block_on(make_gossip_message_and_header_using_genesis(api, 0));
+	let data = BlockAnnounceData::try_from(&signal).unwrap().encode();
+
+	for _ in 0..2 {
+		let res = block_on(validator.validate(&header, &data));
+		assert_eq!(Validation::Success { is_new_best: true }, res.unwrap());
+	}
+}
+
+#[test]
+fn detects_collator_equivocation() {
+	let (validator, api) = make_validator_and_api();
+	let proofs = Arc::new(Mutex::new(Vec::new()));
+	let proofs_for_handler = proofs.clone();
+	let mut validator = validator.with_equivocation_handler(Arc::new(
+		move |proof: CollatorEquivocationProof<Header>| proofs_for_handler.lock().push(proof),
+	));
+
+	let relay_parent = api.relay_client.hash(0).ok().flatten().expect("Genesis hash exists");
+	let collator = CollatorPair::generate().0;
+	let first = default_header();
+	let second = Header { state_root: Hash::random(), ..default_header() };
+
+	let first_data = BlockAnnounceData::try_from(&block_on(make_gossip_message_for_header(
+		api.clone(),
+		relay_parent,
+		0,
+		&first,
+		&collator,
+	)))
+	.unwrap()
+	.encode();
+	let res = block_on(validator.validate(&first, &first_data));
+	assert_eq!(Validation::Success { is_new_best: true }, res.unwrap());
+
+	// Another collator building on the same parent is fine.
+	let other_data = BlockAnnounceData::try_from(&block_on(make_gossip_message_for_header(
+		api.clone(),
+		relay_parent,
+		0,
+		&second,
+		&CollatorPair::generate().0,
+	)))
+	.unwrap()
+	.encode();
+	let res = block_on(validator.validate(&second, &other_data));
+	assert_eq!(Validation::Success { is_new_best: true }, res.unwrap());
+	assert!(proofs.lock().is_empty());
+
+	let second_data = BlockAnnounceData::try_from(&block_on(make_gossip_message_for_header(
+		api,
+		relay_parent,
+		0,
+		&second,
+		&collator,
+	)))
+	.unwrap()
+	.encode();
+	// Every announcement of the conflicting block is rejected and the collator is reported once.
+	for _ in 0..2 {
+		let res = block_on(validator.validate(&second, &second_data));
+		assert_eq!(Validation::Failure { disconnect: false }, res.unwrap());
+	}
+
+	// The first block is still accepted.
+	let res = block_on(validator.validate(&first, &first_data));
+	assert_eq!(Validation::Success { is_new_best: true }, res.unwrap());
+
+	let proofs = proofs.lock();
+	assert_eq!(1, proofs.len());
+	assert!(proofs[0].check());
+	assert_eq!(&collator.public(), proofs[0].collator());
+	assert_eq!(first, proofs[0].first.header);
+	assert_eq!(second, proofs[0].second.header);
+}
+
 /// Test that ensures that we postpone the block announce verification until
 /// a relay chain block is imported. This is important for when we receive a
 /// block announcement before we have imported the associated relay chain block
@@ -19,11 +19,14 @@ use sp_runtime::traits::Block as BlockT;
 use selendra_node_primitives::AvailableData;
 use selendra_node_subsystem::messages::AvailabilityRecoveryMessage;
 use selendra_overseer::Handle as OverseerHandle;
+use selendra_primitives::v2::{CandidateReceipt, SessionIndex};
 
 use futures::{channel::oneshot, stream::FuturesUnordered, Future, FutureExt, StreamExt};
 
 use std::{collections::HashSet, pin::Pin};
 
+use crate::metrics::Metrics;
+
 /// The active candidate recovery.
 ///
 /// This handles the candidate recovery and tracks the activate recoveries.
@@ -35,38 +38,48 @@ pub(crate) struct ActiveCandidateRecovery<Block: BlockT> {
 	/// The block hashes of the candidates currently being recovered.
 	candidates: HashSet<Block::Hash>,
 	overseer_handle: OverseerHandle,
+	metrics: Metrics,
 }
 
 impl<Block: BlockT> ActiveCandidateRecovery<Block> {
-	pub fn new(overseer_handle: OverseerHandle) -> Self {
-		Self { recoveries: Default::default(), candidates: Default::default(), overseer_handle }
+	pub fn new(overseer_handle: OverseerHandle, metrics: Metrics) -> Self {
+		Self {
+			recoveries: Default::default(),
+			candidates: Default::default(),
+			overseer_handle,
+			metrics,
+		}
 	}
 
-	/// Recover the given `pending_candidate`.
+	/// Recover the candidate with the given `receipt` that belongs to `block_hash`.
 	pub async fn recover_candidate(
 		&mut self,
 		block_hash: Block::Hash,
-		pending_candidate: crate::PendingCandidate<Block>,
+		receipt: CandidateReceipt,
+		session_index: SessionIndex,
 	) {
 		let (tx, rx) = oneshot::channel();
 
 		self.overseer_handle
 			.send_msg(
-				AvailabilityRecoveryMessage::RecoverAvailableData(
-					pending_candidate.receipt,
-					pending_candidate.session_index,
-					None,
-					tx,
-				),
+				AvailabilityRecoveryMessage::RecoverAvailableData(receipt, session_index, None, tx),
 				"ActiveCandidateRecovery",
 			)
 			.await;
 
 		self.candidates.insert(block_hash);
+		self.metrics.on_recovery_started();
 
+		let timer = self.metrics.time_recovery();
+		let metrics = self.metrics.clone();
 		self.recoveries.push(
 			async move {
-				match rx.await {
+				let res = rx.await;
+				if let Some(timer) = timer {
+					timer.observe_duration();
+				}
+
+				match res {
 					Ok(Ok(res)) => (block_hash, Some(res)),
 					Ok(Err(error)) => {
 						tracing::debug!(
@@ -75,6 +88,7 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 							?block_hash,
 							"Availability recovery failed",
 						);
+						metrics.on_recovery_unavailable();
 						(block_hash, None)
 					},
 					Err(_) => {
@@ -82,6 +96,7 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 							target: crate::LOG_TARGET,
 							"Availability recovery oneshot channel closed",
 						);
+						metrics.on_recovery_unavailable();
 						(block_hash, None)
 					},
 				}
@@ -95,6 +110,11 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 		self.candidates.contains(candidate)
 	}
 
+	/// Returns the number of recoveries that are currently being executed.
+	pub fn num_active(&self) -> usize {
+		self.candidates.len()
+	}
+
 	/// Waits for the next recovery.
 	///
 	/// If the returned [`AvailableData`] is `None`, it means that the recovery failed.
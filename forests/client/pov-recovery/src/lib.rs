@@ -28,15 +28,20 @@
 //! It works in the following way:
 //!
 //! 1. For every included relay chain block we note the backed candidate of our parachain. If the
-//!    block belonging to the PoV is already known, we do nothing. Otherwise we start
-//!    a timer that waits a random time between 0..relay_chain_slot_length before starting to recover
-//!    the PoV.
+//!    block belonging to the PoV is already known, we do nothing. Otherwise the
+//!    [`RecoveryStrategy`] decides when to recover it: collators recover the PoV right away,
+//!    while full nodes wait a random delay to give the block the chance to be announced.
 //!
-//! 2. If between starting and firing the timer the block is imported, we skip the recovery of the
-//!    PoV.
+//! 2. If between noting the candidate and starting the recovery the block is imported, we skip the
+//!    recovery of the PoV.
 //!
-//! 3. If the timer fired we recover the PoV using the relay chain PoV recovery protocol. After it
-//!    is recovered, we restore the block and import it.
+//! 3. Candidates that are due are recovered closest to finality first, with at most
+//!    [`MAX_PARALLEL_RECOVERIES`] recoveries running at the same time. If the parent of a due
+//!    candidate is itself a known, but missing candidate, the whole chain of missing ancestors is
+//!    scheduled in one go.
+//!
+//! 4. We recover the PoV using the relay chain PoV recovery protocol. After it is recovered, we
+//!    restore the block and import it.
 //!
 //! If we need to recover multiple PoV blocks (which should hopefully not happen in real life), we
 //! make sure that the blocks are imported in the correct order.
@@ -58,28 +63,39 @@ use selendra_primitives::v2::{
 use forests_primitives_core::ParachainBlockData;
 use forests_relay_chain_interface::{RelayChainInterface, RelayChainResult};
 
+use substrate_prometheus_endpoint::Registry;
+
 use codec::Decode;
 use futures::{select, stream::FuturesUnordered, Future, FutureExt, Stream, StreamExt};
 use futures_timer::Delay;
 use rand::{thread_rng, Rng};
 
 use std::{
-	collections::{HashMap, VecDeque},
+	cmp::Reverse,
+	collections::{BinaryHeap, HashMap, VecDeque},
 	pin::Pin,
 	sync::Arc,
 	time::Duration,
 };
 
 mod active_candidate_recovery;
+mod metrics;
 use active_candidate_recovery::ActiveCandidateRecovery;
+use metrics::Metrics;
 
 const LOG_TARGET: &str = "forests-pov-recovery";
 
+/// The maximum number of candidates that are recovered at the same time.
+pub const MAX_PARALLEL_RECOVERIES: usize = 4;
+
 /// Represents a pending candidate.
 struct PendingCandidate<Block: BlockT> {
 	receipt: CandidateReceipt,
 	session_index: SessionIndex,
 	block_number: NumberFor<Block>,
+	parent_hash: Block::Hash,
+	/// Is the candidate scheduled for recovery or being recovered?
+	scheduled: bool,
 }
 
 /// The delay between observing an unknown block and recovering this block.
@@ -102,22 +118,39 @@ impl RecoveryDelay {
 	}
 }
 
+/// When to recover an unknown block.
+#[derive(Clone, Copy)]
+pub enum RecoveryStrategy {
+	/// Recover the block as soon as its candidate is pending availability.
+	///
+	/// Collators should use this, as they depend on the block to build on top of it.
+	Eager,
+	/// Give the block the chance to be announced and only recover it after the given delay.
+	///
+	/// Full nodes should use this, as they are just the "last resort" for block recovery.
+	Delayed(RecoveryDelay),
+}
+
 /// Encapsulates the logic of the pov recovery.
 pub struct PoVRecovery<Block: BlockT, PC, IQ, RC> {
-	/// All the pending candidates that we are waiting for to be imported or that need to be
-	/// recovered when `next_candidate_to_recover` tells us to do so.
+	/// All the pending candidates that we are waiting for to be imported or finalized.
 	pending_candidates: HashMap<Block::Hash, PendingCandidate<Block>>,
-	/// A stream of futures that resolve to hashes of candidates that need to be recovered.
+	/// A stream of futures that resolve to hashes of candidates whose recovery delay expired.
 	///
 	/// The candidates to the hashes are stored in `pending_candidates`. If a candidate is not
 	/// available anymore in this map, it means that it was already imported.
 	next_candidate_to_recover: FuturesUnordered<Pin<Box<dyn Future<Output = Block::Hash> + Send>>>,
+	/// Candidates that are due to be recovered, lowest block number first.
+	///
+	/// Entries whose candidate is not in `pending_candidates` anymore are skipped.
+	to_recover: BinaryHeap<Reverse<(NumberFor<Block>, Block::Hash)>>,
 	active_candidate_recovery: ActiveCandidateRecovery<Block>,
 	/// Blocks that wait that the parent is imported.
 	///
 	/// Uses parent -> blocks mapping.
 	waiting_for_parent: HashMap<Block::Hash, Vec<Block>>,
-	recovery_delay: RecoveryDelay,
+	recovery_strategy: RecoveryStrategy,
+	metrics: Metrics,
 	parachain_client: Arc<PC>,
 	parachain_import_queue: IQ,
 	relay_chain_interface: RC,
@@ -133,17 +166,28 @@ where
 	/// Create a new instance.
 	pub fn new(
 		overseer_handle: OverseerHandle,
-		recovery_delay: RecoveryDelay,
+		recovery_strategy: RecoveryStrategy,
 		parachain_client: Arc<PC>,
 		parachain_import_queue: IQ,
 		relay_chain_interface: RCInterface,
 		para_id: ParaId,
+		prometheus_registry: Option<&Registry>,
 	) -> Self {
+		let metrics = Metrics::register(prometheus_registry).unwrap_or_else(|error| {
+			tracing::warn!(target: LOG_TARGET, ?error, "Failed to register PoV recovery metrics");
+			Metrics::default()
+		});
+
 		Self {
 			pending_candidates: HashMap::new(),
 			next_candidate_to_recover: Default::default(),
-			active_candidate_recovery: ActiveCandidateRecovery::new(overseer_handle),
-			recovery_delay,
+			to_recover: BinaryHeap::new(),
+			active_candidate_recovery: ActiveCandidateRecovery::new(
+				overseer_handle,
+				metrics.clone(),
+			),
+			recovery_strategy,
+			metrics,
 			waiting_for_parent: HashMap::new(),
 			parachain_client,
 			parachain_import_queue,
@@ -196,8 +240,10 @@ where
 				hash,
 				PendingCandidate {
 					block_number: *header.number(),
+					parent_hash: *header.parent_hash(),
 					receipt: receipt.to_plain(),
 					session_index,
+					scheduled: false,
 				},
 			)
 			.is_some()
@@ -205,15 +251,61 @@ where
 			return
 		}
 
-		// Delay the recovery by some random time to not spam the relay chain.
-		let delay = self.recovery_delay.as_delay();
-		self.next_candidate_to_recover.push(
-			async move {
-				delay.await;
-				hash
+		match self.recovery_strategy {
+			RecoveryStrategy::Eager => self.schedule_recovery(hash),
+			RecoveryStrategy::Delayed(recovery_delay) => {
+				// Delay the recovery by some random time to not spam the relay chain.
+				let delay = recovery_delay.as_delay();
+				self.next_candidate_to_recover.push(
+					async move {
+						delay.await;
+						hash
+					}
+					.boxed(),
+				);
+			},
+		}
+	}
+
+	/// Schedule the recovery of the candidate for the given `block_hash`.
+	///
+	/// Walks up the chain of parents and schedules all pending candidates on the way whose block
+	/// is unknown as well, so that a chain of missing blocks is recovered in one pass.
+	fn schedule_recovery(&mut self, block_hash: Block::Hash) {
+		let mut next = Some(block_hash);
+
+		while let Some(hash) = next.take() {
+			let candidate = match self.pending_candidates.get_mut(&hash) {
+				Some(candidate) if !candidate.scheduled => candidate,
+				// Either imported, finalized or already taken care of.
+				_ => return,
+			};
+
+			if hash != block_hash &&
+				!matches!(
+					self.parachain_client.block_status(&BlockId::Hash(hash)),
+					Ok(BlockStatus::Unknown)
+				) {
+				return
 			}
-			.boxed(),
-		);
+
+			candidate.scheduled = true;
+			self.to_recover.push(Reverse((candidate.block_number, hash)));
+			next = Some(candidate.parent_hash);
+		}
+	}
+
+	/// Start recovering the scheduled candidates closest to finality, as long as there is room
+	/// for more parallel recoveries.
+	async fn start_recoveries(&mut self) {
+		while self.active_candidate_recovery.num_active() < MAX_PARALLEL_RECOVERIES {
+			let block_hash = match self.to_recover.pop() {
+				Some(Reverse((_, block_hash))) => block_hash,
+				None => return,
+			};
+
+			self.recover_candidate(block_hash).await;
+		}
 	}
 
 	/// Handle an imported block.
@@ -228,22 +320,29 @@ where
 
 	/// Recover the candidate for the given `block_hash`.
 	async fn recover_candidate(&mut self, block_hash: Block::Hash) {
-		let pending_candidate = match self.pending_candidates.remove(&block_hash) {
-			Some(pending_candidate) => pending_candidate,
+		let (receipt, session_index) = match self.pending_candidates.get(&block_hash) {
+			Some(pending_candidate) =>
+				(pending_candidate.receipt.clone(), pending_candidate.session_index),
 			None => return,
 		};
 
 		self.active_candidate_recovery
-			.recover_candidate(block_hash, pending_candidate)
+			.recover_candidate(block_hash, receipt, session_index)
 			.await;
 	}
 
 	/// Clear `waiting_for_parent` from the given `hash` and do this recursively for all child
 	/// blocks.
+	///
+	/// All the affected candidates are not considered as scheduled for recovery anymore.
 	fn clear_waiting_for_parent(&mut self, hash: Block::Hash) {
 		let mut blocks_to_delete = vec![hash];
 
 		while let Some(delete) = blocks_to_delete.pop() {
+			if let Some(candidate) = self.pending_candidates.get_mut(&delete) {
+				candidate.scheduled = false;
+			}
+
 			if let Some(childs) = self.waiting_for_parent.remove(&delete) {
 				blocks_to_delete.extend(childs.iter().map(BlockT::hash));
 			}
@@ -272,6 +371,7 @@ where
 			Err(error) => {
 				tracing::debug!(target: LOG_TARGET, ?error, "Failed to decompress PoV");
 
+				self.metrics.on_recovery_invalid();
 				self.clear_waiting_for_parent(block_hash);
 
 				return
@@ -287,19 +387,28 @@ where
 					"Failed to decode parachain block data from recovered PoV",
 				);
 
+				self.metrics.on_recovery_invalid();
 				self.clear_waiting_for_parent(block_hash);
 
 				return
 			},
 		};
 
+		self.metrics.on_recovery_succeeded();
+
 		let block = block_data.into_block();
 
 		let parent = *block.header().parent_hash();
 
 		match self.parachain_client.block_status(&BlockId::hash(parent)) {
 			Ok(BlockStatus::Unknown) => {
-				if self.active_candidate_recovery.is_being_recovered(&parent) {
+				// The parent may be a missing candidate we did not get to yet.
+				self.schedule_recovery(parent);
+
+				let parent_scheduled =
+					self.pending_candidates.get(&parent).map_or(false, |c| c.scheduled);
+
+				if parent_scheduled || self.active_candidate_recovery.is_being_recovered(&parent) {
 					tracing::debug!(
 						target: "forests-consensus",
 						?block_hash,
@@ -392,6 +501,8 @@ where
 		futures::pin_mut!(pending_candidates);
 
 		loop {
+			self.start_recoveries().await;
+
 			select! {
 				pending_candidate = pending_candidates.next() => {
 					if let Some((receipt, session_index)) = pending_candidate {
@@ -428,7 +539,7 @@ where
 				},
 				next_to_recover = self.next_candidate_to_recover.next() => {
 					if let Some(block_hash) = next_to_recover {
-						self.recover_candidate(block_hash).await;
+						self.schedule_recovery(block_hash);
 					}
 				},
 				(block_hash, available_data) =
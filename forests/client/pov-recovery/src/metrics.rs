@@ -0,0 +1,114 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the PoV recovery.
+
+use substrate_prometheus_endpoint::{
+	prometheus::HistogramTimer, register, Counter, CounterVec, Histogram, HistogramOpts, Opts,
+	PrometheusError, Registry, U64,
+};
+
+/// PoV recovery metrics.
+#[derive(Clone, Default)]
+pub(crate) struct Metrics(Option<MetricsInner>);
+
+#[derive(Clone)]
+struct MetricsInner {
+	/// Number of recoveries that have been started.
+	recoveries_started: Counter<U64>,
+
+	/// Number of recoveries that have been finished one way or the other.
+	///
+	/// Split by result:
+	/// - `success` ... the block was recovered from the available data.
+	/// - `unavailable` ... the available data could not be recovered from the relay chain.
+	/// - `invalid` ... the available data was recovered, but the block could not be decoded.
+	recoveries_finished: CounterVec<U64>,
+
+	/// Time from starting a recovery until the available data was recovered or the recovery
+	/// failed.
+	time_recovery: Histogram,
+}
+
+impl Metrics {
+	/// Register the metrics in the given `registry`, if any.
+	pub fn register(registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+		let registry = match registry {
+			Some(registry) => registry,
+			None => return Ok(Self(None)),
+		};
+
+		Ok(Self(Some(MetricsInner {
+			recoveries_started: register(
+				Counter::new(
+					"forests_pov_recovery_recoveries_started",
+					"Total number of started PoV recoveries.",
+				)?,
+				registry,
+			)?,
+			recoveries_finished: register(
+				CounterVec::new(
+					Opts::new(
+						"forests_pov_recovery_recoveries_finished",
+						"Total number of finished PoV recoveries.",
+					),
+					&["result"],
+				)?,
+				registry,
+			)?,
+			time_recovery: register(
+				Histogram::with_opts(HistogramOpts::new(
+					"forests_pov_recovery_time",
+					"Time spent recovering the available data of a candidate.",
+				))?,
+				registry,
+			)?,
+		})))
+	}
+
+	/// A recovery was started.
+	pub fn on_recovery_started(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.recoveries_started.inc()
+		}
+	}
+
+	/// Get a timer to measure the time of a recovery.
+	pub fn time_recovery(&self) -> Option<HistogramTimer> {
+		self.0.as_ref().map(|metrics| metrics.time_recovery.start_timer())
+	}
+
+	/// A block was recovered from the available data.
+	pub fn on_recovery_succeeded(&self) {
+		self.on_recovery_finished("success")
+	}
+
+	/// The available data could not be recovered.
+	pub fn on_recovery_unavailable(&self) {
+		self.on_recovery_finished("unavailable")
+	}
+
+	/// The available data was recovered, but did not contain a valid block.
+	pub fn on_recovery_invalid(&self) {
+		self.on_recovery_finished("invalid")
+	}
+
+	fn on_recovery_finished(&self, result: &str) {
+		if let Some(metrics) = &self.0 {
+			metrics.recoveries_finished.with_label_values(&[result]).inc()
+		}
+	}
+}
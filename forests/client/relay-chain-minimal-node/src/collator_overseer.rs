@@ -0,0 +1,167 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Forests.
+
+// Forests is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Forests is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Forests.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Overseer for the minimal relay chain node, running only the subsystems a collator needs.
+
+use std::sync::Arc;
+
+use forests_primitives_core::relay_chain::{Block as PBlock, Hash as PHash};
+use sc_authority_discovery::Service as AuthorityDiscoveryService;
+use sc_network::NetworkService;
+use sc_network_common::service::NetworkStateInfo;
+use sc_service::SpawnTaskHandle;
+use selendra_availability_distribution::IncomingRequestReceivers;
+use selendra_node_network_protocol::request_response::{
+	v1 as request_v1, IncomingRequestReceiver, ReqProtocolNames,
+};
+use selendra_node_subsystem_util::metrics::{prometheus::Registry, Metrics};
+use selendra_overseer::{
+	dummy::dummy_overseer_builder, BlockInfo, Overseer, OverseerConnector, OverseerHandle,
+	SpawnGlue,
+};
+use selendra_service::{
+	overseer::{
+		AvailabilityDistributionSubsystem, AvailabilityRecoverySubsystem, ChainApiSubsystem,
+		CollatorProtocolSubsystem, NetworkBridgeMetrics, NetworkBridgeRxSubsystem,
+		NetworkBridgeTxSubsystem, ProtocolSide, RuntimeApiSubsystem,
+	},
+	Error, IsCollator,
+};
+use sp_keystore::SyncCryptoStorePtr;
+
+use crate::{LightClient, LightRuntimeClient};
+
+/// Arguments passed for overseer construction.
+pub struct CollatorOverseerGenArgs<'a> {
+	/// Leaves of the relay chain the overseer starts with.
+	pub leaves: Vec<BlockInfo>,
+	/// The keystore, only used to look up validator keys, which a collator does not have.
+	pub keystore: SyncCryptoStorePtr,
+	/// Header-only client of the relay chain, used by the chain API subsystem.
+	pub client: Arc<LightClient>,
+	/// Runtime client of the relay chain, used by the runtime API subsystem.
+	pub runtime_client: Arc<LightRuntimeClient>,
+	/// Underlying network service implementation.
+	pub network_service: Arc<NetworkService<PBlock, PHash>>,
+	/// Underlying authority discovery service.
+	pub authority_discovery_service: AuthorityDiscoveryService,
+	/// POV request receiver
+	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
+	/// Erasure chunks request receiver
+	pub chunk_req_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
+	/// Collations request receiver
+	pub collation_req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+	/// Receiver for available data requests
+	pub available_data_req_receiver:
+		IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+	/// Prometheus registry, commonly used for production systems, less so for test.
+	pub registry: Option<&'a Registry>,
+	/// Task spawner to be used throughout the overseer and the APIs it provides.
+	pub spawner: SpawnTaskHandle,
+	/// Determines the behavior of the collator.
+	pub is_collator: IsCollator,
+	/// Request-response protocol names source.
+	pub req_protocol_names: ReqProtocolNames,
+}
+
+/// Build an overseer that only runs the networking, collator-protocol and availability
+/// subsystems, together with the chain and runtime API subsystems they depend on.
+///
+/// All other subsystems, most notably everything related to validation, approval voting and
+/// disputes, are replaced by dummies that drop every message they receive.
+pub fn build_collator_overseer(
+	connector: OverseerConnector,
+	CollatorOverseerGenArgs {
+		leaves,
+		keystore,
+		client,
+		runtime_client,
+		network_service,
+		authority_discovery_service,
+		pov_req_receiver,
+		chunk_req_receiver,
+		collation_req_receiver,
+		available_data_req_receiver,
+		registry,
+		spawner,
+		is_collator,
+		req_protocol_names,
+	}: CollatorOverseerGenArgs<'_>,
+) -> Result<(Overseer<SpawnGlue<SpawnTaskHandle>, Arc<LightRuntimeClient>>, OverseerHandle), Error>
+{
+	let network_bridge_metrics: NetworkBridgeMetrics = Metrics::register(registry)?;
+	let availability_distribution_metrics = Metrics::register(registry)?;
+	let availability_recovery_metrics = Metrics::register(registry)?;
+	let chain_api_metrics = Metrics::register(registry)?;
+	let runtime_api_metrics = Metrics::register(registry)?;
+
+	let collator_protocol_side = match is_collator {
+		IsCollator::Yes(collator_pair) => ProtocolSide::Collator(
+			network_service.local_peer_id().clone(),
+			collator_pair,
+			collation_req_receiver,
+			Metrics::register(registry)?,
+		),
+		IsCollator::No => ProtocolSide::Validator {
+			keystore: keystore.clone(),
+			eviction_policy: Default::default(),
+			metrics: Metrics::register(registry)?,
+		},
+	};
+
+	let builder = dummy_overseer_builder(spawner.clone(), runtime_client.clone(), registry)?
+		.replace_network_bridge_tx(|_| {
+			NetworkBridgeTxSubsystem::new(
+				network_service.clone(),
+				authority_discovery_service.clone(),
+				network_bridge_metrics.clone(),
+				req_protocol_names,
+			)
+		})
+		.replace_network_bridge_rx(|_| {
+			NetworkBridgeRxSubsystem::new(
+				network_service.clone(),
+				authority_discovery_service.clone(),
+				Box::new(network_service.clone()),
+				network_bridge_metrics,
+			)
+		})
+		.replace_availability_distribution(|_| {
+			AvailabilityDistributionSubsystem::new(
+				keystore.clone(),
+				IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
+				availability_distribution_metrics,
+			)
+		})
+		.replace_availability_recovery(|_| {
+			AvailabilityRecoverySubsystem::with_chunks_only(
+				available_data_req_receiver,
+				availability_recovery_metrics,
+			)
+		})
+		.replace_collator_protocol(|_| CollatorProtocolSubsystem::new(collator_protocol_side))
+		.replace_chain_api(|_| ChainApiSubsystem::new(client, chain_api_metrics))
+		.replace_runtime_api(|_| {
+			RuntimeApiSubsystem::new(runtime_client, runtime_api_metrics, SpawnGlue(spawner))
+		})
+		.leaves(Vec::from_iter(
+			leaves
+				.into_iter()
+				.map(|BlockInfo { hash, parent_hash: _, number }| (hash, number)),
+		));
+
+	builder.build_with_connector(connector).map_err(|e| e.into())
+}
@@ -0,0 +1,563 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Forests.
+
+// Forests is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Forests is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Forests.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A minimal relay chain node embedded into the collator.
+//!
+//! Compared to the full in-process relay chain node, the minimal node:
+//!
+//! - only runs the networking, collator-protocol and availability subsystems. Everything related
+//!   to validation, approval voting and disputes is disabled.
+//! - is a light client of the relay chain. It only syncs headers and justifications, checks the
+//!   BABE seals of the headers and follows finality by checking GRANDPA justifications and
+//!   commit messages. It does not import block bodies and keeps no state besides the genesis
+//!   state.
+//! - reads relay chain state through storage proofs and executes runtime calls on top of
+//!   execution proofs, both fetched from its peers and checked against the state root of the
+//!   synced headers.
+//!
+//! This gives collators a trustless view of the relay chain at a fraction of the resources of a
+//! full node.
+
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use forests_primitives_core::{
+	relay_chain::{
+		v2::{CommittedCandidateReceipt, OccupiedCoreAssumption, SessionIndex, ValidatorId},
+		Block as PBlock, BlockId, Hash as PHash, Header as PHeader, InboundHrmpMessage,
+	},
+	InboundDownwardMessage, ParaId, PersistedValidationData,
+};
+use forests_relay_chain_inprocess_interface::RelayChainInProcessInterface;
+use forests_relay_chain_interface::{RelayChainError, RelayChainInterface, RelayChainResult};
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use sc_cli::SubstrateCli;
+use sc_client_api::{
+	AuxStore, Backend, BlockBackend, ClientInfo, ExecutorProvider, HeaderBackend, StorageProof,
+	UsageProvider,
+};
+use sc_consensus::LongestChain;
+use sc_executor::NativeElseWasmExecutor;
+use sc_network::{Event, NetworkService};
+use sc_network_common::service::NetworkEventStream;
+use sc_service::Role;
+use sc_telemetry::TelemetryWorkerHandle;
+use selendra_client::{FullBackend, FullClient};
+use selendra_node_network_protocol::{
+	peer_set::{peer_sets_info, IsAuthority},
+	request_response::{IncomingRequest, ReqProtocolNames},
+};
+use selendra_overseer::{BlockInfo, OverseerConnector, RuntimeApiSubsystemClient};
+use selendra_service::{
+	selendra_runtime::RuntimeApi, CollatorPair, Configuration, Handle, IsCollator,
+	SelendraExecutorDispatch, TaskManager,
+};
+use sp_api::{ApiRef, ProvideRuntimeApi};
+use sp_consensus::SyncOracle;
+use sp_core::{sp_std::collections::btree_map::BTreeMap, Pair};
+use sp_runtime::traits::Header as HeaderT;
+use sp_state_machine::StorageValue;
+
+mod collator_overseer;
+mod network;
+mod remote_state;
+mod runtime_client;
+
+pub use collator_overseer::{build_collator_overseer, CollatorOverseerGenArgs};
+pub use remote_state::RemoteStateReader;
+pub use runtime_client::{AuthorityDiscoveryClient, LightRuntimeClient};
+
+/// The header-only relay chain client of the minimal node.
+///
+/// Only the genesis state is available locally, runtime calls at any other block have to go
+/// through [`LightRuntimeClient`].
+pub type LightClient = FullClient<RuntimeApi, SelendraExecutorDispatch>;
+
+/// Provides an implementation of the [`RelayChainInterface`] using a minimal relay chain node.
+///
+/// Notifications and block information are served by the local header-only client. Runtime calls
+/// and storage reads are answered from proofs fetched from peers.
+pub struct RelayChainMinimalNodeInterface {
+	local: RelayChainInProcessInterface<LightClient>,
+	backend: Arc<FullBackend>,
+	runtime_client: Arc<LightRuntimeClient>,
+	remote_state: RemoteStateReader,
+}
+
+impl RelayChainMinimalNodeInterface {
+	/// Create a new instance of [`RelayChainMinimalNodeInterface`]
+	pub fn new(
+		local: RelayChainInProcessInterface<LightClient>,
+		backend: Arc<FullBackend>,
+		runtime_client: Arc<LightRuntimeClient>,
+		remote_state: RemoteStateReader,
+	) -> Self {
+		Self { local, backend, runtime_client, remote_state }
+	}
+
+	/// Returns the header of `relay_parent` if the state of the block is not available locally.
+	fn header_without_state(&self, relay_parent: PHash) -> RelayChainResult<Option<PHeader>> {
+		let header =
+			self.backend.blockchain().header(BlockId::Hash(relay_parent))?.ok_or_else(|| {
+				RelayChainError::GenericError(format!("Unknown relay parent {}", relay_parent))
+			})?;
+
+		if self.backend.have_state_at(&relay_parent, header.number) {
+			Ok(None)
+		} else {
+			Ok(Some(header))
+		}
+	}
+}
+
+impl Clone for RelayChainMinimalNodeInterface {
+	fn clone(&self) -> Self {
+		Self {
+			local: self.local.clone(),
+			backend: self.backend.clone(),
+			runtime_client: self.runtime_client.clone(),
+			remote_state: self.remote_state.clone(),
+		}
+	}
+}
+
+#[async_trait]
+impl RelayChainInterface for RelayChainMinimalNodeInterface {
+	async fn retrieve_dmq_contents(
+		&self,
+		para_id: ParaId,
+		relay_parent: PHash,
+	) -> RelayChainResult<Vec<InboundDownwardMessage>> {
+		Ok(self.runtime_client.dmq_contents(relay_parent, para_id).await?)
+	}
+
+	async fn retrieve_all_inbound_hrmp_channel_contents(
+		&self,
+		para_id: ParaId,
+		relay_parent: PHash,
+	) -> RelayChainResult<BTreeMap<ParaId, Vec<InboundHrmpMessage>>> {
+		Ok(self
+			.runtime_client
+			.inbound_hrmp_channels_contents(relay_parent, para_id)
+			.await?)
+	}
+
+	async fn persisted_validation_data(
+		&self,
+		hash: PHash,
+		para_id: ParaId,
+		occupied_core_assumption: OccupiedCoreAssumption,
+	) -> RelayChainResult<Option<PersistedValidationData>> {
+		Ok(self
+			.runtime_client
+			.persisted_validation_data(hash, para_id, occupied_core_assumption)
+			.await?)
+	}
+
+	async fn candidate_pending_availability(
+		&self,
+		hash: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<Option<CommittedCandidateReceipt>> {
+		Ok(self.runtime_client.candidate_pending_availability(hash, para_id).await?)
+	}
+
+	async fn session_index_for_child(&self, hash: PHash) -> RelayChainResult<SessionIndex> {
+		Ok(self.runtime_client.session_index_for_child(hash).await?)
+	}
+
+	async fn validators(&self, hash: PHash) -> RelayChainResult<Vec<ValidatorId>> {
+		Ok(self.runtime_client.validators(hash).await?)
+	}
+
+	async fn import_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		self.local.import_notification_stream().await
+	}
+
+	async fn finality_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		self.local.finality_notification_stream().await
+	}
+
+	async fn best_block_hash(&self) -> RelayChainResult<PHash> {
+		self.local.best_block_hash().await
+	}
+
+	async fn is_major_syncing(&self) -> RelayChainResult<bool> {
+		self.local.is_major_syncing().await
+	}
+
+	fn overseer_handle(&self) -> RelayChainResult<Option<Handle>> {
+		self.local.overseer_handle()
+	}
+
+	async fn get_storage_by_key(
+		&self,
+		relay_parent: PHash,
+		key: &[u8],
+	) -> RelayChainResult<Option<StorageValue>> {
+		match self.header_without_state(relay_parent)? {
+			Some(header) => self.remote_state.storage(&header, key).await,
+			None => self.local.get_storage_by_key(relay_parent, key).await,
+		}
+	}
+
+	async fn prove_read(
+		&self,
+		relay_parent: PHash,
+		relevant_keys: &Vec<Vec<u8>>,
+	) -> RelayChainResult<StorageProof> {
+		match self.header_without_state(relay_parent)? {
+			Some(header) => self.remote_state.read_proof(&header, relevant_keys).await,
+			None => self.local.prove_read(relay_parent, relevant_keys).await,
+		}
+	}
+
+	async fn wait_for_block(&self, hash: PHash) -> RelayChainResult<()> {
+		self.local.wait_for_block(hash).await
+	}
+
+	async fn new_best_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		self.local.new_best_notification_stream().await
+	}
+}
+
+/// Reads the BABE configuration from the genesis state.
+///
+/// `sc_consensus_babe::Config::get` reads the configuration at the best block, whose state the
+/// header-only client does not have. The configuration can't change after genesis, so reading it
+/// from the genesis state, which the client always keeps, gives the same result.
+struct GenesisConfigReader<'a>(&'a LightClient);
+
+impl ProvideRuntimeApi<PBlock> for GenesisConfigReader<'_> {
+	type Api = <LightClient as ProvideRuntimeApi<PBlock>>::Api;
+
+	fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+		self.0.runtime_api()
+	}
+}
+
+impl UsageProvider<PBlock> for GenesisConfigReader<'_> {
+	fn usage_info(&self) -> ClientInfo<PBlock> {
+		let mut info = self.0.usage_info();
+		info.chain.best_hash = info.chain.genesis_hash;
+		info.chain.best_number = 0;
+		info
+	}
+}
+
+impl AuxStore for GenesisConfigReader<'_> {
+	fn insert_aux<
+		'a,
+		'b: 'a,
+		'c: 'a,
+		I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+		D: IntoIterator<Item = &'a &'b [u8]>,
+	>(
+		&self,
+		insert: I,
+		delete: D,
+	) -> sp_blockchain::Result<()> {
+		self.0.insert_aux(insert, delete)
+	}
+
+	fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		self.0.get_aux(key)
+	}
+}
+
+/// The components of a running minimal relay chain node.
+struct MinimalNode {
+	task_manager: TaskManager,
+	client: Arc<LightClient>,
+	backend: Arc<FullBackend>,
+	network: Arc<NetworkService<PBlock, PHash>>,
+	runtime_client: Arc<LightRuntimeClient>,
+	remote_state: RemoteStateReader,
+	overseer_handle: Handle,
+}
+
+/// Build the minimal Selendra node using the given `config`.
+#[sc_tracing::logging::prefix_logs_with("Relaychain")]
+fn build_selendra_minimal_node(
+	mut config: Configuration,
+	parachain_config: &Configuration,
+	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
+) -> Result<(MinimalNode, Option<CollatorPair>), selendra_service::Error> {
+	let (is_collator, maybe_collator_key) = if parachain_config.role.is_authority() {
+		let collator_key = CollatorPair::generate().0;
+		(IsCollator::Yes(collator_key.clone()), Some(collator_key))
+	} else {
+		(IsCollator::No, None)
+	};
+
+	let telemetry = telemetry_worker_handle
+		.zip(config.telemetry_endpoints.clone().filter(|endpoints| !endpoints.is_empty()))
+		.map(|(mut worker_handle, endpoints)| worker_handle.new_telemetry(endpoints));
+	let prometheus_registry = config.prometheus_registry().cloned();
+
+	let executor = NativeElseWasmExecutor::<SelendraExecutorDispatch>::new(
+		config.wasm_method,
+		config.default_heap_pages,
+		config.max_runtime_instances,
+		config.runtime_cache_size,
+	);
+
+	let (client, backend, keystore_container, task_manager) =
+		sc_service::new_full_parts::<PBlock, RuntimeApi, _>(
+			&config,
+			telemetry.as_ref().map(|telemetry| telemetry.handle()),
+			executor.clone(),
+		)?;
+	let client = Arc::new(client);
+	let select_chain = LongestChain::new(backend.clone());
+
+	// Blocks are imported without executing them, GRANDPA and BABE only check the headers.
+	let (grandpa_block_import, grandpa_link) =
+		sc_finality_grandpa::block_import_with_authority_set_hard_forks(
+			client.clone(),
+			&(client.clone() as Arc<_>),
+			select_chain.clone(),
+			Vec::new(),
+			telemetry.as_ref().map(|telemetry| telemetry.handle()),
+		)?;
+	let justification_import = grandpa_block_import.clone();
+
+	let babe_config = sc_consensus_babe::Config::get(&GenesisConfigReader(&client))?;
+	let (block_import, babe_link) =
+		sc_consensus_babe::block_import(babe_config, grandpa_block_import, client.clone())?;
+
+	let slot_duration = babe_link.config().slot_duration();
+	let import_queue = sc_consensus_babe::import_queue(
+		babe_link,
+		block_import,
+		Some(Box::new(justification_import)),
+		client.clone(),
+		select_chain,
+		move |_, ()| async move {
+			let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+			let slot =
+				sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+					*timestamp,
+					slot_duration,
+				);
+
+			Ok((timestamp, slot))
+		},
+		&task_manager.spawn_essential_handle(),
+		prometheus_registry.as_ref(),
+		sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
+		telemetry.as_ref().map(|telemetry| telemetry.handle()),
+	)?;
+
+	let genesis_hash = client.block_hash(0).ok().flatten().expect("Genesis block exists; qed");
+
+	let grandpa_protocol_name =
+		sc_finality_grandpa::protocol_standard_name(&genesis_hash, &config.chain_spec);
+	config
+		.network
+		.extra_sets
+		.push(sc_finality_grandpa::grandpa_peers_set_config(grandpa_protocol_name.clone()));
+	config.network.extra_sets.extend(peer_sets_info(IsAuthority::No));
+
+	let req_protocol_names = ReqProtocolNames::new(&genesis_hash, config.chain_spec.fork_id());
+	let (pov_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
+	config.network.request_response_protocols.push(cfg);
+	let (chunk_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
+	config.network.request_response_protocols.push(cfg);
+	let (collation_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
+	config.network.request_response_protocols.push(cfg);
+	let (available_data_req_receiver, cfg) =
+		IncomingRequest::get_config_receiver(&req_protocol_names);
+	config.network.request_response_protocols.push(cfg);
+
+	let network = network::build_light_network(
+		&config,
+		client.clone(),
+		import_queue,
+		task_manager.spawn_handle(),
+	)?;
+
+	// The observer follows the GRANDPA commit messages of the validators, so that finality does
+	// not lag behind until the next block with a justification is synced.
+	let grandpa_config = sc_finality_grandpa::Config {
+		gossip_duration: Duration::from_millis(1000),
+		justification_period: 512,
+		name: Some(config.network.node_name.clone()),
+		observer_enabled: true,
+		keystore: None,
+		local_role: Role::Full,
+		telemetry: telemetry.as_ref().map(|telemetry| telemetry.handle()),
+		protocol_name: grandpa_protocol_name,
+	};
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"grandpa-observer",
+		None,
+		sc_finality_grandpa::run_grandpa_observer(grandpa_config, grandpa_link, network.clone())?,
+	);
+
+	let remote_state = RemoteStateReader::new(
+		network.clone(),
+		remote_state::protocol_name(&genesis_hash, config.chain_spec.fork_id()),
+		task_manager.spawn_handle(),
+	);
+	let runtime_client = Arc::new(LightRuntimeClient::new(
+		client.clone(),
+		remote_state.clone(),
+		executor,
+		task_manager.spawn_handle(),
+	));
+
+	let authority_discovery_service = {
+		let dht_event_stream =
+			network.event_stream("authority-discovery").filter_map(|e| async move {
+				match e {
+					Event::Dht(e) => Some(e),
+					_ => None,
+				}
+			});
+		let (worker, service) = sc_authority_discovery::new_worker_and_service_with_config(
+			sc_authority_discovery::WorkerConfig {
+				publish_non_global_ips: config.network.allow_non_globals_in_dht,
+				// Require that authority discovery records are signed.
+				strict_record_validation: true,
+				..Default::default()
+			},
+			Arc::new(AuthorityDiscoveryClient::new((*runtime_client).clone())),
+			network.clone(),
+			Box::pin(dht_event_stream),
+			// Collators don't publish their addresses.
+			sc_authority_discovery::Role::Discover,
+			prometheus_registry.clone(),
+		);
+
+		task_manager.spawn_handle().spawn(
+			"authority-discovery-worker",
+			Some("authority-discovery"),
+			Box::pin(worker.run()),
+		);
+		service
+	};
+
+	let best_hash = client.info().best_hash;
+	let best_header = client
+		.header(&BlockId::Hash(best_hash))?
+		.ok_or_else(|| sp_blockchain::Error::UnknownBlock(best_hash.to_string()))?;
+	let leaves = vec![BlockInfo {
+		hash: best_hash,
+		parent_hash: *best_header.parent_hash(),
+		number: *best_header.number(),
+	}];
+
+	let (overseer, overseer_handle) = build_collator_overseer(
+		OverseerConnector::default(),
+		CollatorOverseerGenArgs {
+			leaves,
+			keystore: keystore_container.sync_keystore(),
+			client: client.clone(),
+			runtime_client: runtime_client.clone(),
+			network_service: network.clone(),
+			authority_discovery_service,
+			pov_req_receiver,
+			chunk_req_receiver,
+			collation_req_receiver,
+			available_data_req_receiver,
+			registry: prometheus_registry.as_ref(),
+			spawner: task_manager.spawn_handle(),
+			is_collator,
+			req_protocol_names,
+		},
+	)?;
+	let overseer_handle = Handle::new(overseer_handle);
+
+	{
+		let handle = overseer_handle.clone();
+		let client = client.clone();
+		task_manager.spawn_essential_handle().spawn_blocking(
+			"overseer",
+			None,
+			Box::pin(async move {
+				use futures::{pin_mut, select, FutureExt};
+
+				let forward = selendra_overseer::forward_events(client, handle).fuse();
+				let overseer_fut = overseer.run().fuse();
+
+				pin_mut!(overseer_fut);
+				pin_mut!(forward);
+
+				select! {
+					_ = forward => (),
+					_ = overseer_fut => (),
+					complete => (),
+				}
+			}),
+		);
+	}
+
+	let minimal_node = MinimalNode {
+		task_manager,
+		client,
+		backend,
+		network,
+		runtime_client,
+		remote_state,
+		overseer_handle,
+	};
+
+	Ok((minimal_node, maybe_collator_key))
+}
+
+/// Builds a relay chain interface by constructing a minimal relay chain node.
+pub fn build_minimal_relay_chain_node(
+	mut selendra_config: Configuration,
+	parachain_config: &Configuration,
+	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
+	task_manager: &mut TaskManager,
+) -> RelayChainResult<(Arc<(dyn RelayChainInterface + 'static)>, Option<CollatorPair>)> {
+	// This is essentially a hack, but we want to ensure that we send the correct node version
+	// to the telemetry.
+	selendra_config.impl_version = selendra_cli::Cli::impl_version();
+	selendra_config.impl_name = selendra_cli::Cli::impl_name();
+
+	let (minimal_node, collator_key) =
+		build_selendra_minimal_node(selendra_config, parachain_config, telemetry_worker_handle)?;
+
+	let sync_oracle: Box<dyn SyncOracle + Send + Sync> = Box::new(minimal_node.network.clone());
+	let sync_oracle = Arc::new(Mutex::new(sync_oracle));
+	let local = RelayChainInProcessInterface::new(
+		minimal_node.client.clone(),
+		minimal_node.backend.clone(),
+		sync_oracle,
+		Some(minimal_node.overseer_handle.clone()),
+	);
+	let relay_chain_interface = RelayChainMinimalNodeInterface::new(
+		local,
+		minimal_node.backend,
+		minimal_node.runtime_client,
+		minimal_node.remote_state,
+	);
+
+	task_manager.add_child(minimal_node.task_manager);
+
+	Ok((Arc::new(relay_chain_interface), collator_key))
+}
@@ -0,0 +1,152 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Forests.
+
+// Forests is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Forests is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Forests.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Networking of the minimal relay chain node.
+//!
+//! `sc_service::build_network` only offers full, fast and warp sync. The minimal node needs the
+//! light sync mode, which downloads headers and justifications only, so the network is built
+//! here instead.
+
+use std::sync::Arc;
+
+use forests_primitives_core::relay_chain::{Block as PBlock, Hash as PHash};
+use futures::{FutureExt, StreamExt};
+use sc_client_api::{BlockBackend, BlockchainEvents};
+use sc_consensus::ImportQueue;
+use sc_network::{
+	config::{EmptyTransactionPool, Params},
+	NetworkService, NetworkWorker,
+};
+use sc_network_common::sync::SyncMode;
+use sc_network_sync::ChainSync;
+use sc_service::{Configuration, SpawnTaskHandle};
+use sp_consensus::block_validation::DefaultBlockAnnounceValidator;
+use sp_runtime::traits::Header as HeaderT;
+
+use crate::LightClient;
+
+/// Build the network of the minimal node and spawn its worker.
+///
+/// The node syncs headers and justifications only. Requests of other peers for blocks, state or
+/// proofs are refused, as the node has none of them to serve.
+pub fn build_light_network(
+	config: &Configuration,
+	client: Arc<LightClient>,
+	import_queue: impl ImportQueue<PBlock> + 'static,
+	spawn_handle: SpawnTaskHandle,
+) -> Result<Arc<NetworkService<PBlock, PHash>>, sc_service::Error> {
+	let protocol_id = config.protocol_id();
+	let fork_id = config.chain_spec.fork_id();
+	let genesis_hash = client.block_hash(0).ok().flatten().expect("Genesis block exists; qed");
+
+	let block_request_protocol_config =
+		sc_network_sync::block_request_handler::generate_protocol_config(
+			&protocol_id,
+			genesis_hash,
+			fork_id,
+		);
+	let state_request_protocol_config =
+		sc_network_sync::state_request_handler::generate_protocol_config(
+			&protocol_id,
+			genesis_hash,
+			fork_id,
+		);
+	let light_client_request_protocol_config =
+		sc_network_light::light_client_requests::generate_protocol_config(
+			&protocol_id,
+			genesis_hash,
+			fork_id,
+		);
+
+	let chain_sync = ChainSync::new(
+		SyncMode::Light,
+		client.clone(),
+		Box::new(DefaultBlockAnnounceValidator),
+		config.network.max_parallel_downloads,
+		None,
+	)?;
+
+	let network_params = Params {
+		role: config.role.clone(),
+		executor: {
+			let spawn_handle = spawn_handle.clone();
+			Some(Box::new(move |fut| {
+				spawn_handle.spawn("libp2p-node", Some("networking"), fut);
+			}))
+		},
+		transactions_handler_executor: {
+			let spawn_handle = spawn_handle.clone();
+			Box::new(move |fut| {
+				spawn_handle.spawn("network-transactions-handler", Some("networking"), fut);
+			})
+		},
+		network_config: config.network.clone(),
+		chain: client.clone(),
+		transaction_pool: Arc::new(EmptyTransactionPool),
+		import_queue: Box::new(import_queue),
+		protocol_id,
+		fork_id: fork_id.map(ToOwned::to_owned),
+		chain_sync: Box::new(chain_sync),
+		metrics_registry: config.prometheus_registry().cloned(),
+		block_request_protocol_config,
+		state_request_protocol_config,
+		warp_sync_protocol_config: None,
+		light_client_request_protocol_config,
+	};
+
+	let network_worker = NetworkWorker::new(network_params)?;
+	let network = network_worker.service().clone();
+
+	spawn_handle.spawn_blocking(
+		"network-worker",
+		Some("networking"),
+		network_future(network_worker, client),
+	);
+
+	Ok(network)
+}
+
+/// Drive the network worker and keep it informed about the blocks the client imports and
+/// finalizes.
+async fn network_future(
+	mut network: NetworkWorker<PBlock, PHash, LightClient>,
+	client: Arc<LightClient>,
+) {
+	let mut imported_blocks_stream = client.import_notification_stream().fuse();
+	let mut finality_notification_stream = client.finality_notification_stream().fuse();
+
+	loop {
+		futures::select! {
+			notification = imported_blocks_stream.next() => {
+				let notification = match notification {
+					Some(notification) => notification,
+					None => return,
+				};
+
+				if notification.is_new_best {
+					network.service().new_best_block_imported(
+						notification.hash,
+						*notification.header.number(),
+					);
+				}
+			},
+			notification = finality_notification_stream.select_next_some() => {
+				network.on_block_finalized(notification.hash, notification.header);
+			},
+			_ = (&mut network).fuse() => return,
+		}
+	}
+}
@@ -0,0 +1,353 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Forests.
+
+// Forests is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Forests is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Forests.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fetch relay chain state that is not available locally from peers.
+//!
+//! Full nodes serve storage read and execution proofs over the light client request protocol.
+//! The proofs are checked against the state root of the locally synced header before they are
+//! used, so the peers answering the requests don't need to be trusted.
+
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use forests_primitives_core::relay_chain::{Block as PBlock, Hash as PHash, Header as PHeader};
+use forests_relay_chain_interface::{RelayChainError, RelayChainResult};
+use futures::StreamExt;
+use parity_scale_codec::Decode;
+use parking_lot::Mutex;
+use prost::Message;
+use sc_client_api::StorageProof;
+use sc_network::{Event, IfDisconnected, NetworkService, PeerId};
+use sc_network_common::service::{NetworkEventStream, NetworkRequest};
+use sp_core::traits::SpawnNamed;
+use sp_runtime::traits::{BlakeTwo256, Header as HeaderT};
+use sp_state_machine::StorageValue;
+
+const LOG_TARGET: &str = "minimal-relay-chain-node";
+
+/// The number of peers a read proof is requested from before giving up.
+const MAX_REQUEST_ATTEMPTS: usize = 3;
+
+/// Messages of the light client request protocol, see `light.v1.proto` in Substrate.
+///
+/// Only the call and storage read requests are needed, so the other messages are left out.
+mod schema {
+	#[derive(Clone, PartialEq, prost::Message)]
+	pub struct Request {
+		#[prost(oneof = "request::Request", tags = "1, 2")]
+		pub request: Option<request::Request>,
+	}
+
+	pub mod request {
+		#[derive(Clone, PartialEq, prost::Oneof)]
+		pub enum Request {
+			#[prost(message, tag = "1")]
+			RemoteCallRequest(super::RemoteCallRequest),
+			#[prost(message, tag = "2")]
+			RemoteReadRequest(super::RemoteReadRequest),
+		}
+	}
+
+	#[derive(Clone, PartialEq, prost::Message)]
+	pub struct Response {
+		#[prost(oneof = "response::Response", tags = "1, 2")]
+		pub response: Option<response::Response>,
+	}
+
+	pub mod response {
+		#[derive(Clone, PartialEq, prost::Oneof)]
+		pub enum Response {
+			#[prost(message, tag = "1")]
+			RemoteCallResponse(super::RemoteCallResponse),
+			#[prost(message, tag = "2")]
+			RemoteReadResponse(super::RemoteReadResponse),
+		}
+	}
+
+	/// Runtime call request for the given block.
+	#[derive(Clone, PartialEq, prost::Message)]
+	pub struct RemoteCallRequest {
+		/// SCALE encoded hash of the block to execute the call at.
+		#[prost(bytes = "vec", tag = "2")]
+		pub block: Vec<u8>,
+		/// The runtime method to call.
+		#[prost(string, tag = "3")]
+		pub method: String,
+		/// SCALE encoded arguments of the call.
+		#[prost(bytes = "vec", tag = "4")]
+		pub data: Vec<u8>,
+	}
+
+	/// Runtime call response.
+	#[derive(Clone, PartialEq, prost::Message)]
+	pub struct RemoteCallResponse {
+		/// SCALE encoded proof of all the state the call accessed.
+		#[prost(bytes = "vec", tag = "2")]
+		pub proof: Vec<u8>,
+	}
+
+	/// Storage read request for the given block.
+	#[derive(Clone, PartialEq, prost::Message)]
+	pub struct RemoteReadRequest {
+		/// SCALE encoded hash of the block to read the storage at.
+		#[prost(bytes = "vec", tag = "2")]
+		pub block: Vec<u8>,
+		/// The storage keys to read.
+		#[prost(bytes = "vec", repeated, tag = "3")]
+		pub keys: Vec<Vec<u8>>,
+	}
+
+	/// Storage read response.
+	#[derive(Clone, PartialEq, prost::Message)]
+	pub struct RemoteReadResponse {
+		/// SCALE encoded storage proof of the requested keys.
+		#[prost(bytes = "vec", tag = "2")]
+		pub proof: Vec<u8>,
+	}
+}
+
+/// Name of the light client request protocol of the chain with the given genesis hash.
+pub fn protocol_name(genesis_hash: &PHash, fork_id: Option<&str>) -> String {
+	match fork_id {
+		Some(fork_id) => format!("/{}/{}/light/2", hex::encode(genesis_hash), fork_id),
+		None => format!("/{}/light/2", hex::encode(genesis_hash)),
+	}
+}
+
+/// Reads relay chain state from peers, verifying every answer against the local headers.
+#[derive(Clone)]
+pub struct RemoteStateReader {
+	network: Arc<NetworkService<PBlock, PHash>>,
+	protocol_name: Cow<'static, str>,
+	peers: Arc<Mutex<Vec<PeerId>>>,
+}
+
+impl RemoteStateReader {
+	/// Create a new reader and spawn the task that keeps track of the connected peers.
+	pub fn new(
+		network: Arc<NetworkService<PBlock, PHash>>,
+		protocol_name: String,
+		spawner: impl SpawnNamed,
+	) -> Self {
+		let peers = Arc::new(Mutex::new(Vec::new()));
+
+		let mut events = network.event_stream("minimal-node-remote-state");
+		let tracked_peers = peers.clone();
+		spawner.spawn(
+			"minimal-node-peer-tracker",
+			Some("minimal-relay-chain-node"),
+			Box::pin(async move {
+				while let Some(event) = events.next().await {
+					match event {
+						Event::SyncConnected { remote } => tracked_peers.lock().push(remote),
+						Event::SyncDisconnected { remote } =>
+							tracked_peers.lock().retain(|peer| *peer != remote),
+						_ => {},
+					}
+				}
+			}),
+		);
+
+		Self { network, protocol_name: protocol_name.into(), peers }
+	}
+
+	/// Fetch a proof of the values of `keys` at `header` from a peer.
+	///
+	/// The proof is only returned if it is valid for the state root of `header`.
+	pub async fn read_proof(
+		&self,
+		header: &PHeader,
+		keys: &[Vec<u8>],
+	) -> RelayChainResult<StorageProof> {
+		let request = schema::request::Request::RemoteReadRequest(schema::RemoteReadRequest {
+			block: header.hash().as_ref().to_vec(),
+			keys: keys.to_vec(),
+		});
+
+		self.request_proof(request, |proof| {
+			check_read_proof(header.state_root, proof.clone(), keys).map(|_| ())
+		})
+		.await
+	}
+
+	/// Fetch a proof of the state accessed by calling the runtime `method` at `header` from a
+	/// peer.
+	///
+	/// The proof is not checked here. Executing the call on top of it, as done by
+	/// [`LightRuntimeClient`](crate::LightRuntimeClient), fails if the proof is incomplete or
+	/// does not match the state root of `header`.
+	pub async fn execution_proof(
+		&self,
+		header: &PHeader,
+		method: &str,
+		call_data: &[u8],
+	) -> RelayChainResult<StorageProof> {
+		let request = schema::request::Request::RemoteCallRequest(schema::RemoteCallRequest {
+			block: header.hash().as_ref().to_vec(),
+			method: method.into(),
+			data: call_data.to_vec(),
+		});
+
+		self.request_proof(request, |_| Ok(())).await
+	}
+
+	/// Send `request` to up to [`MAX_REQUEST_ATTEMPTS`] peers until one of them answers with a
+	/// proof that passes `check`.
+	async fn request_proof(
+		&self,
+		request: schema::request::Request,
+		check: impl Fn(&StorageProof) -> RelayChainResult<()>,
+	) -> RelayChainResult<StorageProof> {
+		let peers = self.peers.lock().clone();
+		if peers.is_empty() {
+			return Err(RelayChainError::GenericError(
+				"No peers to fetch relay chain state from.".into(),
+			))
+		}
+
+		let request = schema::Request { request: Some(request) }.encode_to_vec();
+
+		let mut last_error = None;
+		for peer in peers.into_iter().take(MAX_REQUEST_ATTEMPTS) {
+			let result = match self
+				.network
+				.request(
+					peer,
+					self.protocol_name.clone(),
+					request.clone(),
+					IfDisconnected::ImmediateError,
+				)
+				.await
+			{
+				Ok(response) =>
+					decode_response(&response).and_then(|proof| check(&proof).map(|_| proof)),
+				Err(err) => Err(RelayChainError::GenericError(err.to_string())),
+			};
+
+			match result {
+				Ok(proof) => return Ok(proof),
+				Err(err) => {
+					tracing::debug!(target: LOG_TARGET, %peer, %err, "Unable to fetch proof from peer.");
+					last_error = Some(err);
+				},
+			}
+		}
+
+		Err(last_error.expect("Requested the proof from at least one peer; qed"))
+	}
+
+	/// Fetch the value of `key` at `header` from a peer.
+	pub async fn storage(
+		&self,
+		header: &PHeader,
+		key: &[u8],
+	) -> RelayChainResult<Option<StorageValue>> {
+		let keys = [key.to_vec()];
+		let proof = self.read_proof(header, &keys).await?;
+		let mut values = check_read_proof(header.state_root, proof, &keys)?;
+		Ok(values.remove(key).flatten())
+	}
+}
+
+/// Decode the proof of a light client response.
+fn decode_response(response: &[u8]) -> RelayChainResult<StorageProof> {
+	let response = schema::Response::decode(response)
+		.map_err(|err| RelayChainError::GenericError(err.to_string()))?;
+
+	match response.response {
+		Some(schema::response::Response::RemoteCallResponse(response)) =>
+			Ok(StorageProof::decode(&mut &response.proof[..])?),
+		Some(schema::response::Response::RemoteReadResponse(response)) =>
+			Ok(StorageProof::decode(&mut &response.proof[..])?),
+		None => Err(RelayChainError::GenericError("Unexpected light client response.".into())),
+	}
+}
+
+/// Check that `proof` proves the values of all `keys` in the state with the given root.
+fn check_read_proof(
+	state_root: PHash,
+	proof: StorageProof,
+	keys: &[Vec<u8>],
+) -> RelayChainResult<HashMap<Vec<u8>, Option<StorageValue>>> {
+	sp_state_machine::read_proof_check::<BlakeTwo256, _>(state_root, proof, keys)
+		.map_err(RelayChainError::StateMachineError)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use parity_scale_codec::Encode;
+	use sp_core::storage::StateVersion;
+	use sp_state_machine::{prove_read, InMemoryBackend};
+
+	fn backend() -> InMemoryBackend<BlakeTwo256> {
+		InMemoryBackend::<BlakeTwo256>::from((
+			vec![(
+				None,
+				vec![
+					(b"alice".to_vec(), Some(b"1".to_vec())),
+					(b"bob".to_vec(), Some(b"2".to_vec())),
+				],
+			)],
+			StateVersion::V1,
+		))
+	}
+
+	#[test]
+	fn read_proof_is_checked_against_the_state_root() {
+		let backend = backend();
+		let root = *backend.root();
+		let keys = vec![b"alice".to_vec(), b"charlie".to_vec()];
+		let proof = prove_read(backend, &keys).unwrap();
+
+		let values = check_read_proof(root, proof.clone(), &keys).unwrap();
+		assert_eq!(values[&b"alice".to_vec()], Some(b"1".to_vec()));
+		assert_eq!(values[&b"charlie".to_vec()], None);
+
+		// A proof for another state is rejected.
+		assert!(check_read_proof(PHash::repeat_byte(1), proof, &keys).is_err());
+	}
+
+	#[test]
+	fn response_roundtrip() {
+		let proof = prove_read(backend(), &[b"bob"]).unwrap();
+		let read_response = schema::Response {
+			response: Some(schema::response::Response::RemoteReadResponse(
+				schema::RemoteReadResponse { proof: proof.encode() },
+			)),
+		}
+		.encode_to_vec();
+		let call_response = schema::Response {
+			response: Some(schema::response::Response::RemoteCallResponse(
+				schema::RemoteCallResponse { proof: proof.encode() },
+			)),
+		}
+		.encode_to_vec();
+
+		assert_eq!(decode_response(&read_response).unwrap(), proof);
+		assert_eq!(decode_response(&call_response).unwrap(), proof);
+		assert!(decode_response(&schema::Response { response: None }.encode_to_vec()).is_err());
+	}
+
+	#[test]
+	fn protocol_name_includes_fork_id() {
+		let genesis = PHash::repeat_byte(0xab);
+		let hex_genesis = hex::encode(genesis);
+
+		assert_eq!(protocol_name(&genesis, None), format!("/{}/light/2", hex_genesis));
+		assert_eq!(protocol_name(&genesis, Some("fork")), format!("/{}/fork/light/2", hex_genesis));
+	}
+}
@@ -0,0 +1,387 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Forests.
+
+// Forests is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Forests is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Forests.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Relay chain runtime calls for the header-only client.
+//!
+//! The minimal node does not keep any relay chain state besides the genesis state. Runtime calls
+//! are executed locally on top of execution proofs fetched from peers. A proof that is missing
+//! some of the state the call accesses, or that does not match the state root of the synced
+//! header, makes the execution fail, so the peers answering the requests don't need to be
+//! trusted.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_trait::async_trait;
+use forests_primitives_core::relay_chain::{
+	runtime_api::ParachainHost,
+	v2::{
+		AuthorityDiscoveryId, Block as PBlock, BlockNumber, CandidateCommitments, CandidateEvent,
+		CandidateHash, CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo,
+		Hash as PHash, Header as PHeader, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
+		OccupiedCoreAssumption, OldV1SessionInfo, PersistedValidationData, PvfCheckStatement,
+		ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidationCode, ValidationCodeHash,
+		ValidatorId, ValidatorIndex, ValidatorSignature,
+	},
+};
+use forests_relay_chain_interface::{RelayChainError, RelayChainResult};
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::StorageProof;
+use sc_executor::NativeElseWasmExecutor;
+use sc_service::SpawnTaskHandle;
+use selendra_overseer::RuntimeApiSubsystemClient;
+use selendra_service::SelendraExecutorDispatch;
+use sp_api::{ApiError, ApiRef, BlockId, NativeOrEncoded, ProvideRuntimeApi, RuntimeApiInfo};
+use sp_authority_discovery::AuthorityDiscoveryApi;
+use sp_blockchain::{BlockStatus, HeaderBackend, Info};
+use sp_consensus_babe::Epoch;
+use sp_runtime::traits::BlakeTwo256;
+use sp_state_machine::{backend::BackendRuntimeCode, OverlayedChanges};
+
+use crate::{remote_state::RemoteStateReader, LightClient};
+
+/// Executes relay chain runtime calls on top of execution proofs fetched from peers.
+///
+/// Implements [`RuntimeApiSubsystemClient`], so it can serve the runtime API subsystem and the
+/// collator in place of a full client.
+#[derive(Clone)]
+pub struct LightRuntimeClient {
+	client: Arc<LightClient>,
+	remote_state: RemoteStateReader,
+	executor: NativeElseWasmExecutor<SelendraExecutorDispatch>,
+	spawner: SpawnTaskHandle,
+}
+
+impl LightRuntimeClient {
+	/// Create a new instance of [`LightRuntimeClient`].
+	pub fn new(
+		client: Arc<LightClient>,
+		remote_state: RemoteStateReader,
+		executor: NativeElseWasmExecutor<SelendraExecutorDispatch>,
+		spawner: SpawnTaskHandle,
+	) -> Self {
+		Self { client, remote_state, executor, spawner }
+	}
+
+	/// Call the runtime `method` with the given arguments at the relay chain block `at`.
+	pub async fn call<R: Decode>(
+		&self,
+		at: PHash,
+		method: &'static str,
+		args: impl Encode,
+	) -> Result<R, ApiError> {
+		let header = self.header(at).map_err(|err| ApiError::Application(Box::new(err)))?;
+		let call_data = args.encode();
+
+		let proof = self
+			.remote_state
+			.execution_proof(&header, method, &call_data)
+			.await
+			.map_err(|err| ApiError::Application(Box::new(err)))?;
+		let result = check_execution_proof(
+			&self.executor,
+			self.spawner.clone(),
+			header.state_root,
+			proof,
+			method,
+			&call_data,
+		)
+		.map_err(|err| ApiError::Application(Box::new(err)))?;
+
+		R::decode(&mut &result[..])
+			.map_err(|error| ApiError::FailedToDecodeReturnValue { function: method, error })
+	}
+
+	fn header(&self, at: PHash) -> RelayChainResult<PHeader> {
+		self.client.header(&BlockId::Hash(at))?.ok_or_else(|| {
+			RelayChainError::GenericError(format!("Unknown relay chain block {}", at))
+		})
+	}
+}
+
+/// Execute the runtime `method` on top of `proof`.
+///
+/// Fails if the proof does not contain all the state accessed by the call, including the
+/// runtime code, in the state with the given root.
+fn check_execution_proof(
+	executor: &NativeElseWasmExecutor<SelendraExecutorDispatch>,
+	spawner: SpawnTaskHandle,
+	state_root: PHash,
+	proof: StorageProof,
+	method: &str,
+	call_data: &[u8],
+) -> RelayChainResult<Vec<u8>> {
+	let backend = sp_state_machine::create_proof_check_backend::<BlakeTwo256>(state_root, proof)
+		.map_err(RelayChainError::StateMachineError)?;
+	let runtime_code = BackendRuntimeCode::new(&backend)
+		.runtime_code()
+		.map_err(|err| RelayChainError::GenericError(err.into()))?;
+
+	sp_state_machine::execution_proof_check_on_trie_backend(
+		&backend,
+		&mut OverlayedChanges::default(),
+		executor,
+		spawner,
+		method,
+		call_data,
+		&runtime_code,
+	)
+	.map_err(RelayChainError::StateMachineError)
+}
+
+#[async_trait]
+impl RuntimeApiSubsystemClient for LightRuntimeClient {
+	async fn api_version_parachain_host(&self, at: PHash) -> Result<Option<u32>, ApiError> {
+		let version: sp_api::RuntimeVersion = self.call(at, "Core_version", ()).await?;
+		Ok(version.api_version(&<dyn ParachainHost<PBlock>>::ID))
+	}
+
+	async fn validators(&self, at: PHash) -> Result<Vec<ValidatorId>, ApiError> {
+		self.call(at, "ParachainHost_validators", ()).await
+	}
+
+	async fn validator_groups(
+		&self,
+		at: PHash,
+	) -> Result<(Vec<Vec<ValidatorIndex>>, GroupRotationInfo<BlockNumber>), ApiError> {
+		self.call(at, "ParachainHost_validator_groups", ()).await
+	}
+
+	async fn availability_cores(
+		&self,
+		at: PHash,
+	) -> Result<Vec<CoreState<PHash, BlockNumber>>, ApiError> {
+		self.call(at, "ParachainHost_availability_cores", ()).await
+	}
+
+	async fn persisted_validation_data(
+		&self,
+		at: PHash,
+		para_id: ParaId,
+		assumption: OccupiedCoreAssumption,
+	) -> Result<Option<PersistedValidationData<PHash, BlockNumber>>, ApiError> {
+		self.call(at, "ParachainHost_persisted_validation_data", (para_id, assumption))
+			.await
+	}
+
+	async fn assumed_validation_data(
+		&self,
+		at: PHash,
+		para_id: ParaId,
+		expected_persisted_validation_data_hash: PHash,
+	) -> Result<Option<(PersistedValidationData<PHash, BlockNumber>, ValidationCodeHash)>, ApiError>
+	{
+		self.call(
+			at,
+			"ParachainHost_assumed_validation_data",
+			(para_id, expected_persisted_validation_data_hash),
+		)
+		.await
+	}
+
+	async fn check_validation_outputs(
+		&self,
+		at: PHash,
+		para_id: ParaId,
+		outputs: CandidateCommitments,
+	) -> Result<bool, ApiError> {
+		self.call(at, "ParachainHost_check_validation_outputs", (para_id, outputs))
+			.await
+	}
+
+	async fn session_index_for_child(&self, at: PHash) -> Result<SessionIndex, ApiError> {
+		self.call(at, "ParachainHost_session_index_for_child", ()).await
+	}
+
+	async fn validation_code(
+		&self,
+		at: PHash,
+		para_id: ParaId,
+		assumption: OccupiedCoreAssumption,
+	) -> Result<Option<ValidationCode>, ApiError> {
+		self.call(at, "ParachainHost_validation_code", (para_id, assumption)).await
+	}
+
+	async fn candidate_pending_availability(
+		&self,
+		at: PHash,
+		para_id: ParaId,
+	) -> Result<Option<CommittedCandidateReceipt<PHash>>, ApiError> {
+		self.call(at, "ParachainHost_candidate_pending_availability", para_id).await
+	}
+
+	async fn candidate_events(&self, at: PHash) -> Result<Vec<CandidateEvent<PHash>>, ApiError> {
+		self.call(at, "ParachainHost_candidate_events", ()).await
+	}
+
+	async fn dmq_contents(
+		&self,
+		at: PHash,
+		recipient: ParaId,
+	) -> Result<Vec<InboundDownwardMessage<BlockNumber>>, ApiError> {
+		self.call(at, "ParachainHost_dmq_contents", recipient).await
+	}
+
+	async fn inbound_hrmp_channels_contents(
+		&self,
+		at: PHash,
+		recipient: ParaId,
+	) -> Result<BTreeMap<ParaId, Vec<InboundHrmpMessage<BlockNumber>>>, ApiError> {
+		self.call(at, "ParachainHost_inbound_hrmp_channels_contents", recipient).await
+	}
+
+	async fn validation_code_by_hash(
+		&self,
+		at: PHash,
+		hash: ValidationCodeHash,
+	) -> Result<Option<ValidationCode>, ApiError> {
+		self.call(at, "ParachainHost_validation_code_by_hash", hash).await
+	}
+
+	async fn on_chain_votes(
+		&self,
+		at: PHash,
+	) -> Result<Option<ScrapedOnChainVotes<PHash>>, ApiError> {
+		self.call(at, "ParachainHost_on_chain_votes", ()).await
+	}
+
+	async fn session_info(
+		&self,
+		at: PHash,
+		index: SessionIndex,
+	) -> Result<Option<SessionInfo>, ApiError> {
+		self.call(at, "ParachainHost_session_info", index).await
+	}
+
+	async fn session_info_before_version_2(
+		&self,
+		at: PHash,
+		index: SessionIndex,
+	) -> Result<Option<OldV1SessionInfo>, ApiError> {
+		self.call(at, "ParachainHost_session_info", index).await
+	}
+
+	async fn submit_pvf_check_statement(
+		&self,
+		_: PHash,
+		_: PvfCheckStatement,
+		_: ValidatorSignature,
+	) -> Result<(), ApiError> {
+		Err(ApiError::Application(
+			"The minimal relay chain node can not submit PVF check statements.".into(),
+		))
+	}
+
+	async fn pvfs_require_precheck(&self, at: PHash) -> Result<Vec<ValidationCodeHash>, ApiError> {
+		self.call(at, "ParachainHost_pvfs_require_precheck", ()).await
+	}
+
+	async fn validation_code_hash(
+		&self,
+		at: PHash,
+		para_id: ParaId,
+		assumption: OccupiedCoreAssumption,
+	) -> Result<Option<ValidationCodeHash>, ApiError> {
+		self.call(at, "ParachainHost_validation_code_hash", (para_id, assumption)).await
+	}
+
+	async fn staging_get_disputes(
+		&self,
+		at: PHash,
+	) -> Result<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>, ApiError> {
+		self.call(at, "ParachainHost_staging_get_disputes", ()).await
+	}
+
+	async fn current_epoch(&self, at: PHash) -> Result<Epoch, ApiError> {
+		self.call(at, "BabeApi_current_epoch", ()).await
+	}
+
+	async fn authorities(&self, at: PHash) -> Result<Vec<AuthorityDiscoveryId>, ApiError> {
+		self.call(at, "AuthorityDiscoveryApi_authorities", ()).await
+	}
+}
+
+/// Provides the authority discovery runtime API through a [`LightRuntimeClient`].
+///
+/// The authority discovery worker needs a client implementing [`ProvideRuntimeApi`] to learn
+/// which authorities to look up.
+#[derive(Clone)]
+pub struct AuthorityDiscoveryClient(LightRuntimeClient);
+
+impl AuthorityDiscoveryClient {
+	/// Create a new instance of [`AuthorityDiscoveryClient`].
+	pub fn new(runtime_client: LightRuntimeClient) -> Self {
+		Self(runtime_client)
+	}
+}
+
+impl ProvideRuntimeApi<PBlock> for AuthorityDiscoveryClient {
+	type Api = AuthorityDiscoveryRuntimeApi;
+
+	fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+		AuthorityDiscoveryRuntimeApi(self.0.clone()).into()
+	}
+}
+
+impl HeaderBackend<PBlock> for AuthorityDiscoveryClient {
+	fn header(&self, id: BlockId<PBlock>) -> sp_blockchain::Result<Option<PHeader>> {
+		HeaderBackend::header(&*self.0.client, id)
+	}
+
+	fn info(&self) -> Info<PBlock> {
+		HeaderBackend::info(&*self.0.client)
+	}
+
+	fn status(&self, id: BlockId<PBlock>) -> sp_blockchain::Result<BlockStatus> {
+		HeaderBackend::status(&*self.0.client, id)
+	}
+
+	fn number(&self, hash: PHash) -> sp_blockchain::Result<Option<BlockNumber>> {
+		HeaderBackend::number(&*self.0.client, hash)
+	}
+
+	fn hash(&self, number: BlockNumber) -> sp_blockchain::Result<Option<PHash>> {
+		HeaderBackend::hash(&*self.0.client, number)
+	}
+}
+
+/// The runtime API handed out by [`AuthorityDiscoveryClient`].
+pub struct AuthorityDiscoveryRuntimeApi(LightRuntimeClient);
+
+sp_api::mock_impl_runtime_apis! {
+	impl AuthorityDiscoveryApi<PBlock> for AuthorityDiscoveryRuntimeApi {
+		#[advanced]
+		fn authorities(
+			&self,
+			at: &BlockId<PBlock>,
+		) -> Result<NativeOrEncoded<Vec<AuthorityDiscoveryId>>, ApiError> {
+			let hash = match *at {
+				BlockId::Hash(hash) => hash,
+				BlockId::Number(number) => HeaderBackend::hash(&*self.0.client, number)
+					.map_err(|err| ApiError::Application(Box::new(err)))?
+					.ok_or_else(|| {
+						ApiError::Application(
+							format!("Unknown relay chain block #{}", number).into(),
+						)
+					})?,
+			};
+
+			// The worker calls the runtime API from an async context. The proof is fetched by the
+			// network worker running on another task, so blocking here does not deadlock.
+			futures::executor::block_on(RuntimeApiSubsystemClient::authorities(&self.0, hash))
+				.map(Into::into)
+		}
+	}
+}
@@ -35,8 +35,12 @@ use std::pin::Pin;
 pub use url::Url;
 
 mod rpc_client;
+mod rpc_worker;
 pub use rpc_client::{create_client_and_start_worker, RelayChainRpcClient};
 
+#[cfg(test)]
+mod tests;
+
 const TIMEOUT_IN_SECONDS: u64 = 6;
 
 /// RelayChainRpcInterface is used to interact with a full node that is running locally
@@ -23,18 +23,11 @@ use forests_primitives_core::{
 	InboundDownwardMessage, ParaId, PersistedValidationData,
 };
 use forests_relay_chain_interface::{RelayChainError, RelayChainResult};
-use futures::{
-	channel::mpsc::{Receiver, Sender},
-	StreamExt,
-};
+use futures::channel::mpsc::Receiver;
 use jsonrpsee::{
-	core::{
-		client::{Client as JsonRpcClient, ClientT, Subscription, SubscriptionClientT},
-		Error as JsonRpseeError,
-	},
+	core::{client::ClientT, Error as JsonRpseeError},
 	rpc_params,
 	types::ParamsSer,
-	ws_client::WsClientBuilder,
 };
 use parity_scale_codec::{Decode, Encode};
 use sc_client_api::StorageData;
@@ -43,10 +36,9 @@ use selendra_service::TaskManager;
 use sp_core::sp_std::collections::btree_map::BTreeMap;
 use sp_runtime::DeserializeOwned;
 use sp_storage::StorageKey;
-use std::sync::Arc;
-use tokio::sync::mpsc::{
-	channel as tokio_channel, Receiver as TokioReceiver, Sender as TokioSender,
-};
+use tokio::sync::mpsc::Sender as TokioSender;
+
+use crate::rpc_worker::{NotificationRegisterMessage, RpcStreamWorker, SharedWsClient};
 
 pub use url::Url;
 
@@ -57,8 +49,8 @@ const NOTIFICATION_CHANNEL_SIZE_LIMIT: usize = 20;
 /// Client that maps RPC methods and deserializes results
 #[derive(Clone)]
 pub struct RelayChainRpcClient {
-	/// Websocket client to make calls
-	ws_client: Arc<JsonRpcClient>,
+	/// Websocket client of the active endpoint to make calls
+	ws_client: SharedWsClient,
 
 	/// Retry strategy that should be used for requests and subscriptions
 	retry_strategy: ExponentialBackoff,
@@ -67,45 +59,15 @@ pub struct RelayChainRpcClient {
 	to_worker_channel: TokioSender<NotificationRegisterMessage>,
 }
 
-/// Worker messages to register new notification listeners
-#[derive(Clone, Debug)]
-pub enum NotificationRegisterMessage {
-	RegisterBestHeadListener(Sender<PHeader>),
-	RegisterImportListener(Sender<PHeader>),
-	RegisterFinalizationListener(Sender<PHeader>),
-}
-
-/// Worker that should be used in combination with [`RelayChainRpcClient`]. Must be polled to distribute header notifications to listeners.
-struct RpcStreamWorker {
-	// Communication channel with the RPC client
-	client_receiver: TokioReceiver<NotificationRegisterMessage>,
-
-	// Senders to distribute incoming header notifications to
-	imported_header_listeners: Vec<Sender<PHeader>>,
-	finalized_header_listeners: Vec<Sender<PHeader>>,
-	best_header_listeners: Vec<Sender<PHeader>>,
-
-	// Incoming notification subscriptions
-	rpc_imported_header_subscription: Subscription<PHeader>,
-	rpc_finalized_header_subscription: Subscription<PHeader>,
-	rpc_best_header_subscription: Subscription<PHeader>,
-}
-
 /// Entry point to create [`RelayChainRpcClient`] and start a worker that distributes notifications.
+///
+/// The worker connects to the first reachable endpoint of `urls` and fails over to the next one
+/// whenever the active endpoint goes away or reports itself unhealthy.
 pub async fn create_client_and_start_worker(
-	url: Url,
+	urls: Vec<Url>,
 	task_manager: &mut TaskManager,
 ) -> RelayChainResult<RelayChainRpcClient> {
-	tracing::info!(target: LOG_TARGET, url = %url.to_string(), "Initializing RPC Client");
-	let ws_client = WsClientBuilder::default().build(url.as_str()).await?;
-
-	let best_head_stream = RelayChainRpcClient::subscribe_new_best_heads(&ws_client).await?;
-	let finalized_head_stream = RelayChainRpcClient::subscribe_finalized_heads(&ws_client).await?;
-	let imported_head_stream = RelayChainRpcClient::subscribe_imported_heads(&ws_client).await?;
-
-	let (worker, sender) =
-		RpcStreamWorker::new(imported_head_stream, best_head_stream, finalized_head_stream);
-	let client = RelayChainRpcClient::new(ws_client, sender).await?;
+	let (client, worker) = create_client_and_worker(urls).await?;
 
 	task_manager
 		.spawn_essential_handle()
@@ -114,117 +76,20 @@ pub async fn create_client_and_start_worker(
 	Ok(client)
 }
 
-fn handle_event_distribution(
-	event: Option<Result<PHeader, JsonRpseeError>>,
-	senders: &mut Vec<Sender<PHeader>>,
-) -> Result<(), String> {
-	match event {
-		Some(Ok(header)) => {
-			senders.retain_mut(|e| {
-				match e.try_send(header.clone()) {
-					// Receiver has been dropped, remove Sender from list.
-					Err(error) if error.is_disconnected() => false,
-					// Channel is full. This should not happen.
-					// TODO: Improve error handling here
-					// https://github.com/paritytech/forests/issues/1482
-					Err(error) => {
-						tracing::error!(target: LOG_TARGET, ?error, "Event distribution channel has reached its limit. This can lead to missed notifications.");
-						true
-					},
-					_ => true,
-				}
-			});
-			Ok(())
-		},
-		None => Err("RPC Subscription closed.".to_string()),
-		Some(Err(err)) => Err(format!("Error in RPC subscription: {}", err)),
-	}
-}
-
-impl RpcStreamWorker {
-	/// Create new worker. Returns the worker and a channel to register new listeners.
-	fn new(
-		import_sub: Subscription<PHeader>,
-		best_sub: Subscription<PHeader>,
-		finalized_sub: Subscription<PHeader>,
-	) -> (RpcStreamWorker, TokioSender<NotificationRegisterMessage>) {
-		let (tx, rx) = tokio_channel(100);
-		let worker = RpcStreamWorker {
-			client_receiver: rx,
-			imported_header_listeners: Vec::new(),
-			finalized_header_listeners: Vec::new(),
-			best_header_listeners: Vec::new(),
-			rpc_imported_header_subscription: import_sub,
-			rpc_best_header_subscription: best_sub,
-			rpc_finalized_header_subscription: finalized_sub,
-		};
-		(worker, tx)
-	}
-
-	/// Run this worker to drive notification streams.
-	/// The worker does two things:
-	/// 1. Listen for `NotificationRegisterMessage` and register new listeners for the notification streams
-	/// 2. Distribute incoming import, best head and finalization notifications to registered listeners.
-	///    If an error occurs during sending, the receiver has been closed and we remove the sender from the list.
-	pub async fn run(mut self) {
-		let mut import_sub = self.rpc_imported_header_subscription.fuse();
-		let mut best_head_sub = self.rpc_best_header_subscription.fuse();
-		let mut finalized_sub = self.rpc_finalized_header_subscription.fuse();
-		loop {
-			tokio::select! {
-				evt = self.client_receiver.recv() => match evt {
-					Some(NotificationRegisterMessage::RegisterBestHeadListener(tx)) => {
-						self.best_header_listeners.push(tx);
-					},
-					Some(NotificationRegisterMessage::RegisterImportListener(tx)) => {
-						self.imported_header_listeners.push(tx)
-					},
-					Some(NotificationRegisterMessage::RegisterFinalizationListener(tx)) => {
-						self.finalized_header_listeners.push(tx)
-					},
-					None => {
-						tracing::error!(target: LOG_TARGET, "RPC client receiver closed. Stopping RPC Worker.");
-						return;
-					}
-				},
-				import_event = import_sub.next() => {
-					if let Err(err) = handle_event_distribution(import_event, &mut self.imported_header_listeners) {
-						tracing::error!(target: LOG_TARGET, err, "Encountered error while processing imported header notification. Stopping RPC Worker.");
-						return;
-					}
-				},
-				best_header_event = best_head_sub.next() => {
-					if let Err(err) = handle_event_distribution(best_header_event, &mut self.best_header_listeners) {
-						tracing::error!(target: LOG_TARGET, err, "Encountered error while processing best header notification. Stopping RPC Worker.");
-						return;
-					}
-				}
-				finalized_event = finalized_sub.next() => {
-					if let Err(err) = handle_event_distribution(finalized_event, &mut self.finalized_header_listeners) {
-						tracing::error!(target: LOG_TARGET, err, "Encountered error while processing finalized header notification. Stopping RPC Worker.");
-						return;
-					}
-				}
-			}
-		}
-	}
+/// Create [`RelayChainRpcClient`] together with the worker that has to be run alongside it.
+pub(crate) async fn create_client_and_worker(
+	urls: Vec<Url>,
+) -> RelayChainResult<(RelayChainRpcClient, RpcStreamWorker)> {
+	tracing::info!(target: LOG_TARGET, urls = ?urls.iter().map(Url::as_str).collect::<Vec<_>>(), "Initializing RPC Client");
+	let retry_strategy = ExponentialBackoff::default();
+	let (worker, ws_client, sender) =
+		RpcStreamWorker::connect(urls, retry_strategy.clone()).await?;
+	let client = RelayChainRpcClient { to_worker_channel: sender, ws_client, retry_strategy };
+
+	Ok((client, worker))
 }
 
 impl RelayChainRpcClient {
-	/// Initialize new RPC Client.
-	async fn new(
-		ws_client: JsonRpcClient,
-		sender: TokioSender<NotificationRegisterMessage>,
-	) -> RelayChainResult<Self> {
-		let client = RelayChainRpcClient {
-			to_worker_channel: sender,
-			ws_client: Arc::new(ws_client),
-			retry_strategy: ExponentialBackoff::default(),
-		};
-
-		Ok(client)
-	}
-
 	/// Call a call to `state_call` rpc method.
 	pub async fn call_remote_runtime_function<R: Decode>(
 		&self,
@@ -286,8 +151,11 @@ impl RelayChainRpcClient {
 		retry_notify(
 			self.retry_strategy.clone(),
 			|| async {
-				self.ws_client.request(method, params.clone()).await.map_err(|err| match err {
-					JsonRpseeError::Transport(_) =>
+				// The worker replaces the client when it switches endpoints, so fetch the current
+				// one on every attempt.
+				let ws_client = self.ws_client.read().clone();
+				ws_client.request(method, params.clone()).await.map_err(|err| match err {
+					JsonRpseeError::Transport(_) | JsonRpseeError::RestartNeeded(_) =>
 						backoff::Error::Transient { err, retry_after: None },
 					_ => backoff::Error::Permanent(err),
 				})
@@ -431,36 +299,4 @@ impl RelayChainRpcClient {
 		)?;
 		Ok(rx)
 	}
-
-	async fn subscribe_imported_heads(
-		ws_client: &JsonRpcClient,
-	) -> Result<Subscription<PHeader>, RelayChainError> {
-		Ok(ws_client
-			.subscribe::<PHeader>("chain_subscribeAllHeads", None, "chain_unsubscribeAllHeads")
-			.await?)
-	}
-
-	async fn subscribe_finalized_heads(
-		ws_client: &JsonRpcClient,
-	) -> Result<Subscription<PHeader>, RelayChainError> {
-		Ok(ws_client
-			.subscribe::<PHeader>(
-				"chain_subscribeFinalizedHeads",
-				None,
-				"chain_unsubscribeFinalizedHeads",
-			)
-			.await?)
-	}
-
-	async fn subscribe_new_best_heads(
-		ws_client: &JsonRpcClient,
-	) -> Result<Subscription<PHeader>, RelayChainError> {
-		Ok(ws_client
-			.subscribe::<PHeader>(
-				"chain_subscribeNewHeads",
-				None,
-				"chain_unsubscribeFinalizedHeads",
-			)
-			.await?)
-	}
 }
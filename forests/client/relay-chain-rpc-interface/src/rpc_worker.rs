@@ -0,0 +1,424 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Forests.
+
+// Forests is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Forests is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Forests.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Worker that keeps the connection to one of several relay chain RPC endpoints alive and
+//! distributes the head notifications of the active endpoint to the registered listeners.
+
+use backoff::{backoff::Backoff, ExponentialBackoff};
+use forests_primitives_core::relay_chain::{Hash as PHash, Header as PHeader};
+use forests_relay_chain_interface::{RelayChainError, RelayChainResult};
+use futures::{channel::mpsc::Sender, StreamExt};
+use futures_timer::Delay;
+use jsonrpsee::{
+	core::{
+		client::{Client as JsonRpcClient, ClientT, Subscription, SubscriptionClientT},
+		Error as JsonRpseeError,
+	},
+	rpc_params,
+	ws_client::WsClientBuilder,
+};
+use parking_lot::RwLock;
+use sc_rpc_api::system::Health;
+use sp_runtime::traits::Header as HeaderT;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc::{
+	channel as tokio_channel, Receiver as TokioReceiver, Sender as TokioSender,
+};
+use url::Url;
+
+const LOG_TARGET: &str = "relay-chain-rpc-worker";
+
+/// Time between two health checks of the active endpoint.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Maximum number of headers fetched to close the gap in a head stream after a reconnection.
+const MAX_BACKFILL_HEADERS: usize = 256;
+
+/// Websocket client of the active endpoint. The worker swaps it out on every reconnection, so
+/// requests issued afterwards go to the new endpoint.
+pub(crate) type SharedWsClient = Arc<RwLock<Arc<JsonRpcClient>>>;
+
+/// Worker messages to register new notification listeners
+#[derive(Clone, Debug)]
+pub enum NotificationRegisterMessage {
+	RegisterBestHeadListener(Sender<PHeader>),
+	RegisterImportListener(Sender<PHeader>),
+	RegisterFinalizationListener(Sender<PHeader>),
+}
+
+/// The relay chain RPC endpoints to connect to, in order of preference.
+pub(crate) struct Endpoints {
+	urls: Vec<Url>,
+	active: usize,
+}
+
+impl Endpoints {
+	/// Create a new list of endpoints. Fails if `urls` is empty.
+	pub(crate) fn new(urls: Vec<Url>) -> RelayChainResult<Self> {
+		if urls.is_empty() {
+			return Err(RelayChainError::GenericError(
+				"At least one relay chain RPC URL is required.".to_string(),
+			))
+		}
+		Ok(Self { urls, active: 0 })
+	}
+
+	/// The endpoint that is currently in use.
+	pub(crate) fn active(&self) -> &Url {
+		&self.urls[self.active]
+	}
+
+	/// Switch to the next endpoint, wrapping around at the end of the list.
+	pub(crate) fn rotate(&mut self) -> &Url {
+		self.active = (self.active + 1) % self.urls.len();
+		self.active()
+	}
+
+	/// The number of configured endpoints.
+	pub(crate) fn len(&self) -> usize {
+		self.urls.len()
+	}
+}
+
+/// Head subscriptions opened on a single endpoint.
+struct HeadSubscriptions {
+	imported: Subscription<PHeader>,
+	best: Subscription<PHeader>,
+	finalized: Subscription<PHeader>,
+}
+
+/// Listeners of a head stream together with what is needed to resume the stream without gaps
+/// on another endpoint.
+#[derive(Default)]
+struct HeadStream {
+	listeners: Vec<Sender<PHeader>>,
+	/// Number and hash of the highest header distributed so far.
+	last_seen: Option<(u32, PHash)>,
+	/// Headers distributed while backfilling that the fresh subscription may still repeat.
+	backfilled: Vec<PHash>,
+}
+
+impl HeadStream {
+	/// Handle an event of the underlying subscription. Returns an error if the subscription
+	/// is no longer usable.
+	fn handle_event(
+		&mut self,
+		event: Option<Result<PHeader, JsonRpseeError>>,
+	) -> Result<(), String> {
+		match event {
+			Some(Ok(header)) => {
+				let hash = header.hash();
+				if let Some(pos) = self.backfilled.iter().position(|h| *h == hash) {
+					self.backfilled.remove(pos);
+					return Ok(())
+				}
+				self.backfilled.clear();
+				self.distribute(header);
+				Ok(())
+			},
+			None => Err("RPC Subscription closed.".to_string()),
+			Some(Err(err)) => Err(format!("Error in RPC subscription: {}", err)),
+		}
+	}
+
+	fn distribute(&mut self, header: PHeader) {
+		if self.last_seen.map_or(true, |(number, _)| header.number >= number) {
+			self.last_seen = Some((header.number, header.hash()));
+		}
+
+		self.listeners.retain_mut(|e| {
+			match e.try_send(header.clone()) {
+				// Receiver has been dropped, remove Sender from list.
+				Err(error) if error.is_disconnected() => false,
+				// Channel is full. This should not happen.
+				// TODO: Improve error handling here
+				// https://github.com/paritytech/forests/issues/1482
+				Err(error) => {
+					tracing::error!(target: LOG_TARGET, ?error, "Event distribution channel has reached its limit. This can lead to missed notifications.");
+					true
+				},
+				_ => true,
+			}
+		});
+	}
+
+	/// Distribute the headers between the last seen one and `tip`, fetching them from `client`.
+	///
+	/// If `only_tip` is set, only `tip` itself is distributed, which is enough for streams where
+	/// listeners only care about the latest value.
+	async fn backfill(&mut self, client: &JsonRpcClient, tip: PHeader, only_tip: bool) {
+		let (last_number, last_hash) = match self.last_seen {
+			Some(last_seen) => last_seen,
+			// Nothing was distributed yet, so nothing can have been missed.
+			None => return,
+		};
+		if tip.hash() == last_hash {
+			return
+		}
+
+		let mut missing = Vec::new();
+		let mut current = tip;
+		while current.number > last_number && missing.len() < MAX_BACKFILL_HEADERS {
+			let parent_hash = current.parent_hash;
+			missing.push(current);
+			if only_tip {
+				break
+			}
+			current = match get_header(client, Some(parent_hash)).await {
+				Ok(Some(header)) => header,
+				Ok(None) => break,
+				Err(err) => {
+					tracing::warn!(target: LOG_TARGET, %err, "Unable to fetch header for backfilling.");
+					break
+				},
+			};
+		}
+
+		self.backfilled = missing.iter().map(|header| header.hash()).collect();
+		for header in missing.into_iter().rev() {
+			self.distribute(header);
+		}
+	}
+}
+
+/// Worker that should be used in combination with [`RelayChainRpcClient`]. Must be polled to
+/// distribute header notifications to listeners.
+///
+/// When the active endpoint closes the head subscriptions or reports itself unhealthy, the
+/// worker fails over to the next endpoint and fills the gaps in the head streams.
+///
+/// [`RelayChainRpcClient`]: crate::RelayChainRpcClient
+pub(crate) struct RpcStreamWorker {
+	// Communication channel with the RPC client
+	client_receiver: TokioReceiver<NotificationRegisterMessage>,
+
+	endpoints: Endpoints,
+	ws_client: SharedWsClient,
+	subscriptions: HeadSubscriptions,
+	retry_strategy: ExponentialBackoff,
+
+	imported: HeadStream,
+	best: HeadStream,
+	finalized: HeadStream,
+}
+
+impl RpcStreamWorker {
+	/// Connect to the first reachable endpoint. Returns the worker, the shared client of the
+	/// active endpoint and a channel to register new listeners.
+	pub(crate) async fn connect(
+		urls: Vec<Url>,
+		retry_strategy: ExponentialBackoff,
+	) -> RelayChainResult<(Self, SharedWsClient, TokioSender<NotificationRegisterMessage>)> {
+		let mut endpoints = Endpoints::new(urls)?;
+
+		let mut last_error = None;
+		for _ in 0..endpoints.len() {
+			match connect(endpoints.active()).await {
+				Ok((ws_client, subscriptions)) => {
+					let ws_client = Arc::new(RwLock::new(Arc::new(ws_client)));
+					let (tx, rx) = tokio_channel(100);
+					let worker = RpcStreamWorker {
+						client_receiver: rx,
+						endpoints,
+						ws_client: ws_client.clone(),
+						subscriptions,
+						retry_strategy,
+						imported: Default::default(),
+						best: Default::default(),
+						finalized: Default::default(),
+					};
+					return Ok((worker, ws_client, tx))
+				},
+				Err(err) => {
+					tracing::warn!(target: LOG_TARGET, url = %endpoints.active(), %err, "Unable to connect to relay chain RPC endpoint.");
+					last_error = Some(err);
+					endpoints.rotate();
+				},
+			}
+		}
+
+		Err(last_error.expect("There is at least one endpoint; qed"))
+	}
+
+	/// Run this worker to drive notification streams.
+	/// The worker does three things:
+	/// 1. Listen for `NotificationRegisterMessage` and register new listeners for the notification streams
+	/// 2. Distribute incoming import, best head and finalization notifications to registered listeners.
+	///    If an error occurs during sending, the receiver has been closed and we remove the sender from the list.
+	/// 3. Fail over to the next endpoint if the subscriptions of the active one end or it becomes unhealthy.
+	pub async fn run(mut self) {
+		let mut health_check = Delay::new(HEALTH_CHECK_INTERVAL);
+		loop {
+			let reconnect = tokio::select! {
+				evt = self.client_receiver.recv() => match evt {
+					Some(NotificationRegisterMessage::RegisterBestHeadListener(tx)) => {
+						self.best.listeners.push(tx);
+						false
+					},
+					Some(NotificationRegisterMessage::RegisterImportListener(tx)) => {
+						self.imported.listeners.push(tx);
+						false
+					},
+					Some(NotificationRegisterMessage::RegisterFinalizationListener(tx)) => {
+						self.finalized.listeners.push(tx);
+						false
+					},
+					None => {
+						tracing::error!(target: LOG_TARGET, "RPC client receiver closed. Stopping RPC Worker.");
+						return;
+					}
+				},
+				import_event = self.subscriptions.imported.next() => {
+					self.imported.handle_event(import_event).map_err(|err| {
+						tracing::warn!(target: LOG_TARGET, err, "Encountered error while processing imported header notification.");
+					}).is_err()
+				},
+				best_header_event = self.subscriptions.best.next() => {
+					self.best.handle_event(best_header_event).map_err(|err| {
+						tracing::warn!(target: LOG_TARGET, err, "Encountered error while processing best header notification.");
+					}).is_err()
+				},
+				finalized_event = self.subscriptions.finalized.next() => {
+					self.finalized.handle_event(finalized_event).map_err(|err| {
+						tracing::warn!(target: LOG_TARGET, err, "Encountered error while processing finalized header notification.");
+					}).is_err()
+				},
+				_ = &mut health_check => {
+					health_check = Delay::new(HEALTH_CHECK_INTERVAL);
+					self.endpoints.len() > 1 && !self.is_healthy().await
+				},
+			};
+
+			if reconnect {
+				if let Err(err) = self.reconnect().await {
+					tracing::error!(target: LOG_TARGET, %err, "Unable to reconnect to any relay chain RPC endpoint. Stopping RPC Worker.");
+					return
+				}
+			}
+		}
+	}
+
+	/// Whether the active endpoint is synced and connected to the network.
+	async fn is_healthy(&self) -> bool {
+		let client = self.ws_client.read().clone();
+		match client.request::<Health>("system_health", None).await {
+			Ok(health) => !health.is_syncing && (health.peers > 0 || !health.should_have_peers),
+			Err(err) => {
+				tracing::debug!(target: LOG_TARGET, %err, "Health check failed.");
+				false
+			},
+		}
+	}
+
+	/// Connect to the next reachable endpoint, backing off between attempts, and fill the gaps
+	/// in the head streams from it.
+	async fn reconnect(&mut self) -> RelayChainResult<()> {
+		let mut backoff = self.retry_strategy.clone();
+		backoff.reset();
+
+		loop {
+			let url = self.endpoints.rotate().clone();
+			match connect(&url).await {
+				Ok((ws_client, subscriptions)) => {
+					tracing::info!(target: LOG_TARGET, url = %url, "Switched relay chain RPC endpoint.");
+					if let Err(err) = self.backfill(&ws_client).await {
+						tracing::warn!(target: LOG_TARGET, %err, "Unable to backfill head streams.");
+					}
+					*self.ws_client.write() = Arc::new(ws_client);
+					self.subscriptions = subscriptions;
+					return Ok(())
+				},
+				Err(err) => {
+					tracing::warn!(target: LOG_TARGET, url = %url, %err, "Unable to connect to relay chain RPC endpoint.");
+					match backoff.next_backoff() {
+						Some(delay) => Delay::new(delay).await,
+						None => return Err(err),
+					}
+				},
+			}
+		}
+	}
+
+	/// Distribute the headers the listeners missed while the worker was switching endpoints.
+	async fn backfill(&mut self, client: &JsonRpcClient) -> RelayChainResult<()> {
+		let best = get_header(client, None).await?.ok_or_else(|| {
+			RelayChainError::GenericError("Endpoint did not return a best header.".to_string())
+		})?;
+		let finalized_hash = client
+			.request::<PHash>("chain_getFinalizedHead", None)
+			.await
+			.map_err(|err| RelayChainError::RpcCallError("chain_getFinalizedHead".into(), err))?;
+		let finalized = get_header(client, Some(finalized_hash)).await?.ok_or_else(|| {
+			RelayChainError::GenericError("Endpoint did not return the finalized header.".into())
+		})?;
+
+		self.imported.backfill(client, best.clone(), false).await;
+		self.best.backfill(client, best, true).await;
+		self.finalized.backfill(client, finalized, false).await;
+		Ok(())
+	}
+}
+
+/// Connect to `url` and open the head subscriptions.
+async fn connect(url: &Url) -> RelayChainResult<(JsonRpcClient, HeadSubscriptions)> {
+	tracing::info!(target: LOG_TARGET, url = %url.to_string(), "Connecting to relay chain RPC endpoint");
+	let ws_client = WsClientBuilder::default().build(url.as_str()).await?;
+
+	let subscriptions = HeadSubscriptions {
+		best: subscribe_new_best_heads(&ws_client).await?,
+		finalized: subscribe_finalized_heads(&ws_client).await?,
+		imported: subscribe_imported_heads(&ws_client).await?,
+	};
+	Ok((ws_client, subscriptions))
+}
+
+async fn get_header(
+	client: &JsonRpcClient,
+	hash: Option<PHash>,
+) -> RelayChainResult<Option<PHeader>> {
+	client
+		.request("chain_getHeader", rpc_params!(hash))
+		.await
+		.map_err(|err| RelayChainError::RpcCallError("chain_getHeader".into(), err))
+}
+
+async fn subscribe_imported_heads(
+	ws_client: &JsonRpcClient,
+) -> Result<Subscription<PHeader>, RelayChainError> {
+	Ok(ws_client
+		.subscribe::<PHeader>("chain_subscribeAllHeads", None, "chain_unsubscribeAllHeads")
+		.await?)
+}
+
+async fn subscribe_finalized_heads(
+	ws_client: &JsonRpcClient,
+) -> Result<Subscription<PHeader>, RelayChainError> {
+	Ok(ws_client
+		.subscribe::<PHeader>(
+			"chain_subscribeFinalizedHeads",
+			None,
+			"chain_unsubscribeFinalizedHeads",
+		)
+		.await?)
+}
+
+async fn subscribe_new_best_heads(
+	ws_client: &JsonRpcClient,
+) -> Result<Subscription<PHeader>, RelayChainError> {
+	Ok(ws_client
+		.subscribe::<PHeader>("chain_subscribeNewHeads", None, "chain_unsubscribeNewHeads")
+		.await?)
+}
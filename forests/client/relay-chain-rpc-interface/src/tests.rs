@@ -0,0 +1,206 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Forests.
+
+// Forests is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Forests is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Forests.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{rpc_client::create_client_and_worker, rpc_worker::Endpoints, Url};
+use forests_primitives_core::relay_chain::{Hash as PHash, Header as PHeader};
+use futures::{
+	channel::mpsc::{unbounded, UnboundedSender},
+	StreamExt,
+};
+use futures_timer::Delay;
+use jsonrpsee::{
+	ws_server::{WsServerBuilder, WsServerHandle},
+	RpcModule,
+};
+use parking_lot::Mutex;
+use sc_rpc_api::system::Health;
+use sp_runtime::traits::Header as HeaderT;
+use std::{sync::Arc, time::Duration};
+
+/// Relay chain state shared by all mock endpoints.
+#[derive(Default)]
+struct MockChain {
+	headers: Mutex<Vec<PHeader>>,
+}
+
+impl MockChain {
+	/// Import a new header on top of the current best one.
+	fn import(&self) -> PHeader {
+		let mut headers = self.headers.lock();
+		let (number, parent_hash) =
+			headers.last().map_or((0, PHash::default()), |h| (h.number + 1, h.hash()));
+		let header = PHeader {
+			parent_hash,
+			number,
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		};
+		headers.push(header.clone());
+		header
+	}
+
+	fn best(&self) -> PHeader {
+		self.headers.lock().last().cloned().expect("Chain has a genesis header; qed")
+	}
+
+	fn header(&self, hash: PHash) -> Option<PHeader> {
+		self.headers.lock().iter().find(|h| h.hash() == hash).cloned()
+	}
+}
+
+/// Subscribers of one kind of head notification.
+type Subscribers = Arc<Mutex<Vec<UnboundedSender<PHeader>>>>;
+
+/// A mock relay chain RPC endpoint serving headers of a [`MockChain`].
+struct MockEndpoint {
+	url: Url,
+	handle: WsServerHandle,
+	imported: Subscribers,
+	best: Subscribers,
+}
+
+impl MockEndpoint {
+	async fn start(chain: Arc<MockChain>) -> Self {
+		let server = WsServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+		let url = Url::parse(&format!("ws://{}", server.local_addr().unwrap())).unwrap();
+
+		let imported = Subscribers::default();
+		let best = Subscribers::default();
+		let mut module = RpcModule::new(chain);
+		module
+			.register_method("chain_getHeader", |params, chain| {
+				let hash = params.sequence().optional_next::<PHash>()?;
+				Ok(match hash {
+					Some(hash) => chain.header(hash),
+					None => Some(chain.best()),
+				})
+			})
+			.unwrap();
+		module
+			.register_method(
+				"chain_getFinalizedHead",
+				|_, chain| Ok(chain.headers.lock()[0].hash()),
+			)
+			.unwrap();
+		module
+			.register_method("system_health", |_, _| {
+				Ok(Health { peers: 1, is_syncing: false, should_have_peers: true })
+			})
+			.unwrap();
+		for (subscribe, notification, unsubscribe, subscribers) in [
+			(
+				"chain_subscribeAllHeads",
+				"chain_allHead",
+				"chain_unsubscribeAllHeads",
+				imported.clone(),
+			),
+			("chain_subscribeNewHeads", "chain_newHead", "chain_unsubscribeNewHeads", best.clone()),
+			(
+				"chain_subscribeFinalizedHeads",
+				"chain_finalizedHead",
+				"chain_unsubscribeFinalizedHeads",
+				Subscribers::default(),
+			),
+		] {
+			module
+				.register_subscription(
+					subscribe,
+					notification,
+					unsubscribe,
+					move |_, pending, _| {
+						let (tx, rx) = unbounded();
+						subscribers.lock().push(tx);
+						if let Some(mut sink) = pending.accept() {
+							tokio::spawn(async move {
+								sink.pipe_from_stream(rx).await;
+							});
+						}
+					},
+				)
+				.unwrap();
+		}
+
+		let handle = server.start(module).unwrap();
+		MockEndpoint { url, handle, imported, best }
+	}
+
+	/// Notify the subscribers of this endpoint about a new imported best header.
+	fn announce(&self, header: &PHeader) {
+		for subscribers in [&self.imported, &self.best] {
+			subscribers.lock().retain(|tx| tx.unbounded_send(header.clone()).is_ok());
+		}
+	}
+}
+
+#[test]
+fn endpoints_rotate_in_order() {
+	let urls: Vec<Url> = ["ws://a:9944", "ws://b:9944", "ws://c:9944"]
+		.iter()
+		.map(|url| Url::parse(url).unwrap())
+		.collect();
+	let mut endpoints = Endpoints::new(urls.clone()).unwrap();
+
+	assert_eq!(endpoints.len(), 3);
+	assert_eq!(endpoints.active(), &urls[0]);
+	assert_eq!(endpoints.rotate(), &urls[1]);
+	assert_eq!(endpoints.rotate(), &urls[2]);
+	assert_eq!(endpoints.rotate(), &urls[0]);
+
+	assert!(Endpoints::new(Vec::new()).is_err());
+}
+
+#[tokio::test]
+async fn fails_over_to_next_endpoint_and_backfills_missed_heads() {
+	let chain = Arc::new(MockChain::default());
+	chain.import();
+
+	let endpoint_a = MockEndpoint::start(chain.clone()).await;
+	let endpoint_b = MockEndpoint::start(chain.clone()).await;
+
+	let (client, worker) =
+		create_client_and_worker(vec![endpoint_a.url.clone(), endpoint_b.url.clone()])
+			.await
+			.unwrap();
+	tokio::spawn(worker.run());
+
+	let mut imported = client.get_imported_heads_stream().await.unwrap();
+	// Give the worker a chance to register the listener.
+	Delay::new(Duration::from_millis(100)).await;
+
+	for _ in 1..=5 {
+		let header = chain.import();
+		endpoint_a.announce(&header);
+		assert_eq!(imported.next().await.unwrap(), header);
+	}
+
+	// Blocks 6 to 8 are imported while the active endpoint goes away.
+	let missed = (6..=8).map(|_| chain.import()).collect::<Vec<_>>();
+	endpoint_a.handle.stop().unwrap();
+
+	for header in &missed {
+		assert_eq!(&imported.next().await.unwrap(), header);
+	}
+
+	// Notifications of the new endpoint for blocks that were backfilled are not repeated.
+	endpoint_b.announce(missed.last().unwrap());
+	let header = chain.import();
+	endpoint_b.announce(&header);
+	assert_eq!(imported.next().await.unwrap(), header);
+
+	// Requests are served by the new endpoint.
+	assert_eq!(client.chain_get_header(None).await.unwrap(), Some(header));
+}
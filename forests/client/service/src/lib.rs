@@ -21,7 +21,10 @@
 use forests_client_cli::CollatorOptions;
 use forests_client_consensus_common::ParachainConsensus;
 use forests_primitives_core::{CollectCollationInfo, ParaId};
-use forests_relay_chain_interface::RelayChainInterface;
+use forests_relay_chain_inprocess_interface::build_inprocess_relay_chain;
+use forests_relay_chain_interface::{RelayChainInterface, RelayChainResult};
+use forests_relay_chain_minimal_node::build_minimal_relay_chain_node;
+use forests_relay_chain_rpc_interface::{create_client_and_start_worker, RelayChainRpcInterface};
 use sc_client_api::{
 	Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, UsageProvider,
 };
@@ -30,6 +33,7 @@ use sc_consensus::{
 	BlockImport,
 };
 use sc_service::{Configuration, TaskManager};
+use sc_telemetry::TelemetryWorkerHandle;
 use selendra_primitives::v2::CollatorPair;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
@@ -40,6 +44,7 @@ use sp_runtime::{
 	Justifications,
 };
 use std::{sync::Arc, time::Duration};
+use substrate_prometheus_endpoint::Registry;
 
 /// Parameters given to [`start_collator`].
 pub struct StartCollatorParams<'a, Block: BlockT, BS, Client, RCInterface, Spawner, IQ> {
@@ -54,6 +59,9 @@ pub struct StartCollatorParams<'a, Block: BlockT, BS, Client, RCInterface, Spawn
 	pub import_queue: IQ,
 	pub collator_key: CollatorPair,
 	pub relay_chain_slot_duration: Duration,
+	/// How many blocks behind the relay chain tip the collator builds on.
+	pub relay_parent_offset: u32,
+	pub prometheus_registry: Option<&'a Registry>,
 }
 
 /// Start a collator node for a parachain.
@@ -73,7 +81,9 @@ pub async fn start_collator<'a, Block, BS, Client, Backend, RCInterface, Spawner
 		parachain_consensus,
 		import_queue,
 		collator_key,
-		relay_chain_slot_duration,
+		relay_chain_slot_duration: _,
+		relay_parent_offset,
+		prometheus_registry,
 	}: StartCollatorParams<'a, Block, BS, Client, RCInterface, Spawner, IQ>,
 ) -> sc_service::error::Result<()>
 where
@@ -113,13 +123,14 @@ where
 
 	let pov_recovery = forests_client_pov_recovery::PoVRecovery::new(
 		overseer_handle.clone(),
-		// We want that collators wait at maximum the relay chain slot duration before starting
-		// to recover blocks.
-		forests_client_pov_recovery::RecoveryDelay::WithMax { max: relay_chain_slot_duration },
+		// Collators need the blocks to build on top of them, so they recover missing blocks right
+		// away.
+		forests_client_pov_recovery::RecoveryStrategy::Eager,
 		client.clone(),
 		import_queue,
 		relay_chain_interface.clone(),
 		para_id,
+		prometheus_registry,
 	);
 
 	task_manager
@@ -135,6 +146,7 @@ where
 		para_id,
 		key: collator_key,
 		parachain_consensus,
+		relay_parent_offset,
 	})
 	.await;
 
@@ -151,6 +163,7 @@ pub struct StartFullNodeParams<'a, Block: BlockT, Client, RCInterface, IQ> {
 	pub relay_chain_slot_duration: Duration,
 	pub import_queue: IQ,
 	pub collator_options: CollatorOptions,
+	pub prometheus_registry: Option<&'a Registry>,
 }
 
 /// Start a full node for a parachain.
@@ -167,6 +180,7 @@ pub fn start_full_node<Block, Client, Backend, RCInterface, IQ>(
 		relay_chain_slot_duration,
 		import_queue,
 		collator_options,
+		prometheus_registry,
 	}: StartFullNodeParams<Block, Client, RCInterface, IQ>,
 ) -> sc_service::error::Result<()>
 where
@@ -197,7 +211,7 @@ where
 	// PoV Recovery is currently not supported when we connect to the
 	// relay chain via RPC, so we return early. The node will work, but not be able to recover PoVs from the
 	// relay chain if blocks are not announced on parachain.
-	if collator_options.relay_chain_rpc_url.is_some() {
+	if !collator_options.relay_chain_rpc_urls.is_empty() {
 		return Ok(())
 	}
 
@@ -213,14 +227,17 @@ where
 		// the recovery way before full nodes try to recover a certain block and then share the
 		// block with the network using "the normal way". Full nodes are just the "last resort"
 		// for block recovery.
-		forests_client_pov_recovery::RecoveryDelay::WithMinAndMax {
-			min: relay_chain_slot_duration * 25,
-			max: relay_chain_slot_duration * 50,
-		},
+		forests_client_pov_recovery::RecoveryStrategy::Delayed(
+			forests_client_pov_recovery::RecoveryDelay::WithMinAndMax {
+				min: relay_chain_slot_duration * 25,
+				max: relay_chain_slot_duration * 50,
+			},
+		),
 		client,
 		import_queue,
 		relay_chain_interface,
 		para_id,
+		prometheus_registry,
 	);
 
 	task_manager
@@ -230,6 +247,46 @@ where
 	Ok(())
 }
 
+/// Build the relay chain interface selected by the `collator_options`.
+///
+/// Connects to the given relay chain RPC nodes if there are any. Otherwise embeds either a
+/// minimal or a full relay chain node, built from `selendra_config`.
+///
+/// Returns the interface together with the collator key if the parachain node is an authority
+/// and the embedded relay chain node generated one.
+pub async fn build_relay_chain_interface(
+	selendra_config: Configuration,
+	parachain_config: &Configuration,
+	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
+	task_manager: &mut TaskManager,
+	collator_options: CollatorOptions,
+	hwbench: Option<sc_sysinfo::HwBench>,
+) -> RelayChainResult<(Arc<(dyn RelayChainInterface + 'static)>, Option<CollatorPair>)> {
+	if !collator_options.relay_chain_rpc_urls.is_empty() {
+		let client =
+			create_client_and_start_worker(collator_options.relay_chain_rpc_urls, task_manager)
+				.await?;
+		return Ok((Arc::new(RelayChainRpcInterface::new(client)) as Arc<_>, None))
+	}
+
+	if collator_options.relay_chain_minimal_node {
+		return build_minimal_relay_chain_node(
+			selendra_config,
+			parachain_config,
+			telemetry_worker_handle,
+			task_manager,
+		)
+	}
+
+	build_inprocess_relay_chain(
+		selendra_config,
+		parachain_config,
+		telemetry_worker_handle,
+		task_manager,
+		hwbench,
+	)
+}
+
 /// Prepare the parachain's node configuration
 ///
 /// This function will disable the default announcement of Substrate for the parachain in favor
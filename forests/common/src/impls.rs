@@ -226,6 +226,12 @@ mod tests {
 		type ValidatorIdOf = IdentityCollator;
 		type ValidatorRegistration = IsRegistered;
 		type KickThreshold = ();
+		type KickSlashFraction = ();
+		type MaxDelegatorsPerCandidate = MaxCandidates;
+		type MinDelegation = ();
+		type MaxCommission = ();
+		type UnbondingDelay = ();
+		type MaxUnbondingChunks = MaxCandidates;
 		type WeightInfo = ();
 	}
 
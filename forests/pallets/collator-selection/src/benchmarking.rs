@@ -23,7 +23,8 @@ use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whiteli
 use frame_support::{
 	assert_ok,
 	codec::Decode,
-	traits::{Currency, EnsureOrigin, Get},
+	traits::{Currency, EnsureOrigin, Get, ReservableCurrency},
+	BoundedVec,
 };
 use frame_system::{EventRecord, RawOrigin};
 use pallet_authorship::EventHandler;
@@ -94,6 +95,23 @@ fn register_validators<T: Config + session::Config>(count: u32) -> Vec<T::Accoun
 	validators.into_iter().map(|(who, _)| who).collect()
 }
 
+fn delegation_amount<T: Config>() -> BalanceOf<T> {
+	T::MinDelegation::get().max(T::Currency::minimum_balance())
+}
+
+fn register_delegators<T: Config>(candidate: &T::AccountId, count: u32) {
+	for i in 0..count {
+		let delegator = create_funded_user::<T>("delegator", i, 1000);
+		T::Currency::make_free_balance_be(&delegator, delegation_amount::<T>() * 2u32.into());
+		<CollatorSelection<T>>::delegate(
+			RawOrigin::Signed(delegator).into(),
+			candidate.clone(),
+			delegation_amount::<T>(),
+		)
+		.unwrap();
+	}
+}
+
 fn register_candidates<T: Config>(count: u32) {
 	let candidates = (0..count).map(|c| account("candidate", c, SEED)).collect::<Vec<_>>();
 	assert!(<CandidacyBond<T>>::get() > 0u32.into(), "Bond cannot be zero!");
@@ -148,6 +166,7 @@ benchmarks! {
 	// one.
 	register_as_candidate {
 		let c in 1 .. T::MaxCandidates::get();
+		let d in 0 .. T::MaxDelegatorsPerCandidate::get();
 
 		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
 		<DesiredCandidates<T>>::put(c + 1);
@@ -165,6 +184,11 @@ benchmarks! {
 			Vec::new()
 		).unwrap();
 
+		// delegations left from an earlier candidacy.
+		for i in 0..d {
+			let delegator: T::AccountId = account("delegator", i, SEED);
+			<Delegations<T>>::insert(&caller, &delegator, delegation_amount::<T>());
+		}
 	}: _(RawOrigin::Signed(caller.clone()))
 	verify {
 		assert_last_event::<T>(Event::CandidateAdded{account_id: caller, deposit: bond / 2u32.into()}.into());
@@ -186,22 +210,27 @@ benchmarks! {
 		assert_last_event::<T>(Event::CandidateRemoved{account_id: leaving}.into());
 	}
 
-	// worse case is paying a non-existing candidate account.
+	// worse case is paying a candidate with the maximum number of delegators.
 	note_author {
+		let d in 0 .. T::MaxDelegatorsPerCandidate::get();
+
 		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
 		T::Currency::make_free_balance_be(
 			&<CollatorSelection<T>>::account_id(),
-			T::Currency::minimum_balance() * 4u32.into(),
+			T::Currency::minimum_balance() * 1_000_000u32.into(),
 		);
-		let author = account("author", 0, SEED);
-		let new_block: T::BlockNumber = 10u32.into();
+		register_validators::<T>(1);
+		register_candidates::<T>(1);
+		let author: T::AccountId = account("candidate", 0, SEED);
+		register_delegators::<T>(&author, d);
 
+		let new_block: T::BlockNumber = 10u32.into();
 		frame_system::Pallet::<T>::set_block_number(new_block);
-		assert!(T::Currency::free_balance(&author) == 0u32.into());
+		let balance_before = T::Currency::free_balance(&author);
 	}: {
 		<CollatorSelection<T> as EventHandler<_, _>>::note_author(author.clone())
 	} verify {
-		assert!(T::Currency::free_balance(&author) > 0u32.into());
+		assert!(T::Currency::free_balance(&author) > balance_before);
 		assert_eq!(frame_system::Pallet::<T>::block_number(), new_block);
 	}
 
@@ -211,7 +240,6 @@ benchmarks! {
 		let c in 1 .. T::MaxCandidates::get();
 
 		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
-		<DesiredCandidates<T>>::put(c);
 		frame_system::Pallet::<T>::set_block_number(0u32.into());
 
 		register_validators::<T>(c);
@@ -221,6 +249,17 @@ benchmarks! {
 		let zero_block: T::BlockNumber = 0u32.into();
 		let candidates = <Candidates<T>>::get();
 
+		// every candidate authored in the ending session, so the stale ones can be kicked, and
+		// none is selected for the next one, so the kick timer of all remaining ones is reset.
+		let selected: BoundedVec<_, T::MaxCandidates> = candidates
+			.iter()
+			.map(|c| c.who.clone())
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+		<SelectedCandidates<T>>::put(selected);
+		<DesiredCandidates<T>>::put(0);
+
 		let non_removals = c.saturating_sub(r);
 
 		for i in 0..c {
@@ -253,6 +292,72 @@ benchmarks! {
 			assert!(<Candidates<T>>::get().len() == pre_length);
 		}
 	}
+
+	// worse case is the last delegator slot of a candidate being taken.
+	delegate {
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		register_validators::<T>(1);
+		register_candidates::<T>(1);
+		let candidate: T::AccountId = account("candidate", 0, SEED);
+		register_delegators::<T>(&candidate, T::MaxDelegatorsPerCandidate::get().saturating_sub(1));
+
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = delegation_amount::<T>();
+		T::Currency::make_free_balance_be(&caller, amount * 2u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), candidate.clone(), amount)
+	verify {
+		assert_last_event::<T>(Event::Delegated{delegator: caller, candidate, amount}.into());
+	}
+
+	// worse case is the delegation being removed entirely.
+	undelegate {
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		register_validators::<T>(1);
+		register_candidates::<T>(1);
+		let candidate: T::AccountId = account("candidate", 0, SEED);
+
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = delegation_amount::<T>();
+		T::Currency::make_free_balance_be(&caller, amount * 2u32.into());
+		<CollatorSelection<T>>::delegate(
+			RawOrigin::Signed(caller.clone()).into(),
+			candidate.clone(),
+			amount,
+		)
+		.unwrap();
+	}: _(RawOrigin::Signed(caller.clone()), candidate.clone(), amount)
+	verify {
+		assert_last_event::<T>(Event::Undelegated{delegator: caller, candidate, amount}.into());
+	}
+
+	// worse case is all unbonding chunks being withdrawn.
+	withdraw_unbonded {
+		let caller: T::AccountId = whitelisted_caller();
+		let value = T::Currency::minimum_balance();
+		T::Currency::make_free_balance_be(
+			&caller,
+			value * (T::MaxUnbondingChunks::get() + 1).into(),
+		);
+		T::Currency::reserve(&caller, value * T::MaxUnbondingChunks::get().into()).unwrap();
+		let chunks = (0..T::MaxUnbondingChunks::get())
+			.map(|_| UnlockChunk { value, unlock_at: 0u32.into() })
+			.collect::<Vec<_>>();
+		<Unbonding<T>>::insert(&caller, BoundedVec::try_from(chunks).unwrap());
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(<Unbonding<T>>::get(&caller).is_empty());
+	}
+
+	set_commission {
+		<CandidacyBond<T>>::put(T::Currency::minimum_balance());
+		register_validators::<T>(1);
+		register_candidates::<T>(1);
+		let candidate: T::AccountId = account("candidate", 0, SEED);
+		let commission = sp_runtime::Perbill::from_percent(10);
+	}: _(RawOrigin::Signed(candidate.clone()), commission)
+	verify {
+		assert_last_event::<T>(Event::CommissionScheduled{account_id: candidate, commission}.into());
+	}
 }
 
 impl_benchmark_test_suite!(CollatorSelection, crate::mock::new_test_ext(), crate::mock::Test,);
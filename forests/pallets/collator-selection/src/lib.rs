@@ -27,6 +27,8 @@
 //!
 //! - Collator: A parachain block producer.
 //! - Bond: An amount of `Balance` _reserved_ for candidate registration.
+//! - Delegation: An amount of `Balance` _reserved_ by a token holder in support of a candidate.
+//! - Stake: The bond of a candidate plus all delegations made to it.
 //! - Invulnerable: An account guaranteed to be in the collator set.
 //!
 //! ## Implementation
@@ -38,11 +40,17 @@
 //! 2. [`Candidates`]: these are *candidates to the collation task* and may or may not be elected as
 //!    a final collator.
 //!
-//! The current implementation resolves congestion of [`Candidates`] in a first-come-first-serve
-//! manner.
+//! Up to [`Config::MaxCandidates`] accounts may register as candidates. At every session boundary
+//! the candidates are ordered by stake and the top [`DesiredCandidates`] of them are selected to
+//! collate; ties are resolved in a first-come-first-serve manner.
 //!
-//! Candidates will not be allowed to get kicked or leave_intent if the total number of candidates
-//! fall below MinCandidates. This is for potential disaster recovery scenarios.
+//! Selected candidates that did not produce a block within [`Config::KickThreshold`] are kicked
+//! and lose [`Config::KickSlashFraction`] of their bond to the Pot. Candidates will not be allowed
+//! to get kicked or leave_intent if the total number of candidates fall below MinCandidates. This
+//! is for potential disaster recovery scenarios.
+//!
+//! Bonds of leaving or kicked candidates and withdrawn delegations stay reserved for
+//! [`Config::UnbondingDelay`] blocks before they can be claimed with `withdraw_unbonded`.
 //!
 //! ### Rewards
 //!
@@ -53,6 +61,11 @@
 //! - Half the value of the transaction fees within the block. The other half of the transaction
 //!   fees are deposited into the Pot.
 //!
+//! When the author is a candidate, its commission is taken from the Pot reward first and the rest
+//! is split between the candidate and its delegators pro rata to their stake. Invulnerables keep
+//! the whole Pot reward. Candidates can set a commission of at most `MaxCommission`, and a new
+//! commission only takes effect at the next session change.
+//!
 //! To initiate rewards an ED needs to be transferred to the pot address.
 //!
 
@@ -68,6 +81,7 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migration;
 pub mod weights;
 
 #[frame_support::pallet]
@@ -80,11 +94,11 @@ pub mod pallet {
 		pallet_prelude::*,
 		sp_runtime::{
 			traits::{AccountIdConversion, CheckedSub, Saturating, Zero},
-			RuntimeDebug,
+			Perbill, RuntimeDebug,
 		},
 		traits::{
-			Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, ReservableCurrency,
-			ValidatorRegistration,
+			BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement::KeepAlive,
+			ReservableCurrency, ValidatorRegistration,
 		},
 		weights::DispatchClass,
 		BoundedVec, PalletId,
@@ -137,6 +151,26 @@ pub mod pallet {
 		// Will be kicked if block is not produced in threshold.
 		type KickThreshold: Get<Self::BlockNumber>;
 
+		/// Portion of the bond that is moved to the Pot when a candidate is kicked for not
+		/// producing blocks within `KickThreshold`.
+		type KickSlashFraction: Get<Perbill>;
+
+		/// Maximum number of accounts that can delegate to a single candidate.
+		type MaxDelegatorsPerCandidate: Get<u32>;
+
+		/// Minimum amount an account must have delegated to a candidate.
+		type MinDelegation: Get<BalanceOf<Self>>;
+
+		/// Highest commission a candidate can set.
+		type MaxCommission: Get<Perbill>;
+
+		/// Number of blocks unbonded funds stay reserved before they can be withdrawn.
+		type UnbondingDelay: Get<Self::BlockNumber>;
+
+		/// Maximum number of unbonding chunks an account can have. Further unbonding requests are
+		/// merged into the latest chunk.
+		type MaxUnbondingChunks: Get<u32>;
+
 		/// A stable ID for a validator.
 		type ValidatorId: Member + Parameter;
 
@@ -161,10 +195,36 @@ pub mod pallet {
 		pub who: AccountId,
 		/// Reserved deposit.
 		pub deposit: Balance,
+		/// Total amount delegated to the candidate.
+		pub delegated: Balance,
+		/// Number of accounts delegating to the candidate.
+		pub delegators: u32,
+		/// Share of the block rewards kept by the candidate before splitting them with its
+		/// delegators.
+		pub commission: Perbill,
+	}
+
+	impl<AccountId, Balance: Saturating + Copy> CandidateInfo<AccountId, Balance> {
+		/// The bond of the candidate plus everything delegated to it.
+		pub fn stake(&self) -> Balance {
+			self.deposit.saturating_add(self.delegated)
+		}
+	}
+
+	/// An amount of reserved balance that can be withdrawn once `unlock_at` is reached.
+	#[derive(
+		PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, scale_info::TypeInfo, MaxEncodedLen,
+	)]
+	pub struct UnlockChunk<Balance, BlockNumber> {
+		/// Amount of funds to be unlocked.
+		pub value: Balance,
+		/// Block number at which the funds can be withdrawn.
+		pub unlock_at: BlockNumber,
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(crate::migration::STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The invulnerable, fixed collators.
@@ -173,7 +233,8 @@ pub mod pallet {
 	pub type Invulnerables<T: Config> =
 		StorageValue<_, BoundedVec<T::AccountId, T::MaxInvulnerables>, ValueQuery>;
 
-	/// The (community, limited) collation candidates.
+	/// The (community, limited) collation candidates, ordered by stake as of the last session
+	/// change.
 	#[pallet::storage]
 	#[pallet::getter(fn candidates)]
 	pub type Candidates<T: Config> = StorageValue<
@@ -182,6 +243,42 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The candidates selected into the collator set at the last session change.
+	#[pallet::storage]
+	#[pallet::getter(fn selected_candidates)]
+	pub type SelectedCandidates<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxCandidates>, ValueQuery>;
+
+	/// Amount delegated by an account (second key) to a candidate (first key).
+	#[pallet::storage]
+	#[pallet::getter(fn delegations)]
+	pub type Delegations<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		OptionQuery,
+	>;
+
+	/// Commissions set by candidates that take effect at the next session change.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_commissions)]
+	pub type PendingCommissions<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, Perbill, OptionQuery>;
+
+	/// Funds of an account that are waiting for the unbonding delay to pass.
+	#[pallet::storage]
+	#[pallet::getter(fn unbonding)]
+	pub type Unbonding<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<UnlockChunk<BalanceOf<T>, T::BlockNumber>, T::MaxUnbondingChunks>,
+		ValueQuery,
+	>;
+
 	/// Last block authored by collator.
 	#[pallet::storage]
 	#[pallet::getter(fn last_authored_block)]
@@ -197,7 +294,7 @@ pub mod pallet {
 
 	/// Fixed amount to deposit to become a collator.
 	///
-	/// When a collator calls `leave_intent` they immediately receive the deposit back.
+	/// When a collator calls `leave_intent` the deposit starts unbonding.
 	#[pallet::storage]
 	#[pallet::getter(fn candidacy_bond)]
 	pub type CandidacyBond<T> = StorageValue<_, BalanceOf<T>, ValueQuery>;
@@ -252,6 +349,12 @@ pub mod pallet {
 		NewCandidacyBond { bond_amount: BalanceOf<T> },
 		CandidateAdded { account_id: T::AccountId, deposit: BalanceOf<T> },
 		CandidateRemoved { account_id: T::AccountId },
+		CandidateSlashed { account_id: T::AccountId, amount: BalanceOf<T> },
+		CommissionScheduled { account_id: T::AccountId, commission: Perbill },
+		CommissionSet { account_id: T::AccountId, commission: Perbill },
+		Delegated { delegator: T::AccountId, candidate: T::AccountId, amount: BalanceOf<T> },
+		Undelegated { delegator: T::AccountId, candidate: T::AccountId, amount: BalanceOf<T> },
+		Withdrawn { account_id: T::AccountId, amount: BalanceOf<T> },
 	}
 
 	// Errors inform users that something went wrong.
@@ -277,10 +380,26 @@ pub mod pallet {
 		NoAssociatedValidatorId,
 		/// Validator ID is not yet registered
 		ValidatorNotRegistered,
+		/// Candidate already has the maximum number of delegators
+		TooManyDelegators,
+		/// Delegation would be below `MinDelegation`
+		DelegationTooLow,
+		/// User has no delegation to the candidate
+		NotDelegator,
+		/// User has less delegated to the candidate than requested
+		InsufficientDelegation,
+		/// No unbonded funds are ready to be withdrawn
+		NothingToWithdraw,
+		/// Commission is above `MaxCommission`
+		CommissionTooHigh,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migration::migrate_to_latest::<T>()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -345,13 +464,16 @@ pub mod pallet {
 		/// registered session keys and (b) be able to reserve the `CandidacyBond`.
 		///
 		/// This call is not available to `Invulnerable` collators.
-		#[pallet::weight(T::WeightInfo::register_as_candidate(T::MaxCandidates::get()))]
+		#[pallet::weight(T::WeightInfo::register_as_candidate(
+			T::MaxCandidates::get(),
+			T::MaxDelegatorsPerCandidate::get(),
+		))]
 		pub fn register_as_candidate(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
 			// ensure we are below limit.
 			let length = <Candidates<T>>::decode_len().unwrap_or_default();
-			ensure!((length as u32) < Self::desired_candidates(), Error::<T>::TooManyCandidates);
+			ensure!((length as u32) < T::MaxCandidates::get(), Error::<T>::TooManyCandidates);
 			ensure!(!Self::invulnerables().contains(&who), Error::<T>::AlreadyInvulnerable);
 
 			let validator_key = T::ValidatorIdOf::convert(who.clone())
@@ -362,8 +484,20 @@ pub mod pallet {
 			);
 
 			let deposit = Self::candidacy_bond();
-			// First authored block is current block plus kick threshold to handle session delay
-			let incoming = CandidateInfo { who: who.clone(), deposit };
+			// delegations made before a previous departure are picked up again. There can't be more
+			// than `MaxDelegatorsPerCandidate` of them, unless it was lowered since.
+			let (delegated, delegators) = <Delegations<T>>::iter_prefix_values(&who)
+				.take(T::MaxDelegatorsPerCandidate::get() as usize)
+				.fold((BalanceOf::<T>::zero(), 0u32), |(total, count), amount| {
+					(total.saturating_add(amount), count.saturating_add(1))
+				});
+			let incoming = CandidateInfo {
+				who: who.clone(),
+				deposit,
+				delegated,
+				delegators,
+				commission: Perbill::zero(),
+			};
 
 			let current_count =
 				<Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
@@ -372,6 +506,8 @@ pub mod pallet {
 					} else {
 						T::Currency::reserve(&who, deposit)?;
 						candidates.try_push(incoming).map_err(|_| Error::<T>::TooManyCandidates)?;
+						// First authored block is current block plus kick threshold to handle
+						// session delay
 						<LastAuthoredBlock<T>>::insert(
 							who.clone(),
 							frame_system::Pallet::<T>::block_number() + T::KickThreshold::get(),
@@ -381,11 +517,11 @@ pub mod pallet {
 				})?;
 
 			Self::deposit_event(Event::CandidateAdded { account_id: who, deposit });
-			Ok(Some(T::WeightInfo::register_as_candidate(current_count as u32)).into())
+			Ok(Some(T::WeightInfo::register_as_candidate(current_count as u32, delegators)).into())
 		}
 
 		/// Deregister `origin` as a collator candidate. Note that the collator can only leave on
-		/// session change. The `CandidacyBond` will be unreserved immediately.
+		/// session change. The `CandidacyBond` will be unbonding for `UnbondingDelay` blocks.
 		///
 		/// This call will fail if the total number of candidates would drop below `MinCandidates`.
 		///
@@ -397,10 +533,136 @@ pub mod pallet {
 				Self::candidates().len() as u32 > T::MinCandidates::get(),
 				Error::<T>::TooFewCandidates
 			);
-			let current_count = Self::try_remove_candidate(&who)?;
+			let current_count = Self::try_remove_candidate(&who, false)?;
 
 			Ok(Some(T::WeightInfo::leave_intent(current_count as u32)).into())
 		}
+
+		/// Delegate `amount` to the collator candidate `candidate`, reserving it from `origin`.
+		///
+		/// Delegations count towards the stake the candidate is ranked by and entitle the
+		/// delegator to a share of the rewards of the blocks the candidate authors.
+		#[pallet::weight(T::WeightInfo::delegate())]
+		pub fn delegate(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			<Candidates<T>>::try_mutate(|candidates| -> DispatchResult {
+				let info = candidates
+					.iter_mut()
+					.find(|c| c.who == candidate)
+					.ok_or(Error::<T>::NotCandidate)?;
+				let existing = <Delegations<T>>::get(&candidate, &who);
+				let total = existing.unwrap_or_else(Zero::zero).saturating_add(amount);
+				ensure!(total >= T::MinDelegation::get(), Error::<T>::DelegationTooLow);
+				if existing.is_none() {
+					ensure!(
+						info.delegators < T::MaxDelegatorsPerCandidate::get(),
+						Error::<T>::TooManyDelegators
+					);
+					info.delegators += 1;
+				}
+
+				T::Currency::reserve(&who, amount)?;
+				info.delegated = info.delegated.saturating_add(amount);
+				<Delegations<T>>::insert(&candidate, &who, total);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::Delegated { delegator: who, candidate, amount });
+			Ok(().into())
+		}
+
+		/// Withdraw `amount` of the delegation of `origin` to `candidate`. The funds will be
+		/// unbonding for `UnbondingDelay` blocks.
+		///
+		/// This also works if `candidate` is no longer a candidate.
+		#[pallet::weight(T::WeightInfo::undelegate())]
+		pub fn undelegate(
+			origin: OriginFor<T>,
+			candidate: T::AccountId,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let existing =
+				<Delegations<T>>::get(&candidate, &who).ok_or(Error::<T>::NotDelegator)?;
+			let remaining =
+				existing.checked_sub(&amount).ok_or(Error::<T>::InsufficientDelegation)?;
+			ensure!(
+				remaining.is_zero() || remaining >= T::MinDelegation::get(),
+				Error::<T>::DelegationTooLow
+			);
+
+			<Candidates<T>>::mutate(|candidates| {
+				if let Some(info) = candidates.iter_mut().find(|c| c.who == candidate) {
+					info.delegated = info.delegated.saturating_sub(amount);
+					if remaining.is_zero() {
+						info.delegators = info.delegators.saturating_sub(1);
+					}
+				}
+			});
+			if remaining.is_zero() {
+				<Delegations<T>>::remove(&candidate, &who);
+			} else {
+				<Delegations<T>>::insert(&candidate, &who, remaining);
+			}
+			Self::schedule_unbond(&who, amount);
+
+			Self::deposit_event(Event::Undelegated { delegator: who, candidate, amount });
+			Ok(().into())
+		}
+
+		/// Unreserve all funds of `origin` whose unbonding delay has passed.
+		#[pallet::weight(T::WeightInfo::withdraw_unbonded())]
+		pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			let mut amount = BalanceOf::<T>::zero();
+			<Unbonding<T>>::mutate_exists(&who, |maybe_chunks| {
+				if let Some(chunks) = maybe_chunks {
+					chunks.retain(|chunk| {
+						if chunk.unlock_at <= now {
+							amount = amount.saturating_add(chunk.value);
+							false
+						} else {
+							true
+						}
+					});
+					if chunks.is_empty() {
+						*maybe_chunks = None;
+					}
+				}
+			});
+			ensure!(!amount.is_zero(), Error::<T>::NothingToWithdraw);
+
+			T::Currency::unreserve(&who, amount);
+			Self::deposit_event(Event::Withdrawn { account_id: who, amount });
+			Ok(().into())
+		}
+
+		/// Set the share of the block rewards `origin` keeps before splitting them with its
+		/// delegators.
+		///
+		/// The commission can be at most `MaxCommission` and takes effect at the next session
+		/// change, so the rewards of the current session are split with the commission delegators
+		/// have seen.
+		#[pallet::weight(T::WeightInfo::set_commission())]
+		pub fn set_commission(
+			origin: OriginFor<T>,
+			commission: Perbill,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(commission <= T::MaxCommission::get(), Error::<T>::CommissionTooHigh);
+			ensure!(Self::candidates().iter().any(|c| c.who == who), Error::<T>::NotCandidate);
+			<PendingCommissions<T>>::insert(&who, commission);
+			Self::deposit_event(Event::CommissionScheduled { account_id: who, commission });
+			Ok(().into())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -409,23 +671,109 @@ pub mod pallet {
 			T::PotId::get().into_account_truncating()
 		}
 
-		/// Removes a candidate if they exist and sends them back their deposit
-		fn try_remove_candidate(who: &T::AccountId) -> Result<usize, DispatchError> {
-			let current_count =
-				<Candidates<T>>::try_mutate(|candidates| -> Result<usize, DispatchError> {
+		/// Removes a candidate if they exist and starts unbonding their deposit. If `slash` is
+		/// set, `KickSlashFraction` of the deposit is moved to the pot first.
+		///
+		/// Delegations to the candidate are left in place until their owners withdraw them.
+		fn try_remove_candidate(who: &T::AccountId, slash: bool) -> Result<usize, DispatchError> {
+			let (current_count, deposit) = <Candidates<T>>::try_mutate(
+				|candidates| -> Result<(usize, BalanceOf<T>), DispatchError> {
 					let index = candidates
 						.iter()
 						.position(|candidate| candidate.who == *who)
 						.ok_or(Error::<T>::NotCandidate)?;
 					let candidate = candidates.remove(index);
-					T::Currency::unreserve(who, candidate.deposit);
 					<LastAuthoredBlock<T>>::remove(who.clone());
-					Ok(candidates.len())
-				})?;
+					<PendingCommissions<T>>::remove(who);
+					Ok((candidates.len(), candidate.deposit))
+				},
+			)?;
+
+			let mut remaining = deposit;
+			if slash {
+				let amount = T::KickSlashFraction::get() * deposit;
+				let not_slashed = T::Currency::repatriate_reserved(
+					who,
+					&Self::account_id(),
+					amount,
+					BalanceStatus::Free,
+				)
+				.unwrap_or(amount);
+				let slashed = amount.saturating_sub(not_slashed);
+				remaining = remaining.saturating_sub(slashed);
+				if !slashed.is_zero() {
+					Self::deposit_event(Event::CandidateSlashed {
+						account_id: who.clone(),
+						amount: slashed,
+					});
+				}
+			}
+			Self::schedule_unbond(who, remaining);
+
 			Self::deposit_event(Event::CandidateRemoved { account_id: who.clone() });
 			Ok(current_count)
 		}
 
+		/// Keep `value` of the reserved balance of `who` locked for `UnbondingDelay` blocks.
+		///
+		/// If `who` already has `MaxUnbondingChunks` chunks, `value` is merged into the latest one,
+		/// postponing its unlock.
+		fn schedule_unbond(who: &T::AccountId, value: BalanceOf<T>) {
+			if value.is_zero() {
+				return
+			}
+			let unlock_at = frame_system::Pallet::<T>::block_number() + T::UnbondingDelay::get();
+			<Unbonding<T>>::mutate(who, |chunks| {
+				if chunks.try_push(UnlockChunk { value, unlock_at }).is_err() {
+					match chunks.last_mut() {
+						Some(last) => {
+							last.value = last.value.saturating_add(value);
+							last.unlock_at = unlock_at;
+						},
+						// no chunks allowed at all, so there is no delay to honour.
+						None => {
+							T::Currency::unreserve(who, value);
+						},
+					}
+				}
+			});
+		}
+
+		/// Pay `reward` from the pot to `author`, sharing it with the delegators of `author` if it
+		/// is a candidate.
+		///
+		/// Returns the number of delegations that were iterated, whether their share could be paid
+		/// or not.
+		fn distribute_reward(
+			pot: &T::AccountId,
+			author: &T::AccountId,
+			reward: BalanceOf<T>,
+		) -> u32 {
+			let mut author_reward = reward;
+			let mut delegations = 0u32;
+			if let Some(info) = Self::candidates().into_iter().find(|c| c.who == *author) {
+				let stake = info.stake();
+				if !info.delegated.is_zero() && !stake.is_zero() {
+					let shared = reward.saturating_sub(info.commission * reward);
+					for (delegator, amount) in <Delegations<T>>::iter_prefix(author) {
+						delegations += 1;
+						let share = Perbill::from_rational(amount, stake) * shared;
+						if share.is_zero() {
+							continue
+						}
+						// the shares add up to at most `reward`, this should never fail.
+						if T::Currency::transfer(pot, &delegator, share, KeepAlive).is_ok() {
+							author_reward = author_reward.saturating_sub(share);
+						}
+					}
+				}
+			}
+			// `reward` is half of pot account minus ED, this should never fail.
+			let _success = T::Currency::transfer(pot, author, author_reward, KeepAlive);
+			debug_assert!(_success.is_ok());
+			delegations
+		}
+
 		/// Assemble the current set of candidates and invulnerables into the next collator set.
 		///
 		/// This is done on the fly, as frequent as we are told to do so, as the session manager.
@@ -437,24 +785,29 @@ pub mod pallet {
 			collators
 		}
 
-		/// Kicks out candidates that did not produce a block in the kick threshold
-		/// and refund their deposits.
+		/// Kicks out selected candidates that did not produce a block in the kick threshold,
+		/// slashing their deposits and unbonding the rest.
+		///
+		/// Candidates that were not selected at the last session change could not author blocks
+		/// and are never kicked.
 		pub fn kick_stale_candidates(
 			candidates: BoundedVec<CandidateInfo<T::AccountId, BalanceOf<T>>, T::MaxCandidates>,
-		) -> BoundedVec<T::AccountId, T::MaxCandidates> {
+		) -> BoundedVec<CandidateInfo<T::AccountId, BalanceOf<T>>, T::MaxCandidates> {
 			let now = frame_system::Pallet::<T>::block_number();
 			let kick_threshold = T::KickThreshold::get();
+			let selected = Self::selected_candidates();
 			candidates
 				.into_iter()
 				.filter_map(|c| {
 					let last_block = <LastAuthoredBlock<T>>::get(c.who.clone());
 					let since_last = now.saturating_sub(last_block);
 					if since_last < kick_threshold ||
+						!selected.contains(&c.who) ||
 						Self::candidates().len() as u32 <= T::MinCandidates::get()
 					{
-						Some(c.who)
+						Some(c)
 					} else {
-						let outcome = Self::try_remove_candidate(&c.who);
+						let outcome = Self::try_remove_candidate(&c.who, true);
 						if let Err(why) = outcome {
 							log::warn!("Failed to remove candidate {:?}", why);
 							debug_assert!(false, "failed to remove candidate {:?}", why);
@@ -466,6 +819,47 @@ pub mod pallet {
 				.try_into()
 				.expect("filter_map operation can't result in a bounded vec larger than its original; qed")
 		}
+
+		/// Applies the pending commissions, orders `candidates` by stake and selects the top
+		/// `DesiredCandidates` of them.
+		///
+		/// Candidates that are not selected cannot author blocks, so their kick timer is reset.
+		pub fn select_candidates(
+			candidates: BoundedVec<CandidateInfo<T::AccountId, BalanceOf<T>>, T::MaxCandidates>,
+		) -> BoundedVec<T::AccountId, T::MaxCandidates> {
+			let mut candidates = candidates.into_inner();
+			for candidate in candidates.iter_mut() {
+				if let Some(commission) = <PendingCommissions<T>>::take(&candidate.who) {
+					candidate.commission = commission;
+					Self::deposit_event(Event::CommissionSet {
+						account_id: candidate.who.clone(),
+						commission,
+					});
+				}
+			}
+			// stable sort, so equal stakes keep their registration order.
+			candidates.sort_by(|a, b| b.stake().cmp(&a.stake()));
+
+			let desired = Self::desired_candidates() as usize;
+			let grace = frame_system::Pallet::<T>::block_number() + T::KickThreshold::get();
+			for waiting in candidates.iter().skip(desired) {
+				<LastAuthoredBlock<T>>::insert(waiting.who.clone(), grace);
+			}
+			let selected: BoundedVec<_, T::MaxCandidates> = candidates
+				.iter()
+				.take(desired)
+				.map(|c| c.who.clone())
+				.collect::<Vec<_>>()
+				.try_into()
+				.expect("selected candidates are a subset of the candidates; qed");
+
+			<Candidates<T>>::put(
+				BoundedVec::<_, T::MaxCandidates>::try_from(candidates)
+					.expect("sorting does not change the number of candidates; qed"),
+			);
+			<SelectedCandidates<T>>::put(&selected);
+			selected
+		}
 	}
 
 	/// Keep track of number of authored blocks per authority, uncles are counted as well since
@@ -480,13 +874,11 @@ pub mod pallet {
 				.checked_sub(&T::Currency::minimum_balance())
 				.unwrap_or_else(Zero::zero)
 				.div(2u32.into());
-			// `reward` is half of pot account minus ED, this should never fail.
-			let _success = T::Currency::transfer(&pot, &author, reward, KeepAlive);
-			debug_assert!(_success.is_ok());
+			let delegations = Self::distribute_reward(&pot, &author, reward);
 			<LastAuthoredBlock<T>>::insert(author, frame_system::Pallet::<T>::block_number());
 
 			frame_system::Pallet::<T>::register_extra_weight_unchecked(
-				T::WeightInfo::note_author(),
+				T::WeightInfo::note_author(delegations),
 				DispatchClass::Mandatory,
 			);
 		}
@@ -509,7 +901,7 @@ pub mod pallet {
 			let candidates_len_before = candidates.len();
 			let active_candidates = Self::kick_stale_candidates(candidates);
 			let removed = candidates_len_before - active_candidates.len();
-			let result = Self::assemble_collators(active_candidates);
+			let result = Self::assemble_collators(Self::select_candidates(active_candidates));
 
 			frame_system::Pallet::<T>::register_extra_weight_unchecked(
 				T::WeightInfo::new_session(candidates_len_before as u32, removed as u32),
@@ -0,0 +1,135 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A module that is responsible for migration of storage.
+
+use crate::{CandidateInfo, Config, Pallet, Store};
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{traits::Zero, Perbill},
+	traits::{Currency, StorageVersion},
+	weights::Weight,
+};
+use sp_std::prelude::*;
+
+/// The current storage version.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Migrates the pallet storage to the most recent version, checking and setting the
+/// `StorageVersion`.
+pub fn migrate_to_latest<T: Config>() -> Weight {
+	let mut weight = 0;
+
+	if StorageVersion::get::<Pallet<T>>() == 0 {
+		weight += migrate_to_v1::<T>();
+		StorageVersion::new(1).put::<Pallet<T>>();
+	}
+
+	weight
+}
+
+mod v0 {
+	use codec::{Decode, Encode};
+
+	#[derive(Encode, Decode, Debug)]
+	pub struct CandidateInfo<AccountId, Balance> {
+		pub who: AccountId,
+		pub deposit: Balance,
+	}
+}
+
+/// Migrates `Candidates` from v0 (bond only) to v1 (with delegations and commission), and seeds
+/// `SelectedCandidates` with the candidates that were part of the collator set so that kicking
+/// keeps working across the upgrade.
+///
+/// NOTE: Only use this function if you know what you're doing. Default to using
+/// `migrate_to_latest`.
+pub fn migrate_to_v1<T: Config>() -> Weight {
+	let translate =
+		|pre: BoundedVec<v0::CandidateInfo<T::AccountId, BalanceOf<T>>, T::MaxCandidates>| {
+			let candidates = pre
+				.into_iter()
+				.map(|c| CandidateInfo {
+					who: c.who,
+					deposit: c.deposit,
+					delegated: Zero::zero(),
+					delegators: 0,
+					commission: Perbill::zero(),
+				})
+				.collect::<Vec<_>>();
+			BoundedVec::<_, T::MaxCandidates>::try_from(candidates)
+				.expect("translation keeps the number of candidates; qed")
+		};
+
+	match <Pallet<T> as Store>::Candidates::translate(|pre| pre.map(translate)) {
+		Ok(candidates) => {
+			let selected = candidates
+				.unwrap_or_default()
+				.into_iter()
+				.take(<Pallet<T>>::desired_candidates() as usize)
+				.map(|c| c.who)
+				.collect::<Vec<_>>();
+			<Pallet<T> as Store>::SelectedCandidates::put(
+				BoundedVec::<_, T::MaxCandidates>::try_from(selected)
+					.expect("selected candidates are a subset of the candidates; qed"),
+			);
+		},
+		Err(_) => {
+			log::error!(
+				"unexpected error when performing translation of the Candidates type during storage upgrade to v1"
+			);
+		},
+	}
+
+	T::DbWeight::get().reads_writes(2, 2)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::storage::unhashed;
+
+	#[test]
+	fn test_migration_to_v1() {
+		let v0 = vec![
+			v0::CandidateInfo::<u64, u64> { who: 3, deposit: 10 },
+			v0::CandidateInfo::<u64, u64> { who: 4, deposit: 20 },
+			v0::CandidateInfo::<u64, u64> { who: 5, deposit: 10 },
+		];
+
+		new_test_ext().execute_with(|| {
+			// Put the v0 version in the state
+			unhashed::put_raw(&crate::Candidates::<Test>::hashed_key(), &v0.encode());
+
+			migrate_to_v1::<Test>();
+
+			let v1 = crate::Candidates::<Test>::get();
+			assert_eq!(v1.len(), 3);
+			for (old, new) in v0.iter().zip(v1.iter()) {
+				assert_eq!(old.who, new.who);
+				assert_eq!(old.deposit, new.deposit);
+				assert_eq!(new.delegated, 0);
+				assert_eq!(new.delegators, 0);
+				assert_eq!(new.commission, Perbill::zero());
+			}
+			// desired candidates is 2 in the mock.
+			assert_eq!(crate::SelectedCandidates::<Test>::get().to_vec(), vec![3, 4]);
+		});
+	}
+}
@@ -26,7 +26,7 @@ use sp_core::H256;
 use sp_runtime::{
 	testing::{Header, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup, OpaqueKeys},
-	RuntimeAppPublic,
+	Perbill, RuntimeAppPublic,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -189,10 +189,16 @@ ord_parameter_types! {
 
 parameter_types! {
 	pub const PotId: PalletId = PalletId(*b"PotStake");
-	pub const MaxCandidates: u32 = 20;
+	pub static MaxCandidates: u32 = 20;
 	pub const MaxInvulnerables: u32 = 20;
 	pub const MinCandidates: u32 = 1;
 	pub const MaxAuthorities: u32 = 100_000;
+	pub const KickSlashFraction: Perbill = Perbill::from_percent(10);
+	pub const MaxDelegatorsPerCandidate: u32 = 2;
+	pub const MinDelegation: u64 = 5;
+	pub const MaxCommission: Perbill = Perbill::from_percent(50);
+	pub const UnbondingDelay: u64 = 5;
+	pub const MaxUnbondingChunks: u32 = 2;
 }
 
 pub struct IsRegistered;
@@ -215,6 +221,12 @@ impl Config for Test {
 	type MinCandidates = MinCandidates;
 	type MaxInvulnerables = MaxInvulnerables;
 	type KickThreshold = Period;
+	type KickSlashFraction = KickSlashFraction;
+	type MaxDelegatorsPerCandidate = MaxDelegatorsPerCandidate;
+	type MinDelegation = MinDelegation;
+	type MaxCommission = MaxCommission;
+	type UnbondingDelay = UnbondingDelay;
+	type MaxUnbondingChunks = MaxUnbondingChunks;
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = IdentityCollator;
 	type ValidatorRegistration = IsRegistered;
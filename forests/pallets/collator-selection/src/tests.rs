@@ -14,13 +14,18 @@
 // limitations under the License.
 
 use crate as collator_selection;
-use crate::{mock::*, CandidateInfo, Error};
+use crate::{mock::*, CandidateInfo, Error, WeightInfo};
 use frame_support::{
 	assert_noop, assert_ok,
 	traits::{Currency, GenesisBuild, OnInitialize},
+	weights::DispatchClass,
 };
 use pallet_balances::Error as BalancesError;
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::{traits::BadOrigin, Perbill};
+
+fn candidate(who: u64, deposit: u64) -> CandidateInfo<u64, u64> {
+	CandidateInfo { who, deposit, delegated: 0, delegators: 0, commission: Perbill::zero() }
+}
 
 #[test]
 fn basic_setup_works() {
@@ -97,8 +102,8 @@ fn set_candidacy_bond() {
 #[test]
 fn cannot_register_candidate_if_too_many() {
 	new_test_ext().execute_with(|| {
-		// reset desired candidates:
-		<crate::DesiredCandidates<Test>>::put(0);
+		// reset max candidates:
+		MaxCandidates::set(0);
 
 		// can't accept anyone anymore.
 		assert_noop!(
@@ -106,8 +111,8 @@ fn cannot_register_candidate_if_too_many() {
 			Error::<Test>::TooManyCandidates,
 		);
 
-		// reset desired candidates:
-		<crate::DesiredCandidates<Test>>::put(1);
+		// reset max candidates:
+		MaxCandidates::set(1);
 		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(4)));
 
 		// but no more
@@ -162,7 +167,7 @@ fn cannot_register_dupe_candidate() {
 	new_test_ext().execute_with(|| {
 		// can add 3 as candidate
 		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
-		let addition = CandidateInfo { who: 3, deposit: 10 };
+		let addition = candidate(3, 10);
 		assert_eq!(CollatorSelection::candidates(), vec![addition]);
 		assert_eq!(CollatorSelection::last_authored_block(3), 10);
 		assert_eq!(Balances::free_balance(3), 90);
@@ -232,10 +237,20 @@ fn leave_intent() {
 			Error::<Test>::NotCandidate
 		);
 
-		// bond is returned
+		// bond starts unbonding
 		assert_ok!(CollatorSelection::leave_intent(Origin::signed(3)));
-		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Balances::free_balance(3), 90);
 		assert_eq!(CollatorSelection::last_authored_block(3), 0);
+		assert_noop!(
+			CollatorSelection::withdraw_unbonded(Origin::signed(3)),
+			Error::<Test>::NothingToWithdraw
+		);
+
+		// and is returned after the unbonding delay
+		System::set_block_number(5);
+		assert_ok!(CollatorSelection::withdraw_unbonded(Origin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 100);
+		assert!(CollatorSelection::unbonding(3).is_empty());
 	});
 }
 
@@ -251,7 +266,7 @@ fn authorship_event_handler() {
 		// triggers `note_author`
 		Authorship::on_initialize(1);
 
-		let collator = CandidateInfo { who: 4, deposit: 10 };
+		let collator = candidate(4, 10);
 
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 0);
@@ -276,7 +291,7 @@ fn fees_edgecases() {
 		// triggers `note_author`
 		Authorship::on_initialize(1);
 
-		let collator = CandidateInfo { who: 4, deposit: 10 };
+		let collator = candidate(4, 10);
 
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 0);
@@ -338,14 +353,21 @@ fn kick_mechanism() {
 		assert_eq!(CollatorSelection::candidates().len(), 1);
 		// 3 will be kicked after 1 session delay
 		assert_eq!(SessionHandlerCollators::get(), vec![1, 2, 3, 4]);
-		let collator = CandidateInfo { who: 4, deposit: 10 };
+		let collator = candidate(4, 10);
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 20);
+		// kicked collator is slashed and the rest of the bond is unbonding
+		System::assert_has_event(Event::CollatorSelection(crate::Event::CandidateSlashed {
+			account_id: 3,
+			amount: 1,
+		}));
+		assert_eq!(Balances::reserved_balance(3), 9);
 		initialize_to_block(30);
 		// 3 gets kicked after 1 session delay
 		assert_eq!(SessionHandlerCollators::get(), vec![1, 2, 4]);
-		// kicked collator gets funds back
-		assert_eq!(Balances::free_balance(3), 100);
+		// kicked collator gets the rest of the funds back
+		assert_ok!(CollatorSelection::withdraw_unbonded(Origin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 99);
 	});
 }
 
@@ -363,14 +385,241 @@ fn should_not_kick_mechanism_too_few() {
 		assert_eq!(CollatorSelection::candidates().len(), 1);
 		// 3 will be kicked after 1 session delay
 		assert_eq!(SessionHandlerCollators::get(), vec![1, 2, 3, 5]);
-		let collator = CandidateInfo { who: 5, deposit: 10 };
+		let collator = candidate(5, 10);
 		assert_eq!(CollatorSelection::candidates(), vec![collator]);
 		assert_eq!(CollatorSelection::last_authored_block(4), 20);
 		initialize_to_block(30);
 		// 3 gets kicked after 1 session delay
 		assert_eq!(SessionHandlerCollators::get(), vec![1, 2, 5]);
-		// kicked collator gets funds back
-		assert_eq!(Balances::free_balance(3), 100);
+		// kicked collator gets the rest of the funds back
+		assert_ok!(CollatorSelection::withdraw_unbonded(Origin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 99);
+	});
+}
+
+#[test]
+fn delegate_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
+
+		// can only delegate to candidates.
+		assert_noop!(
+			CollatorSelection::delegate(Origin::signed(5), 4, 20),
+			Error::<Test>::NotCandidate
+		);
+		// must delegate at least `MinDelegation`.
+		assert_noop!(
+			CollatorSelection::delegate(Origin::signed(5), 3, 4),
+			Error::<Test>::DelegationTooLow
+		);
+
+		assert_ok!(CollatorSelection::delegate(Origin::signed(5), 3, 20));
+		assert_eq!(Balances::free_balance(5), 80);
+		assert_eq!(CollatorSelection::delegations(3, 5), Some(20));
+
+		// existing delegators can top up with less than `MinDelegation`.
+		assert_ok!(CollatorSelection::delegate(Origin::signed(5), 3, 1));
+		assert_ok!(CollatorSelection::delegate(Origin::signed(1), 3, 10));
+		assert_eq!(
+			CollatorSelection::candidates(),
+			vec![CandidateInfo { delegated: 31, delegators: 2, ..candidate(3, 10) }]
+		);
+		assert_eq!(CollatorSelection::candidates()[0].stake(), 41);
+
+		// at most `MaxDelegatorsPerCandidate` delegators.
+		assert_noop!(
+			CollatorSelection::delegate(Origin::signed(2), 3, 10),
+			Error::<Test>::TooManyDelegators
+		);
+	});
+}
+
+#[test]
+fn undelegate_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
+		assert_ok!(CollatorSelection::delegate(Origin::signed(5), 3, 20));
+
+		assert_noop!(
+			CollatorSelection::undelegate(Origin::signed(4), 3, 10),
+			Error::<Test>::NotDelegator
+		);
+		assert_noop!(
+			CollatorSelection::undelegate(Origin::signed(5), 3, 30),
+			Error::<Test>::InsufficientDelegation
+		);
+		// remaining delegation would be below `MinDelegation`.
+		assert_noop!(
+			CollatorSelection::undelegate(Origin::signed(5), 3, 17),
+			Error::<Test>::DelegationTooLow
+		);
+
+		assert_ok!(CollatorSelection::undelegate(Origin::signed(5), 3, 10));
+		assert_eq!(CollatorSelection::delegations(3, 5), Some(10));
+		assert_eq!(
+			CollatorSelection::candidates(),
+			vec![CandidateInfo { delegated: 10, delegators: 1, ..candidate(3, 10) }]
+		);
+
+		assert_ok!(CollatorSelection::undelegate(Origin::signed(5), 3, 10));
+		assert_eq!(CollatorSelection::delegations(3, 5), None);
+		assert_eq!(CollatorSelection::candidates(), vec![candidate(3, 10)]);
+
+		// funds are unbonding.
+		assert_eq!(Balances::free_balance(5), 80);
+		assert_noop!(
+			CollatorSelection::withdraw_unbonded(Origin::signed(5)),
+			Error::<Test>::NothingToWithdraw
+		);
+		System::set_block_number(5);
+		assert_ok!(CollatorSelection::withdraw_unbonded(Origin::signed(5)));
+		assert_eq!(Balances::free_balance(5), 100);
+	});
+}
+
+#[test]
+fn undelegate_from_former_candidate_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(4)));
+		assert_ok!(CollatorSelection::delegate(Origin::signed(5), 3, 20));
+		assert_ok!(CollatorSelection::leave_intent(Origin::signed(3)));
+
+		// the delegation is picked up again when re-registering.
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
+		assert_eq!(
+			CollatorSelection::candidates()[1],
+			CandidateInfo { delegated: 20, delegators: 1, ..candidate(3, 10) }
+		);
+
+		assert_ok!(CollatorSelection::leave_intent(Origin::signed(3)));
+		assert_ok!(CollatorSelection::undelegate(Origin::signed(5), 3, 20));
+		assert_eq!(CollatorSelection::delegations(3, 5), None);
+	});
+}
+
+#[test]
+fn unbonding_chunks_are_merged() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
+		assert_ok!(CollatorSelection::delegate(Origin::signed(5), 3, 30));
+
+		for block in 1..=3 {
+			System::set_block_number(block);
+			assert_ok!(CollatorSelection::undelegate(Origin::signed(5), 3, 10));
+		}
+		// only `MaxUnbondingChunks` chunks, the last one got postponed.
+		assert_eq!(
+			CollatorSelection::unbonding(5).to_vec(),
+			vec![
+				crate::UnlockChunk { value: 10, unlock_at: 6 },
+				crate::UnlockChunk { value: 20, unlock_at: 8 },
+			]
+		);
+
+		System::set_block_number(7);
+		assert_ok!(CollatorSelection::withdraw_unbonded(Origin::signed(5)));
+		assert_eq!(Balances::free_balance(5), 80);
+
+		System::set_block_number(8);
+		assert_ok!(CollatorSelection::withdraw_unbonded(Origin::signed(5)));
+		assert_eq!(Balances::free_balance(5), 100);
+	});
+}
+
+#[test]
+fn set_commission_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CollatorSelection::set_commission(Origin::signed(3), Perbill::from_percent(10)),
+			Error::<Test>::NotCandidate
+		);
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
+		assert_noop!(
+			CollatorSelection::set_commission(Origin::signed(3), Perbill::from_percent(60)),
+			Error::<Test>::CommissionTooHigh
+		);
+		assert_ok!(CollatorSelection::set_commission(Origin::signed(3), Perbill::from_percent(10)));
+
+		// the commission only changes at the next session change.
+		assert_eq!(CollatorSelection::candidates()[0].commission, Perbill::zero());
+		assert_eq!(CollatorSelection::pending_commissions(3), Some(Perbill::from_percent(10)));
+
+		initialize_to_block(10);
+		assert_eq!(CollatorSelection::candidates()[0].commission, Perbill::from_percent(10));
+		assert_eq!(CollatorSelection::pending_commissions(3), None);
+	});
+}
+
+#[test]
+fn rewards_are_split_with_delegators() {
+	new_test_ext().execute_with(|| {
+		// put 100 in the pot + 5 for ED
+		Balances::make_free_balance_be(&CollatorSelection::account_id(), 105);
+
+		// 4 is the default author.
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(4)));
+		assert_ok!(CollatorSelection::set_commission(Origin::signed(4), Perbill::from_percent(10)));
+		assert_ok!(CollatorSelection::delegate(Origin::signed(5), 4, 40));
+		// apply the commission, as the next session change would.
+		CollatorSelection::select_candidates(CollatorSelection::candidates());
+		// triggers `note_author`
+		Authorship::on_initialize(1);
+
+		// 50 is paid out, 5 of it as commission. 5 holds 4/5 of the stake and gets 4/5 of 45.
+		assert_eq!(Balances::free_balance(5), 60 + 36);
+		assert_eq!(Balances::free_balance(4), 90 + 14);
+		// half + ED stays.
+		assert_eq!(Balances::free_balance(CollatorSelection::account_id()), 55);
+	});
+}
+
+#[test]
+fn note_author_weighs_every_delegation() {
+	new_test_ext().execute_with(|| {
+		// nothing in the pot, so none of the delegators is paid.
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(4)));
+		assert_ok!(CollatorSelection::delegate(Origin::signed(5), 4, 40));
+		let before = *System::block_weight().get(DispatchClass::Mandatory);
+		// triggers `note_author`
+		Authorship::on_initialize(1);
+
+		assert_eq!(
+			*System::block_weight().get(DispatchClass::Mandatory),
+			before + <() as WeightInfo>::note_author(1),
+		);
+	});
+}
+
+#[test]
+fn candidates_are_selected_by_stake() {
+	new_test_ext().execute_with(|| {
+		<crate::DesiredCandidates<Test>>::put(1);
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(Origin::signed(4)));
+		// more candidates than desired can register.
+		assert_eq!(CollatorSelection::candidates().len(), 2);
+
+		// 1 backs 4, which overtakes 3.
+		assert_ok!(CollatorSelection::delegate(Origin::signed(1), 4, 10));
+
+		initialize_to_block(10);
+		assert_eq!(
+			Session::queued_keys().into_iter().map(|(who, _)| who).collect::<Vec<_>>(),
+			vec![1, 2, 4]
+		);
+		assert_eq!(CollatorSelection::selected_candidates().to_vec(), vec![4]);
+		assert_eq!(
+			CollatorSelection::candidates().into_iter().map(|c| c.who).collect::<Vec<_>>(),
+			vec![4, 3]
+		);
+		// 3 is waiting, so its kick timer is reset.
+		assert_eq!(CollatorSelection::last_authored_block(3), 20);
+
+		// 3 never authored but is not kicked as it was not selected.
+		initialize_to_block(20);
+		assert_eq!(CollatorSelection::candidates().len(), 2);
+		assert_eq!(CollatorSelection::last_authored_block(3), 30);
 	});
 }
 
@@ -27,10 +27,14 @@ pub trait WeightInfo {
 	fn set_invulnerables(_b: u32) -> Weight;
 	fn set_desired_candidates() -> Weight;
 	fn set_candidacy_bond() -> Weight;
-	fn register_as_candidate(_c: u32) -> Weight;
+	fn register_as_candidate(_c: u32, _d: u32) -> Weight;
 	fn leave_intent(_c: u32) -> Weight;
-	fn note_author() -> Weight;
+	fn note_author(_d: u32) -> Weight;
 	fn new_session(_c: u32, _r: u32) -> Weight;
+	fn delegate() -> Weight;
+	fn undelegate() -> Weight;
+	fn withdraw_unbonded() -> Weight;
+	fn set_commission() -> Weight;
 }
 
 /// Weights for pallet_collator_selection using the Substrate node and recommended hardware.
@@ -48,11 +52,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	fn set_candidacy_bond() -> Weight {
 		(16_840_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn register_as_candidate(c: u32) -> Weight {
+	// The benchmarked weight, plus a read for every delegation picked up again, which was added
+	// after the benchmark ran.
+	fn register_as_candidate(c: u32, d: u32) -> Weight {
 		(71_196_000 as Weight)
 			// Standard Error: 0
 			.saturating_add((198_000 as Weight).saturating_mul(c as Weight))
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(d as Weight)))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
 	fn leave_intent(c: u32) -> Weight {
@@ -62,21 +69,62 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
-	fn note_author() -> Weight {
+	// Not benchmarked yet, composed from benchmarked numbers: the `note_author` benchmark from
+	// before delegations, one more read of `Candidates`, and for every delegation iterated the
+	// read of the delegation plus a `transfer_keep_alive` from the Selendra `pallet_balances`
+	// weights.
+	fn note_author(d: u32) -> Weight {
 		(71_461_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add((94_572_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(d as Weight)))
 			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(d as Weight)))
 	}
+	// The benchmarked weight, plus the storage accesses of `select_candidates` that were added
+	// after the benchmark ran: `DesiredCandidates` and `SelectedCandidates` are read, `Candidates`
+	// and `SelectedCandidates` are written, and at worst the kick timer and the pending
+	// commission of every candidate are updated. Sorting the candidates happens in memory and is
+	// small next to the per-candidate execution time.
 	fn new_session(r: u32, c: u32) -> Weight {
 		(0 as Weight)
 			// Standard Error: 1_010_000
 			.saturating_add((109_961_000 as Weight).saturating_mul(r as Weight))
 			// Standard Error: 1_010_000
 			.saturating_add((151_952_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
 			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
-			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Not benchmarked yet, the execution time of `register_as_candidate`, which also reserves
+	// funds and updates `Candidates`.
+	fn delegate() -> Weight {
+		(71_196_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Not benchmarked yet, the execution time of `leave_intent`, which also updates
+	// `Candidates` and schedules an unbonding.
+	fn undelegate() -> Weight {
+		(55_336_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Not benchmarked yet, the execution time of `leave_intent`, which also unbonds funds.
+	fn withdraw_unbonded() -> Weight {
+		(55_336_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Not benchmarked yet, the execution time of `register_as_candidate`, which also looks up
+	// and updates `Candidates`.
+	fn set_commission() -> Weight {
+		(71_196_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 }
 
@@ -94,11 +142,14 @@ impl WeightInfo for () {
 	fn set_candidacy_bond() -> Weight {
 		(16_840_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn register_as_candidate(c: u32) -> Weight {
+	// The benchmarked weight, plus a read for every delegation picked up again, which was added
+	// after the benchmark ran.
+	fn register_as_candidate(c: u32, d: u32) -> Weight {
 		(71_196_000 as Weight)
 			// Standard Error: 0
 			.saturating_add((198_000 as Weight).saturating_mul(c as Weight))
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(d as Weight)))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
 	fn leave_intent(c: u32) -> Weight {
@@ -108,20 +159,61 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
-	fn note_author() -> Weight {
+	// Not benchmarked yet, composed from benchmarked numbers: the `note_author` benchmark from
+	// before delegations, one more read of `Candidates`, and for every delegation iterated the
+	// read of the delegation plus a `transfer_keep_alive` from the Selendra `pallet_balances`
+	// weights.
+	fn note_author(d: u32) -> Weight {
 		(71_461_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add((94_572_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(d as Weight)))
 			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(d as Weight)))
 	}
+	// The benchmarked weight, plus the storage accesses of `select_candidates` that were added
+	// after the benchmark ran: `DesiredCandidates` and `SelectedCandidates` are read, `Candidates`
+	// and `SelectedCandidates` are written, and at worst the kick timer and the pending
+	// commission of every candidate are updated. Sorting the candidates happens in memory and is
+	// small next to the per-candidate execution time.
 	fn new_session(r: u32, c: u32) -> Weight {
 		(0 as Weight)
 			// Standard Error: 1_010_000
 			.saturating_add((109_961_000 as Weight).saturating_mul(r as Weight))
 			// Standard Error: 1_010_000
 			.saturating_add((151_952_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
 			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
-			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	// Not benchmarked yet, the execution time of `register_as_candidate`, which also reserves
+	// funds and updates `Candidates`.
+	fn delegate() -> Weight {
+		(71_196_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Not benchmarked yet, the execution time of `leave_intent`, which also updates
+	// `Candidates` and schedules an unbonding.
+	fn undelegate() -> Weight {
+		(55_336_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	// Not benchmarked yet, the execution time of `leave_intent`, which also unbonds funds.
+	fn withdraw_unbonded() -> Weight {
+		(55_336_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Not benchmarked yet, the execution time of `register_as_candidate`, which also looks up
+	// and updates `Candidates`.
+	fn set_commission() -> Weight {
+		(71_196_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
 }
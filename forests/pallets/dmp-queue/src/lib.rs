@@ -17,22 +17,37 @@
 //! Pallet implementing a message queue for downward messages from the relay-chain.
 //! Executes downward messages if there is enough weight available and schedules the rest for later
 //! execution (by `on_idle` or another `handle_dmp_messages` call). Individual overweight messages
-//! are scheduled into a separate queue that is only serviced by explicit extrinsic calls.
+//! are scheduled into a separate queue that is only serviced by explicit extrinsic calls. Overweight
+//! messages that are not serviced within `OverweightExpiry` blocks are dropped, and the assets they
+//! carried that the relay chain is trusted for, by `IsReserve` or `IsTeleporter`, are placed into
+//! the `AssetTrap`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Decode, DecodeLimit, Encode};
-use forests_primitives_core::{relay_chain::BlockNumber as RelayBlockNumber, DmpMessageHandler};
+pub mod migration;
+
+use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
+use forests_primitives_core::{
+	assets_in_transit, relay_chain::BlockNumber as RelayBlockNumber, DmpMessageHandler,
+	OverweightMessage, QueuedMessage,
+};
 use frame_support::{
-	dispatch::Weight, traits::EnsureOrigin, weights::constants::WEIGHT_PER_MILLIS,
+	dispatch::Weight,
+	traits::{EnsureOrigin, Get},
+	weights::constants::WEIGHT_PER_MILLIS,
+	BoundedVec,
 };
 pub use pallet::*;
 use scale_info::TypeInfo;
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	RuntimeDebug,
+};
 use sp_std::{convert::TryFrom, prelude::*};
 use xcm::{latest::prelude::*, VersionedXcm, MAX_XCM_DECODE_DEPTH};
+use xcm_executor::traits::{DropAssets, FilterAssetLocation};
 
-#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
 pub struct ConfigData {
 	/// The maximum amount of weight any individual message may consume. Messages above this weight
 	/// go into the overweight queue and may only be serviced explicitly by the
@@ -49,7 +64,9 @@ impl Default for ConfigData {
 }
 
 /// Information concerning our message pages.
-#[derive(Copy, Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[derive(
+	Copy, Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
 pub struct PageIndexData {
 	/// The lowest used page index.
 	begin_used: PageCounter,
@@ -69,6 +86,29 @@ pub type OverweightIndex = u64;
 /// Index used to identify normal pages.
 pub type PageCounter = u32;
 
+/// A downward message, bounded by `MaxMessageLen`.
+pub type MessageOf<T> = BoundedVec<u8, <T as Config>::MaxMessageLen>;
+
+/// A page of downward messages, bounded by `MaxPageLen`.
+pub type PageOf<T> = BoundedVec<(RelayBlockNumber, MessageOf<T>), <T as Config>::MaxPageLen>;
+
+/// An overweight message along with what is needed to service or expire it.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct OverweightRecord<BlockNumber, Message> {
+	/// The relay chain block number at which the message was sent.
+	pub sent_at: RelayBlockNumber,
+	/// The block number at which the message was put into the overweight queue.
+	pub enqueued_at: BlockNumber,
+	/// The weight the message requires to be executed.
+	pub required_weight: Weight,
+	/// The encoded message.
+	pub message: Message,
+}
+
+/// The overweight record type of a runtime.
+pub type OverweightRecordOf<T> =
+	OverweightRecord<<T as frame_system::Config>::BlockNumber, MessageOf<T>>;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -77,7 +117,7 @@ pub mod pallet {
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
-	#[pallet::without_storage_info]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The module configuration trait.
@@ -90,6 +130,31 @@ pub mod pallet {
 
 		/// Origin which is allowed to execute overweight messages.
 		type ExecuteOverweightOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The maximum encoded length of a queued message. This should be at least the relay
+		/// chain's `max_downward_message_size`; longer messages are dropped instead of queued.
+		#[pallet::constant]
+		type MaxMessageLen: Get<u32>;
+
+		/// The maximum number of messages in a single page of the queue.
+		#[pallet::constant]
+		type MaxPageLen: Get<u32>;
+
+		/// The number of blocks after which an overweight message that was not serviced expires.
+		/// Zero disables expiry.
+		#[pallet::constant]
+		type OverweightExpiry: Get<Self::BlockNumber>;
+
+		/// Where the assets carried by expired overweight messages are dropped.
+		type AssetTrap: DropAssets;
+
+		/// Which reserve-transferred assets are trusted from which origins, as configured for the
+		/// `XcmExecutor`. Only trusted assets of expired overweight messages are trapped.
+		type IsReserve: FilterAssetLocation;
+
+		/// Which teleported assets are trusted from which origins, as configured for the
+		/// `XcmExecutor`. Only trusted assets of expired overweight messages are trapped.
+		type IsTeleporter: FilterAssetLocation;
 	}
 
 	/// The configuration.
@@ -102,13 +167,17 @@ pub mod pallet {
 
 	/// The queue pages.
 	#[pallet::storage]
-	pub(super) type Pages<T> =
-		StorageMap<_, Blake2_128Concat, PageCounter, Vec<(RelayBlockNumber, Vec<u8>)>, ValueQuery>;
+	pub(super) type Pages<T: Config> =
+		StorageMap<_, Blake2_128Concat, PageCounter, PageOf<T>, ValueQuery>;
 
 	/// The overweight messages.
 	#[pallet::storage]
-	pub(super) type Overweight<T> =
-		StorageMap<_, Blake2_128Concat, OverweightIndex, (RelayBlockNumber, Vec<u8>), OptionQuery>;
+	pub(super) type Overweight<T: Config> =
+		StorageMap<_, Blake2_128Concat, OverweightIndex, OverweightRecordOf<T>, OptionQuery>;
+
+	/// The lowest overweight index that has not yet been checked for expiry.
+	#[pallet::storage]
+	pub(super) type OverweightExpiryCursor<T> = StorageValue<_, OverweightIndex, ValueQuery>;
 
 	#[pallet::error]
 	pub enum Error<T> {
@@ -120,9 +189,14 @@ pub mod pallet {
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn on_idle(_now: T::BlockNumber, max_weight: Weight) -> Weight {
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_latest::<T>()
+		}
+
+		fn on_idle(now: T::BlockNumber, max_weight: Weight) -> Weight {
 			// on_idle processes additional messages with any remaining block weight.
-			Self::service_queue(max_weight)
+			let used = Self::service_queue(max_weight);
+			used.saturating_add(Self::expire_overweight(now, max_weight.saturating_sub(used)))
 		}
 	}
 
@@ -148,9 +222,10 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			T::ExecuteOverweightOrigin::ensure_origin(origin)?;
 
-			let (sent_at, data) = Overweight::<T>::get(index).ok_or(Error::<T>::Unknown)?;
-			let weight_used = Self::try_service_message(weight_limit, sent_at, &data[..])
-				.map_err(|_| Error::<T>::OverLimit)?;
+			let record = Overweight::<T>::get(index).ok_or(Error::<T>::Unknown)?;
+			let weight_used =
+				Self::try_service_message(weight_limit, record.sent_at, &record.message[..])
+					.map_err(|_| Error::<T>::OverLimit)?;
 			Overweight::<T>::remove(index);
 			Self::deposit_event(Event::OverweightServiced { overweight_index: index, weight_used });
 			Ok(Some(weight_used.saturating_add(1_000_000)).into())
@@ -176,6 +251,10 @@ pub mod pallet {
 		},
 		/// Downward message from the overweight queue was executed.
 		OverweightServiced { overweight_index: OverweightIndex, weight_used: Weight },
+		/// Downward message from the overweight queue was not serviced in time and was dropped.
+		OverweightExpired { overweight_index: OverweightIndex },
+		/// Downward message is longer than `MaxMessageLen` and could not be queued.
+		OversizedMessageDropped { message_id: MessageId },
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -192,16 +271,22 @@ pub mod pallet {
 			let mut used = 0;
 			while page_index.begin_used < page_index.end_used {
 				let page = Pages::<T>::take(page_index.begin_used);
-				for (i, &(sent_at, ref data)) in page.iter().enumerate() {
+				let mut serviced = 0;
+				for &(sent_at, ref data) in page.iter() {
 					match Self::try_service_message(limit.saturating_sub(used), sent_at, &data[..])
 					{
 						Ok(w) => used += w,
-						Err(..) => {
-							// Too much weight needed - put the remaining messages back and bail
-							Pages::<T>::insert(page_index.begin_used, &page[i..]);
-							return used
-						},
+						Err(..) => break,
 					}
+					serviced += 1;
+				}
+				if serviced < page.len() {
+					// Too much weight needed - put the remaining messages back and bail
+					let mut page = page.into_inner();
+					let remaining = PageOf::<T>::try_from(page.split_off(serviced))
+						.expect("a part of a bounded page is bounded; qed");
+					Pages::<T>::insert(page_index.begin_used, remaining);
+					return used
 				}
 				page_index.begin_used += 1;
 			}
@@ -252,6 +337,129 @@ pub mod pallet {
 				},
 			}
 		}
+
+		/// Drop the overweight messages that were enqueued at least `OverweightExpiry` blocks
+		/// before `now`, placing the assets they carried into the `AssetTrap`.
+		///
+		/// Returns the weight consumed, which is kept below `limit` unless trapping the assets of
+		/// an expired message turns out heavier than expected.
+		pub(crate) fn expire_overweight(now: T::BlockNumber, limit: Weight) -> Weight {
+			let expiry = T::OverweightExpiry::get();
+			let mut used = T::DbWeight::get().reads(2);
+			if expiry.is_zero() || used > limit {
+				return 0
+			}
+
+			let overweight_count = PageIndex::<T>::get().overweight_count;
+			let step = T::DbWeight::get().reads_writes(1, 1);
+			let mut cursor = OverweightExpiryCursor::<T>::get();
+			while cursor < overweight_count && used.saturating_add(step) <= limit {
+				used += step;
+				match Overweight::<T>::get(cursor) {
+					None => {},
+					Some(record) if record.enqueued_at.saturating_add(expiry) <= now => {
+						Overweight::<T>::remove(cursor);
+						used = used.saturating_add(Self::trap_assets(&record.message[..]));
+						Self::deposit_event(Event::OverweightExpired { overweight_index: cursor });
+					},
+					// messages are enqueued in order, so none of the later ones expired either.
+					Some(_) => break,
+				}
+				cursor += 1;
+			}
+			OverweightExpiryCursor::<T>::put(cursor);
+			used.saturating_add(T::DbWeight::get().writes(1))
+		}
+
+		/// Drop the assets carried by the encoded downward message `data` that the relay chain is
+		/// trusted for into the `AssetTrap`.
+		fn trap_assets(mut data: &[u8]) -> Weight {
+			let maybe_msg =
+				VersionedXcm::<()>::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut data)
+					.map(Xcm::<()>::try_from);
+			match maybe_msg {
+				Ok(Ok(message)) => {
+					let origin = MultiLocation::parent();
+					let assets = assets_in_transit(
+						&message,
+						|asset| T::IsReserve::filter_asset_location(asset, &origin),
+						|asset| T::IsTeleporter::filter_asset_location(asset, &origin),
+					);
+					if assets.is_none() {
+						0
+					} else {
+						T::AssetTrap::drop_assets(&origin, assets.into())
+					}
+				},
+				_ => 0,
+			}
+		}
+
+		/// Bound `data` by `MaxMessageLen`, dropping it with an event if it is too long.
+		fn bound_message(data: Vec<u8>) -> Option<MessageOf<T>> {
+			if data.len() > T::MaxMessageLen::get() as usize {
+				let message_id = sp_io::hashing::blake2_256(&data);
+				Self::deposit_event(Event::OversizedMessageDropped { message_id });
+				return None
+			}
+			MessageOf::<T>::try_from(data).ok()
+		}
+
+		/// Put `data` into the overweight queue, returning its index.
+		fn stash_overweight(
+			page_index: &mut PageIndexData,
+			sent_at: RelayBlockNumber,
+			message: MessageOf<T>,
+			required_weight: Weight,
+		) -> OverweightIndex {
+			let overweight_index = page_index.overweight_count;
+			let enqueued_at = frame_system::Pallet::<T>::block_number();
+			Overweight::<T>::insert(
+				overweight_index,
+				OverweightRecord { sent_at, enqueued_at, required_weight, message },
+			);
+			page_index.overweight_count += 1;
+			overweight_index
+		}
+
+		/// The pages of the queue, in the order they will be executed.
+		pub fn queued_pages() -> Vec<Vec<QueuedMessage>> {
+			let page_index = PageIndex::<T>::get();
+			(page_index.begin_used..page_index.end_used)
+				.map(|page| {
+					Pages::<T>::get(page)
+						.into_iter()
+						.map(|(sent_at, data)| QueuedMessage {
+							sent_at,
+							message: VersionedXcm::<()>::decode_all_with_depth_limit(
+								MAX_XCM_DECODE_DEPTH,
+								&mut &data[..],
+							)
+							.ok(),
+						})
+						.collect()
+				})
+				.collect()
+		}
+
+		/// All messages in the overweight queue, ordered by index.
+		pub fn overweight_messages() -> Vec<OverweightMessage> {
+			let mut messages = Overweight::<T>::iter()
+				.map(|(index, record)| OverweightMessage {
+					index,
+					sender: None,
+					sent_at: record.sent_at,
+					required_weight: record.required_weight,
+					message: VersionedXcm::<()>::decode_all_with_depth_limit(
+						MAX_XCM_DECODE_DEPTH,
+						&mut &record.message[..],
+					)
+					.ok(),
+				})
+				.collect::<Vec<_>>();
+			messages.sort_by_key(|m| m.index);
+			messages
+		}
 	}
 
 	/// For an incoming downward message, this just adapts an XCM executor and executes DMP messages
@@ -270,15 +478,14 @@ pub mod pallet {
 
 			// Then if the queue is empty, use the weight remaining to service the incoming messages
 			// and once we run out of weight, place them in the queue.
-			let item_count = iter.size_hint().0;
 			let mut maybe_enqueue_page = if page_index.end_used > page_index.begin_used {
 				// queue is already non-empty - start a fresh page.
-				Some(Vec::with_capacity(item_count))
+				Some(PageOf::<T>::default())
 			} else {
 				None
 			};
 
-			for (i, (sent_at, data)) in iter.enumerate() {
+			for (sent_at, data) in iter {
 				if maybe_enqueue_page.is_none() {
 					// We're not currently enqueuing - try to execute inline.
 					let remaining_weight = limit.saturating_sub(used);
@@ -290,22 +497,28 @@ pub mod pallet {
 							if required_weight > config.max_individual {
 								// overweight - add to overweight queue and continue with
 								// message execution.
-								let overweight_index = page_index.overweight_count;
-								Overweight::<T>::insert(overweight_index, (sent_at, data));
+								let message = match Self::bound_message(data) {
+									Some(message) => message,
+									None => continue,
+								};
+								let overweight_index = Self::stash_overweight(
+									&mut page_index,
+									sent_at,
+									message,
+									required_weight,
+								);
 								Self::deposit_event(Event::OverweightEnqueued {
 									message_id,
 									overweight_index,
 									required_weight,
 								});
-								page_index.overweight_count += 1;
 								// Not needed for control flow, but only to ensure that the compiler
 								// understands that we won't attempt to re-use `data` later.
 								continue
 							} else {
 								// not overweight. stop executing inline and enqueue normally
 								// from here on.
-								let item_count_left = item_count.saturating_sub(i);
-								maybe_enqueue_page = Some(Vec::with_capacity(item_count_left));
+								maybe_enqueue_page = Some(PageOf::<T>::default());
 								Self::deposit_event(Event::WeightExhausted {
 									message_id,
 									remaining_weight,
@@ -317,14 +530,27 @@ pub mod pallet {
 				}
 				// Cannot be an `else` here since the `maybe_enqueue_page` may have changed.
 				if let Some(ref mut enqueue_page) = maybe_enqueue_page {
-					enqueue_page.push((sent_at, data));
+					let message = match Self::bound_message(data) {
+						Some(message) => message,
+						None => continue,
+					};
+					if enqueue_page.len() >= T::MaxPageLen::get() as usize {
+						// The page is full; deposit it and start a new one.
+						let full_page = sp_std::mem::take(enqueue_page);
+						Pages::<T>::insert(page_index.end_used, full_page);
+						page_index.end_used += 1;
+					}
+					// `MaxPageLen` is expected to be non-zero, so this cannot fail on an empty page.
+					let _ = enqueue_page.try_push((sent_at, message));
 				}
 			}
 
 			// Deposit the enqueued page if any and save the index.
 			if let Some(enqueue_page) = maybe_enqueue_page {
-				Pages::<T>::insert(page_index.end_used, enqueue_page);
-				page_index.end_used += 1;
+				if !enqueue_page.is_empty() {
+					Pages::<T>::insert(page_index.end_used, enqueue_page);
+					page_index.end_used += 1;
+				}
 			}
 			PageIndex::<T>::put(page_index);
 
@@ -350,6 +576,7 @@ mod tests {
 	use sp_version::RuntimeVersion;
 	use std::cell::RefCell;
 	use xcm::latest::{MultiLocation, OriginKind};
+	use xcm_executor::Assets;
 
 	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 	type Block = frame_system::mocking::MockBlock<Test>;
@@ -381,6 +608,9 @@ mod tests {
 		pub const ParachainId: ParaId = ParaId::new(200);
 		pub const ReservedXcmpWeight: Weight = 0;
 		pub const ReservedDmpWeight: Weight = 0;
+		pub const MaxMessageLen: u32 = 256;
+		pub const MaxPageLen: u32 = 4;
+		pub const OverweightExpiry: u64 = 10;
 	}
 
 	type AccountId = u64;
@@ -448,10 +678,36 @@ mod tests {
 		}
 	}
 
+	thread_local! {
+		pub static TRAPPED: RefCell<Vec<(MultiLocation, Assets)>> = RefCell::new(Vec::new());
+	}
+
+	pub struct MockAssetTrap;
+	impl DropAssets for MockAssetTrap {
+		fn drop_assets(origin: &MultiLocation, assets: Assets) -> Weight {
+			TRAPPED.with(|t| t.borrow_mut().push((origin.clone(), assets)));
+			100
+		}
+	}
+
+	/// Trusts an origin as the reserve of its own native asset only.
+	pub struct NativeAsset;
+	impl FilterAssetLocation for NativeAsset {
+		fn filter_asset_location(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+			matches!(&asset.id, Concrete(id) if id == origin)
+		}
+	}
+
 	impl Config for Test {
 		type Event = Event;
 		type XcmExecutor = MockExec;
 		type ExecuteOverweightOrigin = frame_system::EnsureRoot<AccountId>;
+		type MaxMessageLen = MaxMessageLen;
+		type MaxPageLen = MaxPageLen;
+		type OverweightExpiry = OverweightExpiry;
+		type AssetTrap = MockAssetTrap;
+		type IsReserve = NativeAsset;
+		type IsTeleporter = ();
 	}
 
 	pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
@@ -463,10 +719,16 @@ mod tests {
 			let mut index = PageIndex::<Test>::get();
 			Pages::<Test>::insert(
 				index.end_used,
-				enqueued
-					.iter()
-					.map(|m| (0, VersionedXcm::<Call>::from(m.clone()).encode()))
-					.collect::<Vec<_>>(),
+				PageOf::<Test>::try_from(
+					enqueued
+						.iter()
+						.map(|m| {
+							let data = VersionedXcm::<Call>::from(m.clone()).encode();
+							(0, MessageOf::<Test>::try_from(data).unwrap())
+						})
+						.collect::<Vec<_>>(),
+				)
+				.unwrap(),
 			);
 			index.end_used += 1;
 			PageIndex::<Test>::put(index);
@@ -791,4 +1053,208 @@ mod tests {
 			assert_eq!(pages_queued(), 1);
 		});
 	}
+
+	fn reserve_transfer(amount: u128) -> Xcm {
+		Xcm(vec![ReserveAssetDeposited((Parent, amount).into()), ClearOrigin])
+	}
+
+	#[test]
+	fn enqueue_splits_into_bounded_pages() {
+		new_test_ext().execute_with(|| {
+			let incoming = (0..10).map(|i| msg(1000 + i)).collect::<Vec<_>>();
+			let weight_used = handle_messages(&incoming, 999);
+			assert_eq!(weight_used, 0);
+			assert_eq!(pages_queued(), 3);
+			assert_eq!(Pages::<Test>::get(0).len(), 4);
+			assert_eq!(Pages::<Test>::get(1).len(), 4);
+			assert_eq!(Pages::<Test>::get(2).len(), 2);
+			take_trace();
+
+			let weight_used = handle_messages(&[], 100_000);
+			assert_eq!(weight_used, (0..10).map(|i| 1000 + i).sum::<Weight>());
+			assert!(queue_is_empty());
+		});
+	}
+
+	#[test]
+	fn oversized_messages_are_dropped() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let oversized = vec![0u8; MaxMessageLen::get() as usize + 1];
+			let message_id = sp_io::hashing::blake2_256(&oversized);
+			let incoming =
+				vec![(0, VersionedXcm::<Call>::from(msg(1000)).encode()), (0, oversized)];
+			let weight_used = DmpQueue::handle_dmp_messages(incoming.into_iter(), 999);
+			assert_eq!(weight_used, 0);
+			assert_eq!(pages_queued(), 1);
+			assert_eq!(Pages::<Test>::get(0).len(), 1);
+			System::assert_last_event(
+				dmp_queue::Event::<Test>::OversizedMessageDropped { message_id }.into(),
+			);
+		});
+	}
+
+	#[test]
+	fn overweight_records_required_weight() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(3);
+			Configuration::<Test>::put(ConfigData { max_individual: 9999 });
+
+			handle_messages(&[msg(10000)], 2500);
+			let record = Overweight::<Test>::get(0).unwrap();
+			assert_eq!(record.enqueued_at, 3);
+			assert_eq!(record.required_weight, 10000);
+
+			let messages = DmpQueue::overweight_messages();
+			assert_eq!(messages.len(), 1);
+			assert_eq!(messages[0].index, 0);
+			assert_eq!(messages[0].sender, None);
+			assert_eq!(messages[0].required_weight, 10000);
+		});
+	}
+
+	#[test]
+	fn overweight_messages_expire_into_asset_trap() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let stash = |enqueued_at, m: Xcm| {
+				let mut page_index = PageIndex::<Test>::get();
+				let data = VersionedXcm::<Call>::from(m).encode();
+				let message = MessageOf::<Test>::try_from(data).unwrap();
+				System::set_block_number(enqueued_at);
+				DmpQueue::stash_overweight(&mut page_index, 0, message, 10000);
+				PageIndex::<Test>::put(page_index);
+			};
+			stash(1, reserve_transfer(42));
+			stash(5, msg(10000));
+			assert_eq!(overweights(), vec![0, 1]);
+
+			// Nothing expired yet.
+			DmpQueue::on_idle(10, 1_000_000);
+			assert_eq!(overweights(), vec![0, 1]);
+			assert_eq!(OverweightExpiryCursor::<Test>::get(), 0);
+
+			DmpQueue::on_idle(11, 1_000_000);
+			assert_eq!(overweights(), vec![1]);
+			assert_eq!(OverweightExpiryCursor::<Test>::get(), 1);
+			System::assert_last_event(
+				dmp_queue::Event::<Test>::OverweightExpired { overweight_index: 0 }.into(),
+			);
+			let trapped = TRAPPED.with(|t| t.borrow_mut().split_off(0));
+			assert_eq!(trapped, vec![(Parent.into(), MultiAsset::from((Parent, 42u128)).into())]);
+
+			// Messages that carry no assets are dropped without trapping anything.
+			DmpQueue::on_idle(15, 1_000_000);
+			assert!(overweights().is_empty());
+			assert!(TRAPPED.with(|t| t.borrow().is_empty()));
+			assert_noop!(
+				DmpQueue::service_overweight(Origin::root(), 1, 20000),
+				Error::<Test>::Unknown
+			);
+		});
+	}
+
+	#[test]
+	fn only_trusted_assets_of_expired_messages_are_trapped() {
+		new_test_ext().execute_with(|| {
+			let stash = |m: Xcm| {
+				let mut page_index = PageIndex::<Test>::get();
+				let data = VersionedXcm::<Call>::from(m).encode();
+				let message = MessageOf::<Test>::try_from(data).unwrap();
+				DmpQueue::stash_overweight(&mut page_index, 0, message, 10000);
+				PageIndex::<Test>::put(page_index);
+			};
+			System::set_block_number(1);
+			let foreign = MultiLocation::new(1, X1(Parachain(1000)));
+			let assets: MultiAssets =
+				vec![(Parent, 42u128).into(), (foreign.clone(), 7u128).into()].into();
+			stash(Xcm(vec![ReserveAssetDeposited(assets), ClearOrigin]));
+			// the relay chain is not a trusted teleporter.
+			stash(Xcm(vec![ReceiveTeleportedAsset((Parent, 5u128).into()), ClearOrigin]));
+
+			DmpQueue::on_idle(11, 1_000_000);
+			assert!(overweights().is_empty());
+			let trapped = TRAPPED.with(|t| t.borrow_mut().split_off(0));
+			assert_eq!(trapped, vec![(Parent.into(), MultiAsset::from((Parent, 42u128)).into())]);
+		});
+	}
+
+	#[test]
+	fn overweight_expiry_skips_serviced_messages() {
+		new_test_ext().execute_with(|| {
+			Configuration::<Test>::put(ConfigData { max_individual: 9999 });
+			handle_messages(&[msg(10000), msg(10001)], 0);
+			assert_eq!(overweights(), vec![0, 1]);
+			assert!(DmpQueue::service_overweight(Origin::root(), 0, 20000).is_ok());
+
+			DmpQueue::on_idle(10, 1_000_000);
+			assert!(overweights().is_empty());
+			assert_eq!(OverweightExpiryCursor::<Test>::get(), 2);
+		});
+	}
+
+	#[test]
+	fn queued_pages_decodes_messages() {
+		new_test_ext().execute_with(|| {
+			enqueue(&[msg(1000), msg(1001)]);
+			enqueue(&[msg(1002)]);
+
+			let pages = DmpQueue::queued_pages();
+			assert_eq!(pages.len(), 2);
+			assert_eq!(pages[0].len(), 2);
+			assert_eq!(pages[1].len(), 1);
+			assert_eq!(pages[1][0].sent_at, 0);
+			let expected = VersionedXcm::<()>::from(xcm::latest::Xcm::<()>(vec![Transact {
+				origin_type: OriginKind::Native,
+				require_weight_at_most: 1002,
+				call: Vec::new().into(),
+			}]));
+			assert_eq!(pages[1][0].message, Some(expected));
+		});
+	}
+
+	#[test]
+	fn migration_to_v1_repages_queue() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(7);
+			let encoded = |w| VersionedXcm::<Call>::from(msg(w)).encode();
+			let old_page =
+				|ws: &[Weight]| ws.iter().map(|&w| (0u32, encoded(w))).collect::<Vec<_>>();
+			frame_support::storage::unhashed::put_raw(
+				&Pages::<Test>::hashed_key_for(1),
+				&old_page(&[1000, 1001, 1002]).encode(),
+			);
+			frame_support::storage::unhashed::put_raw(
+				&Pages::<Test>::hashed_key_for(2),
+				&old_page(&[1003, 1004, 1005]).encode(),
+			);
+			frame_support::storage::unhashed::put_raw(
+				&Overweight::<Test>::hashed_key_for(0),
+				&(5u32, encoded(10000)).encode(),
+			);
+			PageIndex::<Test>::put(PageIndexData {
+				begin_used: 1,
+				end_used: 3,
+				overweight_count: 1,
+			});
+
+			migration::migrate_to_v1::<Test>();
+
+			assert_eq!(
+				PageIndex::<Test>::get(),
+				PageIndexData { begin_used: 0, end_used: 2, overweight_count: 1 }
+			);
+			assert_eq!(Pages::<Test>::get(0).len(), 4);
+			assert_eq!(Pages::<Test>::get(1).len(), 2);
+			assert!(!Pages::<Test>::contains_key(2));
+			let record = Overweight::<Test>::get(0).unwrap();
+			assert_eq!(record.sent_at, 5);
+			assert_eq!(record.enqueued_at, 7);
+			assert_eq!(record.message.into_inner(), encoded(10000));
+
+			let weight_used = handle_messages(&[], 100_000);
+			assert_eq!(weight_used, 6015);
+			assert_eq!(take_trace(), (1000..1006).map(msg_complete).collect::<Vec<_>>(),);
+		});
+	}
 }
@@ -0,0 +1,106 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A module that is responsible for migration of storage.
+
+use crate::{
+	Config, MessageOf, OverweightIndex, OverweightRecord, PageCounter, PageIndex, PageOf, Pallet,
+	Store,
+};
+use forests_primitives_core::relay_chain::BlockNumber as RelayBlockNumber;
+use frame_support::{pallet_prelude::*, traits::StorageVersion, weights::Weight};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+/// The current storage version.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// Migrates the pallet storage to the most recent version, checking and setting the
+/// `StorageVersion`.
+pub fn migrate_to_latest<T: Config>() -> Weight {
+	let mut weight = 0;
+
+	if StorageVersion::get::<Pallet<T>>() == 0 {
+		weight += migrate_to_v1::<T>();
+		StorageVersion::new(1).put::<Pallet<T>>();
+	}
+
+	weight
+}
+
+mod v0 {
+	use super::*;
+
+	pub type Page = Vec<(RelayBlockNumber, Vec<u8>)>;
+	pub type OverweightEntry = (RelayBlockNumber, Vec<u8>);
+}
+
+/// Migrates `Pages` and `Overweight` from v0 (unbounded) to v1 (bounded by `MaxMessageLen` and
+/// `MaxPageLen`). The queued messages are re-paged in order, starting from page zero. Overweight
+/// messages are considered enqueued at the block of the upgrade, with an unknown required weight
+/// of zero. Messages longer than `MaxMessageLen` cannot be kept and are dropped.
+///
+/// NOTE: Only use this function if you know what you're doing. Default to using
+/// `migrate_to_latest`.
+pub fn migrate_to_v1<T: Config>() -> Weight {
+	let mut reads: u64 = 1;
+	let mut writes: u64 = 1;
+
+	let mut old_pages = BTreeMap::<PageCounter, v0::Page>::new();
+	<Pallet<T> as Store>::Pages::translate::<v0::Page, _>(|index, page| {
+		reads += 1;
+		writes += 1;
+		old_pages.insert(index, page);
+		None
+	});
+
+	let mut page_index = PageIndex::<T>::get();
+	let mut end_used = 0;
+	let mut page = PageOf::<T>::default();
+	for (_, old_page) in old_pages.range(page_index.begin_used..page_index.end_used) {
+		for (sent_at, data) in old_page {
+			let message = match MessageOf::<T>::try_from(data.clone()) {
+				Ok(message) => message,
+				Err(_) => continue,
+			};
+			if page.len() >= T::MaxPageLen::get() as usize {
+				<Pallet<T> as Store>::Pages::insert(end_used, sp_std::mem::take(&mut page));
+				writes += 1;
+				end_used += 1;
+			}
+			let _ = page.try_push((*sent_at, message));
+		}
+	}
+	if !page.is_empty() {
+		<Pallet<T> as Store>::Pages::insert(end_used, page);
+		writes += 1;
+		end_used += 1;
+	}
+	page_index.begin_used = 0;
+	page_index.end_used = end_used;
+	PageIndex::<T>::put(page_index);
+
+	let now = frame_system::Pallet::<T>::block_number();
+	<Pallet<T> as Store>::Overweight::translate::<v0::OverweightEntry, _>(
+		|_: OverweightIndex, (sent_at, data)| {
+			reads += 1;
+			writes += 1;
+			let message = MessageOf::<T>::try_from(data).ok()?;
+			Some(OverweightRecord { sent_at, enqueued_at: now, required_weight: 0, message })
+		},
+	);
+
+	T::DbWeight::get().reads_writes(reads, writes)
+}
@@ -27,7 +27,7 @@
 //!
 //! Users must ensure that they register this pallet as an inherent provider.
 
-use codec::Encode;
+use codec::{Decode, Encode};
 use forests_primitives_core::{
 	relay_chain, AbridgedHostConfiguration, ChannelStatus, CollationInfo, DmpMessageHandler,
 	GetChannelInfo, InboundDownwardMessage, InboundHrmpMessage, MessageSendError,
@@ -53,9 +53,14 @@ use sp_runtime::{
 	},
 };
 use sp_std::{cmp, collections::btree_map::BTreeMap, prelude::*};
+use unincluded_segment::{
+	Ancestor, HrmpChannelUpdate, OutboundBandwidthLimits, SegmentTracker, UsedBandwidth,
+};
 
+pub mod consensus_hook;
 mod migration;
 mod relay_state_snapshot;
+mod unincluded_segment;
 #[macro_use]
 pub mod validate_block;
 #[cfg(test)]
@@ -83,8 +88,9 @@ mod tests;
 ///
 /// # fn main() {}
 /// ```
+pub use consensus_hook::{ConsensusHook, ExpectParentIncluded};
 pub use forests_pallet_parachain_system_proc_macro::register_validate_block;
-pub use relay_state_snapshot::{MessagingStateSnapshot, RelayChainStateProof};
+pub use relay_state_snapshot::{MessagingStateSnapshot, ReadEntryErr, RelayChainStateProof};
 
 pub use pallet::*;
 
@@ -123,6 +129,24 @@ impl CheckAssociatedRelayNumber for RelayNumberStrictlyIncreases {
 	}
 }
 
+/// Provides an implementation of [`CheckAssociatedRelayNumber`].
+///
+/// It will ensure that the associated relay block number monotonically increases between
+/// Parachain blocks. This allows building multiple Parachain blocks on the same relay chain
+/// parent, which is needed for Parachains building ahead with asynchronous backing.
+pub struct RelayNumberMonotonicallyIncreases;
+
+impl CheckAssociatedRelayNumber for RelayNumberMonotonicallyIncreases {
+	fn check_associated_relay_number(
+		current: RelayChainBlockNumber,
+		previous: RelayChainBlockNumber,
+	) {
+		if current < previous {
+			panic!("Relay chain block number needs to monotonically increase between Parachain blocks!")
+		}
+	}
+}
+
 /// Provides an implementation of [`CheckAssociatedRelayNumber`].
 ///
 /// This will accept any relay chain block number combination. This is mainly useful for
@@ -176,6 +200,25 @@ pub mod pallet {
 
 		/// Something that can check the associated relay parent block number.
 		type CheckAssociatedRelayNumber: CheckAssociatedRelayNumber;
+
+		/// An entry-point for higher-level logic to manage the backlog of unincluded parachain
+		/// blocks and authorship rights for those blocks.
+		///
+		/// Typically, this should be a hook tailored to the collator-selection/consensus mechanism
+		/// that is used for this chain.
+		///
+		/// However, to maintain the same behavior as prior to asynchronous backing, provide the
+		/// [`consensus_hook::ExpectParentIncluded`] here. This is only necessary in the case
+		/// that collators aren't expected to have node versions that supply the included block
+		/// in the relay-chain state proof.
+		type ConsensusHook: ConsensusHook;
+
+		/// Additional relay chain storage keys the runtime wants to read.
+		///
+		/// Collators include these keys in the relay chain state proof, and their values as per
+		/// the relay parent can be read with [`Pallet::read_relay_state_entry`] once the
+		/// validation data inherent was processed.
+		type RelayStateKeys: Get<Vec<Vec<u8>>>;
 	}
 
 	#[pallet::hooks]
@@ -187,6 +230,7 @@ pub mod pallet {
 		fn on_finalize(_: T::BlockNumber) {
 			<DidSetValidationCode<T>>::kill();
 			<UpgradeRestrictionSignal<T>>::kill();
+			<RelayStateEntries<T>>::kill();
 
 			assert!(
 				<ValidationData<T>>::exists(),
@@ -215,6 +259,16 @@ pub mod pallet {
 				},
 			};
 
+			let total_bandwidth_out = OutboundBandwidthLimits::from_relay_chain_state(
+				&relevant_messaging_state,
+				&host_config,
+			);
+			// After this point, the `RelevantMessagingState` in storage reflects the resources
+			// which are still available to this block, i.e. the ones not used by the unincluded
+			// segment.
+			let relevant_messaging_state =
+				Self::adjust_egress_bandwidth_limits(relevant_messaging_state);
+
 			<PendingUpwardMessages<T>>::mutate(|up| {
 				let (count, size) = relevant_messaging_state.relay_dispatch_queue_size;
 
@@ -265,6 +319,31 @@ pub mod pallet {
 					.map(|(recipient, data)| OutboundHrmpMessage { recipient, data })
 					.collect::<Vec<_>>();
 
+			// Update the unincluded segment with the resources used by this block.
+			let (ump_msg_count, ump_total_bytes) = UpwardMessages::<T>::get()
+				.iter()
+				.fold((0, 0), |(count, size), msg| (count + 1, size + msg.len() as u32));
+			let mut hrmp_outgoing = BTreeMap::<ParaId, HrmpChannelUpdate>::new();
+			for message in &outbound_messages {
+				let update = hrmp_outgoing.entry(message.recipient).or_default();
+				update.msg_count += 1;
+				update.total_bytes += message.data.len() as u32;
+			}
+			let used_bandwidth = UsedBandwidth { ump_msg_count, ump_total_bytes, hrmp_outgoing };
+
+			let ancestor = Ancestor::new_unchecked(
+				used_bandwidth,
+				<UpgradeGoAhead<T>>::take(),
+				ProcessedDownwardMessages::<T>::get(),
+			);
+			let mut aggregated_segment =
+				AggregatedUnincludedSegment::<T>::get().unwrap_or_default();
+			aggregated_segment
+				.append(&ancestor, HrmpWatermark::<T>::get(), &total_bandwidth_out)
+				.expect("unincluded segment limits exceeded");
+			<UnincludedSegment<T>>::append(ancestor);
+			<AggregatedUnincludedSegment<T>>::put(aggregated_segment);
+
 			HrmpOutboundMessages::<T>::put(outbound_messages);
 		}
 
@@ -289,6 +368,15 @@ pub mod pallet {
 
 			weight += T::DbWeight::get().writes(6);
 
+			// The hash of the parent block was unknown while it was finalized, record it now as
+			// the output of the latest block of the unincluded segment.
+			<UnincludedSegment<T>>::mutate(|segment| {
+				if let Some(ancestor) = segment.last_mut() {
+					ancestor.replace_para_head_hash(frame_system::Pallet::<T>::parent_hash());
+				}
+			});
+			weight += T::DbWeight::get().reads_writes(1, 1);
+
 			// Here, in `on_initialize` we must report the weight for both `on_initialize` and
 			// `on_finalize`.
 			//
@@ -315,8 +403,8 @@ pub mod pallet {
 
 			// NOTE that the actual weight consumed by `on_finalize` may turn out lower.
 			weight += T::DbWeight::get().reads_writes(
-				3 + hrmp_max_message_num_per_candidate as u64,
-				4 + hrmp_max_message_num_per_candidate as u64,
+				6 + hrmp_max_message_num_per_candidate as u64,
+				8 + hrmp_max_message_num_per_candidate as u64,
 			);
 
 			weight
@@ -369,13 +457,35 @@ pub mod pallet {
 			)
 			.expect("Invalid relay chain state proof");
 
+			// TODO: This is more than zero, but will need benchmarking to figure out what.
+			let mut total_weight = 0;
+
+			// Update the desired maximum capacity according to the consensus hook and drop the
+			// ancestors which have been included by the relay chain in the meantime.
+			let (consensus_hook_weight, capacity) =
+				T::ConsensusHook::on_state_proof(&relay_state_proof);
+			total_weight += consensus_hook_weight;
+			total_weight += Self::maybe_drop_included_ancestors(&relay_state_proof, capacity);
+
 			// initialization logic: we know that this runs exactly once every block,
 			// which means we can put the initialization logic here to remove the
 			// sequencing problem.
 			let upgrade_go_ahead_signal = relay_state_proof
 				.read_upgrade_go_ahead_signal()
 				.expect("Invalid upgrade go ahead signal");
+
+			let aggregated_segment = AggregatedUnincludedSegment::<T>::get();
+			let upgrade_signal_in_segment =
+				aggregated_segment.as_ref().and_then(SegmentTracker::consumed_go_ahead_signal);
+			if let Some(signal) = upgrade_signal_in_segment.as_ref() {
+				// An unincluded ancestor consuming the upgrade signal is still within the
+				// segment, sanity check that it matches the signal from the relay chain.
+				assert_eq!(upgrade_go_ahead_signal, Some(*signal));
+			}
 			match upgrade_go_ahead_signal {
+				Some(_signal) if upgrade_signal_in_segment.is_some() => {
+					// Do nothing, the signal was already processed by an unincluded ancestor.
+				},
 				Some(relay_chain::v2::UpgradeGoAhead::GoAhead) => {
 					assert!(
 						<PendingValidationCode<T>>::exists(),
@@ -388,10 +498,12 @@ pub mod pallet {
 					Self::deposit_event(Event::ValidationFunctionApplied {
 						relay_chain_block_num: vfp.relay_parent_number,
 					});
+					<UpgradeGoAhead<T>>::put(Some(relay_chain::v2::UpgradeGoAhead::GoAhead));
 				},
 				Some(relay_chain::v2::UpgradeGoAhead::Abort) => {
 					<PendingValidationCode<T>>::kill();
 					Self::deposit_event(Event::ValidationFunctionDiscarded);
+					<UpgradeGoAhead<T>>::put(Some(relay_chain::v2::UpgradeGoAhead::Abort));
 				},
 				None => {},
 			}
@@ -407,16 +519,44 @@ pub mod pallet {
 			let relevant_messaging_state = relay_state_proof
 				.read_messaging_state_snapshot()
 				.expect("Invalid messaging state in relay chain state proof");
+			// Collators which do not know about the declared keys leave them out of the proof.
+			// Those entries are skipped, so reading them fails instead of the whole block.
+			let relay_state_entries = T::RelayStateKeys::get()
+				.into_iter()
+				.filter_map(|key| {
+					let value = relay_state_proof.read_raw_entry(&key).ok()?;
+					Some((key, value))
+				})
+				.collect::<Vec<_>>();
 
 			<ValidationData<T>>::put(&vfp);
 			<RelayStateProof<T>>::put(relay_chain_state);
 			<RelevantMessagingState<T>>::put(relevant_messaging_state.clone());
 			<HostConfiguration<T>>::put(host_config);
+			<RelayStateEntries<T>>::put(relay_state_entries);
 
 			<T::OnSystemEvent as OnSystemEvent>::on_validation_data(&vfp);
 
-			// TODO: This is more than zero, but will need benchmarking to figure out what.
-			let mut total_weight = 0;
+			// Messages which were already processed by the unincluded ancestors are still in the
+			// relay chain queues, as the queues are only pruned once the ancestors are included.
+			let (processed_in_segment, segment_hrmp_watermark) =
+				match (&aggregated_segment, <UnincludedSegment<T>>::decode_len().unwrap_or(0)) {
+					(Some(segment), len) if len > 0 =>
+						(segment.processed_downward_messages(), segment.hrmp_watermark()),
+					_ => (0, None),
+				};
+			let downward_messages =
+				downward_messages.into_iter().skip(processed_in_segment as usize).collect();
+			let horizontal_messages = match segment_hrmp_watermark {
+				Some(watermark) => horizontal_messages
+					.into_iter()
+					.map(|(sender, messages)| {
+						(sender, messages.into_iter().filter(|m| m.sent_at > watermark).collect())
+					})
+					.collect(),
+				None => horizontal_messages,
+			};
+
 			total_weight += Self::process_inbound_downward_messages(
 				relevant_messaging_state.dmq_mqc_head,
 				downward_messages,
@@ -518,6 +658,31 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type NewValidationCode<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
 
+	/// Latest included block descendants the runtime accepted. In other words, these are
+	/// ancestors of the currently executing block which have not been included in the observed
+	/// relay-chain state.
+	///
+	/// The segment length is limited by the capacity returned from the [`ConsensusHook`] configured
+	/// in the pallet.
+	#[pallet::storage]
+	pub(super) type UnincludedSegment<T: Config> =
+		StorageValue<_, Vec<Ancestor<T::Hash>>, ValueQuery>;
+
+	/// Storage field that keeps track of bandwidth used by the unincluded segment along with the
+	/// latest HRMP watermark. Used for limiting the acceptance of new blocks with respect to relay
+	/// chain constraints.
+	#[pallet::storage]
+	pub(super) type AggregatedUnincludedSegment<T: Config> =
+		StorageValue<_, SegmentTracker<T::Hash>, OptionQuery>;
+
+	/// The go-ahead signal consumed by the current block, if any.
+	///
+	/// This value is ephemeral which means it doesn't hit the storage. It is moved into the
+	/// [`UnincludedSegment`] entry of the block in `on_finalize`.
+	#[pallet::storage]
+	pub(super) type UpgradeGoAhead<T: Config> =
+		StorageValue<_, Option<relay_chain::v2::UpgradeGoAhead>, ValueQuery>;
+
 	/// The [`PersistedValidationData`] set for this block.
 	/// This value is expected to be set only once per block and it's never stored
 	/// in the trie.
@@ -576,6 +741,16 @@ pub mod pallet {
 	#[pallet::getter(fn host_configuration)]
 	pub(super) type HostConfiguration<T: Config> = StorageValue<_, AbridgedHostConfiguration>;
 
+	/// The raw values of the keys declared by `Config::RelayStateKeys` as per the relay parent,
+	/// `None` for values that are absent on the relay chain. Keys missing from the relay chain
+	/// state proof have no entry.
+	///
+	/// This field is set with the validation data inherent and killed in `on_finalize`, so it is
+	/// empty outside of that window.
+	#[pallet::storage]
+	pub(super) type RelayStateEntries<T: Config> =
+		StorageValue<_, Vec<(Vec<u8>, Option<Vec<u8>>)>, ValueQuery>;
+
 	/// The last downward message queue chain head we have observed.
 	///
 	/// This value is loaded before and saved after processing inbound downward messages carried
@@ -791,6 +966,107 @@ impl<T: Config> Pallet<T> {
 		});
 	}
 
+	/// Drop the blocks of the unincluded segment which were included by the relay chain, as
+	/// observed in the given state proof.
+	///
+	/// # Panics
+	///
+	/// Panics if there is no space left in the unincluded segment for the current block, or if
+	/// the consensus hook expects the included head in the state proof but it is absent.
+	fn maybe_drop_included_ancestors(
+		relay_state_proof: &RelayChainStateProof,
+		capacity: consensus_hook::UnincludedSegmentCapacity,
+	) -> Weight {
+		let mut weight_used = 0;
+		let para_head =
+			relay_state_proof.read_included_para_head().ok().map(|h| T::Hashing::hash(&h.0));
+
+		let unincluded_segment_len = <UnincludedSegment<T>>::decode_len().unwrap_or(0);
+		weight_used += T::DbWeight::get().reads(1);
+
+		let parent_hash = frame_system::Pallet::<T>::parent_hash();
+		let included_head = match (para_head, capacity.is_expecting_included_parent()) {
+			(Some(h), true) => {
+				assert_eq!(h, parent_hash, "expected parent to be included");
+				h
+			},
+			(Some(h), false) => h,
+			// Collators which are not yet aware of the unincluded segment don't provide the
+			// included head in the state proof, in which case the parent must be included.
+			(None, true) => parent_hash,
+			(None, false) => panic!("included head not present in relay storage proof"),
+		};
+
+		let new_len = if unincluded_segment_len > 0 {
+			let dropped: Vec<Ancestor<T::Hash>> = <UnincludedSegment<T>>::mutate(|segment| {
+				// Drop everything up to (inclusive) the block with the included para head, if
+				// present.
+				let idx = segment
+					.iter()
+					.position(|ancestor| ancestor.para_head_hash() == Some(&included_head))
+					.map_or(0, |idx| idx + 1);
+				segment.drain(..idx).collect()
+			});
+			weight_used += T::DbWeight::get().reads_writes(1, 1);
+
+			if !dropped.is_empty() {
+				<AggregatedUnincludedSegment<T>>::mutate(|aggregated| {
+					let aggregated = aggregated.as_mut().expect(
+						"dropped part of the segment wasn't empty, hence value exists; qed",
+					);
+					for ancestor in &dropped {
+						aggregated.subtract(ancestor);
+					}
+				});
+				weight_used += T::DbWeight::get().reads_writes(1, 1);
+			}
+
+			(unincluded_segment_len - dropped.len()) as u32
+		} else {
+			0
+		};
+
+		// Current block validity check: ensure there is space in the unincluded segment.
+		//
+		// If this fails, the parachain needs to wait for ancestors to be included before
+		// a new block is allowed.
+		assert!(new_len < capacity.get(), "no space left for the block in the unincluded segment");
+
+		weight_used
+	}
+
+	/// Reduce the resources announced in the given messaging state by the ones used by the
+	/// unincluded segment, and store the result as the [`RelevantMessagingState`].
+	fn adjust_egress_bandwidth_limits(
+		mut messaging_state: MessagingStateSnapshot,
+	) -> MessagingStateSnapshot {
+		let aggregated_segment = match AggregatedUnincludedSegment::<T>::get() {
+			Some(segment) => segment,
+			None => return messaging_state,
+		};
+		let used_bandwidth = aggregated_segment.used_bandwidth();
+
+		let channels = &mut messaging_state.egress_channels;
+		for (para_id, used) in used_bandwidth.hrmp_outgoing.iter() {
+			let i = match channels.binary_search_by_key(para_id, |item| item.0) {
+				Ok(i) => i,
+				Err(_) => continue, // indicates channel closed.
+			};
+
+			let channel = &mut channels[i].1;
+			channel.total_size =
+				(channel.total_size + used.total_bytes).min(channel.max_total_size);
+			channel.msg_count = (channel.msg_count + used.msg_count).min(channel.max_capacity);
+		}
+
+		let (count, size) = &mut messaging_state.relay_dispatch_queue_size;
+		*count = count.saturating_add(used_bandwidth.ump_msg_count);
+		*size = size.saturating_add(used_bandwidth.ump_total_bytes);
+
+		<RelevantMessagingState<T>>::put(&messaging_state);
+		messaging_state
+	}
+
 	/// Process all inbound downward messages relayed by the collator.
 	///
 	/// Checks if the sequence of the messages is valid, dispatches them and communicates the
@@ -1017,6 +1293,34 @@ impl<T: Config> Pallet<T> {
 	pub fn set_custom_validation_head_data(head_data: Vec<u8>) {
 		CustomValidationHeadData::<T>::put(head_data);
 	}
+
+	/// Returns the additional relay chain storage keys that collators need to include in the
+	/// relay chain state proof.
+	///
+	/// This is expected to be used by the
+	/// [`RelayStateKeysApi`](forests_primitives_core::RelayStateKeysApi) runtime api.
+	pub fn relay_state_keys() -> Vec<Vec<u8>> {
+		T::RelayStateKeys::get()
+	}
+
+	/// Read the entry given by the relay chain storage `key` as per the relay parent and try to
+	/// decode it. Returns `None` if the value is empty in the relay chain state.
+	///
+	/// Only the keys declared by `Config::RelayStateKeys` can be read. Their values were verified
+	/// against the relay parent storage root when the validation data inherent was processed, and
+	/// are only available from then until `on_finalize`.
+	///
+	/// Returns `Err` in case the key was not declared, was missing from the relay chain state
+	/// proof, or the decoding fails.
+	pub fn read_relay_state_entry<V: Decode>(key: &[u8]) -> Result<Option<V>, ReadEntryErr> {
+		let entries = RelayStateEntries::<T>::get();
+		let (_, value) =
+			entries.iter().find(|(k, _)| k.as_slice() == key).ok_or(ReadEntryErr::Proof)?;
+		value
+			.as_ref()
+			.map(|raw| V::decode(&mut &raw[..]).map_err(|_| ReadEntryErr::Decode))
+			.transpose()
+	}
 }
 
 pub struct ParachainSetCode<T>(sp_std::marker::PhantomData<T>);
@@ -73,6 +73,8 @@ pub enum Error {
 	UpgradeGoAhead(ReadEntryErr),
 	/// The upgrade restriction signal cannot be read.
 	UpgradeRestriction(ReadEntryErr),
+	/// The included para head cannot be read.
+	ParaHead(ReadEntryErr),
 	/// The host configuration cannot be extracted.
 	Config(ReadEntryErr),
 	/// The DMQ MQC head cannot be extracted.
@@ -85,9 +87,11 @@ pub enum Error {
 	HrmpEgressChannelIndex(ReadEntryErr),
 	/// The channel identified by the sender and receiver cannot be extracted.
 	HrmpChannel(ParaId, ParaId, ReadEntryErr),
+	/// The raw entry cannot be read.
+	ReadRawEntry(ReadEntryErr),
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum ReadEntryErr {
 	/// The value cannot be extracted from the proof.
 	Proof,
@@ -278,6 +282,16 @@ impl RelayChainStateProof {
 		.map_err(Error::UpgradeRestriction)
 	}
 
+	/// Read the head of the latest included candidate of this parachain from the relay chain state
+	/// proof.
+	///
+	/// Returns an error if the head is absent from the proof or anything failed at reading or
+	/// decoding.
+	pub fn read_included_para_head(&self) -> Result<relay_chain::v2::HeadData, Error> {
+		read_entry(&self.trie_backend, &relay_chain::well_known_keys::para_head(self.para_id), None)
+			.map_err(Error::ParaHead)
+	}
+
 	/// Read an entry given by the key and try to decode it. If the value specified by the key according
 	/// to the proof is empty, the `fallback` value will be returned.
 	///
@@ -301,4 +315,15 @@ impl RelayChainStateProof {
 	{
 		read_optional_entry(&self.trie_backend, key).map_err(Error::ReadOptionalEntry)
 	}
+
+	/// Read the raw value of an entry given by the key, without decoding it.
+	/// Returns `None` if the value specified by the key according to the proof is empty.
+	///
+	/// Returns `Err` in case the backend can't return the value under the specific key, which
+	/// means the key was not included in the proof.
+	pub fn read_raw_entry(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		self.trie_backend
+			.storage(key)
+			.map_err(|_| Error::ReadRawEntry(ReadEntryErr::Proof))
+	}
 }
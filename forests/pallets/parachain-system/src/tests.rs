@@ -36,10 +36,10 @@ use relay_chain::v2::HrmpChannelId;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
 };
 use sp_version::RuntimeVersion;
-use std::cell::RefCell;
+use std::{cell::RefCell, num::NonZeroU32};
 
 use crate as parachain_system;
 
@@ -72,6 +72,7 @@ parameter_types! {
 	pub const ParachainId: ParaId = ParaId::new(200);
 	pub const ReservedXcmpWeight: Weight = 0;
 	pub const ReservedDmpWeight: Weight = 0;
+	pub static RelayStateKeys: Vec<Vec<u8>> = Vec::new();
 }
 impl frame_system::Config for Test {
 	type Origin = Origin;
@@ -109,6 +110,38 @@ impl Config for Test {
 	type XcmpMessageHandler = SaveIntoThreadLocal;
 	type ReservedXcmpWeight = ReservedXcmpWeight;
 	type CheckAssociatedRelayNumber = RelayNumberStrictlyIncreases;
+	type ConsensusHook = TestConsensusHook;
+	type RelayStateKeys = RelayStateKeys;
+}
+
+std::thread_local! {
+	static CONSENSUS_HOOK_CAPACITY: RefCell<Option<NonZeroU32>> = RefCell::new(None);
+	static FINALIZED_HEADERS: RefCell<Vec<Header>> = RefCell::new(Vec::new());
+}
+
+/// A consensus hook with a capacity that can be configured per test.
+///
+/// Behaves like [`ExpectParentIncluded`] unless a capacity is set.
+pub struct TestConsensusHook;
+
+impl ConsensusHook for TestConsensusHook {
+	fn on_state_proof(
+		state_proof: &RelayChainStateProof,
+	) -> (Weight, consensus_hook::UnincludedSegmentCapacity) {
+		match CONSENSUS_HOOK_CAPACITY.with(|c| *c.borrow()) {
+			Some(capacity) => (0, capacity.into()),
+			None => ExpectParentIncluded::on_state_proof(state_proof),
+		}
+	}
+}
+
+fn set_unincluded_segment_capacity(capacity: u32) {
+	CONSENSUS_HOOK_CAPACITY.with(|c| *c.borrow_mut() = NonZeroU32::new(capacity));
+}
+
+/// The header of the given, already finalized, block of the current [`BlockTests`] run.
+fn finalized_header(n: u64) -> Header {
+	FINALIZED_HEADERS.with(|h| h.borrow()[n as usize - 1].clone())
 }
 
 pub struct FromThreadLocal;
@@ -294,6 +327,7 @@ impl BlockTests {
 	fn run(&mut self) {
 		self.ran = true;
 		wasm_ext().execute_with(|| {
+			let mut parent_hash = Default::default();
 			for BlockTest { n, within_block, after_block } in self.tests.iter() {
 				// clear pending updates, as applicable
 				if let Some(upgrade_block) = self.pending_upgrade {
@@ -304,7 +338,7 @@ impl BlockTests {
 
 				// begin initialization
 				System::reset_events();
-				System::initialize(&n, &Default::default(), &Default::default());
+				System::initialize(&n, &parent_hash, &Default::default());
 
 				// now mess with the storage the way validate_block does
 				let mut sproof_builder = RelayStateSproofBuilder::default();
@@ -364,7 +398,9 @@ impl BlockTests {
 				}
 
 				// clean up
-				System::finalize();
+				let header = System::finalize();
+				parent_hash = header.hash();
+				FINALIZED_HEADERS.with(|h| h.borrow_mut().push(header));
 				if let Some(after_block) = after_block {
 					after_block();
 				}
@@ -974,3 +1010,234 @@ fn test() {
 		.add(1, || {})
 		.add(2, || {});
 }
+
+#[test]
+fn relay_number_monotonically_increases_accepts_same_relay_parent() {
+	RelayNumberMonotonicallyIncreases::check_associated_relay_number(1, 1);
+	RelayNumberMonotonicallyIncreases::check_associated_relay_number(2, 1);
+}
+
+#[test]
+#[should_panic = "Relay chain block number needs to monotonically increase between Parachain blocks!"]
+fn relay_number_monotonically_increases_rejects_decrease() {
+	RelayNumberMonotonicallyIncreases::check_associated_relay_number(1, 2);
+}
+
+#[test]
+fn unincluded_segment_grows_until_included() {
+	set_unincluded_segment_capacity(10);
+
+	BlockTests::new()
+		.with_relay_sproof_builder(|_, _, sproof| {
+			// Nothing of this chain was included yet.
+			sproof.included_para_head = Some(relay_chain::v2::HeadData(vec![1, 2, 3]));
+		})
+		.add(1, || {})
+		.add(2, || {})
+		.add_with_post_test(
+			3,
+			|| {},
+			|| {
+				let segment = UnincludedSegment::<Test>::get();
+				assert_eq!(segment.len(), 3);
+				// The output of the latest block is only known to its descendant.
+				assert_eq!(segment[1].para_head_hash(), Some(&finalized_header(2).hash()));
+				assert!(segment[2].para_head_hash().is_none());
+				assert!(AggregatedUnincludedSegment::<Test>::exists());
+			},
+		);
+}
+
+#[test]
+#[should_panic = "no space left for the block in the unincluded segment"]
+fn unincluded_segment_is_limited() {
+	set_unincluded_segment_capacity(2);
+
+	BlockTests::new()
+		.with_relay_sproof_builder(|_, _, sproof| {
+			sproof.included_para_head = Some(relay_chain::v2::HeadData(vec![1, 2, 3]));
+		})
+		.add(1, || {})
+		.add(2, || {})
+		.add(3, || {});
+}
+
+#[test]
+#[should_panic = "included head not present in relay storage proof"]
+fn unincluded_segment_requires_included_head() {
+	set_unincluded_segment_capacity(2);
+
+	BlockTests::new().add(1, || {});
+}
+
+#[test]
+fn unincluded_segment_drops_included_ancestors() {
+	set_unincluded_segment_capacity(3);
+
+	BlockTests::new()
+		.with_relay_sproof_builder(|_, relay_block_num, sproof| {
+			// The relay chain lags two blocks behind.
+			sproof.included_para_head = Some(match relay_block_num {
+				1 | 2 => relay_chain::v2::HeadData(vec![1, 2, 3]),
+				n => relay_chain::v2::HeadData(finalized_header(n as u64 - 2).encode()),
+			});
+		})
+		.add(1, || {})
+		.add(2, || {})
+		.add_with_post_test(
+			3,
+			|| {},
+			|| {
+				let segment = UnincludedSegment::<Test>::get();
+				assert_eq!(segment.len(), 2);
+				assert_eq!(segment[0].para_head_hash(), Some(&finalized_header(2).hash()));
+			},
+		)
+		.add_with_post_test(
+			4,
+			|| {},
+			|| {
+				let segment = UnincludedSegment::<Test>::get();
+				assert_eq!(segment.len(), 2);
+				assert_eq!(segment[0].para_head_hash(), Some(&finalized_header(3).hash()));
+			},
+		);
+}
+
+#[test]
+fn unincluded_segment_shares_upward_bandwidth() {
+	set_unincluded_segment_capacity(3);
+
+	BlockTests::new()
+		.with_relay_sproof_builder(|_, _, sproof| {
+			sproof.host_config.max_upward_queue_count = 8;
+			sproof.host_config.max_upward_message_num_per_candidate = 5;
+			sproof.relay_dispatch_queue_size = None;
+			sproof.included_para_head = Some(relay_chain::v2::HeadData(vec![1, 2, 3]));
+		})
+		.add_with_post_test(
+			1,
+			|| {
+				for i in 0..5u8 {
+					ParachainSystem::send_upward_message(vec![i]).unwrap();
+				}
+			},
+			|| {
+				assert_eq!(UpwardMessages::<Test>::get().len(), 5);
+			},
+		)
+		.add_with_post_test(
+			2,
+			|| {
+				for i in 5..10u8 {
+					ParachainSystem::send_upward_message(vec![i]).unwrap();
+				}
+			},
+			|| {
+				// The unincluded parent already occupies 5 out of 8 slots of the relay queue.
+				assert_eq!(UpwardMessages::<Test>::get(), vec![vec![5], vec![6], vec![7]]);
+				assert_eq!(PendingUpwardMessages::<Test>::get(), vec![vec![8], vec![9]]);
+			},
+		);
+}
+
+#[test]
+fn unincluded_segment_skips_messages_processed_by_ancestors() {
+	lazy_static::lazy_static! {
+		static ref MSG_1: InboundDownwardMessage = InboundDownwardMessage {
+			sent_at: 1,
+			msg: b"down1".to_vec(),
+		};
+		static ref MSG_2: InboundDownwardMessage = InboundDownwardMessage {
+			sent_at: 2,
+			msg: b"down2".to_vec(),
+		};
+	}
+
+	set_unincluded_segment_capacity(3);
+
+	BlockTests::new()
+		.with_relay_sproof_builder(|_, relay_block_num, sproof| {
+			sproof.included_para_head = Some(relay_chain::v2::HeadData(vec![1, 2, 3]));
+			sproof.dmq_mqc_head = Some(match relay_block_num {
+				1 => MessageQueueChain::default().extend_downward(&MSG_1).head(),
+				_ => MessageQueueChain::default()
+					.extend_downward(&MSG_1)
+					.extend_downward(&MSG_2)
+					.head(),
+			});
+		})
+		.with_inherent_data(|_, relay_block_num, data| {
+			// The relay chain only prunes the queue once the ancestor is included, so the
+			// message processed by the first block is handed out again.
+			data.downward_messages = match relay_block_num {
+				1 => vec![MSG_1.clone()],
+				_ => vec![MSG_1.clone(), MSG_2.clone()],
+			};
+		})
+		.add(1, || {
+			HANDLED_DMP_MESSAGES.with(|m| {
+				let mut m = m.borrow_mut();
+				assert_eq!(&*m, &[(MSG_1.sent_at, MSG_1.msg.clone())]);
+				m.clear();
+			});
+		})
+		.add_with_post_test(
+			2,
+			|| {
+				HANDLED_DMP_MESSAGES.with(|m| {
+					let mut m = m.borrow_mut();
+					assert_eq!(&*m, &[(MSG_2.sent_at, MSG_2.msg.clone())]);
+					m.clear();
+				});
+			},
+			|| {
+				assert_eq!(ProcessedDownwardMessages::<Test>::get(), 1);
+			},
+		);
+}
+
+#[test]
+fn relay_state_entries_are_readable() {
+	let key = b"relay_state_key".to_vec();
+	RelayStateKeys::set(vec![key.clone()]);
+
+	BlockTests::new()
+		.with_relay_sproof_builder(|_, relay_block_num, sproof| {
+			// The collator of block 3 does not include the key in the proof.
+			if relay_block_num != 3 {
+				sproof.additional_key_values =
+					vec![(b"relay_state_key".to_vec(), (relay_block_num * 10).encode())];
+			}
+		})
+		.add(1, || {})
+		.add_with_post_test(
+			2,
+			|| {
+				assert_eq!(
+					ParachainSystem::read_relay_state_entry::<u32>(b"relay_state_key"),
+					Ok(Some(20))
+				);
+				// Keys which were not declared are not part of the proof.
+				assert_eq!(
+					ParachainSystem::read_relay_state_entry::<u32>(b"undeclared_key"),
+					Err(ReadEntryErr::Proof)
+				);
+				assert_eq!(
+					ParachainSystem::read_relay_state_entry::<[u8; 32]>(b"relay_state_key"),
+					Err(ReadEntryErr::Decode)
+				);
+			},
+			move || {
+				assert_eq!(ParachainSystem::relay_state_keys(), vec![key.clone()]);
+				// The entries do not outlive the block.
+				assert!(!RelayStateEntries::<Test>::exists());
+			},
+		)
+		.add(3, || {
+			assert_eq!(
+				ParachainSystem::read_relay_state_entry::<u32>(b"relay_state_key"),
+				Err(ReadEntryErr::Proof)
+			);
+		});
+}
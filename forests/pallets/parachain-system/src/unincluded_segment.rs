@@ -0,0 +1,816 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Primitives used for tracking message queues constraints in an unincluded block segment
+//! of the parachain.
+//!
+//! The unincluded segment is the chain of descendants of the latest included parachain block
+//! which were built by this runtime, but not yet included by the relay chain. All blocks of the
+//! segment share the resources of the relay chain, e.g. the upward message queue, hence their
+//! combined usage must not exceed the limits observed at the relay parent.
+
+use super::relay_state_snapshot::MessagingStateSnapshot;
+use codec::{Decode, Encode};
+use forests_primitives_core::{relay_chain, AbridgedHostConfiguration, ParaId};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData};
+
+/// Constraints on outbound HRMP channel.
+#[derive(Clone, RuntimeDebug)]
+pub struct HrmpOutboundLimits {
+	/// The maximum bytes that can be written to the channel.
+	pub bytes_remaining: u32,
+	/// The maximum messages that can be written to the channel.
+	pub messages_remaining: u32,
+}
+
+/// Limits on outbound message bandwidth.
+#[derive(Clone, RuntimeDebug)]
+pub struct OutboundBandwidthLimits {
+	/// The amount of UMP messages remaining.
+	pub ump_messages_remaining: u32,
+	/// The amount of UMP bytes remaining.
+	pub ump_bytes_remaining: u32,
+	/// The limitations of all registered outbound HRMP channels.
+	pub hrmp_outgoing: BTreeMap<ParaId, HrmpOutboundLimits>,
+}
+
+impl OutboundBandwidthLimits {
+	/// Creates new limits from the messaging state and the host configuration observed at the
+	/// relay parent.
+	pub fn from_relay_chain_state(
+		messaging_state: &MessagingStateSnapshot,
+		host_config: &AbridgedHostConfiguration,
+	) -> Self {
+		let (ump_messages_in_relay, ump_bytes_in_relay) = messaging_state.relay_dispatch_queue_size;
+
+		let hrmp_outgoing = messaging_state
+			.egress_channels
+			.iter()
+			.map(|(id, channel)| {
+				(
+					*id,
+					HrmpOutboundLimits {
+						bytes_remaining: channel.max_total_size.saturating_sub(channel.total_size),
+						messages_remaining: channel.max_capacity.saturating_sub(channel.msg_count),
+					},
+				)
+			})
+			.collect();
+
+		Self {
+			ump_messages_remaining: host_config
+				.max_upward_queue_count
+				.saturating_sub(ump_messages_in_relay),
+			ump_bytes_remaining: host_config
+				.max_upward_queue_size
+				.saturating_sub(ump_bytes_in_relay),
+			hrmp_outgoing,
+		}
+	}
+}
+
+/// The error type for updating bandwidth used by a segment.
+#[derive(RuntimeDebug)]
+pub enum BandwidthUpdateError {
+	/// Too many messages submitted to HRMP channel.
+	HrmpMessagesOverflow {
+		/// Parachain id of the recipient.
+		recipient: ParaId,
+		/// The amount of remaining messages in the capacity of the channel.
+		messages_remaining: u32,
+		/// The amount of messages submitted to the channel.
+		messages_submitted: u32,
+	},
+	/// Too many bytes submitted to HRMP channel.
+	HrmpBytesOverflow {
+		/// Parachain id of the recipient.
+		recipient: ParaId,
+		/// The amount of remaining bytes in the capacity of the channel.
+		bytes_remaining: u32,
+		/// The amount of bytes submitted to the channel.
+		bytes_submitted: u32,
+	},
+	/// Too many messages submitted to UMP queue.
+	UmpMessagesOverflow {
+		/// The amount of remaining messages in the capacity of UMP.
+		messages_remaining: u32,
+		/// The amount of messages submitted to UMP.
+		messages_submitted: u32,
+	},
+	/// Too many bytes submitted to UMP.
+	UmpBytesOverflow {
+		/// The amount of remaining bytes in the capacity of UMP.
+		bytes_remaining: u32,
+		/// The amount of bytes submitted to UMP.
+		bytes_submitted: u32,
+	},
+	/// Invalid HRMP watermark.
+	InvalidHrmpWatermark {
+		/// HRMP watermark submitted by the candidate.
+		submitted: relay_chain::BlockNumber,
+		/// Latest tracked HRMP watermark.
+		latest: relay_chain::BlockNumber,
+	},
+	/// Upgrade signal sent by relay chain was already processed by
+	/// some ancestor from the segment.
+	UpgradeGoAheadAlreadyProcessed,
+}
+
+/// The number of messages and size in bytes submitted to HRMP channel.
+#[derive(RuntimeDebug, Default, Copy, Clone, Encode, Decode, TypeInfo, PartialEq)]
+pub struct HrmpChannelUpdate {
+	/// The amount of messages submitted to the channel.
+	pub msg_count: u32,
+	/// The amount of bytes submitted to the channel.
+	pub total_bytes: u32,
+}
+
+impl HrmpChannelUpdate {
+	/// Returns `true` if the update is empty, `false` otherwise.
+	fn is_empty(&self) -> bool {
+		self.msg_count == 0 && self.total_bytes == 0
+	}
+
+	/// Tries to append another update, respecting given bandwidth limits.
+	fn append(
+		&self,
+		other: &Self,
+		recipient: ParaId,
+		limits: &OutboundBandwidthLimits,
+	) -> Result<Self, BandwidthUpdateError> {
+		let limits = limits
+			.hrmp_outgoing
+			.get(&recipient)
+			.expect("limit for declared hrmp channel must be present; qed");
+
+		let mut new = *self;
+
+		new.msg_count = new.msg_count.saturating_add(other.msg_count);
+		if new.msg_count > limits.messages_remaining {
+			return Err(BandwidthUpdateError::HrmpMessagesOverflow {
+				recipient,
+				messages_remaining: limits.messages_remaining,
+				messages_submitted: new.msg_count,
+			})
+		}
+		new.total_bytes = new.total_bytes.saturating_add(other.total_bytes);
+		if new.total_bytes > limits.bytes_remaining {
+			return Err(BandwidthUpdateError::HrmpBytesOverflow {
+				recipient,
+				bytes_remaining: limits.bytes_remaining,
+				bytes_submitted: new.total_bytes,
+			})
+		}
+
+		Ok(new)
+	}
+
+	/// Subtracts previously added channel update.
+	fn subtract(&mut self, other: &Self) {
+		self.msg_count -= other.msg_count;
+		self.total_bytes -= other.total_bytes;
+	}
+}
+
+/// Bandwidth used by a parachain block(s).
+///
+/// This struct can be created with pub items, however, it should
+/// never hit the storage directly to avoid bypassing limitations checks.
+#[derive(Default, Clone, Encode, Decode, TypeInfo, PartialEq, RuntimeDebug)]
+pub struct UsedBandwidth {
+	/// The amount of UMP messages sent.
+	pub ump_msg_count: u32,
+	/// The amount of UMP bytes sent.
+	pub ump_total_bytes: u32,
+	/// Outbound HRMP channels updates.
+	pub hrmp_outgoing: BTreeMap<ParaId, HrmpChannelUpdate>,
+}
+
+impl UsedBandwidth {
+	/// Tries to append another update, respecting given bandwidth limits.
+	fn append(
+		&self,
+		other: &Self,
+		limits: &OutboundBandwidthLimits,
+	) -> Result<Self, BandwidthUpdateError> {
+		let mut new = self.clone();
+
+		new.ump_msg_count = new.ump_msg_count.saturating_add(other.ump_msg_count);
+		if new.ump_msg_count > limits.ump_messages_remaining {
+			return Err(BandwidthUpdateError::UmpMessagesOverflow {
+				messages_remaining: limits.ump_messages_remaining,
+				messages_submitted: new.ump_msg_count,
+			})
+		}
+		new.ump_total_bytes = new.ump_total_bytes.saturating_add(other.ump_total_bytes);
+		if new.ump_total_bytes > limits.ump_bytes_remaining {
+			return Err(BandwidthUpdateError::UmpBytesOverflow {
+				bytes_remaining: limits.ump_bytes_remaining,
+				bytes_submitted: new.ump_total_bytes,
+			})
+		}
+
+		for (id, channel) in other.hrmp_outgoing.iter() {
+			let current = new.hrmp_outgoing.entry(*id).or_default();
+			*current = current.append(channel, *id, limits)?;
+		}
+
+		Ok(new)
+	}
+
+	/// Subtracts previously added bandwidth update.
+	fn subtract(&mut self, other: &Self) {
+		self.ump_msg_count -= other.ump_msg_count;
+		self.ump_total_bytes -= other.ump_total_bytes;
+
+		for (id, channel) in other.hrmp_outgoing.iter() {
+			let entry = self
+				.hrmp_outgoing
+				.get_mut(id)
+				.expect("entry's been inserted earlier with `append`; qed");
+			entry.subtract(channel);
+		}
+
+		self.hrmp_outgoing.retain(|_, channel| !channel.is_empty());
+	}
+}
+
+/// Ancestor of the block being currently executed, not yet included
+/// into the relay chain.
+#[derive(Encode, Decode, TypeInfo, PartialEq, RuntimeDebug)]
+pub struct Ancestor<H> {
+	/// Output messages bandwidth used by the block.
+	used_bandwidth: UsedBandwidth,
+	/// Output of the block. `None` until the block has been finalized and its hash is known, i.e.
+	/// it's set while building its descendant.
+	para_head_hash: Option<H>,
+	/// Optional go-ahead signal sent by the relay chain which this ancestor has consumed.
+	consumed_go_ahead_signal: Option<relay_chain::v2::UpgradeGoAhead>,
+	/// The number of downward messages the block has processed.
+	processed_downward_messages: u32,
+}
+
+impl<H> Ancestor<H> {
+	/// Creates new ancestor without validating the bandwidth used.
+	pub fn new_unchecked(
+		used_bandwidth: UsedBandwidth,
+		consumed_go_ahead_signal: Option<relay_chain::v2::UpgradeGoAhead>,
+		processed_downward_messages: u32,
+	) -> Self {
+		Self {
+			used_bandwidth,
+			para_head_hash: None,
+			consumed_go_ahead_signal,
+			processed_downward_messages,
+		}
+	}
+
+	/// Returns [`UsedBandwidth`] of this block.
+	pub fn used_bandwidth(&self) -> &UsedBandwidth {
+		&self.used_bandwidth
+	}
+
+	/// Returns hashed [output head data](`relay_chain::v2::HeadData`) of this block.
+	pub fn para_head_hash(&self) -> Option<&H> {
+		self.para_head_hash.as_ref()
+	}
+
+	/// Set para head hash of this block.
+	pub fn replace_para_head_hash(&mut self, para_head_hash: H) {
+		self.para_head_hash.replace(para_head_hash);
+	}
+}
+
+/// Struct that keeps track of bandwidth used by the unincluded part of the chain
+/// along with the latest HRMP watermark.
+#[derive(Default, Encode, Decode, TypeInfo, PartialEq, RuntimeDebug)]
+pub struct SegmentTracker<H> {
+	/// Bandwidth used by the segment.
+	used_bandwidth: UsedBandwidth,
+	/// The mark which specifies the block number up to which all inbound HRMP messages are
+	/// processed.
+	hrmp_watermark: Option<relay_chain::BlockNumber>,
+	/// Optional go-ahead signal sent by the relay chain which was consumed by some block of the
+	/// segment.
+	consumed_go_ahead_signal: Option<relay_chain::v2::UpgradeGoAhead>,
+	/// The number of downward messages processed by the segment.
+	processed_downward_messages: u32,
+	/// `H` is the type of para head hash.
+	phantom_data: PhantomData<H>,
+}
+
+impl<H> SegmentTracker<H> {
+	/// Tries to append another block to the tracker, respecting given bandwidth limits.
+	pub fn append(
+		&mut self,
+		block: &Ancestor<H>,
+		hrmp_watermark: relay_chain::BlockNumber,
+		limits: &OutboundBandwidthLimits,
+	) -> Result<(), BandwidthUpdateError> {
+		if self.consumed_go_ahead_signal.is_some() && block.consumed_go_ahead_signal.is_some() {
+			return Err(BandwidthUpdateError::UpgradeGoAheadAlreadyProcessed)
+		}
+		if let Some(latest) = self.hrmp_watermark {
+			if hrmp_watermark < latest {
+				return Err(BandwidthUpdateError::InvalidHrmpWatermark {
+					submitted: hrmp_watermark,
+					latest,
+				})
+			}
+		}
+
+		self.used_bandwidth = self.used_bandwidth.append(block.used_bandwidth(), limits)?;
+
+		if let Some(consumed) = block.consumed_go_ahead_signal.as_ref() {
+			self.consumed_go_ahead_signal.replace(*consumed);
+		}
+		self.hrmp_watermark.replace(hrmp_watermark);
+		self.processed_downward_messages += block.processed_downward_messages;
+
+		Ok(())
+	}
+
+	/// Removes previously added block from the tracker.
+	pub fn subtract(&mut self, block: &Ancestor<H>) {
+		self.used_bandwidth.subtract(block.used_bandwidth());
+		if block.consumed_go_ahead_signal.is_some() {
+			self.consumed_go_ahead_signal.take();
+		}
+		self.processed_downward_messages -= block.processed_downward_messages;
+		// Watermark doesn't need to be updated since the is always dropped
+		// from the tail of the segment.
+	}
+
+	/// Return a reference to the used bandwidth across the entire segment.
+	pub fn used_bandwidth(&self) -> &UsedBandwidth {
+		&self.used_bandwidth
+	}
+
+	/// Return the latest HRMP watermark of the segment, if any block was added to it.
+	pub fn hrmp_watermark(&self) -> Option<relay_chain::BlockNumber> {
+		self.hrmp_watermark
+	}
+
+	/// Return the go-ahead signal consumed by some block of the segment, if any.
+	pub fn consumed_go_ahead_signal(&self) -> Option<relay_chain::v2::UpgradeGoAhead> {
+		self.consumed_go_ahead_signal
+	}
+
+	/// Return the number of downward messages processed by the segment.
+	pub fn processed_downward_messages(&self) -> u32 {
+		self.processed_downward_messages
+	}
+}
+
+/// Returns the length of the segment that remains after dropping everything up to and including
+/// the ancestor with the given para head hash.
+///
+/// If no ancestor matches, the whole segment is considered unincluded.
+pub fn size_after_included<H: PartialEq>(included_hash: H, segment: &[Ancestor<H>]) -> u32 {
+	let pivot = segment
+		.iter()
+		.position(|ancestor| ancestor.para_head_hash() == Some(&included_hash))
+		.map(|p| p + 1)
+		.unwrap_or(0);
+
+	(segment.len() - pivot) as u32
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use assert_matches::assert_matches;
+
+	#[test]
+	fn outbound_limits_constructed_correctly() {
+		let para_a = ParaId::from(0);
+		let para_a_channel = relay_chain::v2::AbridgedHrmpChannel {
+			max_message_size: 15,
+
+			// Msg count capacity and current msg count.
+			max_capacity: 5,
+			msg_count: 1,
+
+			// Total size capacity and current total size.
+			max_total_size: 50,
+			total_size: 10,
+
+			mqc_head: None,
+		};
+
+		let para_b = ParaId::from(1);
+		let para_b_channel = relay_chain::v2::AbridgedHrmpChannel {
+			max_message_size: 15,
+
+			// Msg count capacity and current msg count.
+			max_capacity: 40,
+			msg_count: 0,
+
+			// Total size capacity and current total size.
+			max_total_size: 500,
+			total_size: 500,
+
+			mqc_head: None,
+		};
+		let messaging_state = MessagingStateSnapshot {
+			dmq_mqc_head: relay_chain::Hash::zero(),
+			relay_dispatch_queue_size: (10, 100),
+			ingress_channels: Vec::new(),
+
+			egress_channels: vec![(para_a, para_a_channel), (para_b, para_b_channel)],
+		};
+		let host_config = AbridgedHostConfiguration {
+			max_code_size: 0,
+			max_head_data_size: 0,
+			max_upward_queue_count: 15,
+			max_upward_queue_size: 150,
+			max_upward_message_size: 0,
+			max_upward_message_num_per_candidate: 0,
+			hrmp_max_message_num_per_candidate: 0,
+			validation_upgrade_cooldown: 0,
+			validation_upgrade_delay: 0,
+		};
+
+		let limits =
+			OutboundBandwidthLimits::from_relay_chain_state(&messaging_state, &host_config);
+
+		assert_eq!(limits.ump_messages_remaining, 5);
+		assert_eq!(limits.ump_bytes_remaining, 50);
+
+		let hrmp_outgoing = limits.hrmp_outgoing;
+		assert_eq!(hrmp_outgoing.len(), 2);
+
+		let para_a_limits = hrmp_outgoing.get(&para_a).expect("channel must be present");
+		let para_b_limits = hrmp_outgoing.get(&para_b).expect("channel must be present");
+		assert_eq!(para_a_limits.bytes_remaining, 40);
+		assert_eq!(para_a_limits.messages_remaining, 4);
+		assert_eq!(para_b_limits.bytes_remaining, 0);
+		assert_eq!(para_b_limits.messages_remaining, 40);
+	}
+
+	#[test]
+	fn hrmp_msg_count_limits() {
+		let para_0 = ParaId::from(0);
+		let para_0_limits = HrmpOutboundLimits { bytes_remaining: u32::MAX, messages_remaining: 5 };
+
+		let para_1 = ParaId::from(1);
+		let para_1_limits = HrmpOutboundLimits { bytes_remaining: u32::MAX, messages_remaining: 3 };
+		let hrmp_outgoing = [(para_0, para_0_limits), (para_1, para_1_limits)].into();
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 0,
+			ump_bytes_remaining: 0,
+			hrmp_outgoing,
+		};
+
+		let mut hrmp_update = HrmpChannelUpdate::default();
+		assert!(hrmp_update.is_empty());
+
+		for _ in 0..5 {
+			hrmp_update = hrmp_update
+				.append(&HrmpChannelUpdate { msg_count: 1, total_bytes: 10 }, para_0, &limits)
+				.expect("update is within the limits");
+		}
+		assert_matches!(
+			hrmp_update.append(
+				&HrmpChannelUpdate { msg_count: 1, total_bytes: 10 },
+				para_0,
+				&limits,
+			),
+			Err(BandwidthUpdateError::HrmpMessagesOverflow {
+				recipient,
+				messages_remaining,
+				messages_submitted,
+			}) if recipient == para_0 && messages_remaining == 5 && messages_submitted == 6
+		);
+
+		let mut hrmp_update = HrmpChannelUpdate::default();
+		hrmp_update = hrmp_update
+			.append(&HrmpChannelUpdate { msg_count: 2, total_bytes: 10 }, para_1, &limits)
+			.expect("update is within the limits");
+		assert_matches!(
+			hrmp_update.append(
+				&HrmpChannelUpdate { msg_count: 3, total_bytes: 10 },
+				para_1,
+				&limits,
+			),
+			Err(BandwidthUpdateError::HrmpMessagesOverflow {
+				recipient,
+				messages_remaining,
+				messages_submitted,
+			}) if recipient == para_1 && messages_remaining == 3 && messages_submitted == 5
+		);
+	}
+
+	#[test]
+	fn hrmp_bytes_limits() {
+		let para_0 = ParaId::from(0);
+		let para_0_limits =
+			HrmpOutboundLimits { bytes_remaining: 25, messages_remaining: u32::MAX };
+
+		let hrmp_outgoing = [(para_0, para_0_limits)].into();
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 0,
+			ump_bytes_remaining: 0,
+			hrmp_outgoing,
+		};
+
+		let mut hrmp_update = HrmpChannelUpdate::default();
+		assert!(hrmp_update.is_empty());
+
+		for _ in 0..5 {
+			hrmp_update = hrmp_update
+				.append(&HrmpChannelUpdate { msg_count: 1, total_bytes: 4 }, para_0, &limits)
+				.expect("update is within the limits");
+		}
+		assert_matches!(
+			hrmp_update.append(
+				&HrmpChannelUpdate { msg_count: 1, total_bytes: 6 },
+				para_0,
+				&limits,
+			),
+			Err(BandwidthUpdateError::HrmpBytesOverflow {
+				recipient,
+				bytes_remaining,
+				bytes_submitted,
+			}) if recipient == para_0 && bytes_remaining == 25 && bytes_submitted == 26
+		);
+	}
+
+	#[test]
+	fn hrmp_limits_with_segment() {
+		let create_used_hrmp =
+			|hrmp_outgoing| UsedBandwidth { ump_msg_count: 0, ump_total_bytes: 0, hrmp_outgoing };
+
+		let para_0 = ParaId::from(0);
+		let para_0_limits = HrmpOutboundLimits { bytes_remaining: 30, messages_remaining: 10 };
+
+		let para_1 = ParaId::from(1);
+		let para_1_limits = HrmpOutboundLimits { bytes_remaining: 20, messages_remaining: 3 };
+		let hrmp_outgoing = [(para_0, para_0_limits), (para_1, para_1_limits)].into();
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 0,
+			ump_bytes_remaining: 0,
+			hrmp_outgoing,
+		};
+
+		let mut segment = SegmentTracker::default();
+
+		let para_0_update = HrmpChannelUpdate { msg_count: 1, total_bytes: 6 };
+		let ancestor_0 =
+			Ancestor::new_unchecked(create_used_hrmp([(para_0, para_0_update)].into()), None, 0);
+		segment.append(&ancestor_0, 0, &limits).expect("update is within the limits");
+
+		for watermark in 1..5 {
+			let ancestor = Ancestor::new_unchecked(
+				create_used_hrmp([(para_0, para_0_update)].into()),
+				None,
+				0,
+			);
+			segment
+				.append(&ancestor, watermark, &limits)
+				.expect("update is within the limits");
+		}
+
+		let para_0_update = HrmpChannelUpdate { msg_count: 1, total_bytes: 1 };
+		let ancestor_5 =
+			Ancestor::new_unchecked(create_used_hrmp([(para_0, para_0_update)].into()), None, 0);
+		assert_matches!(
+			segment.append(&ancestor_5, 5, &limits),
+			Err(BandwidthUpdateError::HrmpBytesOverflow {
+				recipient,
+				bytes_remaining,
+				bytes_submitted,
+			}) if recipient == para_0 && bytes_remaining == 30 && bytes_submitted == 31
+		);
+		// Remove the first ancestor from the segment to make space.
+		segment.subtract(&ancestor_0);
+		segment.append(&ancestor_5, 6, &limits).expect("update is within the limits");
+
+		let para_1_update = HrmpChannelUpdate { msg_count: 3, total_bytes: 10 };
+		let ancestor =
+			Ancestor::new_unchecked(create_used_hrmp([(para_1, para_1_update)].into()), None, 0);
+		segment.append(&ancestor, 7, &limits).expect("update is within the limits");
+
+		assert_matches!(
+			segment.append(&ancestor, 7, &limits),
+			Err(BandwidthUpdateError::HrmpMessagesOverflow {
+				recipient,
+				messages_remaining,
+				messages_submitted,
+			}) if recipient == para_1 && messages_remaining == 3 && messages_submitted == 6
+		);
+	}
+
+	#[test]
+	fn ump_limits_with_segment() {
+		let create_used_ump = |(ump_msg_count, ump_total_bytes)| UsedBandwidth {
+			ump_msg_count,
+			ump_total_bytes,
+			hrmp_outgoing: BTreeMap::default(),
+		};
+
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 7,
+			ump_bytes_remaining: 50,
+			hrmp_outgoing: BTreeMap::default(),
+		};
+
+		let mut segment = SegmentTracker::default();
+
+		let ancestor_0 = Ancestor::new_unchecked(create_used_ump((1, 10)), None, 0);
+		segment.append(&ancestor_0, 0, &limits).expect("update is within the limits");
+
+		for watermark in 1..4 {
+			let ancestor = Ancestor::new_unchecked(create_used_ump((1, 10)), None, 0);
+			segment
+				.append(&ancestor, watermark, &limits)
+				.expect("update is within the limits");
+		}
+
+		let ancestor_4 = Ancestor::new_unchecked(create_used_ump((1, 30)), None, 0);
+		assert_matches!(
+			segment.append(&ancestor_4, 4, &limits),
+			Err(BandwidthUpdateError::UmpBytesOverflow {
+				bytes_remaining,
+				bytes_submitted,
+			}) if bytes_remaining == 50 && bytes_submitted == 70
+		);
+
+		let ancestor = Ancestor::new_unchecked(create_used_ump((1, 5)), None, 0);
+		segment.append(&ancestor, 4, &limits).expect("update is within the limits");
+		segment.append(&ancestor, 5, &limits).expect("update is within the limits");
+
+		let ancestor = Ancestor::new_unchecked(create_used_ump((1, 5)), None, 0);
+		assert_matches!(
+			segment.append(&ancestor, 6, &limits),
+			Err(BandwidthUpdateError::UmpMessagesOverflow {
+				messages_remaining,
+				messages_submitted,
+			}) if messages_remaining == 7 && messages_submitted == 8
+		);
+	}
+
+	#[test]
+	fn segment_hrmp_watermark() {
+		let mut segment = SegmentTracker::default();
+
+		let ancestor = Ancestor::new_unchecked(UsedBandwidth::default(), None, 0);
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 0,
+			ump_bytes_remaining: 0,
+			hrmp_outgoing: BTreeMap::default(),
+		};
+
+		segment
+			.append(&ancestor, 0, &limits)
+			.expect("nothing to compare the watermark with");
+		segment.append(&ancestor, 2, &limits).expect("watermark increased");
+		segment
+			.append(&ancestor, 2, &limits)
+			.expect("watermark may stay on the relay parent");
+		assert_eq!(segment.hrmp_watermark(), Some(2));
+
+		assert_matches!(
+			segment.append(&ancestor, 1, &limits),
+			Err(BandwidthUpdateError::InvalidHrmpWatermark {
+				submitted,
+				latest,
+			}) if submitted == 1 && latest == 2
+		);
+	}
+
+	#[test]
+	fn segment_drops_empty_hrmp_channels() {
+		let create_used_hrmp =
+			|hrmp_outgoing| UsedBandwidth { ump_msg_count: 0, ump_total_bytes: 0, hrmp_outgoing };
+
+		let para_0 = ParaId::from(0);
+		let para_0_limits =
+			HrmpOutboundLimits { bytes_remaining: u32::MAX, messages_remaining: u32::MAX };
+
+		let para_1 = ParaId::from(1);
+		let para_1_limits =
+			HrmpOutboundLimits { bytes_remaining: u32::MAX, messages_remaining: u32::MAX };
+		let hrmp_outgoing = [(para_0, para_0_limits), (para_1, para_1_limits)].into();
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 0,
+			ump_bytes_remaining: 0,
+			hrmp_outgoing,
+		};
+
+		let mut segment = SegmentTracker::default();
+
+		let para_0_update = HrmpChannelUpdate { msg_count: 1, total_bytes: 1 };
+		let ancestor_0 =
+			Ancestor::new_unchecked(create_used_hrmp([(para_0, para_0_update)].into()), None, 0);
+		segment.append(&ancestor_0, 0, &limits).expect("update is within the limits");
+		let para_1_update = HrmpChannelUpdate { msg_count: 3, total_bytes: 10 };
+		let ancestor_1 =
+			Ancestor::new_unchecked(create_used_hrmp([(para_1, para_1_update)].into()), None, 0);
+		segment.append(&ancestor_1, 1, &limits).expect("update is within the limits");
+
+		assert_eq!(segment.used_bandwidth.hrmp_outgoing.len(), 2);
+
+		segment.subtract(&ancestor_0);
+		assert_eq!(segment.used_bandwidth.hrmp_outgoing.len(), 1);
+
+		segment.subtract(&ancestor_1);
+		assert_eq!(segment.used_bandwidth.hrmp_outgoing.len(), 0);
+	}
+
+	#[test]
+	fn segment_go_ahead_signal_is_unique() {
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 0,
+			ump_bytes_remaining: 0,
+			hrmp_outgoing: BTreeMap::default(),
+		};
+
+		let mut segment = SegmentTracker::default();
+
+		let ancestor_0 = Ancestor::new_unchecked(
+			UsedBandwidth::default(),
+			Some(relay_chain::v2::UpgradeGoAhead::GoAhead),
+			0,
+		);
+		segment.append(&ancestor_0, 0, &limits).expect("signal is not yet consumed");
+		assert_eq!(
+			segment.consumed_go_ahead_signal(),
+			Some(relay_chain::v2::UpgradeGoAhead::GoAhead)
+		);
+
+		let ancestor_1 = Ancestor::new_unchecked(
+			UsedBandwidth::default(),
+			Some(relay_chain::v2::UpgradeGoAhead::Abort),
+			0,
+		);
+		assert_matches!(
+			segment.append(&ancestor_1, 0, &limits),
+			Err(BandwidthUpdateError::UpgradeGoAheadAlreadyProcessed)
+		);
+
+		segment.subtract(&ancestor_0);
+		assert_eq!(segment.consumed_go_ahead_signal(), None);
+		segment
+			.append(&ancestor_1, 0, &limits)
+			.expect("signal of the ancestor was dropped");
+	}
+
+	#[test]
+	fn segment_tracks_processed_downward_messages() {
+		let limits = OutboundBandwidthLimits {
+			ump_messages_remaining: 0,
+			ump_bytes_remaining: 0,
+			hrmp_outgoing: BTreeMap::default(),
+		};
+
+		let mut segment = SegmentTracker::default();
+
+		let ancestor_0 = Ancestor::new_unchecked(UsedBandwidth::default(), None, 3);
+		let ancestor_1 = Ancestor::new_unchecked(UsedBandwidth::default(), None, 2);
+		segment.append(&ancestor_0, 0, &limits).expect("update is within the limits");
+		segment.append(&ancestor_1, 0, &limits).expect("update is within the limits");
+		assert_eq!(segment.processed_downward_messages(), 5);
+
+		segment.subtract(&ancestor_0);
+		assert_eq!(segment.processed_downward_messages(), 2);
+	}
+
+	#[test]
+	fn size_after_included_works() {
+		let segment = vec![
+			Ancestor {
+				para_head_hash: Some(0),
+				..Ancestor::new_unchecked(Default::default(), None, 0)
+			},
+			Ancestor {
+				para_head_hash: Some(1),
+				..Ancestor::new_unchecked(Default::default(), None, 0)
+			},
+			Ancestor {
+				para_head_hash: Some(2),
+				..Ancestor::new_unchecked(Default::default(), None, 0)
+			},
+		];
+
+		assert_eq!(size_after_included(0, &segment), 2);
+		assert_eq!(size_after_included(1, &segment), 1);
+		assert_eq!(size_after_included(2, &segment), 0);
+		assert_eq!(size_after_included(3, &segment), 3);
+	}
+}
@@ -23,6 +23,7 @@ use frame_system::RawOrigin;
 benchmarks! {
 	set_config_with_u32 {}: update_resume_threshold(RawOrigin::Root, 100)
 	set_config_with_weight {}: update_weight_restrict_decay(RawOrigin::Root, 3_000_000 as Weight)
+	set_inbound_channel_config {}: update_inbound_priority(RawOrigin::Root, 1000.into(), true)
 }
 
 impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -22,9 +22,14 @@
 //! Also provides an implementation of `SendXcm` which can be placed in a router tuple for relaying
 //! XCM over XCMP if the destination is `Parent/Parachain`. It requires an implementation of
 //! `XcmExecutor` for dispatching incoming XCM messages.
+//!
+//! Overweight messages that are not serviced within `OverweightExpiry` blocks are dropped, and the
+//! assets they carried that the sender is trusted for, by `IsReserve` or `IsTeleporter`, are placed
+//! into the `AssetTrap`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod metrics;
 pub mod migration;
 
 #[cfg(test)]
@@ -38,30 +43,44 @@ mod benchmarking;
 pub mod weights;
 pub use weights::WeightInfo;
 
-use codec::{Decode, DecodeLimit, Encode};
+use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
 use forests_primitives_core::{
-	relay_chain::BlockNumber as RelayBlockNumber, ChannelStatus, GetChannelInfo, MessageSendError,
-	ParaId, XcmpMessageFormat, XcmpMessageHandler, XcmpMessageSource,
+	assets_in_transit, relay_chain::BlockNumber as RelayBlockNumber, ChannelStatus, GetChannelInfo,
+	MessageSendError, OverweightMessage, ParaId, QueuedMessage, XcmpMessageFormat,
+	XcmpMessageHandler, XcmpMessageSource,
 };
 use frame_support::{
-	traits::EnsureOrigin,
+	traits::{EnsureOrigin, Get},
 	weights::{constants::WEIGHT_PER_MILLIS, Weight},
+	BoundedVec,
 };
 use rand_chacha::{
 	rand_core::{RngCore, SeedableRng},
 	ChaChaRng,
 };
 use scale_info::TypeInfo;
-use sp_runtime::{traits::Hash, RuntimeDebug};
+use sp_runtime::{
+	traits::{Hash, Saturating, Zero},
+	RuntimeDebug,
+};
 use sp_std::{convert::TryFrom, prelude::*};
 use xcm::{latest::prelude::*, VersionedXcm, WrapVersion, MAX_XCM_DECODE_DEPTH};
-use xcm_executor::traits::ConvertOrigin;
+use xcm_executor::traits::{ConvertOrigin, DropAssets, FilterAssetLocation};
+
+use metrics::METRICS;
 
 pub use pallet::*;
 
 /// Index used to identify overweight XCMs.
 pub type OverweightIndex = u64;
 
+/// The messages a sender sent in a single relay chain block, bounded by `MaxInboundPageSize`.
+pub type InboundPageOf<T> = BoundedVec<u8, <T as Config>::MaxInboundPageSize>;
+
+/// The overweight record type of a runtime.
+pub type OverweightRecordOf<T> =
+	OverweightRecord<<T as frame_system::Config>::BlockNumber, InboundPageOf<T>>;
+
 const LOG_TARGET: &str = "xcmp_queue";
 
 #[frame_support::pallet]
@@ -99,6 +118,28 @@ pub mod pallet {
 		/// superuser origin.
 		type ControllerOriginConverter: ConvertOrigin<Self::Origin>;
 
+		/// The maximum encoded length of the messages a sender may send in a single relay chain
+		/// block. This should be at least the relay chain's `hrmp_channel_max_message_size`; longer
+		/// pages are dropped instead of queued.
+		#[pallet::constant]
+		type MaxInboundPageSize: Get<u32>;
+
+		/// The number of blocks after which an overweight message that was not serviced expires.
+		/// Zero disables expiry.
+		#[pallet::constant]
+		type OverweightExpiry: Get<Self::BlockNumber>;
+
+		/// Where the assets carried by expired overweight messages are dropped.
+		type AssetTrap: DropAssets;
+
+		/// Which reserve-transferred assets are trusted from which origins, as configured for the
+		/// `XcmExecutor`. Only trusted assets of expired overweight messages are trapped.
+		type IsReserve: FilterAssetLocation;
+
+		/// Which teleported assets are trusted from which origins, as configured for the
+		/// `XcmExecutor`. Only trusted assets of expired overweight messages are trapped.
+		type IsTeleporter: FilterAssetLocation;
+
 		/// The weight information of this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -109,9 +150,10 @@ pub mod pallet {
 			migration::migrate_to_latest::<T>()
 		}
 
-		fn on_idle(_now: T::BlockNumber, max_weight: Weight) -> Weight {
+		fn on_idle(now: T::BlockNumber, max_weight: Weight) -> Weight {
 			// on_idle processes additional messages with any remaining block weight.
-			Self::service_xcmp_queue(max_weight)
+			let used = Self::service_xcmp_queue(max_weight);
+			used.saturating_add(Self::expire_overweight(now, max_weight.saturating_sub(used)))
 		}
 	}
 
@@ -138,14 +180,13 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			T::ExecuteOverweightOrigin::ensure_origin(origin)?;
 
-			let (sender, sent_at, data) =
-				Overweight::<T>::get(index).ok_or(Error::<T>::BadOverweightIndex)?;
+			let record = Overweight::<T>::get(index).ok_or(Error::<T>::BadOverweightIndex)?;
 			let xcm = VersionedXcm::<T::Call>::decode_all_with_depth_limit(
 				MAX_XCM_DECODE_DEPTH,
-				&mut data.as_slice(),
+				&mut &record.message[..],
 			)
 			.map_err(|_| Error::<T>::BadXcm)?;
-			let used = Self::handle_xcm_message(sender, sent_at, xcm, weight_limit)
+			let used = Self::handle_xcm_message(record.sender, record.sent_at, xcm, weight_limit)
 				.map_err(|_| Error::<T>::WeightOverLimit)?;
 			Overweight::<T>::remove(index);
 			Self::deposit_event(Event::OverweightServiced { index, used });
@@ -257,6 +298,78 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Suspends XCM execution for messages from a single sender, leaving all other inbound
+		/// channels untouched.
+		///
+		/// Messages from `sender` keep being queued and the usual backpressure applies.
+		///
+		/// - `origin`: Must pass `ControllerOrigin`.
+		/// - `sender`: The parachain whose messages should no longer be executed.
+		#[pallet::weight((T::WeightInfo::set_inbound_channel_config(), DispatchClass::Operational,))]
+		pub fn suspend_inbound_channel(origin: OriginFor<T>, sender: ParaId) -> DispatchResult {
+			T::ControllerOrigin::ensure_origin(origin)?;
+			Self::mutate_inbound_channel_config(sender, |config| config.suspended = true);
+			Self::deposit_event(Event::InboundChannelSuspended { sender });
+
+			Ok(())
+		}
+
+		/// Resumes XCM execution for messages from `sender` after `suspend_inbound_channel`.
+		///
+		/// - `origin`: Must pass `ControllerOrigin`.
+		/// - `sender`: The parachain whose messages should be executed again.
+		#[pallet::weight((T::WeightInfo::set_inbound_channel_config(), DispatchClass::Operational,))]
+		pub fn resume_inbound_channel(origin: OriginFor<T>, sender: ParaId) -> DispatchResult {
+			T::ControllerOrigin::ensure_origin(origin)?;
+			Self::mutate_inbound_channel_config(sender, |config| config.suspended = false);
+			Self::deposit_event(Event::InboundChannelResumed { sender });
+
+			Ok(())
+		}
+
+		/// Overwrites the maximum amount of weight messages from `sender` may use per block.
+		///
+		/// - `origin`: Must pass `Root`.
+		/// - `sender`: The parachain the quota applies to.
+		/// - `quota`: Desired value for `InboundChannelConfig.max_weight_per_block`. `None` removes
+		///   the limit.
+		#[pallet::weight((T::WeightInfo::set_inbound_channel_config(), DispatchClass::Operational,))]
+		pub fn update_inbound_weight_quota(
+			origin: OriginFor<T>,
+			sender: ParaId,
+			quota: Option<Weight>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::mutate_inbound_channel_config(sender, |config| {
+				config.max_weight_per_block = quota
+			});
+			if quota.is_none() {
+				<InboundWeightUsed<T>>::remove(sender);
+			}
+			Self::deposit_event(Event::InboundWeightQuotaUpdated { sender, quota });
+
+			Ok(())
+		}
+
+		/// Overwrites whether messages from `sender` are serviced before those of all other,
+		/// non-priority channels. Meant for system parachains.
+		///
+		/// - `origin`: Must pass `Root`.
+		/// - `sender`: The parachain the setting applies to.
+		/// - `priority`: Desired value for `InboundChannelConfig.priority`.
+		#[pallet::weight((T::WeightInfo::set_inbound_channel_config(), DispatchClass::Operational,))]
+		pub fn update_inbound_priority(
+			origin: OriginFor<T>,
+			sender: ParaId,
+			priority: bool,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::mutate_inbound_channel_config(sender, |config| config.priority = priority);
+			Self::deposit_event(Event::InboundPriorityUpdated { sender, priority });
+
+			Ok(())
+		}
 	}
 
 	#[pallet::event]
@@ -283,6 +396,21 @@ pub mod pallet {
 		},
 		/// An XCM from the overweight queue was executed with the given actual weight used.
 		OverweightServiced { index: OverweightIndex, used: Weight },
+		/// Execution of messages from a sender was suspended.
+		InboundChannelSuspended { sender: ParaId },
+		/// Execution of messages from a sender was resumed.
+		InboundChannelResumed { sender: ParaId },
+		/// The per-block weight quota of a sender was changed.
+		InboundWeightQuotaUpdated { sender: ParaId, quota: Option<Weight> },
+		/// The priority of a sender was changed.
+		InboundPriorityUpdated { sender: ParaId, priority: bool },
+		/// A sender used up its weight quota for this block; its remaining messages are deferred.
+		InboundWeightQuotaExhausted { sender: ParaId, used: Weight },
+		/// An XCM from the overweight queue was not serviced in time and was dropped.
+		OverweightExpired { index: OverweightIndex },
+		/// The messages a sender sent in a relay chain block are longer than `MaxInboundPageSize`
+		/// and could not be queued.
+		OversizedPageDropped { sender: ParaId, sent_at: RelayBlockNumber },
 	}
 
 	#[pallet::error]
@@ -312,7 +440,7 @@ pub mod pallet {
 		ParaId,
 		Twox64Concat,
 		RelayBlockNumber,
-		Vec<u8>,
+		InboundPageOf<T>,
 		ValueQuery,
 	>;
 
@@ -347,16 +475,32 @@ pub mod pallet {
 	/// `service_overweight`.
 	#[pallet::storage]
 	pub(super) type Overweight<T: Config> =
-		StorageMap<_, Twox64Concat, OverweightIndex, (ParaId, RelayBlockNumber, Vec<u8>)>;
+		StorageMap<_, Twox64Concat, OverweightIndex, OverweightRecordOf<T>>;
 
 	/// The number of overweight messages ever recorded in `Overweight`. Also doubles as the next
 	/// available free overweight index.
 	#[pallet::storage]
 	pub(super) type OverweightCount<T: Config> = StorageValue<_, OverweightIndex, ValueQuery>;
 
+	/// The lowest overweight index that has not yet been checked for expiry.
+	#[pallet::storage]
+	pub(super) type OverweightExpiryCursor<T: Config> =
+		StorageValue<_, OverweightIndex, ValueQuery>;
+
 	/// Whether or not the XCMP queue is suspended from executing incoming XCMs or not.
 	#[pallet::storage]
 	pub(super) type QueueSuspended<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Per-sender configuration of the inbound channels. Senders without an entry use the
+	/// default configuration.
+	#[pallet::storage]
+	pub(super) type InboundChannelConfigs<T: Config> =
+		StorageMap<_, Blake2_128Concat, ParaId, InboundChannelConfig, ValueQuery>;
+
+	/// The weight used by messages of each sender with a quota, and the block it was used in.
+	#[pallet::storage]
+	pub(super) type InboundWeightUsed<T: Config> =
+		StorageMap<_, Blake2_128Concat, ParaId, (T::BlockNumber, Weight), OptionQuery>;
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug, TypeInfo)]
@@ -385,6 +529,21 @@ pub struct InboundChannelDetails {
 	message_metadata: Vec<(RelayBlockNumber, XcmpMessageFormat)>,
 }
 
+/// An overweight XCM along with what is needed to service or expire it.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct OverweightRecord<BlockNumber, Message> {
+	/// The parachain which sent the message.
+	pub sender: ParaId,
+	/// The relay chain block number at which the message was sent.
+	pub sent_at: RelayBlockNumber,
+	/// The block number at which the message was put into the overweight queue.
+	pub enqueued_at: BlockNumber,
+	/// The weight the message requires to be executed.
+	pub required_weight: Weight,
+	/// The encoded message.
+	pub message: Message,
+}
+
 /// Struct containing detailed information about the outbound channel.
 #[derive(Clone, Eq, PartialEq, Encode, Decode, TypeInfo)]
 pub struct OutboundChannelDetails {
@@ -456,6 +615,18 @@ impl Default for QueueConfigData {
 	}
 }
 
+/// Configuration of the inbound channel of a single sender.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct InboundChannelConfig {
+	/// The maximum amount of weight messages from the sender may use per block. `None` means no
+	/// limit besides the weight available to the queue.
+	pub max_weight_per_block: Option<Weight>,
+	/// Whether execution of messages from the sender is suspended.
+	pub suspended: bool,
+	/// Whether the channel is serviced before all non-priority channels.
+	pub priority: bool,
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, TypeInfo)]
 pub enum ChannelSignal {
 	Suspend,
@@ -567,6 +738,23 @@ impl<T: Config> Pallet<T> {
 		Self::send_fragment(recipient, XcmpMessageFormat::ConcatenatedVersionedXcm, xcm)
 	}
 
+	/// Changes the inbound channel configuration of `sender`, removing it once it is the default.
+	fn mutate_inbound_channel_config(sender: ParaId, f: impl FnOnce(&mut InboundChannelConfig)) {
+		<InboundChannelConfigs<T>>::mutate_exists(sender, |maybe_config| {
+			let mut config = maybe_config.unwrap_or_default();
+			f(&mut config);
+			*maybe_config = (config != InboundChannelConfig::default()).then(|| config);
+		});
+	}
+
+	/// The weight used by messages from `sender` in the current block.
+	pub fn inbound_weight_used(sender: ParaId) -> Weight {
+		match <InboundWeightUsed<T>>::get(sender) {
+			Some((at, used)) if at == frame_system::Pallet::<T>::block_number() => used,
+			_ => 0,
+		}
+	}
+
 	fn create_shuffle(len: usize) -> Vec<usize> {
 		// Create a shuffled order for use to iterate through.
 		// Not a great random seed, but good enough for our purposes.
@@ -623,11 +811,20 @@ impl<T: Config> Pallet<T> {
 		result
 	}
 
+	/// Execute the messages of the page `sent_at` of `sender` using up to `max_weight`.
+	///
+	/// Messages needing more than `max_individual_weight` are moved to the overweight queue. A
+	/// message that does not fit into what is left of `max_weight` stays in the page and ends its
+	/// processing if it needs at most `deferrable_weight`, or `max_weight` if that is larger.
+	/// Heavier messages are dropped, as they are not expected to ever fit.
+	///
+	/// Returns the weight used and whether the page is now empty.
 	fn process_xcmp_message(
 		sender: ParaId,
 		(sent_at, format): (RelayBlockNumber, XcmpMessageFormat),
 		max_weight: Weight,
 		max_individual_weight: Weight,
+		deferrable_weight: Weight,
 	) -> (Weight, bool) {
 		let data = <InboundXcmpMessages<T>>::get(sender, sent_at);
 		let mut last_remaining_fragments;
@@ -652,14 +849,22 @@ impl<T: Config> Pallet<T> {
 								let msg_len = last_remaining_fragments
 									.len()
 									.saturating_sub(remaining_fragments.len());
-								let overweight_xcm = last_remaining_fragments[..msg_len].to_vec();
-								let index = Self::stash_overweight(sender, sent_at, overweight_xcm);
+								let overweight_xcm = InboundPageOf::<T>::try_from(
+									last_remaining_fragments[..msg_len].to_vec(),
+								)
+								.expect("a part of a bounded page is bounded; qed");
+								let index = Self::stash_overweight(
+									sender,
+									sent_at,
+									overweight_xcm,
+									required,
+								);
 								let e =
 									Event::OverweightEnqueued { sender, sent_at, index, required };
 								Self::deposit_event(e);
 							},
 							Err(XcmError::WeightLimitReached(required))
-								if required <= max_weight =>
+								if required <= max_weight.max(deferrable_weight) =>
 							{
 								// That message didn't get processed this time because of being
 								// too heavy. We leave it around for next time and bail.
@@ -713,7 +918,9 @@ impl<T: Config> Pallet<T> {
 		if is_empty {
 			<InboundXcmpMessages<T>>::remove(sender, sent_at);
 		} else {
-			<InboundXcmpMessages<T>>::insert(sender, sent_at, remaining_fragments);
+			let remaining = InboundPageOf::<T>::try_from(remaining_fragments.to_vec())
+				.expect("a part of a bounded page is bounded; qed");
+			<InboundXcmpMessages<T>>::insert(sender, sent_at, remaining);
 		}
 		(weight_used, is_empty)
 	}
@@ -722,7 +929,8 @@ impl<T: Config> Pallet<T> {
 	fn stash_overweight(
 		sender: ParaId,
 		sent_at: RelayBlockNumber,
-		xcm: Vec<u8>,
+		message: InboundPageOf<T>,
+		required_weight: Weight,
 	) -> OverweightIndex {
 		let index = <Self as Store>::OverweightCount::mutate(|count| {
 			let index = *count;
@@ -730,10 +938,128 @@ impl<T: Config> Pallet<T> {
 			index
 		});
 
-		<Self as Store>::Overweight::insert(index, (sender, sent_at, xcm));
+		let enqueued_at = frame_system::Pallet::<T>::block_number();
+		<Self as Store>::Overweight::insert(
+			index,
+			OverweightRecord { sender, sent_at, enqueued_at, required_weight, message },
+		);
 		index
 	}
 
+	/// Drop the overweight messages that were enqueued at least `OverweightExpiry` blocks before
+	/// `now`, placing the assets they carried into the `AssetTrap`.
+	///
+	/// Returns the weight consumed, which is kept below `limit` unless trapping the assets of an
+	/// expired message turns out heavier than expected.
+	pub(crate) fn expire_overweight(now: T::BlockNumber, limit: Weight) -> Weight {
+		let expiry = T::OverweightExpiry::get();
+		let mut used = T::DbWeight::get().reads(2);
+		if expiry.is_zero() || used > limit {
+			return 0
+		}
+
+		let overweight_count = <OverweightCount<T>>::get();
+		let step = T::DbWeight::get().reads_writes(1, 1);
+		let mut cursor = <OverweightExpiryCursor<T>>::get();
+		while cursor < overweight_count && used.saturating_add(step) <= limit {
+			used += step;
+			match <Overweight<T>>::get(cursor) {
+				None => {},
+				Some(record) if record.enqueued_at.saturating_add(expiry) <= now => {
+					<Overweight<T>>::remove(cursor);
+					used = used.saturating_add(Self::trap_assets(record.sender, &record.message));
+					Self::deposit_event(Event::OverweightExpired { index: cursor });
+				},
+				// messages are enqueued in order, so none of the later ones expired either.
+				Some(_) => break,
+			}
+			cursor += 1;
+		}
+		<OverweightExpiryCursor<T>>::put(cursor);
+		used.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	/// Drop the assets carried by the encoded message `data` of `sender` that `sender` is trusted
+	/// for into the `AssetTrap`.
+	fn trap_assets(sender: ParaId, mut data: &[u8]) -> Weight {
+		let maybe_msg =
+			VersionedXcm::<()>::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut data)
+				.map(Xcm::<()>::try_from);
+		match maybe_msg {
+			Ok(Ok(message)) => {
+				let origin = MultiLocation::new(1, X1(Parachain(sender.into())));
+				let assets = assets_in_transit(
+					&message,
+					|asset| T::IsReserve::filter_asset_location(asset, &origin),
+					|asset| T::IsTeleporter::filter_asset_location(asset, &origin),
+				);
+				if assets.is_none() {
+					0
+				} else {
+					T::AssetTrap::drop_assets(&origin, assets.into())
+				}
+			},
+			_ => 0,
+		}
+	}
+
+	/// The queued messages of `sender`, one page per relay chain block, in the order they will be
+	/// executed.
+	pub fn queued_pages(sender: ParaId) -> Vec<Vec<QueuedMessage>> {
+		let status = <InboundXcmpStatus<T>>::get();
+		let metadata = match status.iter().find(|s| s.sender == sender) {
+			Some(details) => details.message_metadata.clone(),
+			None => return Vec::new(),
+		};
+		metadata
+			.into_iter()
+			.map(|(sent_at, format)| {
+				let data = <InboundXcmpMessages<T>>::get(sender, sent_at);
+				let mut remaining = &data[..];
+				let mut page = Vec::new();
+				while !remaining.is_empty() {
+					let message = match format {
+						XcmpMessageFormat::ConcatenatedVersionedXcm =>
+							match VersionedXcm::<()>::decode_with_depth_limit(
+								MAX_XCM_DECODE_DEPTH,
+								&mut remaining,
+							) {
+								Ok(xcm) => Some(xcm),
+								Err(_) => break,
+							},
+						XcmpMessageFormat::ConcatenatedEncodedBlob =>
+							match <Vec<u8>>::decode(&mut remaining) {
+								Ok(_) => None,
+								Err(_) => break,
+							},
+						XcmpMessageFormat::Signals => break,
+					};
+					page.push(QueuedMessage { sent_at, message });
+				}
+				page
+			})
+			.collect()
+	}
+
+	/// All messages in the overweight queue, ordered by index.
+	pub fn overweight_messages() -> Vec<OverweightMessage> {
+		let mut messages = <Overweight<T>>::iter()
+			.map(|(index, record)| OverweightMessage {
+				index,
+				sender: Some(record.sender),
+				sent_at: record.sent_at,
+				required_weight: record.required_weight,
+				message: VersionedXcm::<()>::decode_all_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &record.message[..],
+				)
+				.ok(),
+			})
+			.collect::<Vec<_>>();
+		messages.sort_by_key(|m| m.index);
+		messages
+	}
+
 	/// Service the incoming XCMP message queue attempting to execute up to `max_weight` execution
 	/// weight of messages.
 	///
@@ -761,6 +1087,13 @@ impl<T: Config> Pallet<T> {
 	/// half of the `max_weight` available for the first page, then a quarter plus the remainder
 	/// for the second &c. though empirical and or practical factors may give rise to adjusting it
 	/// further.
+	///
+	/// On top of that, each sender is subject to its [`InboundChannelConfig`]: suspended senders
+	/// are skipped, senders with a weight quota are not given more than what is left of it in this
+	/// block, and priority senders are serviced before all others. Messages needing more weight
+	/// than the whole quota of their sender could never be executed, so they are moved to the
+	/// overweight queue. Reading the configurations and quota usage is paid for out of
+	/// `max_weight`.
 	fn service_xcmp_queue(max_weight: Weight) -> Weight {
 		let suspended = QueueSuspended::<T>::get();
 
@@ -777,8 +1110,26 @@ impl<T: Config> Pallet<T> {
 			..
 		} = <QueueConfig<T>>::get();
 
+		let configs = status
+			.iter()
+			.map(|channel| <InboundChannelConfigs<T>>::get(channel.sender))
+			.collect::<Vec<_>>();
+		let mut sender_weight_used = status
+			.iter()
+			.zip(configs.iter())
+			.map(|(channel, config)| {
+				config
+					.max_weight_per_block
+					.map_or(0, |_| Self::inbound_weight_used(channel.sender))
+			})
+			.collect::<Vec<_>>();
+
+		let quota_count = configs.iter().filter(|c| c.max_weight_per_block.is_some()).count();
+
 		let mut shuffled = Self::create_shuffle(status.len());
-		let mut weight_used = 0;
+		// The sort is stable, so priority channels keep their shuffled order among each other.
+		shuffled.sort_by_key(|&index| !configs[index].priority);
+		let mut weight_used = T::DbWeight::get().reads((status.len() + quota_count) as Weight);
 		let mut weight_available = 0;
 
 		// We don't want the possibility of a chain sending a series of really heavy messages and
@@ -807,6 +1158,21 @@ impl<T: Config> Pallet<T> {
 				continue
 			}
 
+			if configs[index].suspended {
+				METRICS.on_channel_suspended();
+				shuffle_index += 1;
+				continue
+			}
+
+			let quota = configs[index].max_weight_per_block;
+			let quota_remaining =
+				quota.map(|quota| quota.saturating_sub(sender_weight_used[index]));
+			if quota_remaining == Some(0) {
+				METRICS.on_quota_exhausted();
+				shuffle_index += 1;
+				continue
+			}
+
 			if weight_available != max_weight {
 				// Get incrementally closer to freeing up max_weight for message execution over the
 				// first round. For the second round we unlock all weight. If we come close enough
@@ -828,18 +1194,35 @@ impl<T: Config> Pallet<T> {
 			} else {
 				// Process up to one block's worth for now.
 				let weight_remaining = weight_available.saturating_sub(weight_used);
+				let weight_remaining =
+					quota_remaining.map_or(weight_remaining, |quota| quota.min(weight_remaining));
+				// Messages that fit into the quota, and into a block, but not into what is left
+				// of them are kept for the next block.
 				let (weight_processed, is_empty) = Self::process_xcmp_message(
 					sender,
 					status[index].message_metadata[0],
 					weight_remaining,
-					xcmp_max_individual_weight,
+					quota.map_or(xcmp_max_individual_weight, |q| q.min(xcmp_max_individual_weight)),
+					quota.map_or(0, |q| q.min(max_weight)),
 				);
+				METRICS.on_weight_used(weight_processed);
 				if is_empty {
 					status[index].message_metadata.remove(0);
 				}
 				weight_processed
 			};
 			weight_used += weight_processed;
+			sender_weight_used[index] = sender_weight_used[index].saturating_add(weight_processed);
+
+			let quota_exhausted = configs[index]
+				.max_weight_per_block
+				.map_or(false, |quota| sender_weight_used[index] >= quota);
+			if quota_exhausted && !status[index].message_metadata.is_empty() {
+				Self::deposit_event(Event::InboundWeightQuotaExhausted {
+					sender,
+					used: sender_weight_used[index],
+				});
+			}
 
 			if status[index].message_metadata.len() as u32 <= resume_threshold &&
 				status[index].state == InboundState::Suspended
@@ -854,7 +1237,7 @@ impl<T: Config> Pallet<T> {
 			// other channels a look in. If we've still not unlocked all weight, then we set them
 			// up for processing a second time anyway.
 			if !status[index].message_metadata.is_empty() &&
-				(weight_processed > 0 || weight_available != max_weight)
+				!quota_exhausted && (weight_processed > 0 || weight_available != max_weight)
 			{
 				if shuffle_index + 1 == shuffled.len() {
 					// Only this queue left. Just run around this loop once more.
@@ -865,6 +1248,16 @@ impl<T: Config> Pallet<T> {
 			shuffle_index += 1;
 		}
 
+		let now = frame_system::Pallet::<T>::block_number();
+		for ((channel, config), used) in
+			status.iter().zip(configs.iter()).zip(sender_weight_used.into_iter())
+		{
+			if config.max_weight_per_block.is_some() && used > 0 {
+				<InboundWeightUsed<T>>::insert(channel.sender, (now, used));
+				weight_used = weight_used.saturating_add(T::DbWeight::get().writes(1));
+			}
+		}
+
 		// Only retain the senders that have non-empty queues.
 		status.retain(|item| !item.message_metadata.is_empty());
 
@@ -936,6 +1329,13 @@ impl<T: Config> XcmpMessageHandler for Pallet<T> {
 					}
 				}
 			} else {
+				let page = match InboundPageOf::<T>::try_from(data_ref.to_vec()) {
+					Ok(page) => page,
+					Err(_) => {
+						Self::deposit_event(Event::OversizedPageDropped { sender, sent_at });
+						continue
+					},
+				};
 				// Record the fact we received it.
 				match status.binary_search_by_key(&sender, |item| item.sender) {
 					Ok(i) => {
@@ -966,7 +1366,7 @@ impl<T: Config> XcmpMessageHandler for Pallet<T> {
 					}),
 				}
 				// Queue the payload for later execution.
-				<InboundXcmpMessages<T>>::insert(sender, sent_at, data_ref);
+				<InboundXcmpMessages<T>>::insert(sender, sent_at, page);
 			}
 
 			// Optimization note; it would make sense to execute messages immediately if
@@ -0,0 +1,51 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime declaration of the inbound XCMP queue metrics.
+
+use forests_primitives_core::relay_chain::v2::metric_definitions::{
+	XCMP_QUEUE_INBOUND_CHANNELS_SKIPPED, XCMP_QUEUE_INBOUND_WEIGHT_USED,
+};
+use selendra_runtime_metrics::{Counter, CounterVec};
+
+pub struct Metrics {
+	/// Counts the inbound channels skipped while servicing the queue.
+	channels_skipped: CounterVec,
+	/// Counts the weight used executing inbound messages.
+	weight_used: Counter,
+}
+
+impl Metrics {
+	/// Increment the number of channels skipped because they were suspended.
+	pub fn on_channel_suspended(&self) {
+		self.channels_skipped.with_label_values(&["suspended"]).inc();
+	}
+
+	/// Increment the number of channels skipped because their weight quota was used up.
+	pub fn on_quota_exhausted(&self) {
+		self.channels_skipped.with_label_values(&["exhausted"]).inc();
+	}
+
+	/// Add to the weight used executing inbound messages.
+	pub fn on_weight_used(&self, value: u64) {
+		self.weight_used.inc_by(value);
+	}
+}
+
+pub const METRICS: Metrics = Metrics {
+	channels_skipped: CounterVec::new(XCMP_QUEUE_INBOUND_CHANNELS_SKIPPED),
+	weight_used: Counter::new(XCMP_QUEUE_INBOUND_WEIGHT_USED),
+};
@@ -16,11 +16,17 @@
 
 //! A module that is responsible for migration of storage.
 
-use crate::{Config, Pallet, Store};
+use crate::{Config, Event, InboundPageOf, OverweightRecord, Pallet, Store};
+use codec::DecodeLimit;
+use forests_primitives_core::{
+	relay_chain::BlockNumber as RelayBlockNumber, ParaId, XcmpMessageFormat,
+};
 use frame_support::{pallet_prelude::*, traits::StorageVersion, weights::Weight};
+use sp_std::prelude::*;
+use xcm::{VersionedXcm, MAX_XCM_DECODE_DEPTH};
 
 /// The current storage version.
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 /// Migrates the pallet storage to the most recent version, checking and setting the
 /// `StorageVersion`.
@@ -32,6 +38,11 @@ pub fn migrate_to_latest<T: Config>() -> Weight {
 		StorageVersion::new(1).put::<Pallet<T>>();
 	}
 
+	if StorageVersion::get::<Pallet<T>>() == 1 {
+		weight += migrate_to_v2::<T>();
+		StorageVersion::new(2).put::<Pallet<T>>();
+	}
+
 	weight
 }
 
@@ -61,6 +72,12 @@ mod v0 {
 	}
 }
 
+mod v1 {
+	use super::*;
+
+	pub type OverweightEntry = (ParaId, RelayBlockNumber, Vec<u8>);
+}
+
 /// Migrates `QueueConfigData` from v0 (without the `xcmp_max_individual_weight` field) to v1 (with
 /// max individual weight).
 /// Uses the `Default` implementation of `QueueConfigData` to choose a value for
@@ -91,6 +108,117 @@ pub fn migrate_to_v1<T: Config>() -> Weight {
 	T::DbWeight::get().reads_writes(1, 1)
 }
 
+/// Migrates `InboundXcmpMessages` and `Overweight` from v1 (unbounded) to v2 (bounded by
+/// `MaxInboundPageSize`). Overweight messages are considered enqueued at the block of the upgrade,
+/// with an unknown required weight of zero.
+///
+/// Pages longer than `MaxInboundPageSize` are split into their messages, which are kept as
+/// overweight messages. Messages that are still too long, and blob pages, are dropped with an
+/// `OversizedPageDropped` event. Overweight messages that are too long are dropped with an
+/// `OverweightExpired` event.
+///
+/// NOTE: Only use this function if you know what you're doing. Default to using
+/// `migrate_to_latest`.
+pub fn migrate_to_v2<T: Config>() -> Weight {
+	let mut count: u64 = 0;
+
+	let mut oversized = Vec::new();
+	<Pallet<T> as Store>::InboundXcmpMessages::translate::<Vec<u8>, _>(|sender, sent_at, page| {
+		count += 1;
+		match InboundPageOf::<T>::try_from(page.clone()) {
+			Ok(page) => Some(page),
+			Err(_) => {
+				oversized.push((sender, sent_at, page));
+				None
+			},
+		}
+	});
+
+	let now = frame_system::Pallet::<T>::block_number();
+	<Pallet<T> as Store>::Overweight::translate::<v1::OverweightEntry, _>(
+		|index, (sender, sent_at, message)| {
+			count += 1;
+			let message = match InboundPageOf::<T>::try_from(message) {
+				Ok(message) => message,
+				Err(_) => {
+					log::warn!(
+						target: super::LOG_TARGET,
+						"dropping oversized overweight XCM {} during storage upgrade to v2",
+						index,
+					);
+					Pallet::<T>::deposit_event(Event::OverweightExpired { index });
+					return None
+				},
+			};
+			Some(OverweightRecord {
+				sender,
+				sent_at,
+				enqueued_at: now,
+				required_weight: 0,
+				message,
+			})
+		},
+	);
+
+	// only now, so that the new overweight records are not translated as v1 entries.
+	if !oversized.is_empty() {
+		let status = <Pallet<T> as Store>::InboundXcmpStatus::get();
+		count += 1;
+		for (sender, sent_at, page) in oversized {
+			let format = status
+				.iter()
+				.find(|channel| channel.sender == sender)
+				.and_then(|channel| channel.message_metadata.iter().find(|(at, _)| *at == sent_at))
+				.map(|(_, format)| *format);
+			let kept = format == Some(XcmpMessageFormat::ConcatenatedVersionedXcm) &&
+				stash_messages::<T>(sender, sent_at, &page[..], &mut count);
+			if !kept {
+				log::warn!(
+					target: super::LOG_TARGET,
+					"dropping oversized XCMP page from {:?} sent at {} during storage upgrade to v2",
+					sender,
+					sent_at,
+				);
+				Pallet::<T>::deposit_event(Event::OversizedPageDropped { sender, sent_at });
+			}
+		}
+	}
+
+	T::DbWeight::get().reads_writes(count, count)
+}
+
+/// Stash the concatenated XCMs of `page` as overweight messages. Returns whether all of them could
+/// be kept.
+fn stash_messages<T: Config>(
+	sender: ParaId,
+	sent_at: RelayBlockNumber,
+	mut page: &[u8],
+	count: &mut u64,
+) -> bool {
+	while !page.is_empty() {
+		let message = page;
+		if VersionedXcm::<T::Call>::decode_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut page)
+			.is_err()
+		{
+			return false
+		}
+		let message =
+			match InboundPageOf::<T>::try_from(message[..message.len() - page.len()].to_vec()) {
+				Ok(message) => message,
+				Err(_) => return false,
+			};
+		*count += 1;
+		let index = Pallet::<T>::stash_overweight(sender, sent_at, message, 0);
+		Pallet::<T>::deposit_event(Event::OverweightEnqueued {
+			sender,
+			sent_at,
+			index,
+			required: 0,
+		});
+	}
+	true
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -125,4 +253,88 @@ mod tests {
 			assert_eq!(v1.xcmp_max_individual_weight, 20_000_000_000);
 		});
 	}
+
+	#[test]
+	fn test_migration_to_v2() {
+		new_test_ext().execute_with(|| {
+			frame_system::Pallet::<Test>::set_block_number(7);
+			let page = vec![1u8, 2, 3];
+			frame_support::storage::unhashed::put_raw(
+				&crate::InboundXcmpMessages::<Test>::hashed_key_for(ParaId::from(2000), 5),
+				&page.encode(),
+			);
+			frame_support::storage::unhashed::put_raw(
+				&crate::Overweight::<Test>::hashed_key_for(0),
+				&(ParaId::from(2001), 4u32, vec![4u8, 5]).encode(),
+			);
+
+			migrate_to_v2::<Test>();
+
+			assert_eq!(
+				crate::InboundXcmpMessages::<Test>::get(ParaId::from(2000), 5).into_inner(),
+				page
+			);
+			let record = crate::Overweight::<Test>::get(0).unwrap();
+			assert_eq!(record.sender, ParaId::from(2001));
+			assert_eq!(record.sent_at, 4);
+			assert_eq!(record.enqueued_at, 7);
+			assert_eq!(record.required_weight, 0);
+			assert_eq!(record.message.into_inner(), vec![4u8, 5]);
+		});
+	}
+
+	#[test]
+	fn migration_to_v2_keeps_messages_of_oversized_pages() {
+		use crate::{InboundChannelDetails, InboundState, InboundXcmpStatus};
+		use xcm::latest::prelude::*;
+
+		new_test_ext().execute_with(|| {
+			frame_system::Pallet::<Test>::set_block_number(1);
+			let message = |len| {
+				VersionedXcm::<crate::mock::Call>::from(Xcm(vec![Transact {
+					origin_type: OriginKind::Native,
+					require_weight_at_most: 0,
+					call: vec![0u8; len].into(),
+				}]))
+				.encode()
+			};
+			// two messages that only fit `MaxInboundPageSize` on their own.
+			let (first, second) = (message(600), message(700));
+			let put_page = |sender: u32, sent_at: u32, page: Vec<u8>| {
+				frame_support::storage::unhashed::put_raw(
+					&crate::InboundXcmpMessages::<Test>::hashed_key_for(
+						ParaId::from(sender),
+						sent_at,
+					),
+					&page.encode(),
+				);
+			};
+			put_page(2000, 5, [first.clone(), second.clone()].concat());
+			put_page(2001, 5, vec![0u8; 2000]);
+			InboundXcmpStatus::<Test>::put(vec![
+				InboundChannelDetails {
+					sender: 2000.into(),
+					state: InboundState::Ok,
+					message_metadata: vec![(5, XcmpMessageFormat::ConcatenatedVersionedXcm)],
+				},
+				InboundChannelDetails {
+					sender: 2001.into(),
+					state: InboundState::Ok,
+					message_metadata: vec![(5, XcmpMessageFormat::ConcatenatedEncodedBlob)],
+				},
+			]);
+
+			migrate_to_v2::<Test>();
+
+			assert!(!crate::InboundXcmpMessages::<Test>::contains_key(ParaId::from(2000), 5));
+			assert!(!crate::InboundXcmpMessages::<Test>::contains_key(ParaId::from(2001), 5));
+			let stashed = (0..2)
+				.map(|i| crate::Overweight::<Test>::get(i).unwrap().message.into_inner())
+				.collect::<Vec<_>>();
+			assert_eq!(stashed, vec![first, second]);
+			frame_system::Pallet::<Test>::assert_has_event(
+				Event::<Test>::OversizedPageDropped { sender: 2001.into(), sent_at: 5 }.into(),
+			);
+		});
+	}
 }
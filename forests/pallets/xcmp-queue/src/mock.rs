@@ -25,11 +25,15 @@ use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
 };
+use std::cell::RefCell;
 use xcm::prelude::*;
 use xcm_builder::{
 	CurrencyAdapter, FixedWeightBounds, IsConcrete, LocationInverter, NativeAsset, ParentIsPreset,
 };
-use xcm_executor::traits::ConvertOrigin;
+use xcm_executor::{
+	traits::{ConvertOrigin, DropAssets},
+	Assets,
+};
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -111,6 +115,8 @@ impl forests_pallet_parachain_system::Config for Test {
 	type XcmpMessageHandler = XcmpQueue;
 	type ReservedXcmpWeight = ();
 	type CheckAssociatedRelayNumber = AnyRelayNumber;
+	type ConsensusHook = forests_pallet_parachain_system::ExpectParentIncluded;
+	type RelayStateKeys = ();
 }
 
 parameter_types! {
@@ -118,6 +124,8 @@ parameter_types! {
 	pub Ancestry: MultiLocation = X1(Parachain(1u32.into())).into();
 	pub UnitWeightCost: Weight = 1_000_000;
 	pub const MaxInstructions: u32 = 100;
+	pub const MaxInboundPageSize: u32 = 1024;
+	pub const OverweightExpiry: u64 = 10;
 }
 
 /// Means for transacting assets on this chain.
@@ -153,6 +161,8 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = ();
 	type AssetClaims = ();
 	type SubscriptionService = ();
+	type Tracer = ();
+	type SafeCallFilter = frame_support::traits::Everything;
 }
 
 pub type XcmRouter = (
@@ -182,6 +192,18 @@ impl<Origin: OriginTrait> ConvertOrigin<Origin> for SystemParachainAsSuperuser<O
 	}
 }
 
+thread_local! {
+	pub static TRAPPED: RefCell<Vec<(MultiLocation, Assets)>> = RefCell::new(Vec::new());
+}
+
+pub struct MockAssetTrap;
+impl DropAssets for MockAssetTrap {
+	fn drop_assets(origin: &MultiLocation, assets: Assets) -> Weight {
+		TRAPPED.with(|t| t.borrow_mut().push((origin.clone(), assets)));
+		0
+	}
+}
+
 impl Config for Test {
 	type Event = Event;
 	type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
@@ -190,6 +212,11 @@ impl Config for Test {
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
 	type ControllerOrigin = EnsureRoot<AccountId>;
 	type ControllerOriginConverter = SystemParachainAsSuperuser<Origin>;
+	type MaxInboundPageSize = MaxInboundPageSize;
+	type OverweightExpiry = OverweightExpiry;
+	type AssetTrap = MockAssetTrap;
+	type IsReserve = NativeAsset;
+	type IsTeleporter = NativeAsset;
 	type WeightInfo = ();
 }
 
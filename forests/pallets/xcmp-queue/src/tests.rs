@@ -15,8 +15,8 @@
 
 use super::*;
 use forests_primitives_core::XcmpMessageHandler;
-use frame_support::{assert_noop, assert_ok};
-use mock::{new_test_ext, Call, Origin, Test, XcmpQueue};
+use frame_support::{assert_noop, assert_ok, traits::OnIdle};
+use mock::{new_test_ext, Call, Origin, System, Test, UnitWeightCost, XcmpQueue};
 use sp_runtime::traits::BadOrigin;
 
 #[test]
@@ -40,10 +40,16 @@ fn bad_message_is_handled() {
 			0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 37, 0,
 			0, 0, 0, 0, 0, 0, 16, 0, 127, 147,
 		];
-		InboundXcmpMessages::<Test>::insert(ParaId::from(1000), 1, bad_data);
+		InboundXcmpMessages::<Test>::insert(ParaId::from(1000), 1, page_of(bad_data));
 		let format = XcmpMessageFormat::ConcatenatedEncodedBlob;
 		// This should exit with an error.
-		XcmpQueue::process_xcmp_message(1000.into(), (1, format), 10_000_000_000, 10_000_000_000);
+		XcmpQueue::process_xcmp_message(
+			1000.into(),
+			(1, format),
+			10_000_000_000,
+			10_000_000_000,
+			0,
+		);
 	});
 }
 
@@ -59,9 +65,15 @@ fn handle_blob_message() {
 			139, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0,
 			37, 0, 0, 0, 0, 0, 0, 0, 16, 0, 127, 147,
 		];
-		InboundXcmpMessages::<Test>::insert(ParaId::from(1000), 1, bad_data);
+		InboundXcmpMessages::<Test>::insert(ParaId::from(1000), 1, page_of(bad_data));
 		let format = XcmpMessageFormat::ConcatenatedEncodedBlob;
-		XcmpQueue::process_xcmp_message(1000.into(), (1, format), 10_000_000_000, 10_000_000_000);
+		XcmpQueue::process_xcmp_message(
+			1000.into(),
+			(1, format),
+			10_000_000_000,
+			10_000_000_000,
+			0,
+		);
 	});
 }
 
@@ -71,9 +83,15 @@ fn handle_blob_message() {
 fn handle_invalid_data() {
 	new_test_ext().execute_with(|| {
 		let data = Xcm::<Test>(vec![]).encode();
-		InboundXcmpMessages::<Test>::insert(ParaId::from(1000), 1, data);
+		InboundXcmpMessages::<Test>::insert(ParaId::from(1000), 1, page_of(data));
 		let format = XcmpMessageFormat::ConcatenatedVersionedXcm;
-		XcmpQueue::process_xcmp_message(1000.into(), (1, format), 10_000_000_000, 10_000_000_000);
+		XcmpQueue::process_xcmp_message(
+			1000.into(),
+			(1, format),
+			10_000_000_000,
+			10_000_000_000,
+			0,
+		);
 	});
 }
 
@@ -91,7 +109,14 @@ fn service_overweight_unknown() {
 fn service_overweight_bad_xcm_format() {
 	new_test_ext().execute_with(|| {
 		let bad_xcm = vec![255];
-		Overweight::<Test>::insert(0, (ParaId::from(1000), 0, bad_xcm));
+		let record = OverweightRecord {
+			sender: ParaId::from(1000),
+			sent_at: 0,
+			enqueued_at: 0,
+			required_weight: 0,
+			message: page_of(bad_xcm),
+		};
+		Overweight::<Test>::insert(0, record);
 
 		assert_noop!(XcmpQueue::service_overweight(Origin::root(), 0, 1000), Error::<Test>::BadXcm);
 	});
@@ -119,7 +144,7 @@ fn suspend_xcm_execution_works() {
 		XcmpQueue::handle_xcmp_messages(messages.into_iter(), Weight::max_value());
 
 		let queued_xcm = InboundXcmpMessages::<Test>::get(ParaId::from(2000), 1u32);
-		assert_eq!(queued_xcm, xcm);
+		assert_eq!(queued_xcm.into_inner(), xcm);
 	});
 }
 
@@ -206,3 +231,222 @@ fn update_xcmp_max_individual_weight() {
 		assert_eq!(data.xcmp_max_individual_weight, 30 * WEIGHT_PER_MILLIS);
 	});
 }
+
+fn page_of(data: Vec<u8>) -> InboundPageOf<Test> {
+	InboundPageOf::<Test>::try_from(data).unwrap()
+}
+
+fn xcmp_page(xcm: &VersionedXcm<Call>) -> Vec<u8> {
+	let mut page = XcmpMessageFormat::ConcatenatedVersionedXcm.encode();
+	page.extend(xcm.encode());
+	page
+}
+
+#[test]
+fn suspend_inbound_channel_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(XcmpQueue::suspend_inbound_channel(Origin::signed(1), 2000.into()), BadOrigin);
+		assert_ok!(XcmpQueue::suspend_inbound_channel(Origin::root(), 2000.into()));
+
+		let xcm = VersionedXcm::from(Xcm::<Call>(vec![Instruction::<Call>::ClearOrigin]));
+		let page = xcmp_page(&xcm);
+		let messages = vec![
+			(ParaId::from(2000), 1u32.into(), page.as_slice()),
+			(ParaId::from(2001), 1u32.into(), page.as_slice()),
+		];
+		XcmpQueue::handle_xcmp_messages(messages.into_iter(), Weight::max_value());
+
+		// Only the suspended sender's message is left.
+		assert_eq!(
+			InboundXcmpMessages::<Test>::get(ParaId::from(2000), 1u32).into_inner(),
+			xcm.encode()
+		);
+		assert!(InboundXcmpMessages::<Test>::get(ParaId::from(2001), 1u32).is_empty());
+
+		assert_ok!(XcmpQueue::resume_inbound_channel(Origin::root(), 2000.into()));
+		assert!(!InboundChannelConfigs::<Test>::contains_key(ParaId::from(2000)));
+
+		XcmpQueue::service_xcmp_queue(Weight::max_value());
+		assert!(InboundXcmpMessages::<Test>::get(ParaId::from(2000), 1u32).is_empty());
+	});
+}
+
+#[test]
+fn inbound_weight_quota_defers_messages() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			XcmpQueue::update_inbound_weight_quota(Origin::signed(1), 2000.into(), Some(1000)),
+			BadOrigin
+		);
+		// enough for a single instruction.
+		assert_ok!(XcmpQueue::update_inbound_weight_quota(
+			Origin::root(),
+			2000.into(),
+			Some(UnitWeightCost::get())
+		));
+		// the sender already used part of its quota in this block, the message doesn't fit into
+		// the rest.
+		InboundWeightUsed::<Test>::insert(ParaId::from(2000), (1, 1000));
+
+		let xcm = VersionedXcm::from(Xcm::<Call>(vec![Instruction::<Call>::ClearOrigin]));
+		let page = xcmp_page(&xcm);
+		let messages = vec![(ParaId::from(2000), 1u32.into(), page.as_slice())];
+		XcmpQueue::handle_xcmp_messages(messages.into_iter(), Weight::max_value());
+		assert_eq!(
+			InboundXcmpMessages::<Test>::get(ParaId::from(2000), 1u32).into_inner(),
+			xcm.encode()
+		);
+
+		// the quota is replenished in the next block.
+		System::set_block_number(2);
+		assert_eq!(XcmpQueue::inbound_weight_used(2000.into()), 0);
+		XcmpQueue::service_xcmp_queue(Weight::max_value());
+		assert!(InboundXcmpMessages::<Test>::get(ParaId::from(2000), 1u32).is_empty());
+		assert_eq!(Overweight::<Test>::iter().count(), 0);
+
+		assert_ok!(XcmpQueue::update_inbound_weight_quota(Origin::root(), 2000.into(), None));
+		assert!(!InboundChannelConfigs::<Test>::contains_key(ParaId::from(2000)));
+	});
+}
+
+#[test]
+fn messages_heavier_than_the_quota_are_overweight() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(XcmpQueue::update_inbound_weight_quota(
+			Origin::root(),
+			2000.into(),
+			Some(UnitWeightCost::get())
+		));
+
+		let xcm = VersionedXcm::from(Xcm::<Call>(vec![
+			Instruction::<Call>::ClearOrigin,
+			Instruction::<Call>::ClearOrigin,
+		]));
+		let page = xcmp_page(&xcm);
+		let messages = vec![(ParaId::from(2000), 1u32.into(), page.as_slice())];
+		XcmpQueue::handle_xcmp_messages(messages.into_iter(), Weight::max_value());
+
+		// the message would never fit into the quota, so it doesn't block the channel.
+		assert!(InboundXcmpMessages::<Test>::get(ParaId::from(2000), 1u32).is_empty());
+		assert_eq!(Overweight::<Test>::get(0).unwrap().required_weight, 2 * UnitWeightCost::get());
+	});
+}
+
+#[test]
+fn priority_channels_are_serviced_first() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			XcmpQueue::update_inbound_priority(Origin::signed(1), 2001.into(), true),
+			BadOrigin
+		);
+		assert_ok!(XcmpQueue::update_inbound_priority(Origin::root(), 2001.into(), true));
+
+		let normal = VersionedXcm::from(Xcm::<Call>(vec![Instruction::<Call>::ClearOrigin]));
+		let priority = VersionedXcm::from(Xcm::<Call>(vec![
+			Instruction::<Call>::ClearOrigin,
+			Instruction::<Call>::ClearOrigin,
+		]));
+		let (normal_page, priority_page) = (xcmp_page(&normal), xcmp_page(&priority));
+		let messages = vec![
+			(ParaId::from(2000), 1u32.into(), normal_page.as_slice()),
+			(ParaId::from(2001), 1u32.into(), priority_page.as_slice()),
+		];
+		XcmpQueue::handle_xcmp_messages(messages.into_iter(), Weight::max_value());
+
+		let executed = frame_system::Pallet::<Test>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				mock::Event::XcmpQueue(Event::Fail { message_hash, .. }) => message_hash,
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			executed,
+			vec![
+				<Test as frame_system::Config>::Hashing::hash_of(&priority),
+				<Test as frame_system::Config>::Hashing::hash_of(&normal),
+			]
+		);
+	});
+}
+
+#[test]
+fn oversized_pages_are_dropped() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let mut page = XcmpMessageFormat::ConcatenatedEncodedBlob.encode();
+		page.extend(vec![0u8; mock::MaxInboundPageSize::get() as usize + 1]);
+		let messages = vec![(ParaId::from(2000), 1u32.into(), page.as_slice())];
+		XcmpQueue::handle_xcmp_messages(messages.into_iter(), Weight::max_value());
+
+		System::assert_last_event(
+			Event::<Test>::OversizedPageDropped { sender: 2000.into(), sent_at: 1 }.into(),
+		);
+		assert!(!InboundXcmpMessages::<Test>::contains_key(ParaId::from(2000), 1u32));
+		assert!(XcmpQueue::queued_pages(2000.into()).is_empty());
+	});
+}
+
+#[test]
+fn queued_pages_decodes_messages() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmpQueue::suspend_inbound_channel(Origin::root(), 2000.into()));
+		let xcm = Xcm::<()>(vec![ClearOrigin]);
+		let mut page = xcmp_page(&VersionedXcm::from(Xcm::<Call>(vec![ClearOrigin])));
+		page.extend(VersionedXcm::from(Xcm::<Call>(vec![ClearOrigin])).encode());
+		let messages = vec![
+			(ParaId::from(2000), 1u32.into(), page.as_slice()),
+			(ParaId::from(2000), 2u32.into(), page.as_slice()),
+		];
+		XcmpQueue::handle_xcmp_messages(messages.into_iter(), Weight::max_value());
+
+		let pages = XcmpQueue::queued_pages(2000.into());
+		assert_eq!(pages.len(), 2);
+		assert_eq!(pages[0].len(), 2);
+		assert_eq!(pages[1][0].sent_at, 2);
+		assert_eq!(pages[1][1].message, Some(VersionedXcm::from(xcm)));
+		assert!(XcmpQueue::queued_pages(2001.into()).is_empty());
+	});
+}
+
+#[test]
+fn overweight_messages_expire_into_asset_trap() {
+	new_test_ext().execute_with(|| {
+		// 2000 is only trusted as the reserve of its own native asset.
+		let native = MultiLocation::new(1, X1(Parachain(2000)));
+		let assets: MultiAssets =
+			vec![(Parent, 42u128).into(), (native.clone(), 7u128).into()].into();
+		let transfer =
+			VersionedXcm::from(Xcm::<Call>(vec![ReserveAssetDeposited(assets), ClearOrigin]));
+		System::set_block_number(1);
+		XcmpQueue::stash_overweight(2000.into(), 1, page_of(transfer.encode()), 5000);
+		System::set_block_number(5);
+		let clear = VersionedXcm::from(Xcm::<Call>(vec![ClearOrigin]));
+		XcmpQueue::stash_overweight(2001.into(), 3, page_of(clear.encode()), 6000);
+
+		let messages = XcmpQueue::overweight_messages();
+		assert_eq!(messages.len(), 2);
+		assert_eq!(messages[0].sender, Some(2000.into()));
+		assert_eq!(messages[0].required_weight, 5000);
+		assert_eq!(messages[1].index, 1);
+		assert_eq!(messages[1].sent_at, 3);
+
+		XcmpQueue::on_idle(10, Weight::max_value());
+		assert_eq!(XcmpQueue::overweight_messages().len(), 2);
+
+		XcmpQueue::on_idle(11, Weight::max_value());
+		System::assert_last_event(Event::<Test>::OverweightExpired { index: 0 }.into());
+		assert!(!Overweight::<Test>::contains_key(0));
+		let trapped = mock::TRAPPED.with(|t| t.borrow_mut().split_off(0));
+		let assets = xcm_executor::Assets::from(MultiAsset::from((native.clone(), 7u128)));
+		assert_eq!(trapped, vec![(native, assets)]);
+
+		XcmpQueue::on_idle(15, Weight::max_value());
+		assert!(XcmpQueue::overweight_messages().is_empty());
+		assert!(mock::TRAPPED.with(|t| t.borrow().is_empty()));
+		assert_eq!(OverweightExpiryCursor::<Test>::get(), 2);
+	});
+}
@@ -27,6 +27,7 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn set_config_with_u32() -> Weight;
 	fn set_config_with_weight() -> Weight;
+	fn set_inbound_channel_config() -> Weight;
 }
 
 pub struct SubstrateWeight<T>(PhantomData<T>);
@@ -45,6 +46,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+
+	// Storage: XcmpQueue InboundChannelConfigs (r:1 w:1)
+	fn set_inbound_channel_config() -> Weight {
+		(3_104_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }
 
 impl WeightInfo for () {
@@ -61,4 +69,11 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
+
+	// Storage: XcmpQueue InboundChannelConfigs (r:1 w:1)
+	fn set_inbound_channel_config() -> Weight {
+		(3_104_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 }
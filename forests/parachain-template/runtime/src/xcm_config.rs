@@ -0,0 +1,223 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! XCM configuration for the template parachain.
+
+use super::{
+	AccountId, Balances, BlockNumber, Call, Event, Origin, ParachainInfo, ParachainSystem, Runtime,
+	SelendraXcm, WeightToFee, XcmpQueue, DAYS,
+};
+use frame_support::{
+	match_types, parameter_types,
+	traits::{ConstU128, Everything, Nothing},
+	weights::Weight,
+};
+use parachains_common::xcm_config::{DenyReserveTransferToRelayChain, DenyThenTry};
+use selendra_parachain::primitives::Sibling;
+use xcm::latest::prelude::*;
+use xcm_builder::{
+	AccountId32Aliases, AllowKnownQueryResponses, AllowSubscriptionsFrom,
+	AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom, CurrencyAdapter, EnsureXcmOrigin,
+	FixedWeightBounds, IsConcrete, LocationInverter, NativeAsset, ParentAsSuperuser,
+	ParentIsPreset, RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
+	SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit,
+	UsingComponents,
+};
+use xcm_executor::XcmExecutor;
+
+parameter_types! {
+	pub const RelayLocation: MultiLocation = MultiLocation::parent();
+	pub const RelayNetwork: NetworkId = NetworkId::Any;
+	pub RelayChainOrigin: Origin = forests_pallet_xcm::Origin::Relay.into();
+	pub Ancestry: MultiLocation = Parachain(ParachainInfo::parachain_id().into()).into();
+}
+
+/// Type for specifying how a `MultiLocation` can be converted into an `AccountId`. This is used
+/// when determining ownership of accounts for asset transacting and when attempting to use XCM
+/// `Transact` in order to determine the dispatch Origin.
+pub type LocationToAccountId = (
+	// The parent (Relay-chain) origin converts to the parent `AccountId`.
+	ParentIsPreset<AccountId>,
+	// Sibling parachain origins convert to AccountId via the `ParaId::into`.
+	SiblingParachainConvertsVia<Sibling, AccountId>,
+	// Straight up local `AccountId32` origins just alias directly to `AccountId`.
+	AccountId32Aliases<RelayNetwork, AccountId>,
+);
+
+/// Means for transacting assets on this chain.
+pub type LocalAssetTransactor = CurrencyAdapter<
+	// Use this currency:
+	Balances,
+	// Use this currency when it is a fungible asset matching the given location or name:
+	IsConcrete<RelayLocation>,
+	// Do a simple punn to convert an AccountId32 MultiLocation into a native chain account ID:
+	LocationToAccountId,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// We don't track any teleports.
+	(),
+>;
+
+/// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
+/// ready for dispatching a transaction with Xcm's `Transact`. There is an `OriginKind` which can
+/// biases the kind of local `Origin` it will become.
+pub type XcmOriginToTransactDispatchOrigin = (
+	// Sovereign account converter; this attempts to derive an `AccountId` from the origin location
+	// using `LocationToAccountId` and then turn that into the usual `Signed` origin. Useful for
+	// foreign chains who want to have a local sovereign account on this chain which they control.
+	SovereignSignedViaLocation<LocationToAccountId, Origin>,
+	// Native converter for Relay-chain (Parent) location; will convert to a `Relay` origin when
+	// recognized.
+	RelayChainAsNative<RelayChainOrigin, Origin>,
+	// Native converter for sibling Parachains; will convert to a `SiblingPara` origin when
+	// recognized.
+	SiblingParachainAsNative<forests_pallet_xcm::Origin, Origin>,
+	// Superuser converter for the Relay-chain (Parent) location. This will allow it to issue a
+	// transaction from the Root origin.
+	ParentAsSuperuser<Origin>,
+	// Native signed account converter; this just converts an `AccountId32` origin into a normal
+	// `Origin::Signed` origin of the same 32-byte value.
+	SignedAccountId32AsNative<RelayNetwork, Origin>,
+	// Xcm origins of relay chain accounts, which note the assets they lock on behalf of
+	// `SelendraXcm`, and of the pallets which may release the locks held here, are passed through
+	// as `pallet_xcm::Origin::Xcm`.
+	pallet_xcm::XcmPassthrough<Origin, (RelayChainAccounts, RemoteLockUnlockers)>,
+);
+
+parameter_types! {
+	// One XCM operation is 1_000_000_000 weight - almost certainly a conservative estimate.
+	pub UnitWeightCost: Weight = 1_000_000_000;
+	pub const MaxInstructions: u32 = 100;
+}
+
+match_types! {
+	pub type ParentOrParentsExecutivePlurality: impl Contains<MultiLocation> = {
+		MultiLocation { parents: 1, interior: Here } |
+		MultiLocation { parents: 1, interior: X1(Plurality { id: BodyId::Executive, .. }) }
+	};
+	pub type RelayChainAccounts: impl Contains<MultiLocation> = {
+		MultiLocation { parents: 1, interior: X1(AccountId32 { .. }) }
+	};
+	pub type RemoteLockUnlockers: impl Contains<MultiLocation> = {
+		MultiLocation { parents: 1, interior: X1(PalletInstance(_)) } |
+		MultiLocation { parents: 1, interior: X2(Parachain(_), PalletInstance(_)) }
+	};
+}
+
+/// The barriers one of which must be passed for an XCM message to be executed.
+///
+/// Reserve transfers towards the relay chain are always denied, as the relay chain does not
+/// act as a reserve of its own token for parachains.
+pub type Barrier = DenyThenTry<
+	DenyReserveTransferToRelayChain,
+	(
+		// Weight that is paid for may be consumed.
+		TakeWeightCredit,
+		// If the message is one that immediately attemps to pay for execution, then allow it.
+		AllowTopLevelPaidExecutionFrom<Everything>,
+		// Parent and its executive plurality get free execution.
+		AllowUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
+		// Expected responses are OK.
+		AllowKnownQueryResponses<SelendraXcm>,
+		// Subscriptions for version tracking are OK.
+		AllowSubscriptionsFrom<Everything>,
+	),
+>;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+	type Call = Call;
+	type XcmSender = XcmRouter;
+	// How to withdraw and deposit an asset. The asset changes made while dry-running are recorded.
+	type AssetTransactor = pallet_xcm::RecordingTransactor<Runtime, LocalAssetTransactor>;
+	type OriginConverter = XcmOriginToTransactDispatchOrigin;
+	type IsReserve = NativeAsset;
+	// Teleporting is disabled.
+	type IsTeleporter = ();
+	type LocationInverter = LocationInverter<Ancestry>;
+	type Barrier = Barrier;
+	type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+	// The weight trader piggybacks on the existing transaction-fee conversion logic.
+	type Trader = UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, ()>;
+	type ResponseHandler = SelendraXcm;
+	type AssetTrap = SelendraXcm;
+	type AssetClaims = SelendraXcm;
+	type SubscriptionService = SelendraXcm;
+	type Tracer = SelendraXcm;
+	type SafeCallFilter = Everything;
+}
+
+/// Converts a local signed origin into the `AccountId32` location of the account, so that signed
+/// accounts are able to send XCM messages.
+pub type LocalOriginToLocation = SignedToAccountId32<Origin, AccountId, RelayNetwork>;
+
+/// The means for routing XCM messages which are not for local execution into the right message
+/// queues. The messages sent while dry-running are recorded by `SelendraXcm`.
+pub type XcmRouter = pallet_xcm::RecordingRouter<
+	Runtime,
+	(
+		// Two routers - use UMP to communicate with the relay chain:
+		forests_primitives_utility::ParentAsUmp<ParachainSystem, SelendraXcm>,
+		// ..and XCMP to communicate with the sibling chains.
+		XcmpQueue,
+	),
+>;
+
+parameter_types! {
+	// We are conservative with the XCM version we advertize.
+	pub const AdvertisedXcmVersion: u32 = 2;
+	pub const MaxAssetTrapsPerOrigin: u32 = 64;
+	pub const AssetTrapExpiry: BlockNumber = 90 * DAYS;
+	pub const MaxRemoteLockConsumers: u32 = 16;
+	// The weight of `note_unlockable` on the chains of the unlockers. This is a safe overestimate.
+	pub const NoteUnlockableWeight: Weight = 1_000_000_000;
+}
+
+impl pallet_xcm::Config for Runtime {
+	type Event = Event;
+	type SendXcmOrigin = EnsureXcmOrigin<Origin, LocalOriginToLocation>;
+	type XcmRouter = XcmRouter;
+	type ExecuteXcmOrigin = EnsureXcmOrigin<Origin, LocalOriginToLocation>;
+	type XcmExecuteFilter = Nothing;
+	type XcmSendFilter = Everything;
+	// ^ Disable dispatchable execute on the XCM pallet.
+	// Needs to be `Everything` for local testing.
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmTeleportFilter = Everything;
+	type XcmReserveTransferFilter = Nothing;
+	type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+	type LocationInverter = LocationInverter<Ancestry>;
+	type Origin = Origin;
+	type Call = Call;
+	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+	type AdvertisedXcmVersion = AdvertisedXcmVersion;
+	type MaxAssetTrapsPerOrigin = MaxAssetTrapsPerOrigin;
+	type AssetTrapExpiry = AssetTrapExpiry;
+	type Currency = Balances;
+	type UnlockOrigin = pallet_xcm::EnsureXcm<RemoteLockUnlockers>;
+	type NoteUnlockableOrigin = pallet_xcm::EnsureXcm<RelayChainAccounts>;
+	type MaxRemoteLockConsumers = MaxRemoteLockConsumers;
+	type NoteUnlockableWeight = NoteUnlockableWeight;
+	type DeliveryFeeBase = ConstU128<0>;
+	type DeliveryFeePerByte = ConstU128<0>;
+	type OnDeliveryFee = ();
+	type WeightInfo = pallet_xcm::weights::SubstrateWeight<Runtime>;
+}
+
+impl forests_pallet_xcm::Config for Runtime {
+	type Event = Event;
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+}
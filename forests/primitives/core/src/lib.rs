@@ -19,9 +19,17 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
+use frame_support::weights::Weight;
 use selendra_parachain::primitives::HeadData;
-use sp_runtime::{traits::Block as BlockT, RuntimeDebug};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	RuntimeDebug,
+};
 use sp_std::prelude::*;
+use xcm::{
+	latest::{Instruction, MultiAsset, MultiAssets, Xcm},
+	VersionedXcm,
+};
 
 pub use selendra_core_primitives::InboundDownwardMessage;
 pub use selendra_parachain::primitives::{
@@ -242,6 +250,100 @@ pub struct CollationInfo {
 	pub head_data: HeadData,
 }
 
+/// A message waiting in one of the inbound XCM queues of the parachain.
+#[derive(Clone, Debug, codec::Decode, codec::Encode, PartialEq)]
+pub struct QueuedMessage {
+	/// The relay chain block number at which the message was sent.
+	pub sent_at: relay_chain::BlockNumber,
+	/// The message, or `None` if it could not be decoded.
+	pub message: Option<VersionedXcm<()>>,
+}
+
+/// An inbound message that exceeded the weight budget and waits to be serviced explicitly.
+#[derive(Clone, Debug, codec::Decode, codec::Encode, PartialEq)]
+pub struct OverweightMessage {
+	/// The index under which the message can be serviced.
+	pub index: u64,
+	/// The sibling parachain that sent the message, or `None` for downward messages.
+	pub sender: Option<ParaId>,
+	/// The relay chain block number at which the message was sent.
+	pub sent_at: relay_chain::BlockNumber,
+	/// The weight the message requires to be executed.
+	pub required_weight: Weight,
+	/// The message, or `None` if it could not be decoded.
+	pub message: Option<VersionedXcm<()>>,
+}
+
+/// A parachain block together with the candidate descriptor a collator signed for it.
+#[derive(Clone, Debug, codec::Decode, codec::Encode, PartialEq)]
+pub struct SignedCandidate<Header> {
+	/// The header of the parachain block.
+	pub header: Header,
+	/// The descriptor of the candidate that produces `header`.
+	pub descriptor: relay_chain::v2::CandidateDescriptor,
+}
+
+/// Proof that a collator signed two different candidates for blocks building on the same parent.
+///
+/// Both blocks have the same parent and number, so the collator announced two conflicting blocks
+/// at the same height, whatever relay parent each candidate was built in the context of.
+#[derive(Clone, Debug, codec::Decode, codec::Encode, PartialEq)]
+pub struct CollatorEquivocationProof<Header> {
+	/// The candidate that was seen first.
+	pub first: SignedCandidate<Header>,
+	/// The conflicting candidate.
+	pub second: SignedCandidate<Header>,
+}
+
+impl<Header: HeaderT> CollatorEquivocationProof<Header> {
+	/// The collator that equivocated.
+	pub fn collator(&self) -> &relay_chain::v2::CollatorId {
+		&self.first.descriptor.collator
+	}
+
+	/// Check that the proof is valid.
+	///
+	/// Both candidates need to be signed by the same collator for the same para and for different
+	/// blocks with the same parent and number.
+	pub fn check(&self) -> bool {
+		let (first, second) = (&self.first.descriptor, &self.second.descriptor);
+		let (first_header, second_header) = (&self.first.header, &self.second.header);
+
+		first.collator == second.collator &&
+			first.para_id == second.para_id &&
+			first_header.parent_hash() == second_header.parent_hash() &&
+			first_header.number() == second_header.number() &&
+			first.para_head != second.para_head &&
+			first.check_collator_signature().is_ok() &&
+			second.check_collator_signature().is_ok() &&
+			HeadData(first_header.encode()).hash() == first.para_head &&
+			HeadData(second_header.encode()).hash() == second.para_head
+	}
+}
+
+/// The assets an XCM deposits into the holding register before doing anything else, limited to
+/// those the sender is trusted for.
+///
+/// These assets have already left the sending chain, so they are lost if the message is never
+/// executed. Assets of a leading `ReserveAssetDeposited` are kept if `is_reserve` accepts them,
+/// those of a leading `ReceiveTeleportedAsset` if `is_teleporter` does, the same checks the
+/// executor applies to the sender before taking them into holding.
+pub fn assets_in_transit<Call>(
+	message: &Xcm<Call>,
+	is_reserve: impl Fn(&MultiAsset) -> bool,
+	is_teleporter: impl Fn(&MultiAsset) -> bool,
+) -> MultiAssets {
+	let (assets, trusted): (_, &dyn Fn(&MultiAsset) -> bool) = match message.0.first() {
+		Some(Instruction::ReserveAssetDeposited(assets)) => (assets, &is_reserve),
+		Some(Instruction::ReceiveTeleportedAsset(assets)) => (assets, &is_teleporter),
+		_ => return MultiAssets::new(),
+	};
+	// a subset of sorted and deduplicated assets is still sorted and deduplicated.
+	MultiAssets::from_sorted_and_deduplicated_skip_checks(
+		assets.inner().iter().filter(|asset| trusted(asset)).cloned().collect(),
+	)
+}
+
 sp_api::decl_runtime_apis! {
 	/// Runtime api to collect information about a collation.
 	#[api_version(2)]
@@ -256,3 +358,23 @@ sp_api::decl_runtime_apis! {
 		fn collect_collation_info(header: &Block::Header) -> CollationInfo;
 	}
 }
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api to inspect the inbound XCM queues of the parachain.
+	pub trait XcmQueueApi {
+		/// The pages of the downward message queue, in the order they will be executed.
+		fn dmp_queue_pages() -> Vec<Vec<QueuedMessage>>;
+		/// The pages queued from `sender` in the XCMP queue, in the order they will be executed.
+		fn xcmp_queue_pages(sender: ParaId) -> Vec<Vec<QueuedMessage>>;
+		/// All overweight downward messages followed by all overweight XCMP messages, each ordered
+		/// by index. Downward messages have no `sender`.
+		fn overweight_messages() -> Vec<OverweightMessage>;
+	}
+
+	/// Runtime api to declare the relay chain storage the parachain runtime wants to read.
+	pub trait RelayStateKeysApi {
+		/// The relay chain storage keys to include in the relay chain state proof, in addition to
+		/// the ones always included by the collator.
+		fn relay_state_keys() -> Vec<Vec<u8>>;
+	}
+}
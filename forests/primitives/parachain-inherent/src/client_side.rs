@@ -28,10 +28,13 @@ const LOG_TARGET: &str = "parachain-inherent";
 
 /// Collect the relevant relay chain state in form of a proof for putting it into the validation
 /// data inherent.
+///
+/// The proof also covers the `additional_keys` requested by the parachain runtime.
 async fn collect_relay_storage_proof(
 	relay_chain_interface: &impl RelayChainInterface,
 	para_id: ParaId,
 	relay_parent: PHash,
+	additional_keys: Vec<Vec<u8>>,
 ) -> Option<sp_state_machine::StorageProof> {
 	use relay_chain::well_known_keys as relay_well_known_keys;
 
@@ -104,12 +107,14 @@ async fn collect_relay_storage_proof(
 	relevant_keys.push(relay_well_known_keys::hrmp_egress_channel_index(para_id));
 	relevant_keys.push(relay_well_known_keys::upgrade_go_ahead_signal(para_id));
 	relevant_keys.push(relay_well_known_keys::upgrade_restriction_signal(para_id));
+	relevant_keys.push(relay_well_known_keys::para_head(para_id));
 	relevant_keys.extend(ingress_channels.into_iter().map(|sender| {
 		relay_well_known_keys::hrmp_channels(HrmpChannelId { sender, recipient: para_id })
 	}));
 	relevant_keys.extend(egress_channels.into_iter().map(|recipient| {
 		relay_well_known_keys::hrmp_channels(HrmpChannelId { sender: para_id, recipient })
 	}));
+	relevant_keys.extend(additional_keys);
 
 	relay_chain_interface
 		.prove_read(relay_parent, &relevant_keys)
@@ -135,8 +140,37 @@ impl ParachainInherentData {
 		validation_data: &PersistedValidationData,
 		para_id: ParaId,
 	) -> Option<ParachainInherentData> {
-		let relay_chain_state =
-			collect_relay_storage_proof(relay_chain_interface, para_id, relay_parent).await?;
+		Self::create_at_with_relay_state_keys(
+			relay_parent,
+			relay_chain_interface,
+			validation_data,
+			para_id,
+			Vec::new(),
+		)
+		.await
+	}
+
+	/// Create the [`ParachainInherentData`] at the given `relay_parent`, proving the given
+	/// additional `relay_state_keys` in the relay chain state proof.
+	///
+	/// The keys are usually obtained from the parachain runtime through the
+	/// [`RelayStateKeysApi`](forests_primitives_core::RelayStateKeysApi) runtime api.
+	///
+	/// Returns `None` if the creation failed.
+	pub async fn create_at_with_relay_state_keys(
+		relay_parent: PHash,
+		relay_chain_interface: &impl RelayChainInterface,
+		validation_data: &PersistedValidationData,
+		para_id: ParaId,
+		relay_state_keys: Vec<Vec<u8>>,
+	) -> Option<ParachainInherentData> {
+		let relay_chain_state = collect_relay_storage_proof(
+			relay_chain_interface,
+			para_id,
+			relay_parent,
+			relay_state_keys,
+		)
+		.await?;
 
 		let downward_messages = relay_chain_interface
 			.retrieve_dmq_contents(para_id, relay_parent)
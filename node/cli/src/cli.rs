@@ -71,6 +71,10 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Offline utilities for decoding, encoding and inspecting XCM data.
+	#[clap(subcommand)]
+	Xcm(crate::xcm_cmd::XcmCmd),
 }
 
 #[allow(missing_docs)]
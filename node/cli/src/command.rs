@@ -534,6 +534,7 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run::<service::Block>(&config))?)
 		},
+		Some(Subcommand::Xcm(cmd)) => cmd.run(),
 	}?;
 
 	#[cfg(feature = "pyroscope")]
@@ -28,6 +28,8 @@ mod command;
 mod error;
 #[cfg(all(feature = "hostperfcheck", build_type = "release"))]
 mod host_perf_check;
+#[cfg(feature = "cli")]
+mod xcm_cmd;
 
 #[cfg(feature = "full-node")]
 pub use service::RuntimeApiCollection;
@@ -43,5 +45,8 @@ pub use cli::*;
 #[cfg(feature = "cli")]
 pub use command::*;
 
+#[cfg(feature = "cli")]
+pub use xcm_cmd::{XcmCmd, XcmDataKind};
+
 #[cfg(feature = "cli")]
 pub use sc_cli::{Error, Result};
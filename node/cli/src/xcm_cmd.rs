@@ -0,0 +1,220 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `xcm` sub-command: offline tooling for inspecting and building XCM data.
+
+use crate::error::Error;
+use parity_scale_codec::{DecodeLimit, Encode};
+use selendra_parachain::primitives::{Id as ParaId, Sibling};
+use serde::{de::DeserializeOwned, Serialize};
+use sp_core::{
+	bytes::{from_hex, to_hex},
+	crypto::{AccountId32, Ss58AddressFormat, Ss58Codec},
+	Get,
+};
+use xcm::{
+	latest::{MultiLocation, NetworkId},
+	VersionedMultiAssets, VersionedMultiLocation, VersionedXcm, MAX_XCM_DECODE_DEPTH,
+};
+use xcm_builder::{
+	AccountId32Aliases, ChildParachainConvertsVia, DescribeAllTerminal, DescribeFamily,
+	HashedDescription, ParentIsPreset, SiblingParachainConvertsVia,
+};
+use xcm_executor::traits::Convert;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// The kind of XCM data handled by `decode` and `encode`.
+#[derive(Debug, Clone, Copy, clap::ArgEnum)]
+pub enum XcmDataKind {
+	/// A `VersionedXcm` message.
+	Message,
+	/// A `VersionedMultiLocation`.
+	Location,
+	/// A `VersionedMultiAssets`.
+	Assets,
+}
+
+/// Offline utilities for XCM messages, locations and assets.
+#[derive(Debug, clap::Subcommand)]
+pub enum XcmCmd {
+	/// Decode SCALE-encoded XCM data given as hex into JSON.
+	Decode {
+		/// The kind of data to decode.
+		#[clap(arg_enum)]
+		kind: XcmDataKind,
+		/// The `0x`-prefixed hex encoding of the data.
+		hex: String,
+	},
+
+	/// Encode XCM data given as JSON into SCALE-encoded hex.
+	///
+	/// Locations may also be given in their textual form, e.g. `../Parachain(2000)`.
+	Encode {
+		/// The kind of data to encode.
+		#[clap(arg_enum)]
+		kind: XcmDataKind,
+		/// The JSON (or, for locations, textual) representation of the data.
+		input: String,
+	},
+
+	/// Compute the account which a location controls on the relay chain or a parachain.
+	///
+	/// This applies the standard location to account conversions: the parent, child and sibling
+	/// parachains, local 32-byte accounts and the derived accounts of remote accounts and pallets.
+	SovereignAccount {
+		/// The location as seen from the chain in question, as JSON or in its textual form.
+		location: String,
+		/// The SS58 prefix to print the account with.
+		#[clap(long, default_value = "204")]
+		ss58_prefix: u16,
+	},
+}
+
+/// A local `AccountId32` junction matches any network.
+struct AnyNetwork;
+impl Get<NetworkId> for AnyNetwork {
+	fn get() -> NetworkId {
+		NetworkId::Any
+	}
+}
+
+type LocationToAccountId = (
+	ParentIsPreset<AccountId32>,
+	ChildParachainConvertsVia<ParaId, AccountId32>,
+	SiblingParachainConvertsVia<Sibling, AccountId32>,
+	AccountId32Aliases<AnyNetwork, AccountId32>,
+	HashedDescription<AccountId32, DescribeFamily<DescribeAllTerminal>>,
+);
+
+impl XcmCmd {
+	/// Run the command, printing its result to stdout.
+	pub fn run(&self) -> Result<()> {
+		println!("{}", self.output()?);
+		Ok(())
+	}
+
+	fn output(&self) -> Result<String> {
+		Ok(match self {
+			XcmCmd::Decode { kind, hex } => {
+				let bytes = from_hex(hex).map_err(|e| format!("Invalid hex: {}", e))?;
+				match kind {
+					XcmDataKind::Message => to_json(&decode::<VersionedXcm<()>>(&bytes)?),
+					XcmDataKind::Location => to_json(&decode::<VersionedMultiLocation>(&bytes)?),
+					XcmDataKind::Assets => to_json(&decode::<VersionedMultiAssets>(&bytes)?),
+				}?
+			},
+			XcmCmd::Encode { kind, input } => {
+				let encoded = match kind {
+					XcmDataKind::Message => from_json::<VersionedXcm<()>>(input)?.encode(),
+					XcmDataKind::Location => parse_location(input)?.encode(),
+					XcmDataKind::Assets => from_json::<VersionedMultiAssets>(input)?.encode(),
+				};
+				to_hex(&encoded, false)
+			},
+			XcmCmd::SovereignAccount { location, ss58_prefix } => {
+				let location =
+					MultiLocation::try_from(parse_location(location)?).map_err(|_| {
+						"Location cannot be represented in the latest version".to_string()
+					})?;
+				let account = LocationToAccountId::convert_ref(&location).map_err(|_| {
+					format!("No sovereign account is defined for location {}", location)
+				})?;
+				format!(
+					"{}\n{}",
+					to_hex(account.as_ref(), false),
+					account.to_ss58check_with_version(Ss58AddressFormat::custom(*ss58_prefix)),
+				)
+			},
+		})
+	}
+}
+
+fn decode<T: DecodeLimit>(bytes: &[u8]) -> Result<T> {
+	T::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut &bytes[..])
+		.map_err(|e| format!("Failed to decode: {}", e).into())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+	serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize: {}", e).into())
+}
+
+fn from_json<T: DeserializeOwned>(input: &str) -> Result<T> {
+	serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {}", e).into())
+}
+
+/// Parse a location given either as JSON or in the textual form of the latest version.
+fn parse_location(input: &str) -> Result<VersionedMultiLocation> {
+	if input.trim_start().starts_with('{') {
+		from_json(input)
+	} else {
+		input
+			.parse::<MultiLocation>()
+			.map(Into::into)
+			.map_err(|e| format!("Invalid location `{}`: {}", input, e).into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn round_trip(kind: XcmDataKind, input: &str) -> String {
+		let hex = XcmCmd::Encode { kind, input: input.into() }.output().unwrap();
+		let json = XcmCmd::Decode { kind, hex: hex.clone() }.output().unwrap();
+		let reencoded = XcmCmd::Encode { kind, input: json }.output().unwrap();
+		assert_eq!(hex, reencoded);
+		hex
+	}
+
+	#[test]
+	fn locations_round_trip() {
+		let textual = round_trip(XcmDataKind::Location, "../Parachain(2000)");
+		let json = XcmCmd::Decode { kind: XcmDataKind::Location, hex: textual.clone() }
+			.output()
+			.unwrap();
+		assert_eq!(round_trip(XcmDataKind::Location, &json), textual);
+	}
+
+	#[test]
+	fn assets_and_messages_round_trip() {
+		let location = parse_location("../Parachain(2000)").unwrap();
+		let assets: VersionedMultiAssets = xcm::latest::MultiAssets::from(vec![(
+			MultiLocation::try_from(location).unwrap(),
+			1_000u128,
+		)
+			.into()])
+		.into();
+		round_trip(XcmDataKind::Assets, &to_json(&assets).unwrap());
+
+		let message: VersionedXcm<()> = xcm::latest::Xcm::<()>(vec![
+			xcm::latest::Instruction::WithdrawAsset(assets.try_into().unwrap()),
+			xcm::latest::Instruction::ClearOrigin,
+		])
+		.into();
+		round_trip(XcmDataKind::Message, &to_json(&message).unwrap());
+	}
+
+	#[test]
+	fn invalid_input_is_rejected() {
+		assert!(XcmCmd::Decode { kind: XcmDataKind::Location, hex: "0xff".into() }
+			.output()
+			.is_err());
+		assert!(XcmCmd::Encode { kind: XcmDataKind::Assets, input: "{".into() }
+			.output()
+			.is_err());
+	}
+}
@@ -79,6 +79,7 @@ pub trait RuntimeApiCollection:
 	+ sp_session::SessionKeys<Block>
 	+ sp_authority_discovery::AuthorityDiscoveryApi<Block>
 	+ beefy_primitives::BeefyApi<Block>
+	+ pallet_xcm::runtime_api::XcmPaymentApi<Block>
 where
 	<Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
@@ -99,7 +100,8 @@ where
 		+ sp_offchain::OffchainWorkerApi<Block>
 		+ sp_session::SessionKeys<Block>
 		+ sp_authority_discovery::AuthorityDiscoveryApi<Block>
-		+ beefy_primitives::BeefyApi<Block>,
+		+ beefy_primitives::BeefyApi<Block>
+		+ pallet_xcm::runtime_api::XcmPaymentApi<Block>,
 	<Self as sp_api::ApiExt<Block>>::StateBackend: sp_api::StateBackend<BlakeTwo256>,
 {
 }
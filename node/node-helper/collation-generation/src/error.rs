@@ -25,6 +25,8 @@ pub enum Error {
 	#[error(transparent)]
 	Runtime(#[from] selendra_node_subsystem::errors::RuntimeApiError),
 	#[error(transparent)]
+	ChainApi(#[from] selendra_node_subsystem::errors::ChainApiError),
+	#[error(transparent)]
 	Util(#[from] selendra_node_subsystem_util::Error),
 	#[error(transparent)]
 	Erasure(#[from] selendra_erasure_coding::Error),
@@ -18,11 +18,17 @@
 
 #![deny(missing_docs)]
 
-use futures::{channel::mpsc, future::FutureExt, join, select, sink::SinkExt, stream::StreamExt};
+use futures::{
+	channel::{mpsc, oneshot},
+	future::FutureExt,
+	join, select,
+	sink::SinkExt,
+	stream::StreamExt,
+};
 use parity_scale_codec::Encode;
 use selendra_node_primitives::{AvailableData, CollationGenerationConfig, PoV};
 use selendra_node_subsystem::{
-	messages::{CollationGenerationMessage, CollatorProtocolMessage},
+	messages::{ChainApiMessage, CollationGenerationMessage, CollatorProtocolMessage},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem,
 	SubsystemContext, SubsystemError, SubsystemResult,
 };
@@ -181,11 +187,27 @@ async fn handle_new_activations<Context>(
 
 	let _overall_timer = metrics.time_new_activations();
 
-	for relay_parent in activated {
+	for leaf in activated {
 		let _relay_parent_timer = metrics.time_new_activations_relay_parent();
 
+		// Collations are built on an ancestor of the leaf if the collator was configured to
+		// build ahead of the relay chain tip, but they are scheduled on the cores of the leaf.
+		let relay_parent =
+			match relay_parent_at_offset(leaf, config.relay_parent_offset, ctx.sender()).await? {
+				Some(relay_parent) => relay_parent,
+				None => {
+					gum::trace!(
+						target: LOG_TARGET,
+						?leaf,
+						offset = config.relay_parent_offset,
+						"leaf doesn't have enough ancestors for the relay parent offset",
+					);
+					continue
+				},
+			};
+
 		let (availability_cores, validators) = join!(
-			request_availability_cores(relay_parent, ctx.sender()).await,
+			request_availability_cores(leaf, ctx.sender()).await,
 			request_validators(relay_parent, ctx.sender()).await,
 		);
 
@@ -404,6 +426,30 @@ async fn handle_new_activations<Context>(
 	Ok(())
 }
 
+/// Returns the ancestor of `leaf` that is `offset` blocks behind it, or `None` if the chain
+/// is not long enough yet.
+async fn relay_parent_at_offset(
+	leaf: Hash,
+	offset: u32,
+	sender: &mut impl overseer::CollationGenerationSenderTrait,
+) -> crate::error::Result<Option<Hash>> {
+	if offset == 0 {
+		return Ok(Some(leaf))
+	}
+
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(ChainApiMessage::Ancestors {
+			hash: leaf,
+			k: offset as usize,
+			response_channel: tx,
+		})
+		.await;
+
+	let ancestors = rx.await??;
+	Ok(ancestors.get(offset as usize - 1).copied())
+}
+
 async fn obtain_current_validation_code_hash(
 	relay_parent: Hash,
 	para_id: ParaId,
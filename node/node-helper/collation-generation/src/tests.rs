@@ -28,7 +28,7 @@ mod handle_new_activations {
 	};
 	use selendra_node_subsystem::{
 		errors::RuntimeApiError,
-		messages::{AllMessages, RuntimeApiMessage, RuntimeApiRequest},
+		messages::{AllMessages, ChainApiMessage, RuntimeApiMessage, RuntimeApiRequest},
 	};
 	use selendra_primitives::v2::{
 		CollatorPair, Id as ParaId, PersistedValidationData, ScheduledCore, ValidationCode,
@@ -78,6 +78,7 @@ mod handle_new_activations {
 			key: CollatorPair::generate().0,
 			collator: Box::new(|_: Hash, _vd: &PersistedValidationData| TestCollator.boxed()),
 			para_id: para_id.into(),
+			relay_parent_offset: 0,
 		})
 	}
 
@@ -207,6 +208,79 @@ mod handle_new_activations {
 		assert_eq!(requested_validation_data, vec![[4; 32].into()]);
 	}
 
+	#[test]
+	fn requests_validation_data_at_relay_parent_offset() {
+		let leaf = Hash::repeat_byte(4);
+		let ancestors = vec![Hash::repeat_byte(3), Hash::repeat_byte(2)];
+
+		let requested_validation_data = Arc::new(Mutex::new(Vec::new()));
+
+		let overseer_requested_validation_data = requested_validation_data.clone();
+		let overseer_ancestors = ancestors.clone();
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			loop {
+				match handle.try_recv().await {
+					None => break,
+					Some(AllMessages::ChainApi(ChainApiMessage::Ancestors {
+						hash,
+						k,
+						response_channel,
+					})) => {
+						assert_eq!(hash, leaf);
+						assert_eq!(k, 2);
+						response_channel.send(Ok(overseer_ancestors.clone())).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						hash,
+						RuntimeApiRequest::AvailabilityCores(tx),
+					))) => {
+						// cores are scheduled on the leaf, not on the relay parent.
+						assert_eq!(hash, leaf);
+						tx.send(Ok(vec![CoreState::Scheduled(scheduled_core_for(16))])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						hash,
+						RuntimeApiRequest::PersistedValidationData(
+							_para_id,
+							_occupied_core_assumption,
+							tx,
+						),
+					))) => {
+						overseer_requested_validation_data.lock().await.push(hash);
+						tx.send(Ok(None)).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::Validators(tx),
+					))) => {
+						tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+					},
+					Some(msg) => {
+						panic!("didn't expect any other overseer requests; got {:?}", msg)
+					},
+				}
+			}
+		};
+
+		let (tx, _rx) = mpsc::channel(0);
+
+		let config = Arc::new(CollationGenerationConfig {
+			relay_parent_offset: 2,
+			..Arc::try_unwrap(test_config(16)).ok().unwrap()
+		});
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			handle_new_activations(config, vec![leaf], &mut ctx, Metrics(None), &tx)
+				.await
+				.unwrap();
+		});
+
+		let requested_validation_data = Arc::try_unwrap(requested_validation_data)
+			.expect("overseer should have shut down by now")
+			.into_inner();
+
+		assert_eq!(requested_validation_data, vec![ancestors[1]]);
+	}
+
 	#[test]
 	fn sends_distribute_collation_message() {
 		let activated_hashes: Vec<Hash> = vec![
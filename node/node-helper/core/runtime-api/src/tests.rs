@@ -25,9 +25,10 @@ use selendra_primitives::{
 	v2::{
 		AuthorityDiscoveryId, Block, BlockNumber, CandidateEvent, CandidateHash,
 		CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Id as ParaId,
-		InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
-		PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo,
-		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+		InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, ParaUpgradeStatus,
+		PersistedValidationData, PvfCheckStatement, ScheduledParaTransition, ScrapedOnChainVotes,
+		SessionIndex, SessionInfo, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+		ValidatorSignature,
 	},
 };
 use sp_api::ProvideRuntimeApi;
@@ -197,6 +198,18 @@ sp_api::mock_impl_runtime_apis! {
 			self.validation_code_hash.get(&para).map(|c| c.clone())
 		}
 
+		fn scheduled_para_transitions() -> Vec<ScheduledParaTransition> {
+			unimplemented!()
+		}
+
+		fn para_upgrade_status(_: ParaId) -> Option<ParaUpgradeStatus<BlockNumber>> {
+			unimplemented!()
+		}
+
+		fn async_backing_params() -> AsyncBackingParams {
+			unimplemented!()
+		}
+
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			unimplemented!()
 		}
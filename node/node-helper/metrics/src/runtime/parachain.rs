@@ -22,7 +22,8 @@ use primitives::v2::metric_definitions::{
 	PARACHAIN_CREATE_INHERENT_BITFIELDS_SIGNATURE_CHECKS,
 	PARACHAIN_INHERENT_DATA_BITFIELDS_PROCESSED, PARACHAIN_INHERENT_DATA_CANDIDATES_PROCESSED,
 	PARACHAIN_INHERENT_DATA_DISPUTE_SETS_INCLUDED, PARACHAIN_INHERENT_DATA_DISPUTE_SETS_PROCESSED,
-	PARACHAIN_INHERENT_DATA_WEIGHT,
+	PARACHAIN_INHERENT_DATA_WEIGHT, XCMP_QUEUE_INBOUND_CHANNELS_SKIPPED,
+	XCMP_QUEUE_INBOUND_WEIGHT_USED,
 };
 
 /// Register the parachain runtime metrics.
@@ -35,4 +36,8 @@ pub fn register_metrics(runtime_metrics_provider: &RuntimeMetricsProvider) {
 	runtime_metrics_provider.register_countervec(PARACHAIN_INHERENT_DATA_CANDIDATES_PROCESSED);
 	runtime_metrics_provider
 		.register_countervec(PARACHAIN_CREATE_INHERENT_BITFIELDS_SIGNATURE_CHECKS);
+
+	// Emitted by the xcmp-queue pallet of parachain runtimes, which collators execute.
+	runtime_metrics_provider.register_counter(XCMP_QUEUE_INBOUND_WEIGHT_USED);
+	runtime_metrics_provider.register_countervec(XCMP_QUEUE_INBOUND_CHANNELS_SKIPPED);
 }
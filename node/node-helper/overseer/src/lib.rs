@@ -545,6 +545,7 @@ pub struct Overseer<SupportsParachains> {
 	#[subsystem(CollationGenerationMessage, sends: [
 		RuntimeApiMessage,
 		CollatorProtocolMessage,
+		ChainApiMessage,
 	])]
 	collation_generation: CollationGeneration,
 
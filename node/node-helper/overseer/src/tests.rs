@@ -811,6 +811,7 @@ fn test_collator_generation_msg() -> CollationGenerationMessage {
 		key: CollatorPair::generate().0,
 		collator: Box::new(|_, _| TestCollator.boxed()),
 		para_id: Default::default(),
+		relay_parent_offset: 0,
 	})
 }
 struct TestCollator;
@@ -370,6 +370,12 @@ pub struct CollationGenerationConfig {
 	pub collator: CollatorFn,
 	/// The parachain that this collator collates for
 	pub para_id: ParaId,
+	/// How many blocks behind the relay chain tip collations should be built on.
+	///
+	/// `0` builds on the tip itself. Building on an older relay-parent only produces candidates
+	/// the relay chain accepts if it allows at least that many ancestors, see
+	/// `AsyncBackingParams::allowed_ancestry_len`.
+	pub relay_parent_offset: u32,
 }
 
 #[cfg(not(target_os = "unknown"))]
@@ -0,0 +1,180 @@
+// This file is part of Selendra.
+
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC methods for inspecting the state of parachains.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use selendra_primitives::{
+	runtime_api::ParachainHost,
+	v2::{
+		self as primitives, Block, BlockNumber, Hash, Id as ParaId, SessionIndex,
+		ValidationCodeHash,
+	},
+};
+use serde::{Deserialize, Serialize};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+/// Error code returned when the runtime does not provide the queried API.
+const UNSUPPORTED_ERROR: i32 = 2;
+
+/// The first `ParachainHost` version providing `para_upgrade_status`.
+const PARA_UPGRADE_STATUS_VERSION: u32 = 3;
+
+/// The go-ahead signal the relay-chain communicates to a para that is upgrading its code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UpgradeGoAhead {
+	/// The upgrade was aborted.
+	Abort,
+	/// The para must apply the upgrade.
+	GoAhead,
+}
+
+/// A tally of the PVF pre-checking votes for a validation code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PvfCheckVotes {
+	/// The hash of the validation code being pre-checked.
+	pub code_hash: Hash,
+	/// The number of validators that voted to accept the code.
+	pub accept: u32,
+	/// The number of validators that voted to reject the code.
+	pub reject: u32,
+	/// The number of validators eligible to vote.
+	pub n_validators: u32,
+	/// The number of session changes the vote has observed.
+	pub age: SessionIndex,
+	/// The relay-chain block number at which the vote was started.
+	pub created_at: BlockNumber,
+}
+
+/// The state of the validation code upgrade process of a para.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeStatus {
+	/// The hash of the validation code the para currently uses.
+	pub current_code_hash: Option<Hash>,
+	/// The hash of the validation code the para is upgrading to.
+	pub future_code_hash: Option<Hash>,
+	/// The relay-chain block number at which the pending upgrade is expected to be applied.
+	pub expected_upgrade_at: Option<BlockNumber>,
+	/// The go-ahead signal currently communicated to the para.
+	pub go_ahead: Option<UpgradeGoAhead>,
+	/// Whether the para is currently restricted from signalling an upgrade.
+	pub upgrade_restricted: bool,
+	/// The relay-chain block number at which the upgrade cooldown of the para ends.
+	pub cooldown_until: Option<BlockNumber>,
+	/// The PVF pre-checking vote the para is waiting on.
+	pub pvf_check: Option<PvfCheckVotes>,
+}
+
+fn code_hash(hash: ValidationCodeHash) -> Hash {
+	Hash::from_slice(hash.as_ref())
+}
+
+impl From<primitives::ParaUpgradeStatus<BlockNumber>> for UpgradeStatus {
+	fn from(status: primitives::ParaUpgradeStatus<BlockNumber>) -> Self {
+		UpgradeStatus {
+			current_code_hash: status.current_code_hash.map(code_hash),
+			future_code_hash: status.future_code_hash.map(code_hash),
+			expected_upgrade_at: status.expected_upgrade_at,
+			go_ahead: status.go_ahead.map(|signal| match signal {
+				primitives::UpgradeGoAhead::Abort => UpgradeGoAhead::Abort,
+				primitives::UpgradeGoAhead::GoAhead => UpgradeGoAhead::GoAhead,
+			}),
+			upgrade_restricted: status.restriction.is_some(),
+			cooldown_until: status.cooldown_until,
+			pvf_check: status.pvf_check.map(|tally| PvfCheckVotes {
+				code_hash: code_hash(tally.code_hash),
+				accept: tally.accept,
+				reject: tally.reject,
+				n_validators: tally.n_validators,
+				age: tally.age,
+				created_at: tally.created_at,
+			}),
+		}
+	}
+}
+
+/// Parachains RPC methods.
+#[rpc(client, server)]
+pub trait ParachainsApi {
+	/// Returns the state of the validation code upgrade process of the given para, or `null` if
+	/// the para is not registered.
+	#[method(name = "parachains_upgradeStatus")]
+	fn upgrade_status(&self, para_id: u32, at: Option<Hash>) -> RpcResult<Option<UpgradeStatus>>;
+}
+
+/// Provides RPC methods for inspecting the state of parachains.
+pub struct Parachains<C> {
+	client: Arc<C>,
+}
+
+impl<C> Parachains<C> {
+	/// Creates a new instance of the parachains RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> ParachainsApiServer for Parachains<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ParachainHost<Block>,
+{
+	fn upgrade_status(&self, para_id: u32, at: Option<Hash>) -> RpcResult<Option<UpgradeStatus>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let api = self.client.runtime_api();
+
+		let version = api.api_version::<dyn ParachainHost<Block>>(&at).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				RUNTIME_ERROR,
+				"Unable to query the ParachainHost version.",
+				Some(e.to_string()),
+			))
+		})?;
+		if version.unwrap_or(0) < PARA_UPGRADE_STATUS_VERSION {
+			return Err(CallError::Custom(ErrorObject::owned(
+				UNSUPPORTED_ERROR,
+				"The runtime does not support querying the upgrade status.",
+				version.map(|v| format!("ParachainHost version {}", v)),
+			))
+			.into())
+		}
+
+		let status = api.para_upgrade_status(&at, ParaId::from(para_id)).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				RUNTIME_ERROR,
+				"Unable to query the upgrade status.",
+				Some(e.to_string()),
+			))
+		})?;
+
+		Ok(status.map(Into::into))
+	}
+}
@@ -0,0 +1,195 @@
+// This file is part of Selendra.
+
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+
+//! RPC methods for estimating the weight and fees of XCM programs.
+//!
+//! Versioned XCM types are passed and returned SCALE-encoded.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_xcm::runtime_api::{XcmPaymentApi, XcmPaymentApiError};
+use parity_scale_codec::{Decode, Encode};
+use selendra_primitives::v2::{Block, Hash};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::generic::BlockId;
+use xcm::{latest::Weight, VersionedMultiLocation, VersionedXcm};
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+/// Error code returned when an argument cannot be decoded.
+const DECODE_ERROR: i32 = 2;
+/// Error code returned when the runtime cannot estimate the weight or fee.
+const ESTIMATION_ERROR: i32 = 3;
+
+fn runtime_error(e: impl std::fmt::Display) -> CallError {
+	CallError::Custom(ErrorObject::owned(
+		RUNTIME_ERROR,
+		"Unable to query the runtime.",
+		Some(e.to_string()),
+	))
+}
+
+fn estimation_error(e: XcmPaymentApiError) -> CallError {
+	CallError::Custom(ErrorObject::owned(
+		ESTIMATION_ERROR,
+		"Unable to estimate the weight or fee.",
+		Some(format!("{:?}", e)),
+	))
+}
+
+fn decode<T: Decode>(what: &str, bytes: Bytes) -> Result<T, CallError> {
+	T::decode(&mut &bytes[..]).map_err(|e| {
+		CallError::Custom(ErrorObject::owned(
+			DECODE_ERROR,
+			format!("Unable to decode the {}.", what),
+			Some(e.to_string()),
+		))
+	})
+}
+
+/// XCM fee estimation RPC methods.
+#[rpc(client, server)]
+pub trait XcmFeesApi {
+	/// Returns the SCALE-encoded `VersionedMultiLocation`s of the assets that can pay for
+	/// execution, expressed in `xcm_version`.
+	#[method(name = "xcm_acceptablePaymentAssets")]
+	fn acceptable_payment_assets(
+		&self,
+		xcm_version: u32,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<Bytes>>;
+
+	/// Returns the weight of executing the SCALE-encoded `VersionedXcm` `message`.
+	#[method(name = "xcm_queryWeight")]
+	fn query_weight(&self, message: Bytes, at: Option<Hash>) -> RpcResult<Weight>;
+
+	/// Returns the fee of buying `weight` in the SCALE-encoded `VersionedMultiLocation` `asset`.
+	#[method(name = "xcm_weightToAssetFee")]
+	fn weight_to_asset_fee(
+		&self,
+		weight: Weight,
+		asset: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<NumberOrHex>;
+
+	/// Returns the SCALE-encoded `VersionedMultiAssets` charged for delivering the
+	/// SCALE-encoded `VersionedXcm` `message` to the `VersionedMultiLocation` `destination`.
+	#[method(name = "xcm_deliveryFees")]
+	fn delivery_fees(
+		&self,
+		destination: Bytes,
+		message: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<Bytes>;
+}
+
+/// Provides RPC methods for estimating the weight and fees of XCM programs.
+pub struct XcmFees<C> {
+	client: Arc<C>,
+}
+
+impl<C> XcmFees<C> {
+	/// Creates a new instance of the XCM fee estimation RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> XcmFees<C>
+where
+	C: HeaderBackend<Block>,
+{
+	fn at(&self, at: Option<Hash>) -> BlockId<Block> {
+		BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+impl<C> XcmFeesApiServer for XcmFees<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: XcmPaymentApi<Block>,
+{
+	fn acceptable_payment_assets(
+		&self,
+		xcm_version: u32,
+		at: Option<Hash>,
+	) -> RpcResult<Vec<Bytes>> {
+		let assets = self
+			.client
+			.runtime_api()
+			.query_acceptable_payment_assets(&self.at(at), xcm_version)
+			.map_err(runtime_error)?
+			.map_err(estimation_error)?;
+
+		Ok(assets.into_iter().map(|asset| asset.encode().into()).collect())
+	}
+
+	fn query_weight(&self, message: Bytes, at: Option<Hash>) -> RpcResult<Weight> {
+		let message: VersionedXcm<()> = decode("message", message)?;
+		let weight = self
+			.client
+			.runtime_api()
+			.query_xcm_weight(&self.at(at), message)
+			.map_err(runtime_error)?
+			.map_err(estimation_error)?;
+
+		Ok(weight)
+	}
+
+	fn weight_to_asset_fee(
+		&self,
+		weight: Weight,
+		asset: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<NumberOrHex> {
+		let asset: VersionedMultiLocation = decode("asset", asset)?;
+		let fee = self
+			.client
+			.runtime_api()
+			.query_weight_to_asset_fee(&self.at(at), weight, asset)
+			.map_err(runtime_error)?
+			.map_err(estimation_error)?;
+
+		Ok(fee.into())
+	}
+
+	fn delivery_fees(
+		&self,
+		destination: Bytes,
+		message: Bytes,
+		at: Option<Hash>,
+	) -> RpcResult<Bytes> {
+		let destination: VersionedMultiLocation = decode("destination", destination)?;
+		let message: VersionedXcm<()> = decode("message", message)?;
+		let fees = self
+			.client
+			.runtime_api()
+			.query_delivery_fees(&self.at(at), destination, message)
+			.map_err(runtime_error)?
+			.map_err(estimation_error)?;
+
+		Ok(fees.encode().into())
+	}
+}
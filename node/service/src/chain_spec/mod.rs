@@ -240,6 +240,8 @@ fn selendra_staging_testnet_config_genesis(
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
 		sudo: selendra::SudoConfig { key: Some(_root_key) },
+		#[cfg(feature = "indranet")]
+		assigned_slots: Default::default(),
 	}
 }
 
@@ -445,6 +447,11 @@ pub fn selendra_testnet_genesis(
 		paras: Default::default(),
 		xcm_pallet: Default::default(),
 		sudo: selendra::SudoConfig { key: Some(_root_key) },
+		#[cfg(feature = "indranet")]
+		assigned_slots: selendra::AssignedSlotsConfig {
+			max_permanent_slots: 8,
+			max_temporary_slots: 20,
+		},
 	}
 }
 
@@ -16,8 +16,257 @@
 
 //! A module that is responsible for migration of storage.
 
-use frame_support::traits::StorageVersion;
+use crate::configuration::{self, Config, Pallet, Store};
+use frame_support::{
+	pallet_prelude::*,
+	traits::{OnRuntimeUpgrade, StorageVersion},
+};
+use sp_std::prelude::*;
 
 /// The current storage version.
 ///
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+/// v2-v3: adds `async_backing_params` to the `HostConfiguration`.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+pub mod v3 {
+	use super::*;
+	use primitives::v2::{AsyncBackingParams, Balance, SessionIndex};
+
+	/// The `HostConfiguration` as it was stored in storage version 2.
+	#[derive(Clone, Encode, Decode)]
+	pub struct OldHostConfiguration<BlockNumber> {
+		pub max_code_size: u32,
+		pub max_head_data_size: u32,
+		pub max_upward_queue_count: u32,
+		pub max_upward_queue_size: u32,
+		pub max_upward_message_size: u32,
+		pub max_upward_message_num_per_candidate: u32,
+		pub hrmp_max_message_num_per_candidate: u32,
+		pub validation_upgrade_cooldown: BlockNumber,
+		pub validation_upgrade_delay: BlockNumber,
+		pub max_pov_size: u32,
+		pub max_downward_message_size: u32,
+		pub ump_service_total_weight: Weight,
+		pub hrmp_max_parachain_outbound_channels: u32,
+		pub hrmp_max_parathread_outbound_channels: u32,
+		pub hrmp_sender_deposit: Balance,
+		pub hrmp_recipient_deposit: Balance,
+		pub hrmp_channel_max_capacity: u32,
+		pub hrmp_channel_max_total_size: u32,
+		pub hrmp_max_parachain_inbound_channels: u32,
+		pub hrmp_max_parathread_inbound_channels: u32,
+		pub hrmp_channel_max_message_size: u32,
+		pub code_retention_period: BlockNumber,
+		pub parathread_cores: u32,
+		pub parathread_retries: u32,
+		pub group_rotation_frequency: BlockNumber,
+		pub chain_availability_period: BlockNumber,
+		pub thread_availability_period: BlockNumber,
+		pub scheduling_lookahead: u32,
+		pub max_validators_per_core: Option<u32>,
+		pub max_validators: Option<u32>,
+		pub dispute_period: SessionIndex,
+		pub dispute_post_conclusion_acceptance_period: BlockNumber,
+		pub dispute_max_spam_slots: u32,
+		pub dispute_conclusion_by_time_out_period: BlockNumber,
+		pub no_show_slots: u32,
+		pub n_delay_tranches: u32,
+		pub zeroth_delay_tranche_width: u32,
+		pub needed_approvals: u32,
+		pub relay_vrf_modulo_samples: u32,
+		pub ump_max_individual_weight: Weight,
+		pub pvf_checking_enabled: bool,
+		pub pvf_voting_ttl: SessionIndex,
+		pub minimum_validation_upgrade_delay: BlockNumber,
+	}
+
+	impl<BlockNumber> OldHostConfiguration<BlockNumber> {
+		fn upgrade(self) -> configuration::HostConfiguration<BlockNumber> {
+			configuration::HostConfiguration {
+				max_code_size: self.max_code_size,
+				max_head_data_size: self.max_head_data_size,
+				max_upward_queue_count: self.max_upward_queue_count,
+				max_upward_queue_size: self.max_upward_queue_size,
+				max_upward_message_size: self.max_upward_message_size,
+				max_upward_message_num_per_candidate: self.max_upward_message_num_per_candidate,
+				hrmp_max_message_num_per_candidate: self.hrmp_max_message_num_per_candidate,
+				validation_upgrade_cooldown: self.validation_upgrade_cooldown,
+				validation_upgrade_delay: self.validation_upgrade_delay,
+				max_pov_size: self.max_pov_size,
+				max_downward_message_size: self.max_downward_message_size,
+				ump_service_total_weight: self.ump_service_total_weight,
+				hrmp_max_parachain_outbound_channels: self.hrmp_max_parachain_outbound_channels,
+				hrmp_max_parathread_outbound_channels: self.hrmp_max_parathread_outbound_channels,
+				hrmp_sender_deposit: self.hrmp_sender_deposit,
+				hrmp_recipient_deposit: self.hrmp_recipient_deposit,
+				hrmp_channel_max_capacity: self.hrmp_channel_max_capacity,
+				hrmp_channel_max_total_size: self.hrmp_channel_max_total_size,
+				hrmp_max_parachain_inbound_channels: self.hrmp_max_parachain_inbound_channels,
+				hrmp_max_parathread_inbound_channels: self.hrmp_max_parathread_inbound_channels,
+				hrmp_channel_max_message_size: self.hrmp_channel_max_message_size,
+				code_retention_period: self.code_retention_period,
+				parathread_cores: self.parathread_cores,
+				parathread_retries: self.parathread_retries,
+				group_rotation_frequency: self.group_rotation_frequency,
+				chain_availability_period: self.chain_availability_period,
+				thread_availability_period: self.thread_availability_period,
+				scheduling_lookahead: self.scheduling_lookahead,
+				max_validators_per_core: self.max_validators_per_core,
+				max_validators: self.max_validators,
+				dispute_period: self.dispute_period,
+				dispute_post_conclusion_acceptance_period: self
+					.dispute_post_conclusion_acceptance_period,
+				dispute_max_spam_slots: self.dispute_max_spam_slots,
+				dispute_conclusion_by_time_out_period: self.dispute_conclusion_by_time_out_period,
+				no_show_slots: self.no_show_slots,
+				n_delay_tranches: self.n_delay_tranches,
+				zeroth_delay_tranche_width: self.zeroth_delay_tranche_width,
+				needed_approvals: self.needed_approvals,
+				relay_vrf_modulo_samples: self.relay_vrf_modulo_samples,
+				ump_max_individual_weight: self.ump_max_individual_weight,
+				pvf_checking_enabled: self.pvf_checking_enabled,
+				pvf_voting_ttl: self.pvf_voting_ttl,
+				minimum_validation_upgrade_delay: self.minimum_validation_upgrade_delay,
+				async_backing_params: AsyncBackingParams::default(),
+			}
+		}
+	}
+
+	/// Adds `async_backing_params` to the active and all pending configurations. The new field
+	/// defaults to all zeroes, which keeps candidates bound to the immediate relay-parent.
+	pub struct MigrateToV3<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() == 2 {
+				let weight_consumed = migrate_to_v3::<T>();
+
+				log::info!(target: configuration::LOG_TARGET, "MigrateToV3 executed successfully");
+				STORAGE_VERSION.put::<Pallet<T>>();
+
+				weight_consumed
+			} else {
+				log::warn!(target: configuration::LOG_TARGET, "MigrateToV3 should be removed.");
+				T::DbWeight::get().reads(1)
+			}
+		}
+	}
+
+	fn migrate_to_v3<T: Config>() -> Weight {
+		if <Pallet<T> as Store>::ActiveConfig::translate(
+			|pre: Option<OldHostConfiguration<T::BlockNumber>>| pre.map(|pre| pre.upgrade()),
+		)
+		.is_err()
+		{
+			// `Err` is returned when the pre-migration type cannot be deserialized. This
+			// cannot happen if the migration runs correctly, i.e. against the expected version.
+			//
+			// This happening almost surely will lead to a panic somewhere else. Corruption seems
+			// to be unlikely to be caused by this. So we just log. Maybe it'll work out still?
+			log::error!(
+				target: configuration::LOG_TARGET,
+				"unexpected error when performing translation of the active configuration during storage upgrade to v3."
+			);
+		}
+
+		if <Pallet<T> as Store>::PendingConfigs::translate(
+			|pre: Option<Vec<(SessionIndex, OldHostConfiguration<T::BlockNumber>)>>| {
+				pre.map(|pending| {
+					pending
+						.into_iter()
+						.map(|(session, config)| (session, config.upgrade()))
+						.collect()
+				})
+			},
+		)
+		.is_err()
+		{
+			log::error!(
+				target: configuration::LOG_TARGET,
+				"unexpected error when performing translation of the pending configurations during storage upgrade to v3."
+			);
+		}
+
+		T::DbWeight::get().reads_writes(3, 3)
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::mock::{new_test_ext, Test};
+
+		#[test]
+		fn v2_configurations_are_upgraded() {
+			let old = OldHostConfiguration::<primitives::v2::BlockNumber> {
+				max_code_size: Default::default(),
+				max_head_data_size: Default::default(),
+				max_upward_queue_count: Default::default(),
+				max_upward_queue_size: Default::default(),
+				max_upward_message_size: Default::default(),
+				max_upward_message_num_per_candidate: Default::default(),
+				hrmp_max_message_num_per_candidate: Default::default(),
+				validation_upgrade_cooldown: Default::default(),
+				validation_upgrade_delay: Default::default(),
+				max_pov_size: Default::default(),
+				max_downward_message_size: Default::default(),
+				ump_service_total_weight: Default::default(),
+				hrmp_max_parachain_outbound_channels: Default::default(),
+				hrmp_max_parathread_outbound_channels: Default::default(),
+				hrmp_sender_deposit: Default::default(),
+				hrmp_recipient_deposit: Default::default(),
+				hrmp_channel_max_capacity: Default::default(),
+				hrmp_channel_max_total_size: Default::default(),
+				hrmp_max_parachain_inbound_channels: Default::default(),
+				hrmp_max_parathread_inbound_channels: Default::default(),
+				hrmp_channel_max_message_size: Default::default(),
+				code_retention_period: Default::default(),
+				parathread_cores: Default::default(),
+				parathread_retries: Default::default(),
+				group_rotation_frequency: Default::default(),
+				chain_availability_period: Default::default(),
+				thread_availability_period: Default::default(),
+				scheduling_lookahead: Default::default(),
+				max_validators_per_core: Default::default(),
+				max_validators: Default::default(),
+				dispute_period: Default::default(),
+				dispute_post_conclusion_acceptance_period: Default::default(),
+				dispute_max_spam_slots: Default::default(),
+				dispute_conclusion_by_time_out_period: Default::default(),
+				no_show_slots: Default::default(),
+				n_delay_tranches: Default::default(),
+				zeroth_delay_tranche_width: Default::default(),
+				needed_approvals: Default::default(),
+				relay_vrf_modulo_samples: Default::default(),
+				ump_max_individual_weight: Default::default(),
+				pvf_checking_enabled: Default::default(),
+				pvf_voting_ttl: Default::default(),
+				minimum_validation_upgrade_delay: Default::default(),
+			};
+			let mut old_with_values = old.clone();
+			old_with_values.max_code_size = 1_000;
+			old_with_values.minimum_validation_upgrade_delay = 20;
+
+			new_test_ext(Default::default()).execute_with(|| {
+				frame_support::storage::unhashed::put_raw(
+					&configuration::ActiveConfig::<Test>::hashed_key(),
+					&old_with_values.encode(),
+				);
+				frame_support::storage::unhashed::put_raw(
+					&configuration::PendingConfigs::<Test>::hashed_key(),
+					&vec![(5 as SessionIndex, old.clone())].encode(),
+				);
+				StorageVersion::new(2).put::<Pallet<Test>>();
+
+				MigrateToV3::<Test>::on_runtime_upgrade();
+
+				let active = configuration::ActiveConfig::<Test>::get();
+				assert_eq!(active.max_code_size, 1_000);
+				assert_eq!(active.minimum_validation_upgrade_delay, 20);
+				assert_eq!(active.async_backing_params, AsyncBackingParams::default());
+
+				let pending = configuration::PendingConfigs::<Test>::get();
+				assert_eq!(pending, vec![(5, old.upgrade())]);
+				assert_eq!(StorageVersion::get::<Pallet<Test>>(), STORAGE_VERSION);
+			});
+		}
+	}
+}
@@ -22,8 +22,10 @@ use crate::shared;
 use frame_support::{pallet_prelude::*, weights::constants::WEIGHT_PER_MILLIS};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v2::{Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE};
-use sp_runtime::traits::Zero;
+use primitives::v2::{
+	AsyncBackingParams, Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE,
+};
+use sp_runtime::traits::{BadOrigin, Zero};
 use sp_std::prelude::*;
 
 #[cfg(test)]
@@ -35,6 +37,7 @@ mod benchmarking;
 pub use pallet::*;
 
 pub mod migration;
+pub mod runtime_api;
 
 const LOG_TARGET: &str = "runtime::configuration";
 
@@ -240,6 +243,10 @@ pub struct HostConfiguration<BlockNumber> {
 	/// This value should be greater than [`chain_availability_period`] and
 	/// [`thread_availability_period`].
 	pub minimum_validation_upgrade_delay: BlockNumber,
+	/// Asynchronous backing parameters.
+	///
+	/// The default of all zeroes keeps parachain candidates bound to the immediate relay-parent.
+	pub async_backing_params: AsyncBackingParams,
 }
 
 impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber> {
@@ -288,6 +295,7 @@ impl<BlockNumber: Default + From<u32>> Default for HostConfiguration<BlockNumber
 			pvf_checking_enabled: false,
 			pvf_voting_ttl: 2u32.into(),
 			minimum_validation_upgrade_delay: 2.into(),
+			async_backing_params: AsyncBackingParams::default(),
 		}
 	}
 }
@@ -419,6 +427,116 @@ where
 	}
 }
 
+/// Generates [`HostConfigurationUpdate`] together with the helpers that map its variants onto the
+/// fields of [`HostConfiguration`].
+macro_rules! host_configuration_updates {
+	($($variant:ident => $field:ident: $ty:ty,)*) => {
+		/// A new value for a single field of the [`HostConfiguration`].
+		///
+		/// Used to batch several configuration changes into one call and to record the history
+		/// of configuration changes.
+		#[derive(Clone, Encode, Decode, PartialEq, sp_core::RuntimeDebug, scale_info::TypeInfo)]
+		pub enum HostConfigurationUpdate<BlockNumber> {
+			$(
+				#[doc = concat!("New value for `", stringify!($field), "`.")]
+				$variant($ty),
+			)*
+		}
+
+		impl<BlockNumber> HostConfigurationUpdate<BlockNumber> {
+			/// Write the value carried by this update into the corresponding field of `config`.
+			pub fn apply(self, config: &mut HostConfiguration<BlockNumber>) {
+				match self {
+					$(Self::$variant(new) => config.$field = new,)*
+				}
+			}
+		}
+
+		impl<BlockNumber: Clone + PartialEq> HostConfiguration<BlockNumber> {
+			/// Returns the `(old, new)` values of every field that differs between `self` and
+			/// `new`, in the field declaration order.
+			pub fn diff(
+				&self,
+				new: &Self,
+			) -> Vec<(HostConfigurationUpdate<BlockNumber>, HostConfigurationUpdate<BlockNumber>)> {
+				let mut changes = Vec::new();
+				$(
+					if self.$field != new.$field {
+						changes.push((
+							HostConfigurationUpdate::$variant(self.$field.clone()),
+							HostConfigurationUpdate::$variant(new.$field.clone()),
+						));
+					}
+				)*
+				changes
+			}
+		}
+	};
+}
+
+host_configuration_updates! {
+	MaxCodeSize => max_code_size: u32,
+	MaxHeadDataSize => max_head_data_size: u32,
+	MaxUpwardQueueCount => max_upward_queue_count: u32,
+	MaxUpwardQueueSize => max_upward_queue_size: u32,
+	MaxUpwardMessageSize => max_upward_message_size: u32,
+	MaxUpwardMessageNumPerCandidate => max_upward_message_num_per_candidate: u32,
+	HrmpMaxMessageNumPerCandidate => hrmp_max_message_num_per_candidate: u32,
+	ValidationUpgradeCooldown => validation_upgrade_cooldown: BlockNumber,
+	ValidationUpgradeDelay => validation_upgrade_delay: BlockNumber,
+	MaxPovSize => max_pov_size: u32,
+	MaxDownwardMessageSize => max_downward_message_size: u32,
+	UmpServiceTotalWeight => ump_service_total_weight: Weight,
+	HrmpMaxParachainOutboundChannels => hrmp_max_parachain_outbound_channels: u32,
+	HrmpMaxParathreadOutboundChannels => hrmp_max_parathread_outbound_channels: u32,
+	HrmpSenderDeposit => hrmp_sender_deposit: Balance,
+	HrmpRecipientDeposit => hrmp_recipient_deposit: Balance,
+	HrmpChannelMaxCapacity => hrmp_channel_max_capacity: u32,
+	HrmpChannelMaxTotalSize => hrmp_channel_max_total_size: u32,
+	HrmpMaxParachainInboundChannels => hrmp_max_parachain_inbound_channels: u32,
+	HrmpMaxParathreadInboundChannels => hrmp_max_parathread_inbound_channels: u32,
+	HrmpChannelMaxMessageSize => hrmp_channel_max_message_size: u32,
+	CodeRetentionPeriod => code_retention_period: BlockNumber,
+	ParathreadCores => parathread_cores: u32,
+	ParathreadRetries => parathread_retries: u32,
+	GroupRotationFrequency => group_rotation_frequency: BlockNumber,
+	ChainAvailabilityPeriod => chain_availability_period: BlockNumber,
+	ThreadAvailabilityPeriod => thread_availability_period: BlockNumber,
+	SchedulingLookahead => scheduling_lookahead: u32,
+	MaxValidatorsPerCore => max_validators_per_core: Option<u32>,
+	MaxValidators => max_validators: Option<u32>,
+	DisputePeriod => dispute_period: SessionIndex,
+	DisputePostConclusionAcceptancePeriod => dispute_post_conclusion_acceptance_period: BlockNumber,
+	DisputeMaxSpamSlots => dispute_max_spam_slots: u32,
+	DisputeConclusionByTimeOutPeriod => dispute_conclusion_by_time_out_period: BlockNumber,
+	NoShowSlots => no_show_slots: u32,
+	NDelayTranches => n_delay_tranches: u32,
+	ZerothDelayTrancheWidth => zeroth_delay_tranche_width: u32,
+	NeededApprovals => needed_approvals: u32,
+	RelayVrfModuloSamples => relay_vrf_modulo_samples: u32,
+	UmpMaxIndividualWeight => ump_max_individual_weight: Weight,
+	PvfCheckingEnabled => pvf_checking_enabled: bool,
+	PvfVotingTtl => pvf_voting_ttl: SessionIndex,
+	MinimumValidationUpgradeDelay => minimum_validation_upgrade_delay: BlockNumber,
+	AsyncBackingParams => async_backing_params: AsyncBackingParams,
+}
+
+/// The maximum number of entries kept in [`ConfigChangeHistory`]. Older entries are dropped first.
+pub const MAX_CONFIG_CHANGE_HISTORY: u32 = 128;
+
+/// A record of a change to a single field of the [`HostConfiguration`].
+#[derive(Clone, Encode, Decode, PartialEq, sp_core::RuntimeDebug, scale_info::TypeInfo)]
+pub struct ConfigChangeRecord<AccountId, BlockNumber> {
+	/// The session at which the change takes effect.
+	pub session: SessionIndex,
+	/// The origin that requested the change.
+	pub origin: frame_system::RawOrigin<AccountId>,
+	/// The value of the field before the change.
+	pub old: HostConfigurationUpdate<BlockNumber>,
+	/// The value of the field after the change.
+	pub new: HostConfigurationUpdate<BlockNumber>,
+}
+
 pub trait WeightInfo {
 	fn set_config_with_block_number() -> Weight;
 	fn set_config_with_u32() -> Weight;
@@ -428,6 +546,11 @@ pub trait WeightInfo {
 	fn set_hrmp_open_request_ttl() -> Weight;
 }
 
+/// Adds the cost of recording a change in [`ConfigChangeHistory`] to the `weight` of a setter.
+fn with_history<T: frame_system::Config>(weight: Weight) -> Weight {
+	weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+}
+
 pub struct TestWeightInfo;
 impl WeightInfo for TestWeightInfo {
 	fn set_config_with_block_number() -> Weight {
@@ -494,6 +617,20 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(crate) type BypassConsistencyCheck<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+	/// The history of configuration changes, one entry per changed field, oldest first.
+	///
+	/// At most [`MAX_CONFIG_CHANGE_HISTORY`] entries are kept.
+	#[pallet::storage]
+	#[pallet::getter(fn config_change_history)]
+	pub(crate) type ConfigChangeHistory<T: Config> = StorageValue<
+		_,
+		BoundedVec<
+			ConfigChangeRecord<T::AccountId, T::BlockNumber>,
+			ConstU32<MAX_CONFIG_CHANGE_HISTORY>,
+		>,
+		ValueQuery,
+	>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub config: HostConfiguration<T::BlockNumber>,
@@ -518,243 +655,243 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		/// Set the validation upgrade cooldown.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_validation_upgrade_cooldown(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.validation_upgrade_cooldown = new;
 			})
 		}
 
 		/// Set the validation upgrade delay.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_validation_upgrade_delay(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.validation_upgrade_delay = new;
 			})
 		}
 
 		/// Set the acceptance period for an included candidate.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_code_retention_period(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.code_retention_period = new;
 			})
 		}
 
 		/// Set the max validation code size for incoming upgrades.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_code_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_code_size = new;
 			})
 		}
 
 		/// Set the max POV block size for incoming upgrades.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_pov_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_pov_size = new;
 			})
 		}
 
 		/// Set the max head data size for paras.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_head_data_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_head_data_size = new;
 			})
 		}
 
 		/// Set the number of parathread execution cores.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_parathread_cores(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.parathread_cores = new;
 			})
 		}
 
 		/// Set the number of retries for a particular parathread.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_parathread_retries(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.parathread_retries = new;
 			})
 		}
 
 		/// Set the parachain validator-group rotation frequency
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_group_rotation_frequency(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.group_rotation_frequency = new;
 			})
 		}
 
 		/// Set the availability period for parachains.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_chain_availability_period(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.chain_availability_period = new;
 			})
 		}
 
 		/// Set the availability period for parathreads.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_thread_availability_period(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.thread_availability_period = new;
 			})
 		}
 
 		/// Set the scheduling lookahead, in expected number of blocks at peak throughput.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_scheduling_lookahead(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.scheduling_lookahead = new;
 			})
 		}
 
 		/// Set the maximum number of validators to assign to any core.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_option_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_option_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_validators_per_core(
 			origin: OriginFor<T>,
 			new: Option<u32>,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_validators_per_core = new;
 			})
 		}
 
 		/// Set the maximum number of validators to use in parachain consensus.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_option_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_option_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_validators(origin: OriginFor<T>, new: Option<u32>) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_validators = new;
 			})
 		}
 
 		/// Set the dispute period, in number of sessions to keep for disputes.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_dispute_period(origin: OriginFor<T>, new: SessionIndex) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.dispute_period = new;
 			})
 		}
 
 		/// Set the dispute post conclusion acceptance period.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_dispute_post_conclusion_acceptance_period(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.dispute_post_conclusion_acceptance_period = new;
 			})
 		}
 
 		/// Set the maximum number of dispute spam slots.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_dispute_max_spam_slots(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.dispute_max_spam_slots = new;
 			})
 		}
 
 		/// Set the dispute conclusion by time out period.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_dispute_conclusion_by_time_out_period(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.dispute_conclusion_by_time_out_period = new;
 			})
 		}
@@ -762,135 +899,135 @@ pub mod pallet {
 		/// Set the no show slots, in number of number of consensus slots.
 		/// Must be at least 1.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_no_show_slots(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.no_show_slots = new;
 			})
 		}
 
 		/// Set the total number of delay tranches.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_n_delay_tranches(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.n_delay_tranches = new;
 			})
 		}
 
 		/// Set the zeroth delay tranche width.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_zeroth_delay_tranche_width(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.zeroth_delay_tranche_width = new;
 			})
 		}
 
 		/// Set the number of validators needed to approve a block.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_needed_approvals(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.needed_approvals = new;
 			})
 		}
 
 		/// Set the number of samples to do of the `RelayVRFModulo` approval assignment criterion.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_relay_vrf_modulo_samples(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.relay_vrf_modulo_samples = new;
 			})
 		}
 
 		/// Sets the maximum items that can present in a upward dispatch queue at once.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_upward_queue_count(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_upward_queue_count = new;
 			})
 		}
 
 		/// Sets the maximum total size of items that can present in a upward dispatch queue at once.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_upward_queue_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_upward_queue_size = new;
 			})
 		}
 
 		/// Set the critical downward message size.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_downward_message_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_downward_message_size = new;
 			})
 		}
 
 		/// Sets the soft limit for the phase of dispatching dispatchable upward messages.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_weight(),
+			with_history::<T>(T::WeightInfo::set_config_with_weight()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_ump_service_total_weight(origin: OriginFor<T>, new: Weight) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.ump_service_total_weight = new;
 			})
 		}
 
 		/// Sets the maximum size of an upward message that can be sent by a candidate.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_upward_message_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_upward_message_size = new;
 			})
 		}
 
 		/// Sets the maximum number of messages that a candidate can contain.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_max_upward_message_num_per_candidate(
 			origin: OriginFor<T>,
 			new: u32,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.max_upward_message_num_per_candidate = new;
 			})
 		}
@@ -908,12 +1045,12 @@ pub mod pallet {
 
 		/// Sets the amount of funds that the sender should provide for opening an HRMP channel.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_balance(),
+			with_history::<T>(T::WeightInfo::set_config_with_balance()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_sender_deposit(origin: OriginFor<T>, new: Balance) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_sender_deposit = new;
 			})
 		}
@@ -921,135 +1058,135 @@ pub mod pallet {
 		/// Sets the amount of funds that the recipient should provide for accepting opening an HRMP
 		/// channel.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_balance(),
+			with_history::<T>(T::WeightInfo::set_config_with_balance()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_recipient_deposit(origin: OriginFor<T>, new: Balance) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_recipient_deposit = new;
 			})
 		}
 
 		/// Sets the maximum number of messages allowed in an HRMP channel at once.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_channel_max_capacity(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_channel_max_capacity = new;
 			})
 		}
 
 		/// Sets the maximum total size of messages in bytes allowed in an HRMP channel at once.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_channel_max_total_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_channel_max_total_size = new;
 			})
 		}
 
 		/// Sets the maximum number of inbound HRMP channels a parachain is allowed to accept.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_max_parachain_inbound_channels(
 			origin: OriginFor<T>,
 			new: u32,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_max_parachain_inbound_channels = new;
 			})
 		}
 
 		/// Sets the maximum number of inbound HRMP channels a parathread is allowed to accept.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_max_parathread_inbound_channels(
 			origin: OriginFor<T>,
 			new: u32,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_max_parathread_inbound_channels = new;
 			})
 		}
 
 		/// Sets the maximum size of a message that could ever be put into an HRMP channel.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_channel_max_message_size(origin: OriginFor<T>, new: u32) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_channel_max_message_size = new;
 			})
 		}
 
 		/// Sets the maximum number of outbound HRMP channels a parachain is allowed to open.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_max_parachain_outbound_channels(
 			origin: OriginFor<T>,
 			new: u32,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_max_parachain_outbound_channels = new;
 			})
 		}
 
 		/// Sets the maximum number of outbound HRMP channels a parathread is allowed to open.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_max_parathread_outbound_channels(
 			origin: OriginFor<T>,
 			new: u32,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_max_parathread_outbound_channels = new;
 			})
 		}
 
 		/// Sets the maximum number of outbound HRMP messages can be sent by a candidate.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_hrmp_max_message_num_per_candidate(
 			origin: OriginFor<T>,
 			new: u32,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.hrmp_max_message_num_per_candidate = new;
 			})
 		}
 
 		/// Sets the maximum amount of weight any individual upward message may consume.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_weight(),
+			with_history::<T>(T::WeightInfo::set_config_with_weight()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_ump_max_individual_weight(origin: OriginFor<T>, new: Weight) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.ump_max_individual_weight = new;
 			})
 		}
@@ -1057,24 +1194,24 @@ pub mod pallet {
 		/// Enable or disable PVF pre-checking. Consult the field documentation prior executing.
 		#[pallet::weight((
 			// Using u32 here is a little bit of cheating, but that should be fine.
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_pvf_checking_enabled(origin: OriginFor<T>, new: bool) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.pvf_checking_enabled = new;
 			})
 		}
 
 		/// Set the number of session changes after which a PVF pre-checking voting is rejected.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_u32(),
+			with_history::<T>(T::WeightInfo::set_config_with_u32()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_pvf_voting_ttl(origin: OriginFor<T>, new: SessionIndex) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.pvf_voting_ttl = new;
 			})
 		}
@@ -1084,19 +1221,34 @@ pub mod pallet {
 		///
 		/// See the field documentation for information and constraints for the new value.
 		#[pallet::weight((
-			T::WeightInfo::set_config_with_block_number(),
+			with_history::<T>(T::WeightInfo::set_config_with_block_number()),
 			DispatchClass::Operational,
 		))]
 		pub fn set_minimum_validation_upgrade_delay(
 			origin: OriginFor<T>,
 			new: T::BlockNumber,
 		) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::schedule_config_update(|config| {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
 				config.minimum_validation_upgrade_delay = new;
 			})
 		}
 
+		/// Set the asynchronous backing parameters.
+		#[pallet::weight((
+			with_history::<T>(T::WeightInfo::set_config_with_option_u32()), // The same size in bytes.
+			DispatchClass::Operational,
+		))]
+		pub fn set_async_backing_params(
+			origin: OriginFor<T>,
+			new: AsyncBackingParams,
+		) -> DispatchResult {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
+				config.async_backing_params = new;
+			})
+		}
+
 		/// Setting this to true will disable consistency checks for the configuration setters.
 		/// Use with caution.
 		#[pallet::weight((
@@ -1108,6 +1260,30 @@ pub mod pallet {
 			<Self as Store>::BypassConsistencyCheck::put(new);
 			Ok(())
 		}
+
+		/// Set several configuration fields at once.
+		///
+		/// The updates are applied in order on top of the latest pending configuration and the
+		/// result is checked for consistency only once, so the fields can be moved into a new
+		/// consistent state that is not reachable by calling the setters one by one.
+		#[pallet::weight((
+			with_history::<T>(
+				T::WeightInfo::set_config_with_balance()
+					.saturating_mul(updates.len().max(1) as Weight)
+			),
+			DispatchClass::Operational,
+		))]
+		pub fn set_config_batch(
+			origin: OriginFor<T>,
+			updates: Vec<HostConfigurationUpdate<T::BlockNumber>>,
+		) -> DispatchResult {
+			let origin = Self::ensure_config_origin(origin)?;
+			Self::schedule_config_update(origin, |config| {
+				for update in updates {
+					update.apply(config);
+				}
+			})
+		}
 	}
 
 	#[pallet::hooks]
@@ -1181,6 +1357,12 @@ impl<T: Config> Pallet<T> {
 		SessionChangeOutcome { prev_config, new_config }
 	}
 
+	/// Returns the configurations that are scheduled to become active, paired with the session
+	/// at which each of them is applied.
+	pub fn pending_configs() -> Vec<(SessionIndex, HostConfiguration<T::BlockNumber>)> {
+		<PendingConfigs<T>>::get()
+	}
+
 	/// Return the session index that should be used for any future scheduled changes.
 	fn scheduled_session() -> SessionIndex {
 		shared::Pallet::<T>::scheduled_session()
@@ -1209,6 +1391,7 @@ impl<T: Config> Pallet<T> {
 	// the sake of essentially avoiding an indirect call. Doesn't worth it.
 	#[inline(never)]
 	fn schedule_config_update(
+		origin: frame_system::RawOrigin<T::AccountId>,
 		updater: impl FnOnce(&mut HostConfiguration<T::BlockNumber>),
 	) -> DispatchResult {
 		let mut pending_configs = <PendingConfigs<T>>::get();
@@ -1246,6 +1429,8 @@ impl<T: Config> Pallet<T> {
 			.unwrap_or_else(Self::config);
 		let base_config_consistent = base_config.check_consistency().is_ok();
 
+		let old_config = base_config.clone();
+
 		// Now, we need to decide what the new configuration should be.
 		// We also move the `base_config` to `new_config` to empahsize that the base config was
 		// destroyed by the `updater`.
@@ -1285,6 +1470,8 @@ impl<T: Config> Pallet<T> {
 
 		let scheduled_session = Self::scheduled_session();
 
+		Self::note_config_changes(origin, scheduled_session, &old_config, &new_config);
+
 		if let Some(&mut (_, ref mut config)) = pending_configs
 			.iter_mut()
 			.find(|&&mut (apply_at_session, _)| apply_at_session >= scheduled_session)
@@ -1299,4 +1486,41 @@ impl<T: Config> Pallet<T> {
 
 		Ok(())
 	}
+
+	/// Append a history entry for every field that differs between `old` and `new`, dropping the
+	/// oldest entries if the history grows beyond [`MAX_CONFIG_CHANGE_HISTORY`].
+	fn note_config_changes(
+		origin: frame_system::RawOrigin<T::AccountId>,
+		session: SessionIndex,
+		old: &HostConfiguration<T::BlockNumber>,
+		new: &HostConfiguration<T::BlockNumber>,
+	) {
+		let changes = old.diff(new);
+		if changes.is_empty() {
+			return
+		}
+
+		<ConfigChangeHistory<T>>::mutate(|history| {
+			let mut records = sp_std::mem::take(history).into_inner();
+			records.extend(changes.into_iter().map(|(old, new)| ConfigChangeRecord {
+				session,
+				origin: origin.clone(),
+				old,
+				new,
+			}));
+			let excess = records.len().saturating_sub(MAX_CONFIG_CHANGE_HISTORY as usize);
+			records.drain(..excess);
+			// The records were just trimmed to the bound.
+			*history = BoundedVec::try_from(records).unwrap_or_default();
+		});
+	}
+
+	/// Ensure that `origin` may change the configuration, returning it so that it can be recorded
+	/// in the [`ConfigChangeHistory`].
+	fn ensure_config_origin(
+		origin: OriginFor<T>,
+	) -> Result<frame_system::RawOrigin<T::AccountId>, DispatchError> {
+		ensure_root(origin.clone())?;
+		origin.into().map_err(|_| BadOrigin.into())
+	}
 }
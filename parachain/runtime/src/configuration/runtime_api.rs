@@ -0,0 +1,37 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime API for inspecting the host configuration and its scheduled changes.
+
+use super::{ConfigChangeRecord, HostConfiguration};
+use parity_scale_codec::Codec;
+use primitives::v2::SessionIndex;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	/// The API for querying the active host configuration and the changes scheduled on top of it.
+	pub trait HostConfigurationApi<AccountId: Codec, BlockNumber: Codec> {
+		/// The host configuration that is active in the current session.
+		fn active_config() -> HostConfiguration<BlockNumber>;
+
+		/// The host configurations that are scheduled to become active, each paired with the
+		/// session at which it is applied. Sorted ascending by session.
+		fn pending_configs() -> Vec<(SessionIndex, HostConfiguration<BlockNumber>)>;
+
+		/// The recorded per-field configuration changes, oldest first.
+		fn config_change_history() -> Vec<ConfigChangeRecord<AccountId, BlockNumber>>;
+	}
+}
@@ -15,7 +15,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 use super::*;
 use crate::mock::{new_test_ext, Configuration, Origin, ParasShared, Test};
-use frame_support::{assert_err, assert_ok};
+use frame_support::{assert_err, assert_noop, assert_ok};
 
 fn on_new_session(session_index: SessionIndex) -> (HostConfiguration<u32>, HostConfiguration<u32>) {
 	ParasShared::set_session_index(session_index);
@@ -288,6 +288,102 @@ fn consistency_bypass_works() {
 	});
 }
 
+#[test]
+fn config_batch_is_checked_for_consistency_as_a_whole() {
+	new_test_ext(Default::default()).execute_with(|| {
+		// On its own, raising the availability period breaks the invariant that the minimum
+		// upgrade delay must exceed it.
+		assert_err!(
+			Configuration::set_chain_availability_period(Origin::root(), 10),
+			Error::<Test>::InvalidNewValue
+		);
+
+		// Half of an inconsistent batch must not be scheduled.
+		assert_err!(
+			Configuration::set_config_batch(
+				Origin::root(),
+				vec![
+					HostConfigurationUpdate::MaxPovSize(1024),
+					HostConfigurationUpdate::ChainAvailabilityPeriod(10),
+				],
+			),
+			Error::<Test>::InvalidNewValue
+		);
+		assert!(<Configuration as Store>::PendingConfigs::get().is_empty());
+		assert!(Configuration::config_change_history().is_empty());
+
+		assert_ok!(Configuration::set_config_batch(
+			Origin::root(),
+			vec![
+				HostConfigurationUpdate::MinimumValidationUpgradeDelay(20),
+				HostConfigurationUpdate::ChainAvailabilityPeriod(10),
+			],
+		));
+
+		let expected = HostConfiguration {
+			minimum_validation_upgrade_delay: 20,
+			chain_availability_period: 10,
+			..Configuration::config()
+		};
+		assert_eq!(Configuration::pending_configs(), vec![(2, expected.clone())]);
+
+		on_new_session(1);
+		on_new_session(2);
+
+		assert_eq!(Configuration::config(), expected);
+	});
+}
+
+#[test]
+fn config_changes_are_recorded_in_history() {
+	new_test_ext(Default::default()).execute_with(|| {
+		assert_noop!(
+			Configuration::set_validation_upgrade_delay(Origin::signed(1), 100),
+			BadOrigin
+		);
+		assert_ok!(Configuration::set_validation_upgrade_delay(Origin::root(), 100));
+		// Setting a field to the value it already has is not recorded.
+		assert_ok!(Configuration::set_validation_upgrade_delay(Origin::root(), 100));
+
+		on_new_session(1);
+		assert_ok!(Configuration::set_validation_upgrade_delay(Origin::root(), 50));
+
+		assert_eq!(
+			Configuration::config_change_history().into_inner(),
+			vec![
+				ConfigChangeRecord {
+					session: 2,
+					origin: frame_system::RawOrigin::Root,
+					old: HostConfigurationUpdate::ValidationUpgradeDelay(
+						HostConfiguration::<u32>::default().validation_upgrade_delay
+					),
+					new: HostConfigurationUpdate::ValidationUpgradeDelay(100),
+				},
+				ConfigChangeRecord {
+					session: 3,
+					origin: frame_system::RawOrigin::Root,
+					old: HostConfigurationUpdate::ValidationUpgradeDelay(100),
+					new: HostConfigurationUpdate::ValidationUpgradeDelay(50),
+				},
+			],
+		);
+
+		// The history is capped, dropping the oldest entries first.
+		for i in 0..MAX_CONFIG_CHANGE_HISTORY {
+			assert_ok!(Configuration::set_max_pov_size(Origin::root(), i + 1));
+		}
+		let history = Configuration::config_change_history();
+		assert_eq!(history.len(), MAX_CONFIG_CHANGE_HISTORY as usize);
+		assert_eq!(
+			history.last().map(|record| &record.new),
+			Some(&HostConfigurationUpdate::MaxPovSize(MAX_CONFIG_CHANGE_HISTORY)),
+		);
+		assert!(history
+			.iter()
+			.all(|record| matches!(record.new, HostConfigurationUpdate::MaxPovSize(_))));
+	});
+}
+
 #[test]
 fn setting_pending_config_members() {
 	new_test_ext(Default::default()).execute_with(|| {
@@ -335,6 +431,10 @@ fn setting_pending_config_members() {
 			pvf_checking_enabled: true,
 			pvf_voting_ttl: 3,
 			minimum_validation_upgrade_delay: 20,
+			async_backing_params: AsyncBackingParams {
+				max_candidate_depth: 4,
+				allowed_ancestry_len: 3,
+			},
 		};
 
 		Configuration::set_validation_upgrade_cooldown(
@@ -497,6 +597,8 @@ fn setting_pending_config_members() {
 		Configuration::set_pvf_checking_enabled(Origin::root(), new_config.pvf_checking_enabled)
 			.unwrap();
 		Configuration::set_pvf_voting_ttl(Origin::root(), new_config.pvf_voting_ttl).unwrap();
+		Configuration::set_async_backing_params(Origin::root(), new_config.async_backing_params)
+			.unwrap();
 
 		assert_eq!(
 			<Configuration as Store>::PendingConfigs::get(),
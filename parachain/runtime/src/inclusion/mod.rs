@@ -20,8 +20,11 @@
 //! to included.
 
 use crate::{
-	configuration, disputes, dmp, hrmp, paras, paras_inherent::DisputedBitfield,
-	scheduler::CoreAssignment, shared, ump,
+	configuration, disputes, dmp, hrmp, paras,
+	paras_inherent::DisputedBitfield,
+	scheduler::CoreAssignment,
+	shared::{self, AllowedRelayParentsTracker},
+	ump,
 };
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use frame_support::pallet_prelude::*;
@@ -33,7 +36,7 @@ use primitives::v2::{
 	ValidatorIndex, ValidityAttestation,
 };
 use scale_info::TypeInfo;
-use sp_runtime::{traits::One, DispatchError};
+use sp_runtime::DispatchError;
 use sp_std::{collections::btree_set::BTreeSet, prelude::*};
 
 pub use pallet::*;
@@ -243,7 +246,8 @@ pub mod pallet {
 		PrematureCodeUpgrade,
 		/// Output code is too large
 		NewCodeTooLarge,
-		/// Candidate not in parent context.
+		/// The candidate's relay-parent is not among the allowed relay-parents, i.e. it is
+		/// neither the parent block nor one of its allowed ancestors.
 		CandidateNotInParentContext,
 		/// Invalid group index in core assignment.
 		InvalidGroupIndex,
@@ -455,13 +459,13 @@ impl<T: Config> Pallet<T> {
 		Ok(freed_cores)
 	}
 
-	/// Process candidates that have been backed. Provide the relay storage root, a set of candidates
-	/// and scheduled cores.
+	/// Process candidates that have been backed. Provide the allowed relay-parents, a set of
+	/// candidates and scheduled cores.
 	///
 	/// Both should be sorted ascending by core index, and the candidates should be a subset of
 	/// scheduled cores. If these conditions are not met, the execution of the function fails.
 	pub(crate) fn process_candidates<GV>(
-		parent_storage_root: T::Hash,
+		allowed_relay_parents: &AllowedRelayParentsTracker<T::Hash, T::BlockNumber>,
 		candidates: Vec<BackedCandidate<T::Hash>>,
 		scheduled: Vec<CoreAssignment>,
 		group_validators: GV,
@@ -476,13 +480,10 @@ impl<T: Config> Pallet<T> {
 		}
 
 		let validators = shared::Pallet::<T>::active_validator_keys();
-		let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+		let session_index = shared::Pallet::<T>::session_index();
 
-		// At the moment we assume (and in fact enforce, below) that the relay-parent is always one
-		// before of the block where we include a candidate (i.e. this code path).
 		let now = <frame_system::Pallet<T>>::block_number();
-		let relay_parent_number = now - One::one();
-		let check_ctx = CandidateCheckContext::<T>::new(now, relay_parent_number);
+		let check_ctx = CandidateCheckContext::<T>::new(now);
 
 		// Collect candidate receipts with backers.
 		let mut candidate_receipt_with_backing_validator_indices =
@@ -504,9 +505,6 @@ impl<T: Config> Pallet<T> {
 				Ok(())
 			};
 
-			let signing_context =
-				SigningContext { parent_hash, session_index: shared::Pallet::<T>::session_index() };
-
 			// We combine an outer loop over candidates with an inner loop over the scheduled,
 			// where each iteration of the outer loop picks up at the position
 			// in scheduled just after the past iteration left off.
@@ -520,9 +518,9 @@ impl<T: Config> Pallet<T> {
 			'next_backed_candidate: for (candidate_idx, backed_candidate) in
 				candidates.iter().enumerate()
 			{
-				match check_ctx.verify_backed_candidate(
-					parent_hash,
-					parent_storage_root,
+				let relay_parent = backed_candidate.descriptor().relay_parent;
+				let relay_parent_number = match check_ctx.verify_backed_candidate(
+					allowed_relay_parents,
 					candidate_idx,
 					backed_candidate,
 				)? {
@@ -531,7 +529,7 @@ impl<T: Config> Pallet<T> {
 							target: LOG_TARGET,
 							"Failed to create PVD for candidate {} on relay parent {:?}",
 							candidate_idx,
-							parent_hash,
+							relay_parent,
 						);
 						// We don't want to error out here because it will
 						// brick the relay-chain. So we return early without
@@ -539,7 +537,10 @@ impl<T: Config> Pallet<T> {
 						return Ok(ProcessedCandidates::default())
 					},
 					Ok(rpn) => rpn,
-				}
+				};
+
+				// Backing statements are signed in the context of the candidate's relay-parent.
+				let signing_context = SigningContext { parent_hash: relay_parent, session_index };
 
 				let para_id = backed_candidate.descriptor().para_id;
 				let mut backers = bitvec::bitvec![u8, BitOrderLsb0; 0; validators.len()];
@@ -619,6 +620,7 @@ impl<T: Config> Pallet<T> {
 							assignment.core,
 							backers,
 							assignment.group_idx,
+							relay_parent_number,
 						));
 						continue 'next_backed_candidate
 					}
@@ -640,8 +642,8 @@ impl<T: Config> Pallet<T> {
 
 		// one more sweep for actually writing to storage.
 		let core_indices =
-			core_indices_and_backers.iter().map(|&(ref c, _, _)| c.clone()).collect();
-		for (candidate, (core, backers, group)) in
+			core_indices_and_backers.iter().map(|&(ref c, _, _, _)| c.clone()).collect();
+		for (candidate, (core, backers, group, relay_parent_number)) in
 			candidates.into_iter().zip(core_indices_and_backers)
 		{
 			let para_id = candidate.descriptor().para_id;
@@ -693,10 +695,11 @@ impl<T: Config> Pallet<T> {
 		// `relay_parent_number` is equal to `now`.
 		let now = <frame_system::Pallet<T>>::block_number();
 		let relay_parent_number = now;
-		let check_ctx = CandidateCheckContext::<T>::new(now, relay_parent_number);
+		let check_ctx = CandidateCheckContext::<T>::new(now);
 
 		if let Err(err) = check_ctx.check_validation_outputs(
 			para_id,
+			relay_parent_number,
 			&validation_outputs.head_data,
 			&validation_outputs.new_validation_code,
 			validation_outputs.processed_downward_messages,
@@ -933,7 +936,6 @@ impl<BlockNumber> AcceptanceCheckErr<BlockNumber> {
 pub(crate) struct CandidateCheckContext<T: Config> {
 	config: configuration::HostConfiguration<T::BlockNumber>,
 	now: T::BlockNumber,
-	relay_parent_number: T::BlockNumber,
 }
 
 /// An error indicating that creating Persisted Validation Data failed
@@ -941,34 +943,38 @@ pub(crate) struct CandidateCheckContext<T: Config> {
 pub(crate) struct FailedToCreatePVD;
 
 impl<T: Config> CandidateCheckContext<T> {
-	pub(crate) fn new(now: T::BlockNumber, relay_parent_number: T::BlockNumber) -> Self {
-		Self { config: <configuration::Pallet<T>>::config(), now, relay_parent_number }
+	pub(crate) fn new(now: T::BlockNumber) -> Self {
+		Self { config: <configuration::Pallet<T>>::config(), now }
 	}
 
 	/// Execute verification of the candidate.
 	///
 	/// Assures:
-	///  * correct expected relay parent reference
+	///  * relay-parent is one of the allowed relay-parents
 	///  * collator signature check passes
 	///  * code hash of commitments matches current code hash
 	///  * para head in the descriptor and commitments match
+	///
+	/// Returns the relay-parent block number.
 	pub(crate) fn verify_backed_candidate(
 		&self,
-		parent_hash: <T as frame_system::Config>::Hash,
-		parent_storage_root: T::Hash,
+		allowed_relay_parents: &AllowedRelayParentsTracker<T::Hash, T::BlockNumber>,
 		candidate_idx: usize,
 		backed_candidate: &BackedCandidate<<T as frame_system::Config>::Hash>,
-	) -> Result<Result<(), FailedToCreatePVD>, Error<T>> {
+	) -> Result<Result<T::BlockNumber, FailedToCreatePVD>, Error<T>> {
 		let para_id = backed_candidate.descriptor().para_id;
-		let now = <frame_system::Pallet<T>>::block_number();
-		let relay_parent_number = now - One::one();
+
+		// we require that the candidate is built on top of one of the allowed relay-parents.
+		let (relay_parent_storage_root, relay_parent_number) = allowed_relay_parents
+			.acquire_info(backed_candidate.descriptor().relay_parent)
+			.ok_or(Error::<T>::CandidateNotInParentContext)?;
 
 		{
 			// this should never fail because the para is registered
 			let persisted_validation_data = match crate::util::make_persisted_validation_data::<T>(
 				para_id,
 				relay_parent_number,
-				parent_storage_root,
+				relay_parent_storage_root,
 			) {
 				Some(l) => l,
 				None => return Ok(Err(FailedToCreatePVD)),
@@ -982,11 +988,6 @@ impl<T: Config> CandidateCheckContext<T> {
 			);
 		}
 
-		// we require that the candidate is in the context of the parent block.
-		ensure!(
-			backed_candidate.descriptor().relay_parent == parent_hash,
-			Error::<T>::CandidateNotInParentContext,
-		);
 		ensure!(
 			backed_candidate.descriptor().check_collator_signature().is_ok(),
 			Error::<T>::NotCollatorSigned,
@@ -1008,6 +1009,7 @@ impl<T: Config> CandidateCheckContext<T> {
 
 		if let Err(err) = self.check_validation_outputs(
 			para_id,
+			relay_parent_number,
 			&backed_candidate.candidate.commitments.head_data,
 			&backed_candidate.candidate.commitments.new_validation_code,
 			backed_candidate.candidate.commitments.processed_downward_messages,
@@ -1024,7 +1026,7 @@ impl<T: Config> CandidateCheckContext<T> {
 			);
 			Err(err.strip_into_dispatch_err::<T>())?;
 		};
-		Ok(Ok(()))
+		Ok(Ok(relay_parent_number))
 	}
 
 	/// Check the given outputs after candidate validation on whether it passes the acceptance
@@ -1032,6 +1034,7 @@ impl<T: Config> CandidateCheckContext<T> {
 	fn check_validation_outputs(
 		&self,
 		para_id: ParaId,
+		relay_parent_number: T::BlockNumber,
 		head_data: &HeadData,
 		new_validation_code: &Option<primitives::v2::ValidationCode>,
 		processed_downward_messages: u32,
@@ -1059,7 +1062,7 @@ impl<T: Config> CandidateCheckContext<T> {
 		// check if the candidate passes the messaging acceptance criteria
 		<dmp::Pallet<T>>::check_processed_downward_messages(para_id, processed_downward_messages)?;
 		<ump::Pallet<T>>::check_upward_messages(&self.config, para_id, upward_messages)?;
-		<hrmp::Pallet<T>>::check_hrmp_watermark(para_id, self.relay_parent_number, hrmp_watermark)?;
+		<hrmp::Pallet<T>>::check_hrmp_watermark(para_id, relay_parent_number, hrmp_watermark)?;
 		<hrmp::Pallet<T>>::check_outbound_hrmp(&self.config, para_id, horizontal_messages)?;
 
 		Ok(())
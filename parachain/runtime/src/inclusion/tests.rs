@@ -304,6 +304,19 @@ pub(crate) fn make_vdata_hash(para_id: ParaId) -> Option<Hash> {
 	Some(persisted_validation_data.hash())
 }
 
+/// Allowed relay-parents consisting only of the parent block, i.e. what is allowed with
+/// asynchronous backing disabled.
+pub(crate) fn default_allowed_relay_parent_tracker() -> AllowedRelayParentsTracker<Hash, BlockNumber>
+{
+	let mut allowed = AllowedRelayParentsTracker::default();
+
+	let relay_parent = System::parent_hash();
+	let parent_number = System::block_number().saturating_sub(1);
+
+	allowed.update(relay_parent, Hash::zero(), parent_number, 0);
+	allowed
+}
+
 #[test]
 fn collect_pending_cleans_up_pending() {
 	let chain_a = ParaId::from(1_u32);
@@ -984,7 +997,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_b_assignment.clone()],
 					&group_validators,
@@ -1039,7 +1052,7 @@ fn candidate_checks() {
 			// out-of-order manifests as unscheduled.
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed_b, backed_a],
 					vec![chain_a_assignment.clone(), chain_b_assignment.clone()],
 					&group_validators,
@@ -1072,7 +1085,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1107,7 +1120,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1142,7 +1155,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![
 						chain_a_assignment.clone(),
@@ -1184,7 +1197,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![thread_a_assignment.clone()],
 					&group_validators,
@@ -1234,7 +1247,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1274,7 +1287,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1318,7 +1331,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1352,7 +1365,7 @@ fn candidate_checks() {
 
 			assert_eq!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1387,7 +1400,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1422,7 +1435,7 @@ fn candidate_checks() {
 
 			assert_noop!(
 				ParaInclusion::process_candidates(
-					Default::default(),
+					&default_allowed_relay_parent_tracker(),
 					vec![backed],
 					vec![chain_a_assignment.clone()],
 					&group_validators,
@@ -1587,7 +1600,7 @@ fn backing_works() {
 			core_indices: occupied_cores,
 			candidate_receipt_with_backing_validator_indices,
 		} = ParaInclusion::process_candidates(
-			Default::default(),
+			&default_allowed_relay_parent_tracker(),
 			backed_candidates.clone(),
 			vec![
 				chain_a_assignment.clone(),
@@ -1788,7 +1801,7 @@ fn can_include_candidate_with_ok_code_upgrade() {
 
 		let ProcessedCandidates { core_indices: occupied_cores, .. } =
 			ParaInclusion::process_candidates(
-				Default::default(),
+				&default_allowed_relay_parent_tracker(),
 				vec![backed_a],
 				vec![chain_a_assignment.clone()],
 				&group_validators,
@@ -1821,6 +1834,119 @@ fn can_include_candidate_with_ok_code_upgrade() {
 	});
 }
 
+#[test]
+fn can_include_candidate_built_on_allowed_ancestor() {
+	let chain_a = ParaId::from(1_u32);
+
+	let paras = vec![(chain_a, true)];
+	let validators = vec![
+		Sr25519Keyring::Alice,
+		Sr25519Keyring::Bob,
+		Sr25519Keyring::Charlie,
+		Sr25519Keyring::Dave,
+		Sr25519Keyring::Ferdie,
+	];
+	let keystore: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	for validator in validators.iter() {
+		SyncCryptoStore::sr25519_generate_new(
+			&*keystore,
+			PARACHAIN_KEY_TYPE_ID,
+			Some(&validator.to_seed()),
+		)
+		.unwrap();
+	}
+	let validator_public = validator_pubkeys(&validators);
+
+	new_test_ext(genesis_config(paras)).execute_with(|| {
+		shared::Pallet::<Test>::set_active_validators_ascending(validator_public.clone());
+		shared::Pallet::<Test>::set_session_index(5);
+
+		run_to_block(5, |_| None);
+
+		// The candidate is built on the grandparent of the current block.
+		let ancestor = Hash::repeat_byte(0xaa);
+		let ancestor_number = System::block_number() - 2;
+		let mut allowed_relay_parents = AllowedRelayParentsTracker::default();
+		allowed_relay_parents.update(ancestor, Hash::zero(), ancestor_number, 1);
+		allowed_relay_parents.update(
+			System::parent_hash(),
+			Hash::zero(),
+			System::block_number() - 1,
+			1,
+		);
+
+		let signing_context = SigningContext { parent_hash: ancestor, session_index: 5 };
+
+		let group_validators = |group_index: GroupIndex| {
+			match group_index {
+				group_index if group_index == GroupIndex::from(0) => Some(vec![0, 1, 2, 3, 4]),
+				_ => panic!("Group index out of bounds for 1 parachain"),
+			}
+			.map(|vs| vs.into_iter().map(ValidatorIndex).collect::<Vec<_>>())
+		};
+
+		let chain_a_assignment = CoreAssignment {
+			core: CoreIndex::from(0),
+			para_id: chain_a,
+			kind: AssignmentKind::Parachain,
+			group_idx: GroupIndex::from(0),
+		};
+
+		let persisted_validation_data_hash = crate::util::make_persisted_validation_data::<Test>(
+			chain_a,
+			ancestor_number,
+			Hash::zero(),
+		)
+		.unwrap()
+		.hash();
+		let mut candidate_a = TestCandidateBuilder {
+			para_id: chain_a,
+			relay_parent: ancestor,
+			pov_hash: Hash::repeat_byte(1),
+			persisted_validation_data_hash,
+			hrmp_watermark: ancestor_number,
+			..Default::default()
+		}
+		.build();
+		collator_sign_candidate(Sr25519Keyring::One, &mut candidate_a);
+
+		let backed_a = block_on(back_candidate(
+			candidate_a.clone(),
+			&validators,
+			group_validators(GroupIndex::from(0)).unwrap().as_ref(),
+			&keystore,
+			&signing_context,
+			BackingKind::Threshold,
+		));
+
+		// Not allowed without the ancestor being tracked.
+		assert_noop!(
+			ParaInclusion::process_candidates(
+				&default_allowed_relay_parent_tracker(),
+				vec![backed_a.clone()],
+				vec![chain_a_assignment.clone()],
+				&group_validators,
+			),
+			Error::<Test>::CandidateNotInParentContext
+		);
+
+		let ProcessedCandidates { core_indices: occupied_cores, .. } =
+			ParaInclusion::process_candidates(
+				&allowed_relay_parents,
+				vec![backed_a],
+				vec![chain_a_assignment.clone()],
+				&group_validators,
+			)
+			.expect("candidates scheduled, in order, and backed");
+
+		assert_eq!(occupied_cores, vec![CoreIndex::from(0)]);
+		assert_eq!(
+			<PendingAvailability<Test>>::get(&chain_a).map(|pending| pending.relay_parent_number),
+			Some(ancestor_number),
+		);
+	});
+}
+
 #[test]
 fn session_change_wipes() {
 	let chain_a = ParaId::from(1_u32);
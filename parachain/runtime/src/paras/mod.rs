@@ -112,8 +112,9 @@ use frame_support::{pallet_prelude::*, traits::EstimateNextSessionRotation};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use primitives::v2::{
-	ConsensusLog, HeadData, Id as ParaId, PvfCheckStatement, SessionIndex, UpgradeGoAhead,
-	UpgradeRestriction, ValidationCode, ValidationCodeHash, ValidatorSignature,
+	ConsensusLog, HeadData, Id as ParaId, ParaUpgradeStatus, PvfCheckStatement, PvfCheckVoteTally,
+	SessionIndex, UpgradeGoAhead, UpgradeRestriction, ValidationCode, ValidationCodeHash,
+	ValidatorSignature,
 };
 use scale_info::TypeInfo;
 use sp_core::RuntimeDebug;
@@ -1646,6 +1647,68 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Returns the state of the code upgrade process of the given para, or `None` if the para is
+	/// not registered.
+	pub fn upgrade_status(id: ParaId) -> Option<ParaUpgradeStatus<T::BlockNumber>> {
+		if !ParaLifecycles::<T>::contains_key(&id) {
+			return None
+		}
+
+		let current_code_hash = CurrentCodeHash::<T>::get(&id);
+		let future_code_hash = FutureCodeHash::<T>::get(&id);
+
+		// A para waits on a pre-checking vote either for its upgrade or, while onboarding, for
+		// its genesis code.
+		let genesis_code_hash =
+			UpcomingParasGenesis::<T>::get(&id).map(|genesis| genesis.validation_code.hash());
+		let pvf_check =
+			future_code_hash.iter().chain(genesis_code_hash.iter()).find_map(|code_hash| {
+				let vote = PvfActiveVoteMap::<T>::get(code_hash)?;
+				if !vote.causes.iter().any(|cause| cause.para_id() == id) {
+					return None
+				}
+				Some(PvfCheckVoteTally {
+					code_hash: *code_hash,
+					accept: vote.votes_accept.count_ones() as u32,
+					reject: vote.votes_reject.count_ones() as u32,
+					n_validators: vote.votes_accept.len() as u32,
+					age: vote.age,
+					created_at: vote.created_at,
+				})
+			});
+
+		let cooldown_until = UpgradeCooldowns::<T>::get()
+			.into_iter()
+			.find(|&(para, _)| para == id)
+			.map(|(_, expire_at)| expire_at);
+
+		Some(ParaUpgradeStatus {
+			current_code_hash,
+			future_code_hash,
+			expected_upgrade_at: FutureCodeUpgrades::<T>::get(&id),
+			go_ahead: UpgradeGoAheadSignal::<T>::get(&id),
+			restriction: UpgradeRestrictionSignal::<T>::get(&id),
+			cooldown_until,
+			pvf_check,
+		})
+	}
+
+	/// Returns all lifecycle transitions queued in the `ActionsQueue`, together with the session
+	/// at which they are going to be applied and the current (transitional) lifecycle of the para.
+	///
+	/// Ordered ascending by session index and then by `ParaId`.
+	pub fn scheduled_lifecycle_transitions() -> Vec<(SessionIndex, ParaId, ParaLifecycle)> {
+		let mut transitions = ActionsQueue::<T>::iter()
+			.flat_map(|(session, paras)| {
+				paras.into_iter().filter_map(move |para| {
+					ParaLifecycles::<T>::get(&para).map(|lifecycle| (session, para, lifecycle))
+				})
+			})
+			.collect::<Vec<_>>();
+		transitions.sort_by_key(|(session, para, _)| (*session, *para));
+		transitions
+	}
+
 	/// Schedule a future code upgrade of the given parachain.
 	///
 	/// If the new code is not known, then the PVF pre-checking will be started for that validation
@@ -925,6 +925,53 @@ fn para_incoming_at_session() {
 	})
 }
 
+#[test]
+fn scheduled_lifecycle_transitions_lists_queued_actions() {
+	let a = ParaId::from(111);
+	let b = ParaId::from(222);
+	let paras = vec![
+		(
+			a,
+			ParaGenesisArgs {
+				parachain: false,
+				genesis_head: dummy_head_data(),
+				validation_code: ValidationCode(vec![1]),
+			},
+		),
+		(
+			b,
+			ParaGenesisArgs {
+				parachain: true,
+				genesis_head: dummy_head_data(),
+				validation_code: ValidationCode(vec![2]),
+			},
+		),
+	];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		let scheduled_session = Paras::scheduled_session();
+
+		assert_ok!(Paras::schedule_parathread_upgrade(a));
+		assert_ok!(Paras::schedule_parachain_downgrade(b));
+		assert_eq!(
+			Paras::scheduled_lifecycle_transitions(),
+			vec![
+				(scheduled_session, a, ParaLifecycle::UpgradingParathread),
+				(scheduled_session, b, ParaLifecycle::DowngradingParachain),
+			],
+		);
+
+		run_to_block(3, Some(vec![2, 3]));
+		assert_eq!(Paras::parachains(), vec![a]);
+		assert!(Paras::scheduled_lifecycle_transitions().is_empty());
+	});
+}
+
 #[test]
 fn code_hash_at_returns_up_to_end_of_code_retention_period() {
 	let code_retention_period = 10;
@@ -1213,6 +1260,79 @@ fn pvf_check_upgrade_reject() {
 	});
 }
 
+#[test]
+fn upgrade_status_reports_pending_upgrade_and_votes() {
+	let a = ParaId::from(111);
+	let old_code: ValidationCode = vec![1, 2, 3].into();
+	let new_code: ValidationCode = vec![3, 2, 1].into();
+
+	let paras = vec![(
+		a,
+		ParaGenesisArgs {
+			parachain: false,
+			genesis_head: Default::default(),
+			validation_code: old_code.clone(),
+		},
+	)];
+
+	let genesis_config = MockGenesisConfig {
+		paras: GenesisConfig { paras, ..Default::default() },
+		configuration: crate::configuration::GenesisConfig {
+			config: HostConfiguration {
+				pvf_checking_enabled: true,
+				validation_upgrade_cooldown: 10,
+				..Default::default()
+			},
+			..Default::default()
+		},
+		..Default::default()
+	};
+
+	new_test_ext(genesis_config).execute_with(|| {
+		run_to_block(2, Some(vec![1]));
+
+		assert_eq!(Paras::upgrade_status(ParaId::from(222)), None);
+		assert_eq!(
+			Paras::upgrade_status(a),
+			Some(ParaUpgradeStatus {
+				current_code_hash: Some(old_code.hash()),
+				future_code_hash: None,
+				expected_upgrade_at: None,
+				go_ahead: None,
+				restriction: None,
+				cooldown_until: None,
+				pvf_check: None,
+			}),
+		);
+
+		const RELAY_PARENT: BlockNumber = 1;
+		Paras::schedule_code_upgrade(a, new_code.clone(), RELAY_PARENT, &Configuration::config());
+
+		IntoIterator::into_iter([0, 1])
+			.map(|i| PvfCheckStatement {
+				accept: i == 0,
+				subject: new_code.hash(),
+				session_index: 1,
+				validator_index: i.into(),
+			})
+			.for_each(sign_and_include_pvf_check_statement);
+
+		let status = Paras::upgrade_status(a).unwrap();
+		assert_eq!(status.current_code_hash, Some(old_code.hash()));
+		assert_eq!(status.future_code_hash, Some(new_code.hash()));
+		// The upgrade is not scheduled until the pre-checking vote concludes.
+		assert_eq!(status.expected_upgrade_at, None);
+		assert_eq!(status.restriction, Some(UpgradeRestriction::Present));
+		assert_eq!(status.cooldown_until, Some(RELAY_PARENT + 10));
+
+		let tally = status.pvf_check.unwrap();
+		assert_eq!(tally.code_hash, new_code.hash());
+		assert_eq!((tally.accept, tally.reject), (1, 1));
+		assert_eq!(tally.n_validators, VALIDATORS.len() as u32);
+		assert_eq!(tally.age, 0);
+	});
+}
+
 #[test]
 fn pvf_check_submit_vote_while_disabled() {
 	let genesis_config = MockGenesisConfig {
@@ -1635,3 +1755,20 @@ fn verify_upgrade_restriction_signal_is_externally_accessible() {
 		);
 	});
 }
+
+#[test]
+fn verify_para_head_is_externally_accessible() {
+	use primitives::v2::well_known_keys;
+
+	let a = ParaId::from(2000);
+
+	new_test_ext(Default::default()).execute_with(|| {
+		assert!(sp_io::storage::get(&well_known_keys::para_head(a)).is_none());
+		let head_data = HeadData(vec![1, 2, 3]);
+		<Paras as Store>::Heads::insert(&a, head_data.clone());
+		assert_eq!(
+			sp_io::storage::get(&well_known_keys::para_head(a)).unwrap(),
+			head_data.encode()
+		);
+	});
+}
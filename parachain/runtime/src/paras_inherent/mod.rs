@@ -28,7 +28,8 @@ use crate::{
 	initializer,
 	metrics::METRICS,
 	scheduler::{self, CoreAssignment, FreedReason},
-	shared, ump, ParaId,
+	shared::{self, AllowedRelayParentsTracker},
+	ump, ParaId,
 };
 use bitvec::prelude::BitVec;
 use frame_support::{
@@ -335,7 +336,7 @@ impl<T: Config> Pallet<T> {
 
 		let current_session = <shared::Pallet<T>>::session_index();
 
-		let max_block_weight = <T as frame_system::Config>::BlockWeights::get().max_block;
+		let max_block_weight = max_inherent_weight::<T>();
 
 		METRICS.on_before_filter(candidates_weight + bitfields_weight + disputes_weight);
 
@@ -496,9 +497,17 @@ impl<T: Config> Pallet<T> {
 
 		METRICS.on_candidates_processed_total(backed_candidates.len() as u64);
 
+		// Note the parent block as an allowed relay-parent for the candidates backed in this block.
+		let allowed_relay_parents = <shared::Pallet<T>>::add_allowed_relay_parent(
+			parent_hash,
+			parent_header.state_root().clone(),
+			now - One::one(),
+			<configuration::Pallet<T>>::config().async_backing_params.allowed_ancestry_len,
+		);
+
 		let scheduled = <scheduler::Pallet<T>>::scheduled();
 		assure_sanity_backed_candidates::<T, _>(
-			parent_hash,
+			&allowed_relay_parents,
 			&backed_candidates,
 			move |_candidate_index: usize, backed_candidate: &BackedCandidate<T::Hash>| -> bool {
 				<T>::DisputesHandler::concluded_invalid(current_session, backed_candidate.hash())
@@ -510,12 +519,11 @@ impl<T: Config> Pallet<T> {
 		METRICS.on_candidates_sanitized(backed_candidates.len() as u64);
 
 		// Process backed candidates according to scheduled cores.
-		let parent_storage_root = parent_header.state_root().clone();
 		let inclusion::ProcessedCandidates::<<T::Header as HeaderT>::Hash> {
 			core_indices: occupied,
 			candidate_receipt_with_backing_validator_indices,
 		} = <inclusion::Pallet<T>>::process_candidates(
-			parent_storage_root,
+			&allowed_relay_parents,
 			backed_candidates,
 			scheduled,
 			<scheduler::Pallet<T>>::group_validators,
@@ -533,7 +541,13 @@ impl<T: Config> Pallet<T> {
 
 		// Give some time slice to dispatch pending upward messages.
 		// this is max config.ump_service_total_weight
-		let _ump_weight = <ump::Pallet<T>>::process_pending_upward_messages();
+		let ump_weight = <ump::Pallet<T>>::process_pending_upward_messages();
+		// The dispatched messages are not part of the inherent's pre-dispatch weight, so they are
+		// charged to the block directly.
+		<frame_system::Pallet<T>>::register_extra_weight_unchecked(
+			ump_weight,
+			DispatchClass::Mandatory,
+		);
 
 		METRICS.on_after_filter(total_consumed_weight);
 
@@ -580,7 +594,7 @@ impl<T: Config> Pallet<T> {
 		let current_session = <shared::Pallet<T>>::session_index();
 		let expected_bits = <scheduler::Pallet<T>>::availability_cores().len();
 		let validator_public = shared::Pallet::<T>::active_validator_keys();
-		let max_block_weight = <T as frame_system::Config>::BlockWeights::get().max_block;
+		let max_block_weight = max_inherent_weight::<T>();
 
 		let entropy = compute_entropy::<T>(parent_hash);
 		let mut rng = rand_chacha::ChaChaRng::from_seed(entropy.into());
@@ -707,12 +721,17 @@ impl<T: Config> Pallet<T> {
 
 			let scheduled = <scheduler::Pallet<T>>::scheduled();
 
-			let relay_parent_number = now - One::one();
-			let parent_storage_root = parent_header.state_root().clone();
+			// The tracker update is rolled back together with the rest of the transaction.
+			let allowed_relay_parents = <shared::Pallet<T>>::add_allowed_relay_parent(
+				parent_hash,
+				parent_header.state_root().clone(),
+				now - One::one(),
+				config.async_backing_params.allowed_ancestry_len,
+			);
 
-			let check_ctx = CandidateCheckContext::<T>::new(now, relay_parent_number);
+			let check_ctx = CandidateCheckContext::<T>::new(now);
 			let backed_candidates = sanitize_backed_candidates::<T, _>(
-				parent_hash,
+				&allowed_relay_parents,
 				backed_candidates,
 				move |candidate_idx: usize,
 				      backed_candidate: &BackedCandidate<<T as frame_system::Config>::Hash>|
@@ -724,7 +743,7 @@ impl<T: Config> Pallet<T> {
 							// That way we avoid possible duplicate checks while assuring all
 							// backed candidates fine to pass on.
 							check_ctx
-								.verify_backed_candidate(parent_hash, parent_storage_root, candidate_idx, backed_candidate)
+								.verify_backed_candidate(&allowed_relay_parents, candidate_idx, backed_candidate)
 								.is_err()
 				},
 				&scheduled[..],
@@ -1107,7 +1126,7 @@ fn sanitize_backed_candidates<
 	T: crate::inclusion::Config,
 	F: FnMut(usize, &BackedCandidate<T::Hash>) -> bool,
 >(
-	relay_parent: T::Hash,
+	allowed_relay_parents: &AllowedRelayParentsTracker<T::Hash, T::BlockNumber>,
 	mut backed_candidates: Vec<BackedCandidate<T::Hash>>,
 	mut candidate_has_concluded_invalid_dispute_or_is_invalid: F,
 	scheduled: &[CoreAssignment],
@@ -1125,16 +1144,16 @@ fn sanitize_backed_candidates<
 
 	// Assure the backed candidate's `ParaId`'s core is free.
 	// This holds under the assumption that `Scheduler::schedule` is called _before_.
-	// Also checks the candidate references the correct relay parent.
+	// Also checks the candidate references an allowed relay parent.
 
 	backed_candidates.retain(|backed_candidate| {
 		let desc = backed_candidate.descriptor();
-		desc.relay_parent == relay_parent &&
+		allowed_relay_parents.acquire_info(desc.relay_parent).is_some() &&
 			scheduled_paras_to_core_idx.get(&desc.para_id).is_some()
 	});
 
 	// Sort the `Vec` last, once there is a guarantee that these
-	// `BackedCandidates` references the expected relay chain parent,
+	// `BackedCandidates` references an allowed relay chain parent,
 	// but more importantly are scheduled for a free core.
 	// This both avoids extra work for obviously invalid candidates,
 	// but also allows this to be done in place.
@@ -1152,7 +1171,7 @@ pub(crate) fn assure_sanity_backed_candidates<
 	T: crate::inclusion::Config,
 	F: FnMut(usize, &BackedCandidate<T::Hash>) -> bool,
 >(
-	relay_parent: T::Hash,
+	allowed_relay_parents: &AllowedRelayParentsTracker<T::Hash, T::BlockNumber>,
 	backed_candidates: &[BackedCandidate<T::Hash>],
 	mut candidate_has_concluded_invalid_dispute_or_is_invalid: F,
 	scheduled: &[CoreAssignment],
@@ -1165,9 +1184,9 @@ pub(crate) fn assure_sanity_backed_candidates<
 		}
 		// Assure the backed candidate's `ParaId`'s core is free.
 		// This holds under the assumption that `Scheduler::schedule` is called _before_.
-		// Also checks the candidate references the correct relay parent.
+		// Also checks the candidate references an allowed relay parent.
 		let desc = backed_candidate.descriptor();
-		if desc.relay_parent != relay_parent {
+		if allowed_relay_parents.acquire_info(desc.relay_parent).is_none() {
 			return Err(Error::<T>::UnexpectedRelayParent)
 		}
 	}
@@ -1187,6 +1206,14 @@ pub(crate) fn assure_sanity_backed_candidates<
 	Ok(())
 }
 
+/// The weight available to the inherent data, leaving room in the block for dispatching the
+/// pending upward messages at the end of the inherent.
+fn max_inherent_weight<T: Config>() -> Weight {
+	<T as frame_system::Config>::BlockWeights::get()
+		.max_block
+		.saturating_sub(<ump::Pallet<T>>::max_dispatch_weight())
+}
+
 /// Derive entropy from babe provided per block randomness.
 ///
 /// In the odd case none is available, uses the `parent_hash` and
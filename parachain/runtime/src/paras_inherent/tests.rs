@@ -1143,6 +1143,9 @@ mod sanitizers {
 		let relay_parent = header.hash();
 		let session_index = SessionIndex::from(0_u32);
 
+		let mut allowed_relay_parents = AllowedRelayParentsTracker::default();
+		allowed_relay_parents.update(relay_parent, Hash::zero(), RELAY_PARENT_NUM, 0);
+
 		let keystore = LocalKeystore::in_memory();
 		let keystore = Arc::new(keystore) as SyncCryptoStorePtr;
 		let signing_context = SigningContext { parent_hash: relay_parent, session_index };
@@ -1219,7 +1222,7 @@ mod sanitizers {
 		// happy path
 		assert_eq!(
 			sanitize_backed_candidates::<Test, _>(
-				relay_parent,
+				&allowed_relay_parents,
 				backed_candidates.clone(),
 				has_concluded_invalid,
 				scheduled
@@ -1231,7 +1234,7 @@ mod sanitizers {
 		{
 			let scheduled = &[][..];
 			assert!(sanitize_backed_candidates::<Test, _>(
-				relay_parent,
+				&allowed_relay_parents,
 				backed_candidates.clone(),
 				has_concluded_invalid,
 				scheduled
@@ -1241,9 +1244,15 @@ mod sanitizers {
 
 		// relay parent mismatch
 		{
-			let relay_parent = Hash::repeat_byte(0xFA);
+			let mut allowed_relay_parents = AllowedRelayParentsTracker::default();
+			allowed_relay_parents.update(
+				Hash::repeat_byte(0xFA),
+				Hash::zero(),
+				RELAY_PARENT_NUM,
+				0,
+			);
 			assert!(sanitize_backed_candidates::<Test, _>(
-				relay_parent,
+				&allowed_relay_parents,
 				backed_candidates.clone(),
 				has_concluded_invalid,
 				scheduled
@@ -1267,7 +1276,7 @@ mod sanitizers {
 				|_idx: usize, candidate: &BackedCandidate| set.contains(&candidate.hash());
 			assert_eq!(
 				sanitize_backed_candidates::<Test, _>(
-					relay_parent,
+					&allowed_relay_parents,
 					backed_candidates.clone(),
 					has_concluded_invalid,
 					scheduled
@@ -22,13 +22,14 @@
 
 use crate::{
 	configuration, dmp, hrmp, inclusion, initializer, paras, paras_inherent, scheduler,
-	session_info, shared,
+	session_info, shared, ParaLifecycle,
 };
 use primitives::v2::{
-	AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreIndex, CoreOccupied,
-	CoreState, GroupIndex, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage,
-	InboundHrmpMessage, OccupiedCore, OccupiedCoreAssumption, PersistedValidationData,
-	PvfCheckStatement, ScheduledCore, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+	AsyncBackingParams, AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreIndex,
+	CoreOccupied, CoreState, GroupIndex, GroupRotationInfo, Hash, Id as ParaId,
+	InboundDownwardMessage, InboundHrmpMessage, OccupiedCore, OccupiedCoreAssumption,
+	ParaLifecycleTransition, ParaUpgradeStatus, PersistedValidationData, PvfCheckStatement,
+	ScheduledCore, ScheduledParaTransition, ScrapedOnChainVotes, SessionIndex, SessionInfo,
 	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 use sp_runtime::traits::One;
@@ -402,3 +403,38 @@ where
 		<paras::Pallet<T>>::current_code_hash(&para_id)
 	})
 }
+
+/// Implementation for the `scheduled_para_transitions` function of the runtime API.
+///
+/// Only covers the transitions queued in `paras`. Runtimes which schedule transitions for later
+/// sessions elsewhere need to add those.
+pub fn scheduled_para_transitions<T: paras::Config>() -> Vec<ScheduledParaTransition> {
+	<paras::Pallet<T>>::scheduled_lifecycle_transitions()
+		.into_iter()
+		.filter_map(|(session, para_id, lifecycle)| {
+			let transition = match lifecycle {
+				ParaLifecycle::Onboarding => ParaLifecycleTransition::Onboarding,
+				ParaLifecycle::UpgradingParathread => ParaLifecycleTransition::UpgradeToParachain,
+				ParaLifecycle::DowngradingParachain =>
+					ParaLifecycleTransition::DowngradeToParathread,
+				ParaLifecycle::OffboardingParathread | ParaLifecycle::OffboardingParachain =>
+					ParaLifecycleTransition::Offboarding,
+				// Stable paras can linger in the queue, e.g. after a rejected PVF pre-check.
+				ParaLifecycle::Parathread | ParaLifecycle::Parachain => return None,
+			};
+			Some(ScheduledParaTransition { para_id, session, transition })
+		})
+		.collect()
+}
+
+/// Implementation for the `para_upgrade_status` function of the runtime API.
+pub fn para_upgrade_status<T: paras::Config>(
+	para_id: ParaId,
+) -> Option<ParaUpgradeStatus<T::BlockNumber>> {
+	<paras::Pallet<T>>::upgrade_status(para_id)
+}
+
+/// Implementation for the `async_backing_params` function of the runtime API.
+pub fn async_backing_params<T: configuration::Config>() -> AsyncBackingParams {
+	<configuration::Pallet<T>>::config().async_backing_params
+}
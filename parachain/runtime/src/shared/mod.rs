@@ -21,6 +21,7 @@
 
 use frame_support::pallet_prelude::*;
 use primitives::v2::{SessionIndex, ValidatorId, ValidatorIndex};
+use sp_runtime::traits::AtLeast32BitUnsigned;
 use sp_std::vec::Vec;
 
 use rand::{seq::SliceRandom, SeedableRng};
@@ -38,6 +39,56 @@ pub(crate) const SESSION_DELAY: SessionIndex = 2;
 #[cfg(test)]
 mod tests;
 
+/// Information about past relay-parents.
+#[derive(Encode, Decode, Default, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct AllowedRelayParentsTracker<Hash, BlockNumber> {
+	// The past relay parents, paired with state roots, that are viable to build upon.
+	//
+	// They are in ascending chronologic order, so the newest relay parents are at
+	// the back of the buffer.
+	buffer: Vec<(Hash, Hash)>,
+
+	// The number of the most recent relay-parent, if any.
+	// If the buffer is empty, this value has no meaning and may
+	// be nonsensical.
+	latest_number: BlockNumber,
+}
+
+impl<Hash: PartialEq + Copy, BlockNumber: AtLeast32BitUnsigned + Copy>
+	AllowedRelayParentsTracker<Hash, BlockNumber>
+{
+	/// Add a new relay-parent to the allowed relay parents, along with info about the header.
+	/// Provide a maximum ancestry length for the buffer, which will cause old relay-parents to be pruned.
+	pub(crate) fn update(
+		&mut self,
+		relay_parent: Hash,
+		state_root: Hash,
+		number: BlockNumber,
+		max_ancestry_len: u32,
+	) {
+		// + 1 for the most recent block, which is always allowed.
+		let buffer_size_limit = max_ancestry_len as usize + 1;
+
+		self.buffer.push((relay_parent, state_root));
+		self.latest_number = number;
+		if self.buffer.len() > buffer_size_limit {
+			let excess = self.buffer.len() - buffer_size_limit;
+			self.buffer.drain(..excess);
+		}
+	}
+
+	/// Attempt to acquire the state root and block number for the given relay-parent.
+	///
+	/// Returns `None` if the relay-parent is not among the allowed relay parents.
+	pub(crate) fn acquire_info(&self, relay_parent: Hash) -> Option<(Hash, BlockNumber)> {
+		let pos = self.buffer.iter().position(|(rp, _)| rp == &relay_parent)?;
+		let age = (self.buffer.len() - 1) - pos;
+		let number = self.latest_number - BlockNumber::from(age as u32);
+
+		Some((self.buffer[pos].1, number))
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -68,6 +119,11 @@ pub mod pallet {
 	#[pallet::getter(fn active_validator_keys)]
 	pub(super) type ActiveValidatorKeys<T: Config> = StorageValue<_, Vec<ValidatorId>, ValueQuery>;
 
+	/// All allowed relay-parents.
+	#[pallet::storage]
+	pub(crate) type AllowedRelayParents<T: Config> =
+		StorageValue<_, AllowedRelayParentsTracker<T::Hash, T::BlockNumber>, ValueQuery>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {}
 }
@@ -110,9 +166,27 @@ impl<T: Config> Pallet<T> {
 		ActiveValidatorIndices::<T>::set(shuffled_indices);
 		ActiveValidatorKeys::<T>::set(active_validator_keys.clone());
 
+		// Candidates are signed in the context of a session, so relay parents from
+		// earlier sessions can't be built upon anymore.
+		AllowedRelayParents::<T>::kill();
+
 		active_validator_keys
 	}
 
+	/// Note a new relay-parent the candidates of the next block may be built upon, pruning
+	/// those older than `max_ancestry_len` blocks. Returns the updated tracker.
+	pub(crate) fn add_allowed_relay_parent(
+		relay_parent: T::Hash,
+		state_root: T::Hash,
+		number: T::BlockNumber,
+		max_ancestry_len: u32,
+	) -> AllowedRelayParentsTracker<T::Hash, T::BlockNumber> {
+		AllowedRelayParents::<T>::mutate(|tracker| {
+			tracker.update(relay_parent, state_root, number, max_ancestry_len);
+			tracker.clone()
+		})
+	}
+
 	/// Return the session index that should be used for any future scheduled changes.
 	pub fn scheduled_session() -> SessionIndex {
 		Self::session_index().saturating_add(SESSION_DELAY)
@@ -19,6 +19,7 @@ use crate::{
 	mock::{new_test_ext, MockGenesisConfig, ParasShared},
 };
 use keyring::Sr25519Keyring;
+use primitives::v2::Hash;
 
 fn validator_pubkeys(val_ids: &[Sr25519Keyring]) -> Vec<ValidatorId> {
 	val_ids.iter().map(|v| v.public().into()).collect()
@@ -96,3 +97,44 @@ fn sets_truncates_and_shuffles_validators() {
 		);
 	});
 }
+
+#[test]
+fn tracker_earliest_block_number() {
+	let mut tracker = AllowedRelayParentsTracker::<Hash, u32>::default();
+
+	// Test it on an empty tracker.
+	assert_eq!(tracker.acquire_info(Hash::zero()), None);
+
+	let max_ancestry_len = 5;
+	let now = 100;
+	for i in 0..=max_ancestry_len {
+		tracker.update(Hash::repeat_byte(i as u8), Hash::zero(), now + i, max_ancestry_len);
+	}
+
+	// The oldest relay parent is still tracked, the latest one is at the back.
+	assert_eq!(tracker.acquire_info(Hash::repeat_byte(0)), Some((Hash::zero(), now)));
+	assert_eq!(
+		tracker.acquire_info(Hash::repeat_byte(max_ancestry_len as u8)),
+		Some((Hash::zero(), now + max_ancestry_len))
+	);
+
+	// One more update prunes the oldest relay parent.
+	tracker.update(Hash::repeat_byte(0xff), Hash::repeat_byte(1), now + 6, max_ancestry_len);
+	assert_eq!(tracker.acquire_info(Hash::repeat_byte(0)), None);
+	assert_eq!(tracker.acquire_info(Hash::repeat_byte(1)), Some((Hash::zero(), now + 1)));
+	assert_eq!(
+		tracker.acquire_info(Hash::repeat_byte(0xff)),
+		Some((Hash::repeat_byte(1), now + 6))
+	);
+}
+
+#[test]
+fn tracker_with_zero_ancestry_only_allows_latest() {
+	let mut tracker = AllowedRelayParentsTracker::<Hash, u32>::default();
+
+	tracker.update(Hash::repeat_byte(1), Hash::zero(), 10, 0);
+	tracker.update(Hash::repeat_byte(2), Hash::zero(), 11, 0);
+
+	assert_eq!(tracker.acquire_info(Hash::repeat_byte(1)), None);
+	assert_eq!(tracker.acquire_info(Hash::repeat_byte(2)), Some((Hash::zero(), 11)));
+}
@@ -89,6 +89,22 @@ frame_benchmarking::benchmarks! {
 		assert!(T::UmpSink::process_upward_message(para, &data[..], Weight::MAX).is_ok());
 	}
 
+	// A dispatch round serving one message from each of `n` paras. The messages are cheap to
+	// execute, so this is dominated by maintaining the dispatch queues.
+	process_pending_upward_messages {
+		let n in 1 .. configuration::ActiveConfig::<T>::get().max_upward_queue_count;
+		let host_conf = configuration::ActiveConfig::<T>::get();
+		let msg = create_message_min_size::<T>(0);
+		// Start with the block number 1. This is needed because should an event be
+		// emitted during the genesis block they will be implicitly wiped.
+		frame_system::Pallet::<T>::set_block_number(1u32.into());
+		for id in 0..n {
+			queue_upward_msg::<T>(&host_conf, ParaId::from(id), msg.clone());
+		}
+	}: {
+		Ump::<T>::process_pending_upward_messages();
+	}
+
 	clean_ump_after_outgoing {
 		// max number of queued messages.
 		let count = configuration::ActiveConfig::<T>::get().max_upward_queue_count;
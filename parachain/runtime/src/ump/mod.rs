@@ -105,6 +105,8 @@ impl<XcmExecutor: xcm::latest::ExecuteXcm<C::Call>, C: Config> UmpSink for XcmSi
 		};
 
 		let id = upward_message_id(&data[..]);
+		// Decoding advances `data`, so the size has to be taken beforehand.
+		let msg_size = data.len() as u32;
 		let maybe_msg_and_weight = VersionedXcm::<C::Call>::decode_all_with_depth_limit(
 			xcm::MAX_XCM_DECODE_DEPTH,
 			&mut data,
@@ -116,7 +118,7 @@ impl<XcmExecutor: xcm::latest::ExecuteXcm<C::Call>, C: Config> UmpSink for XcmSi
 				// The benchmark is timing this whole function with different message sizes and a NOOP extrinsic to
 				// measure the size-dependent weight. But as we use the weight funtion **in** the benchmarked funtion we
 				// are taking call and control-flow overhead into account twice.
-				<C as Config>::WeightInfo::process_upward_message(data.len() as u32),
+				<C as Config>::WeightInfo::process_upward_message(msg_size),
 			)
 		});
 		match maybe_msg_and_weight {
@@ -130,9 +132,15 @@ impl<XcmExecutor: xcm::latest::ExecuteXcm<C::Call>, C: Config> UmpSink for XcmSi
 			},
 			Ok((Ok(xcm_message), weight_used)) => {
 				let xcm_junction = Junction::Parachain(origin.into());
-				let outcome = XcmExecutor::execute_xcm(xcm_junction, xcm_message, max_weight);
+				// The decoding overhead is paid out of the same budget as the execution.
+				let outcome = XcmExecutor::execute_xcm(
+					xcm_junction,
+					xcm_message,
+					max_weight.saturating_sub(weight_used),
+				);
 				match outcome {
-					Outcome::Error(XcmError::WeightLimitReached(required)) => Err((id, required)),
+					Outcome::Error(XcmError::WeightLimitReached(required)) =>
+						Err((id, required.saturating_add(weight_used))),
 					outcome => {
 						let outcome_weight = outcome.weight_used();
 						Pallet::<C>::deposit_event(Event::ExecutedUpward(id, outcome));
@@ -184,6 +192,7 @@ impl fmt::Debug for AcceptanceCheckErr {
 pub trait WeightInfo {
 	fn service_overweight() -> Weight;
 	fn process_upward_message(s: u32) -> Weight;
+	fn process_pending_upward_messages(n: u32) -> Weight;
 	fn clean_ump_after_outgoing() -> Weight;
 }
 
@@ -198,6 +207,12 @@ impl WeightInfo for TestWeightInfo {
 		Weight::MAX
 	}
 
+	fn process_pending_upward_messages(_n: u32) -> Weight {
+		// Charged on top of the dispatched messages in every block, so it must not saturate the
+		// block weight of the mocks.
+		0
+	}
+
 	fn clean_ump_after_outgoing() -> Weight {
 		Weight::MAX
 	}
@@ -500,9 +515,20 @@ impl<T: Config> Pallet<T> {
 		weight
 	}
 
+	/// The most weight the messages dispatched by [`Self::process_pending_upward_messages`] may
+	/// use in a block.
+	pub(crate) fn max_dispatch_weight() -> Weight {
+		let config = <configuration::Pallet<T>>::config();
+		config
+			.ump_service_total_weight
+			.saturating_mul(T::FirstMessageFactorPercent::get().max(100)) /
+			100
+	}
+
 	/// Devote some time into dispatching pending upward messages.
 	pub(crate) fn process_pending_upward_messages() -> Weight {
 		let mut weight_used = 0;
+		let mut dispatched = 0;
 
 		let config = <configuration::Pallet<T>>::config();
 		let mut cursor = NeedsDispatchCursor::new::<T>();
@@ -528,9 +554,10 @@ impl<T: Config> Pallet<T> {
 			// our remaining weight limit, then consume it.
 			let maybe_next = queue_cache.peek_front::<T>(dispatchee);
 			if let Some(upward_message) = maybe_next {
+				dispatched += 1;
 				match T::UmpSink::process_upward_message(dispatchee, upward_message, max_weight) {
 					Ok(used) => {
-						weight_used += used;
+						weight_used = weight_used.saturating_add(used);
 						let _ = queue_cache.consume_front::<T>(dispatchee);
 					},
 					Err((id, required)) => {
@@ -567,7 +594,9 @@ impl<T: Config> Pallet<T> {
 		cursor.flush::<T>();
 		queue_cache.flush::<T>();
 
+		// The sink only accounts for the messages themselves, not for maintaining the queues.
 		weight_used
+			.saturating_add(<T as Config>::WeightInfo::process_pending_upward_messages(dispatched))
 	}
 
 	/// Puts a given upward message into the list of overweight messages allowing it to be executed
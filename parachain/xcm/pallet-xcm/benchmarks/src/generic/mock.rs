@@ -113,6 +113,8 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = TestAssetTrap;
 	type AssetClaims = TestAssetTrap;
 	type SubscriptionService = TestSubscriptionService;
+	type Tracer = ();
+	type SafeCallFilter = Everything;
 }
 
 impl crate::Config for Test {
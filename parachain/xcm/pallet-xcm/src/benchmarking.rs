@@ -0,0 +1,121 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Benchmarks for the remote asset locking calls of `pallet_xcm`.
+
+use super::*;
+use frame_benchmarking::{benchmarks, whitelisted_caller, BenchmarkError};
+use frame_support::BoundedVec;
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+/// The benchmarks of `pallet_xcm`, which need the runtime to provide the locations they use.
+pub struct Pallet<T: Config>(crate::Pallet<T>);
+
+/// The locations the benchmarks of `pallet_xcm` use.
+pub trait Config: crate::Config {
+	/// Does any necessary setup for a reachable pallet of another chain to be able to hold locks
+	/// and release them with `unlock_asset`. Returns that pallet's multi-location.
+	fn remote_lock_unlocker() -> Result<MultiLocation, BenchmarkError>;
+
+	/// Returns the multi-location of an owner of assets locked on another chain, which may note
+	/// them with `note_unlockable`.
+	fn remote_lock_owner() -> Result<MultiLocation, BenchmarkError>;
+}
+
+const LOCK_ID: LockIdentifier = *b"benchmrk";
+
+/// Fill the remote locks of `who` up to one below the maximum, none of them held on behalf of
+/// `unlocker` with `LOCK_ID`.
+fn fill_remote_locks<T: Config>(who: &T::AccountId, unlocker: &MultiLocation) {
+	let locks = (1..T::MaxRemoteLockConsumers::get())
+		.map(|i| RemoteLockedFungibleRecord {
+			id: (i as u64).to_le_bytes(),
+			amount: T::Currency::minimum_balance(),
+			unlocker: unlocker.clone().into(),
+		})
+		.collect::<Vec<_>>();
+	let locks: BoundedVec<_, T::MaxRemoteLockConsumers> =
+		locks.try_into().expect("one below the maximum number of locks; qed");
+	RemoteLockedFungibles::<T>::insert(who, locks);
+}
+
+benchmarks! {
+	where_clause { where <T as frame_system::Config>::Origin: From<crate::Origin> }
+
+	lock_asset {
+		let unlocker = T::remote_lock_unlocker()?;
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 2u32.into());
+		let amount = T::Currency::minimum_balance().saturating_mul(100u32.into());
+		fill_remote_locks::<T>(&caller, &unlocker);
+		SafeXcmVersion::<T>::put(XCM_VERSION);
+		let fees = MultiAsset::from((Here, 1_000_000_000u128));
+	}: _(
+		RawOrigin::Signed(caller.clone()),
+		LOCK_ID,
+		amount,
+		Box::new(unlocker.into()),
+		Box::new(fees.into())
+	)
+	verify {
+		assert_eq!(
+			RemoteLockedFungibles::<T>::get(&caller).len() as u32,
+			T::MaxRemoteLockConsumers::get(),
+		);
+	}
+
+	unlock_asset {
+		let unlocker = T::remote_lock_unlocker()?;
+		let owner: T::AccountId = whitelisted_caller();
+		let amount = T::Currency::minimum_balance().saturating_mul(100u32.into());
+		T::Currency::make_free_balance_be(&owner, amount.saturating_mul(2u32.into()));
+		fill_remote_locks::<T>(&owner, &unlocker);
+		RemoteLockedFungibles::<T>::mutate(&owner, |locks| {
+			locks
+				.try_push(RemoteLockedFungibleRecord {
+					id: LOCK_ID,
+					amount,
+					unlocker: unlocker.clone().into(),
+				})
+				.expect("one below the maximum number of locks was filled; qed")
+		});
+		crate::Pallet::<T>::update_remote_lock(&owner);
+		let origin = <T as frame_system::Config>::Origin::from(crate::Origin::Xcm(unlocker));
+	}: {
+		crate::Pallet::<T>::unlock_asset(origin, LOCK_ID, owner.clone())?;
+	}
+	verify {
+		assert_eq!(
+			RemoteLockedFungibles::<T>::get(&owner).len() as u32,
+			T::MaxRemoteLockConsumers::get() - 1,
+		);
+	}
+
+	note_unlockable {
+		let owner = T::remote_lock_owner()?;
+		let asset: MultiAsset = (Parent, u128::MAX).into();
+		let origin = <T as frame_system::Config>::Origin::from(crate::Origin::Xcm(owner));
+	}: {
+		crate::Pallet::<T>::note_unlockable(origin, LOCK_ID, Box::new(asset.into()))?;
+	}
+
+	impl_benchmark_test_suite!(
+		Pallet,
+		crate::mock::new_test_ext_with_balances(Vec::new()),
+		crate::mock::Test
+	);
+}
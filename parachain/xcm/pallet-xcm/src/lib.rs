@@ -18,24 +18,36 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod runtime_api;
+pub mod weights;
+
 use codec::{Decode, Encode, EncodeLike, MaxEncodedLen};
-use frame_support::traits::{Contains, EnsureOrigin, Get, OriginTrait};
+use frame_support::traits::{
+	Contains, Currency, EnsureOrigin, Everything, ExistenceRequirement, Get, LockIdentifier,
+	LockableCurrency, OnUnbalanced, OriginTrait, WithdrawReasons,
+};
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{BadOrigin, Saturating},
+	traits::{BadOrigin, SaturatedConversion, Saturating, Zero},
 	RuntimeDebug,
 };
 use sp_std::{boxed::Box, marker::PhantomData, prelude::*, result::Result, vec};
 use xcm::prelude::*;
-use xcm_executor::traits::ConvertOrigin;
+use xcm_executor::{
+	traits::{ConvertOrigin, InstructionTrace, TraceXcm, TransactAsset, XcmTrace},
+	Assets,
+};
 
 use frame_support::PalletId;
 pub use pallet::*;
+pub use weights::WeightInfo;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -48,12 +60,13 @@ pub mod pallet {
 	use frame_system::{pallet_prelude::*, Config as SysConfig};
 	use sp_core::H256;
 	use sp_runtime::traits::{AccountIdConversion, BlakeTwo256, BlockNumberProvider, Hash};
-	use xcm_executor::{
-		traits::{
-			ClaimAssets, DropAssets, InvertLocation, OnResponse, VersionChangeNotifier,
-			WeightBounds,
-		},
-		Assets,
+	use xcm_executor::traits::{
+		ClaimAssets, DropAssets, InvertLocation, OnResponse, TransferType, TransferTypeError,
+		VersionChangeNotifier, WeightBounds, WeightTrader, XcmAssetTransfers,
+	};
+
+	use crate::runtime_api::{
+		CallDryRunEffects, XcmDryRunApiError, XcmDryRunEffects, XcmPaymentApiError,
 	};
 
 	parameter_types! {
@@ -88,8 +101,12 @@ pub mod pallet {
 		/// Our XCM filter which messages to be executed using `XcmExecutor` must pass.
 		type XcmExecuteFilter: Contains<(MultiLocation, Xcm<<Self as SysConfig>::Call>)>;
 
+		/// Our XCM filter which messages to be sent using the `send` extrinsic must pass. It is
+		/// given the message as submitted, before it is prefixed with the origin.
+		type XcmSendFilter: Contains<(MultiLocation, Xcm<()>)>;
+
 		/// Something to execute an XCM message.
-		type XcmExecutor: ExecuteXcm<<Self as SysConfig>::Call>;
+		type XcmExecutor: ExecuteXcm<<Self as SysConfig>::Call> + XcmAssetTransfers;
 
 		/// Our XCM filter which messages to be teleported using the dedicated extrinsic must pass.
 		type XcmTeleportFilter: Contains<(MultiLocation, Vec<MultiAsset>)>;
@@ -117,8 +134,61 @@ pub mod pallet {
 		/// The latest supported version that we advertise. Generally just set it to
 		/// `pallet_xcm::CurrentXcmVersion`.
 		type AdvertisedXcmVersion: Get<XcmVersion>;
+
+		/// The maximum number of distinct asset traps listed for a single origin. Traps beyond this
+		/// are not listed, but remain claimable until they expire like the others.
+		type MaxAssetTrapsPerOrigin: Get<u32>;
+
+		/// The number of blocks after which each trapping of some assets expires and can no longer
+		/// be claimed.
+		type AssetTrapExpiry: Get<Self::BlockNumber>;
+
+		/// The native currency, of which accounts may lock some on behalf of a remote location.
+		type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+
+		/// Required origin for releasing a lock held on behalf of a remote location. If
+		/// successful, it resolves to the location of the unlocker. Typically `EnsureXcm<F>`, with
+		/// `F` matching the pallets of other chains which may hold locks here.
+		type UnlockOrigin: EnsureOrigin<<Self as SysConfig>::Origin, Success = MultiLocation>;
+
+		/// Required origin for noting that assets of another chain have been locked on behalf of
+		/// this pallet. If successful, it resolves to the location of the owner of the assets.
+		/// Typically `EnsureXcm<F>`, with `F` matching the accounts of the chains trusted to lock.
+		type NoteUnlockableOrigin: EnsureOrigin<
+			<Self as SysConfig>::Origin,
+			Success = MultiLocation,
+		>;
+
+		/// The maximum number of locks held on behalf of remote locations for a single account.
+		type MaxRemoteLockConsumers: Get<u32>;
+
+		/// The weight of `note_unlockable` on the chains of the unlockers, which `lock_asset`
+		/// notifies of the locks.
+		type NoteUnlockableWeight: Get<Weight>;
+
+		/// The fee charged to a signed account for each message it sends with `send`, or has sent
+		/// by a message it executes with `execute`.
+		type DeliveryFeeBase: Get<BalanceOf<Self>>;
+
+		/// The fee charged to a signed account for each byte of a message it sends with `send`, or
+		/// has sent by a message it executes with `execute`.
+		type DeliveryFeePerByte: Get<BalanceOf<Self>>;
+
+		/// Handler for the delivery fees charged to signed accounts.
+		type OnDeliveryFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as SysConfig>::AccountId>>::Balance;
+	pub type NegativeImbalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as SysConfig>::AccountId>>::NegativeImbalance;
+
+	/// The identifier of the currency lock covering all the remote locks of an account.
+	const REMOTE_LOCK_ID: LockIdentifier = *b"py/xcmlk";
+
 	/// The maximum number of distinct assets allowed to be transferred in a single helper extrinsic.
 	const MAX_ASSETS_FOR_TRANSFER: usize = 2;
 
@@ -209,6 +279,29 @@ pub mod pallet {
 		///
 		/// \[ location, query ID \]
 		NotifyTargetMigrationFail(VersionedMultiLocation, QueryId),
+		/// Some assets have been claimed from an asset trap.
+		///
+		/// \[ hash, origin, assets \]
+		AssetsClaimed(H256, MultiLocation, VersionedMultiAssets),
+		/// Some trapped assets have expired and can no longer be claimed. The assets are those of
+		/// the `AssetsTrapped` event with the same hash.
+		///
+		/// \[ hash, origin \]
+		AssetTrapExpired(H256, VersionedMultiLocation),
+		/// Some of the native asset of an account has been locked on behalf of a remote unlocker,
+		/// which has been notified.
+		///
+		/// \[ owner, lock id, amount, unlocker \]
+		AssetLocked(T::AccountId, LockIdentifier, BalanceOf<T>, MultiLocation),
+		/// A lock held on behalf of a remote unlocker has been released by it.
+		///
+		/// \[ owner, lock id, amount, unlocker \]
+		AssetUnlocked(T::AccountId, LockIdentifier, BalanceOf<T>, MultiLocation),
+		/// Some assets of another chain have been locked on behalf of this pallet, which may
+		/// release them there.
+		///
+		/// \[ owner, lock id, asset \]
+		AssetUnlockable(MultiLocation, LockIdentifier, MultiAsset),
 	}
 
 	#[pallet::origin]
@@ -256,6 +349,37 @@ pub mod pallet {
 		NoSubscription,
 		/// The location is invalid since it already has a subscription from us.
 		AlreadySubscribed,
+		/// The assets are not trapped for the origin.
+		NotTrapped,
+		/// An asset is not concrete, so its reserve cannot be determined.
+		InvalidAssetNotConcrete,
+		/// The reserve of an asset is neither this chain, nor the destination, nor trusted.
+		InvalidAssetUnknownReserve,
+		/// The account does not have enough free balance to lock.
+		LowBalance,
+		/// The account already holds the maximum number of remote locks.
+		TooManyLocks,
+		/// No lock with the given identifier is held for the account on behalf of the origin.
+		LockNotFound,
+		/// The account cannot pay the delivery fee of the message.
+		FeesNotMet,
+		/// The fee asset is not fungible, so it cannot pay for execution on both the reserve and
+		/// the destination.
+		UnsplittableFees,
+		/// A remote lock can only be raised by its owner. It is lowered by being released by its
+		/// unlocker.
+		CannotLowerLock,
+		/// The unlocker is not a pallet of the relay chain or a parachain.
+		BadUnlocker,
+	}
+
+	impl<T> From<TransferTypeError> for Error<T> {
+		fn from(e: TransferTypeError) -> Self {
+			match e {
+				TransferTypeError::NotConcrete => Error::<T>::InvalidAssetNotConcrete,
+				TransferTypeError::UnknownReserve => Error::<T>::InvalidAssetUnknownReserve,
+			}
+		}
 	}
 
 	/// The status of a query.
@@ -273,6 +397,28 @@ pub mod pallet {
 		Ready { response: VersionedResponse, at: BlockNumber },
 	}
 
+	/// Assets trapped for an origin, and what it takes to claim them.
+	#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct TrappedAssetsRecord<BlockNumber> {
+		/// The trapped assets, in the version they must be claimed with.
+		pub assets: VersionedMultiAssets,
+		/// The number of times these assets have been trapped and not yet claimed.
+		pub count: u32,
+		/// The block at which the last trapping of the assets expires.
+		pub expires_at: BlockNumber,
+	}
+
+	/// Some of the native asset of an account locked on behalf of a remote location.
+	#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct RemoteLockedFungibleRecord<Balance> {
+		/// The identifier the lock was requested with.
+		pub id: LockIdentifier,
+		/// The amount locked.
+		pub amount: Balance,
+		/// The location which may release the lock.
+		pub unlocker: VersionedMultiLocation,
+	}
+
 	#[derive(Copy, Clone)]
 	pub(crate) struct LatestVersionedMultiLocation<'a>(pub(crate) &'a MultiLocation);
 	impl<'a> EncodeLike<VersionedMultiLocation> for LatestVersionedMultiLocation<'a> {}
@@ -317,6 +463,52 @@ pub mod pallet {
 	#[pallet::getter(fn asset_trap)]
 	pub(super) type AssetTraps<T: Config> = StorageMap<_, Identity, H256, u32, ValueQuery>;
 
+	/// The asset traps of each origin, keyed by the same hash as [`AssetTraps`].
+	#[pallet::storage]
+	pub(super) type TrappedAssets<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		VersionedMultiLocation,
+		Identity,
+		H256,
+		TrappedAssetsRecord<T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// The number of entries of each origin in [`TrappedAssets`].
+	#[pallet::storage]
+	pub(super) type TrappedAssetsCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, VersionedMultiLocation, u32, ValueQuery>;
+
+	/// The number of trappings of the assets hashed as in [`AssetTraps`] at each block that were
+	/// not claimed and did not expire yet.
+	///
+	/// Claims take the oldest trapping.
+	#[pallet::storage]
+	pub(super) type AssetTrapBlocks<T: Config> =
+		StorageDoubleMap<_, Identity, H256, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	/// The block of each trapping of assets, with the origin and hash of the trap.
+	///
+	/// Entries are indexed in the order the assets were trapped, which is also the order in which
+	/// they expire. An entry is stale if its trapping was claimed since.
+	#[pallet::storage]
+	pub(super) type AssetTrapExpiries<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		u64,
+		(T::BlockNumber, VersionedMultiLocation, H256),
+		OptionQuery,
+	>;
+
+	/// The number of entries ever added to [`AssetTrapExpiries`].
+	#[pallet::storage]
+	pub(super) type AssetTrapExpiryCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// The index of the next entry of [`AssetTrapExpiries`] to check for expiry.
+	#[pallet::storage]
+	pub(super) type AssetTrapExpiryCursor<T: Config> = StorageValue<_, u64, ValueQuery>;
+
 	/// Default version to encode XCM when latest version of destination is unknown. If `None`,
 	/// then the destinations whose XCM version is unknown are considered unreachable.
 	#[pallet::storage]
@@ -381,6 +573,20 @@ pub mod pallet {
 	pub(super) type CurrentMigration<T: Config> =
 		StorageValue<_, VersionMigrationStage, OptionQuery>;
 
+	/// The locks on the native asset of each account held on behalf of remote locations.
+	///
+	/// The locked amounts overlap: the account is covered by a single currency lock of the
+	/// greatest of them.
+	#[pallet::storage]
+	#[pallet::getter(fn remote_locks)]
+	pub(super) type RemoteLockedFungibles<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<RemoteLockedFungibleRecord<BalanceOf<T>>, T::MaxRemoteLockConsumers>,
+		ValueQuery,
+	>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig {
 		/// The default version to encode outgoing XCM messages with.
@@ -416,14 +622,15 @@ pub mod pallet {
 			// Here we aim to get one successful version negotiation request sent per block, ordered
 			// by the destinations being most sent to.
 			let mut q = VersionDiscoveryQueue::<T>::take().into_inner();
-			// TODO: correct weights.
+			// Taking the queue and putting it back.
 			weight_used += T::DbWeight::get().read + T::DbWeight::get().write;
 			q.sort_by_key(|i| i.1);
 			while let Some((versioned_dest, _)) = q.pop() {
 				if let Ok(dest) = MultiLocation::try_from(versioned_dest) {
 					if Self::request_version_notify(dest).is_ok() {
-						// TODO: correct weights.
-						weight_used += T::DbWeight::get().read + T::DbWeight::get().write;
+						// `VersionNotifiers` and `QueryCounter` are read, the latter is written
+						// along with the new entries of `VersionNotifiers` and `Queries`.
+						weight_used += T::DbWeight::get().reads_writes(2, 3);
 						break
 					}
 				}
@@ -435,6 +642,9 @@ pub mod pallet {
 			}
 			weight_used
 		}
+		fn on_idle(n: BlockNumberFor<T>, max_weight: Weight) -> Weight {
+			Self::expire_asset_traps(n, max_weight)
+		}
 		fn on_runtime_upgrade() -> Weight {
 			// Start a migration (this happens before on_initialize so it'll happen later in this
 			// block, which should be good enough)...
@@ -445,17 +655,32 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Send an XCM message as a local origin.
+		///
+		/// The message is prefixed with `DescendOrigin` into the origin's location, so that it is
+		/// always attributed to the sender on the destination.
+		///
+		/// - `origin`: Must resolve to an interior location through `SendXcmOrigin`. A signed
+		///   account pays the delivery fee of the message up front.
+		/// - `dest`: The destination of the message.
+		/// - `message`: The message, which must pass `XcmSendFilter`.
 		#[pallet::weight(100_000_000)]
 		pub fn send(
 			origin: OriginFor<T>,
 			dest: Box<VersionedMultiLocation>,
 			message: Box<VersionedXcm<()>>,
 		) -> DispatchResult {
-			let origin_location = T::SendXcmOrigin::ensure_origin(origin)?;
+			let origin_location = T::SendXcmOrigin::ensure_origin(origin.clone())?;
 			let interior: Junctions =
 				origin_location.clone().try_into().map_err(|_| Error::<T>::InvalidOrigin)?;
 			let dest = MultiLocation::try_from(*dest).map_err(|()| Error::<T>::BadVersion)?;
 			let message: Xcm<()> = (*message).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let value = (origin_location, message);
+			ensure!(T::XcmSendFilter::contains(&value), Error::<T>::Filtered);
+			let (origin_location, message) = value;
+			if let Ok(who) = ensure_signed(origin) {
+				Self::charge_delivery_fee(&who, Self::delivery_fee(&message))?;
+			}
 
 			Self::send_xcm(interior, dest.clone(), message.clone()).map_err(|e| match e {
 				SendError::CannotReachDestination(..) => Error::<T>::Unreachable,
@@ -561,17 +786,23 @@ pub mod pallet {
 		///
 		/// NOTE: A successful return to this does *not* imply that the `msg` was executed successfully
 		/// to completion; only that *some* of it was executed.
+		///
+		/// A signed account pays up front the delivery fee of every message `msg` may send, whether
+		/// or not its execution gets that far.
 		#[pallet::weight(max_weight.saturating_add(100_000_000u64))]
 		pub fn execute(
 			origin: OriginFor<T>,
 			message: Box<VersionedXcm<<T as SysConfig>::Call>>,
 			max_weight: Weight,
 		) -> DispatchResultWithPostInfo {
-			let origin_location = T::ExecuteXcmOrigin::ensure_origin(origin)?;
+			let origin_location = T::ExecuteXcmOrigin::ensure_origin(origin.clone())?;
 			let message = (*message).try_into().map_err(|()| Error::<T>::BadVersion)?;
 			let value = (origin_location, message);
 			ensure!(T::XcmExecuteFilter::contains(&value), Error::<T>::Filtered);
 			let (origin_location, message) = value;
+			if let Ok(who) = ensure_signed(origin) {
+				Self::charge_delivery_fee(&who, Self::outbound_delivery_fee(&message))?;
+			}
 			let outcome = T::XcmExecutor::execute_xcm_in_credit(
 				origin_location,
 				message,
@@ -762,9 +993,596 @@ pub mod pallet {
 				Some(weight_limit),
 			)
 		}
+
+		/// Claim assets trapped for the origin's location and deposit them to `beneficiary`.
+		///
+		/// - `origin`: Must be capable of executing XCM. Its location must be the one the assets
+		///   were trapped for.
+		/// - `assets`: The trapped assets, exactly as recorded in the trap.
+		/// - `beneficiary`: The location to deposit the claimed assets to. Will generally be an
+		///   `AccountId32` value.
+		#[pallet::weight({
+			match ((*assets.clone()).try_into(), (*beneficiary.clone()).try_into()) {
+				(Ok(assets), Ok(beneficiary)) => {
+					let mut message = Self::claim_message(assets, Here.into(), beneficiary);
+					T::Weigher::weight(&mut message).map_or(Weight::max_value(), |w| 100_000_000 + w)
+				},
+				_ => Weight::max_value(),
+			}
+		})]
+		pub fn claim_assets(
+			origin: OriginFor<T>,
+			assets: Box<VersionedMultiAssets>,
+			beneficiary: Box<VersionedMultiLocation>,
+		) -> DispatchResult {
+			let origin_location = T::ExecuteXcmOrigin::ensure_origin(origin)?;
+			let hash = BlakeTwo256::hash_of(&(&origin_location, &assets));
+			ensure!(AssetTraps::<T>::get(hash) > 0, Error::<T>::NotTrapped);
+			// The ticket tells the executor in which version the assets were trapped.
+			let ticket: MultiLocation = match *assets {
+				VersionedMultiAssets::V0(_) => GeneralIndex(0).into(),
+				VersionedMultiAssets::V1(_) => Here.into(),
+			};
+			let assets: MultiAssets = (*assets).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let beneficiary: MultiLocation =
+				(*beneficiary).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let mut message = Self::claim_message(assets, ticket, beneficiary);
+			let weight =
+				T::Weigher::weight(&mut message).map_err(|()| Error::<T>::UnweighableMessage)?;
+			let outcome =
+				T::XcmExecutor::execute_xcm_in_credit(origin_location, message, weight, weight);
+			Self::deposit_event(Event::Attempted(outcome));
+			Ok(())
+		}
+
+		/// Transfer some assets from the local chain to a destination chain, teleporting or
+		/// reserve-transferring them as the trust configuration of the executor allows.
+		///
+		/// An asset is teleported if the destination is a trusted teleporter for it. Otherwise it
+		/// is reserve-transferred through its reserve, which is this chain, the destination or a
+		/// trusted third chain the assets are routed through. Assets transferred in different ways
+		/// are sent in separate messages, one per way.
+		///
+		/// - `origin`: Must be capable of withdrawing the `assets` and executing XCM.
+		/// - `dest`: Destination context for the assets. Will typically be `X2(Parent, Parachain(..))` to send
+		///   from parachain to parachain, or `X1(Parachain(..))` to send from relay to parachain.
+		/// - `beneficiary`: A beneficiary location for the assets in the context of `dest`. Will generally be
+		///   an `AccountId32` value.
+		/// - `assets`: The assets to be withdrawn. This should include the assets used to pay the fee on the
+		///   `dest` side, and on the reserve side if it is a third chain.
+		/// - `fee_asset_item`: The index into `assets` of the item which should be used to pay
+		///   fees. Going through a third reserve, half of it pays on the reserve and half on `dest`.
+		///   The messages that do not carry this item are paid for with their first asset.
+		/// - `weight_limit`: The remote-side weight limit, if any, for the XCM fee purchase.
+		#[pallet::weight({
+			match (
+				(*assets.clone()).try_into(),
+				(*dest.clone()).try_into(),
+				(*beneficiary.clone()).try_into(),
+			) {
+				(Ok(assets), Ok(dest), Ok(beneficiary)) =>
+					Self::transfer_groups(&assets, &dest, *fee_asset_item)
+						.and_then(|groups| Self::transfer_groups_message(
+							groups,
+							dest,
+							beneficiary,
+							weight_limit.clone(),
+						))
+					.ok()
+					.and_then(|mut message| T::Weigher::weight(&mut message).ok())
+					.map_or(Weight::max_value(), |w| 100_000_000 + w),
+				_ => Weight::max_value(),
+			}
+		})]
+		pub fn transfer_assets(
+			origin: OriginFor<T>,
+			dest: Box<VersionedMultiLocation>,
+			beneficiary: Box<VersionedMultiLocation>,
+			assets: Box<VersionedMultiAssets>,
+			fee_asset_item: u32,
+			weight_limit: WeightLimit,
+		) -> DispatchResult {
+			let origin_location = T::ExecuteXcmOrigin::ensure_origin(origin)?;
+			let dest: MultiLocation = (*dest).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let beneficiary: MultiLocation =
+				(*beneficiary).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let assets: MultiAssets = (*assets).try_into().map_err(|()| Error::<T>::BadVersion)?;
+
+			ensure!(assets.len() <= MAX_ASSETS_FOR_TRANSFER, Error::<T>::TooManyAssets);
+			let groups = Self::transfer_groups(&assets, &dest, fee_asset_item)?;
+			for (transfer_type, assets, _) in &groups {
+				let value = (origin_location.clone(), assets.inner().clone());
+				let allowed = match transfer_type {
+					TransferType::Teleport => T::XcmTeleportFilter::contains(&value),
+					_ => T::XcmReserveTransferFilter::contains(&value),
+				};
+				ensure!(allowed, Error::<T>::Filtered);
+			}
+			let mut message =
+				Self::transfer_groups_message(groups, dest, beneficiary, weight_limit)?;
+			let weight =
+				T::Weigher::weight(&mut message).map_err(|()| Error::<T>::UnweighableMessage)?;
+			let outcome =
+				T::XcmExecutor::execute_xcm_in_credit(origin_location, message, weight, weight);
+			Self::deposit_event(Event::Attempted(outcome));
+			Ok(())
+		}
+
+		/// Lock some of the native asset of the origin on behalf of a pallet of another chain,
+		/// which is notified of the lock and is the only one able to release it.
+		///
+		/// The unlocker is notified with a `Transact` of its `note_unlockable` call, dispatched
+		/// with the location of the origin, so it must be the `pallet-xcm` of its chain. The
+		/// delivery fee of the notification is charged to the origin here, and its execution is
+		/// paid for with `fees`, withdrawn from the account of the origin on the unlocker's chain.
+		/// What is left of `fees` is deposited back into that account.
+		///
+		/// Locking again with the same `id` and `unlocker` raises the locked amount, which may
+		/// not be lowered other than by the unlocker releasing the lock.
+		///
+		/// - `origin`: Must be a signed account holding at least `amount` free balance once the
+		///   delivery fee is paid.
+		/// - `id`: An identifier of the lock, chosen by the owner to tell its locks apart.
+		/// - `amount`: The amount to lock.
+		/// - `unlocker`: The pallet which may release the lock with `unlock_asset`, e.g.
+		///   `Parachain(2000)/PalletInstance(31)`.
+		/// - `fees`: The asset paying for the execution of the notification, as seen from the
+		///   unlocker's chain.
+		#[pallet::weight(T::WeightInfo::lock_asset())]
+		pub fn lock_asset(
+			origin: OriginFor<T>,
+			id: LockIdentifier,
+			amount: BalanceOf<T>,
+			unlocker: Box<VersionedMultiLocation>,
+			fees: Box<VersionedMultiAsset>,
+		) -> DispatchResult {
+			let owner = T::SendXcmOrigin::ensure_origin(origin.clone())?;
+			let who = ensure_signed(origin)?;
+			let interior: Junctions =
+				owner.clone().try_into().map_err(|_| Error::<T>::InvalidOrigin)?;
+			let unlocker: MultiLocation =
+				(*unlocker).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let fees: MultiAsset = (*fees).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let (dest, pallet_index) = Self::unlocker_pallet(&unlocker)?;
+			ensure!(!amount.is_zero(), Error::<T>::Empty);
+
+			let ancestry = T::LocationInverter::ancestry();
+			let asset: MultiAsset = (Here, amount.saturated_into::<u128>()).into();
+			let asset =
+				asset.reanchored(&dest, &ancestry).map_err(|_| Error::<T>::CannotReanchor)?;
+			let mut beneficiary = owner;
+			beneficiary
+				.reanchor(&dest, &ancestry)
+				.map_err(|()| Error::<T>::CannotReanchor)?;
+			let notification = Call::<T>::note_unlockable { id, asset: Box::new(asset.into()) };
+			let message = Xcm(vec![
+				WithdrawAsset(fees.clone().into()),
+				BuyExecution { fees, weight_limit: Unlimited },
+				Transact {
+					origin_type: OriginKind::Xcm,
+					require_weight_at_most: T::NoteUnlockableWeight::get(),
+					call: (pallet_index, notification).encode().into(),
+				},
+				RefundSurplus,
+				DepositAsset { assets: Wild(All), max_assets: 1, beneficiary },
+			]);
+			Self::charge_delivery_fee(&who, Self::delivery_fee(&message))?;
+			ensure!(T::Currency::free_balance(&who) >= amount, Error::<T>::LowBalance);
+
+			let versioned_unlocker = VersionedMultiLocation::from(unlocker.clone());
+			RemoteLockedFungibles::<T>::try_mutate(&who, |locks| -> DispatchResult {
+				match locks.iter_mut().find(|l| l.id == id && l.unlocker == versioned_unlocker) {
+					Some(lock) => {
+						ensure!(amount >= lock.amount, Error::<T>::CannotLowerLock);
+						lock.amount = amount;
+					},
+					None => locks
+						.try_push(RemoteLockedFungibleRecord {
+							id,
+							amount,
+							unlocker: versioned_unlocker,
+						})
+						.map_err(|_| Error::<T>::TooManyLocks)?,
+				}
+				Ok(())
+			})?;
+			Self::update_remote_lock(&who);
+
+			Self::send_xcm(interior, dest, message).map_err(|e| match e {
+				SendError::CannotReachDestination(..) => Error::<T>::Unreachable,
+				_ => Error::<T>::SendFailure,
+			})?;
+			Self::deposit_event(Event::AssetLocked(who, id, amount, unlocker));
+			Ok(())
+		}
+
+		/// Release a lock held on behalf of the origin's location.
+		///
+		/// - `origin`: Must be the unlocker of the lock, as resolved by `UnlockOrigin`.
+		/// - `id`: The identifier the lock was requested with.
+		/// - `owner`: The account whose asset is locked.
+		#[pallet::weight(T::WeightInfo::unlock_asset())]
+		pub fn unlock_asset(
+			origin: OriginFor<T>,
+			id: LockIdentifier,
+			owner: T::AccountId,
+		) -> DispatchResult {
+			let unlocker = T::UnlockOrigin::ensure_origin(origin)?;
+			let versioned_unlocker = VersionedMultiLocation::from(unlocker.clone());
+			let lock = RemoteLockedFungibles::<T>::try_mutate(&owner, |locks| {
+				let index = locks
+					.iter()
+					.position(|l| l.id == id && l.unlocker == versioned_unlocker)
+					.ok_or(Error::<T>::LockNotFound)?;
+				Ok::<_, Error<T>>(locks.remove(index))
+			})?;
+			Self::update_remote_lock(&owner);
+			Self::deposit_event(Event::AssetUnlocked(owner, id, lock.amount, unlocker));
+			Ok(())
+		}
+
+		/// Note that some assets of another chain have been locked on behalf of this pallet,
+		/// which may release them there with `unlock_asset`.
+		///
+		/// This is the notification `lock_asset` sends to the unlocker, and only deposits an
+		/// event for the pallets and users of this chain to act upon.
+		///
+		/// - `origin`: Must be the owner of the assets, as resolved by `NoteUnlockableOrigin`.
+		/// - `id`: The identifier the lock was requested with.
+		/// - `asset`: The locked assets, as seen from this chain.
+		#[pallet::weight(T::WeightInfo::note_unlockable())]
+		pub fn note_unlockable(
+			origin: OriginFor<T>,
+			id: LockIdentifier,
+			asset: Box<VersionedMultiAsset>,
+		) -> DispatchResult {
+			let owner = T::NoteUnlockableOrigin::ensure_origin(origin)?;
+			let asset: MultiAsset = (*asset).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			Self::deposit_event(Event::AssetUnlockable(owner, id, asset));
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		/// The fee a signed account is charged for sending `message`.
+		fn delivery_fee(message: &Xcm<()>) -> BalanceOf<T> {
+			let len: BalanceOf<T> = (message.encoded_size() as u32).into();
+			T::DeliveryFeeBase::get()
+				.saturating_add(T::DeliveryFeePerByte::get().saturating_mul(len))
+		}
+
+		/// The fee a signed account is charged for the messages sent by executing `message`.
+		///
+		/// Each instruction which sends a message is priced as the message it sends, which holds
+		/// its assets and its inner `xcm`.
+		fn outbound_delivery_fee(message: &Xcm<<T as SysConfig>::Call>) -> BalanceOf<T> {
+			message.0.iter().fold(Zero::zero(), |fee: BalanceOf<T>, instruction| {
+				let instruction_fee = match instruction {
+					InitiateTeleport { .. } |
+					DepositReserveAsset { .. } |
+					InitiateReserveWithdraw { .. } |
+					TransferReserveAsset { .. } => Self::delivery_fee(&Xcm(vec![instruction.clone().into()])),
+					SetErrorHandler(xcm) | SetAppendix(xcm) => Self::outbound_delivery_fee(xcm),
+					_ => Zero::zero(),
+				};
+				fee.saturating_add(instruction_fee)
+			})
+		}
+
+		/// Withdraw the delivery fee `fee` from `who`.
+		fn charge_delivery_fee(who: &T::AccountId, fee: BalanceOf<T>) -> Result<(), Error<T>> {
+			if fee.is_zero() {
+				return Ok(())
+			}
+			let imbalance = T::Currency::withdraw(
+				who,
+				fee,
+				WithdrawReasons::FEE,
+				ExistenceRequirement::KeepAlive,
+			)
+			.map_err(|_| Error::<T>::FeesNotMet)?;
+			T::OnDeliveryFee::on_unbalanced(imbalance);
+			Ok(())
+		}
+
+		/// Set the currency lock of `who` to cover its remote locks, removing it if there are
+		/// none left.
+		pub(crate) fn update_remote_lock(who: &T::AccountId) {
+			let locks = RemoteLockedFungibles::<T>::get(who);
+			match locks.iter().map(|l| l.amount).max() {
+				Some(amount) =>
+					T::Currency::set_lock(REMOTE_LOCK_ID, who, amount, WithdrawReasons::all()),
+				None => {
+					T::Currency::remove_lock(REMOTE_LOCK_ID, who);
+					RemoteLockedFungibles::<T>::remove(who);
+				},
+			}
+		}
+
+		/// The chain of `unlocker` and the index of `unlocker` within it, if it is a pallet of the
+		/// relay chain or a parachain.
+		fn unlocker_pallet(unlocker: &MultiLocation) -> Result<(MultiLocation, u8), Error<T>> {
+			match unlocker.clone().split_last_interior() {
+				(chain, Some(PalletInstance(index)))
+					if chain != MultiLocation::here() &&
+						matches!(chain.interior, Here | X1(Parachain(_))) =>
+					Ok((chain, index)),
+				_ => Err(Error::<T>::BadUnlocker),
+			}
+		}
+
+		/// The assets transferred to `dest` grouped by the way they are transferred, in the order
+		/// the ways first occur in `assets`.
+		///
+		/// Each group comes with the index of the asset paying for its execution: the item
+		/// `fee_asset_item` of `assets` if the group has it, its first asset otherwise.
+		pub(crate) fn transfer_groups(
+			assets: &MultiAssets,
+			dest: &MultiLocation,
+			fee_asset_item: u32,
+		) -> Result<Vec<(TransferType, MultiAssets, u32)>, Error<T>> {
+			let fees = assets.get(fee_asset_item as usize).ok_or(Error::<T>::Empty)?;
+			let mut groups: Vec<(TransferType, Vec<MultiAsset>, u32)> = Vec::new();
+			for asset in assets.inner() {
+				let transfer_type = T::XcmExecutor::determine_for(asset, dest)?;
+				let index = match groups.iter().position(|(other, ..)| *other == transfer_type) {
+					Some(index) => index,
+					None => {
+						groups.push((transfer_type, Vec::new(), 0));
+						groups.len() - 1
+					},
+				};
+				let (_, group, fee_item) = &mut groups[index];
+				if asset == fees {
+					*fee_item = group.len() as u32;
+				}
+				group.push(asset.clone());
+			}
+			// The assets of each group keep the order of `assets`.
+			Ok(groups
+				.into_iter()
+				.map(|(transfer_type, group, fee_item)| {
+					let group = MultiAssets::from_sorted_and_deduplicated_skip_checks(group);
+					(transfer_type, group, fee_item)
+				})
+				.collect())
+		}
+
+		/// The message transferring the assets of all `groups` to `beneficiary` on `dest`, made of
+		/// the [`Self::transfer_message`] of each group.
+		pub(crate) fn transfer_groups_message<Call>(
+			groups: Vec<(TransferType, MultiAssets, u32)>,
+			dest: MultiLocation,
+			beneficiary: MultiLocation,
+			weight_limit: WeightLimit,
+		) -> Result<Xcm<Call>, Error<T>> {
+			let mut message = Vec::new();
+			for (transfer_type, assets, fee_item) in groups {
+				let Xcm(program) = Self::transfer_message(
+					transfer_type,
+					dest.clone(),
+					beneficiary.clone(),
+					assets,
+					fee_item,
+					weight_limit.clone(),
+				)?;
+				message.extend(program);
+			}
+			Ok(Xcm(message))
+		}
+
+		/// The message transferring `assets` to `beneficiary` on `dest` the given way.
+		pub(crate) fn transfer_message<Call>(
+			transfer_type: TransferType,
+			dest: MultiLocation,
+			beneficiary: MultiLocation,
+			assets: MultiAssets,
+			fee_asset_item: u32,
+			weight_limit: WeightLimit,
+		) -> Result<Xcm<Call>, Error<T>> {
+			let ancestry = T::LocationInverter::ancestry();
+			let mut fees = assets.get(fee_asset_item as usize).ok_or(Error::<T>::Empty)?.clone();
+			// Going through a remote reserve, the fees pay for execution both on the reserve and on
+			// `dest`, so each gets half of them.
+			if matches!(transfer_type, TransferType::RemoteReserve(_)) {
+				match &mut fees.fun {
+					Fungible(amount) => *amount /= 2,
+					NonFungible(_) => return Err(Error::<T>::UnsplittableFees),
+				}
+			}
+			let reanchored = |target: &MultiLocation| {
+				fees.clone()
+					.reanchored(target, &ancestry)
+					.map_err(|_| Error::<T>::CannotReanchor)
+			};
+			let max_assets = assets.len() as u32;
+			let xcm = Xcm(vec![
+				BuyExecution { fees: reanchored(&dest)?, weight_limit: weight_limit.clone() },
+				DepositAsset { assets: Wild(All), max_assets, beneficiary },
+			]);
+			Ok(match transfer_type {
+				TransferType::Teleport => Xcm(vec![
+					WithdrawAsset(assets),
+					InitiateTeleport { assets: Wild(All), dest, xcm },
+				]),
+				TransferType::LocalReserve => Xcm(vec![TransferReserveAsset { assets, dest, xcm }]),
+				TransferType::DestinationReserve => Xcm(vec![
+					WithdrawAsset(assets),
+					InitiateReserveWithdraw { assets: Wild(All), reserve: dest, xcm },
+				]),
+				TransferType::RemoteReserve(reserve) => {
+					// The reserve pays for its own execution and forwards the rest to `dest`.
+					let mut reserve_dest = dest;
+					reserve_dest
+						.reanchor(&reserve, &ancestry)
+						.map_err(|()| Error::<T>::CannotReanchor)?;
+					let reserve_xcm = Xcm(vec![
+						BuyExecution { fees: reanchored(&reserve)?, weight_limit },
+						DepositReserveAsset {
+							assets: Wild(All),
+							max_assets,
+							dest: reserve_dest,
+							xcm,
+						},
+					]);
+					Xcm(vec![
+						WithdrawAsset(assets),
+						InitiateReserveWithdraw { assets: Wild(All), reserve, xcm: reserve_xcm },
+					])
+				},
+			})
+		}
+
+		fn claim_message<Call>(
+			assets: MultiAssets,
+			ticket: MultiLocation,
+			beneficiary: MultiLocation,
+		) -> Xcm<Call> {
+			let max_assets = assets.len() as u32;
+			Xcm(vec![
+				ClaimAsset { assets, ticket },
+				DepositAsset { assets: Wild(All), max_assets, beneficiary },
+			])
+		}
+
+		/// The assets trapped for `origin`, with the hash of each trap.
+		pub fn trapped_assets(
+			origin: VersionedMultiLocation,
+		) -> Vec<(H256, TrappedAssetsRecord<T::BlockNumber>)> {
+			match origin.into_latest() {
+				Ok(origin) => TrappedAssets::<T>::iter_prefix(origin).collect(),
+				Err(()) => Vec::new(),
+			}
+		}
+
+		/// Record that `assets` were trapped for `origin` under `hash` and schedule their expiry.
+		///
+		/// The assets are only listed if `origin` has fewer than `MaxAssetTrapsPerOrigin` listed
+		/// traps, but they expire in any case.
+		fn note_trapped_assets(origin: &MultiLocation, hash: H256, assets: &VersionedMultiAssets) {
+			let origin = VersionedMultiLocation::from(origin.clone());
+			let now = frame_system::Pallet::<T>::block_number();
+			let expires_at = now.saturating_add(T::AssetTrapExpiry::get());
+			AssetTrapBlocks::<T>::mutate(hash, now, |n| n.saturating_inc());
+			TrappedAssets::<T>::mutate(&origin, hash, |maybe_record| match maybe_record {
+				Some(record) => {
+					record.count.saturating_inc();
+					record.expires_at = expires_at;
+				},
+				None => {
+					let count = TrappedAssetsCount::<T>::get(&origin);
+					if count < T::MaxAssetTrapsPerOrigin::get() {
+						TrappedAssetsCount::<T>::insert(&origin, count + 1);
+						*maybe_record = Some(TrappedAssetsRecord {
+							assets: assets.clone(),
+							count: 1,
+							expires_at,
+						});
+					}
+				},
+			});
+			let index = AssetTrapExpiryCount::<T>::mutate(|count| {
+				let index = *count;
+				count.saturating_inc();
+				index
+			});
+			AssetTrapExpiries::<T>::insert(index, (now, origin, hash));
+		}
+
+		/// Record that the assets trapped for `origin` under `hash` were claimed once, which takes
+		/// their oldest trapping.
+		fn note_claimed_assets(origin: &MultiLocation, hash: H256) {
+			let oldest = AssetTrapBlocks::<T>::iter_key_prefix(hash).min();
+			if let Some(trapped_at) = oldest {
+				Self::take_trapping(hash, trapped_at);
+			}
+			Self::unlist_trapped_assets(&VersionedMultiLocation::from(origin.clone()), hash);
+		}
+
+		/// Remove one trapping of the assets under `hash` at `trapped_at`. Returns whether there
+		/// was one.
+		fn take_trapping(hash: H256, trapped_at: T::BlockNumber) -> bool {
+			AssetTrapBlocks::<T>::mutate_exists(hash, trapped_at, |count| {
+				let taken = count.is_some();
+				*count = count.map(|n| n.saturating_sub(1)).filter(|n| *n > 0);
+				taken
+			})
+		}
+
+		/// Decrease the number of trappings in the listing of the assets of `origin` under `hash`,
+		/// removing the listing after the last one.
+		fn unlist_trapped_assets(origin: &VersionedMultiLocation, hash: H256) {
+			let remove =
+				TrappedAssets::<T>::mutate(origin, hash, |maybe_record| match maybe_record {
+					Some(record) if record.count > 1 => {
+						record.count -= 1;
+						false
+					},
+					Some(_) => true,
+					None => false,
+				});
+			if remove {
+				TrappedAssets::<T>::remove(origin, hash);
+				TrappedAssetsCount::<T>::mutate_exists(origin, |count| {
+					*count = count.and_then(|n| n.checked_sub(1)).filter(|n| *n > 0);
+				});
+			}
+		}
+
+		/// Remove the asset traps that expired by `now`, in the order they were made.
+		///
+		/// Returns the weight consumed, which is kept below `limit`.
+		fn expire_asset_traps(now: T::BlockNumber, limit: Weight) -> Weight {
+			let db = T::DbWeight::get();
+			// Reading the count, the cursor and the first entry that did not expire yet, and
+			// writing the cursor back.
+			let mut used = db.reads_writes(3, 1);
+			if used > limit {
+				return 0
+			}
+			// Reading and removing an entry, and if it expired, its trapping, its listing, the
+			// number of listed traps of its origin and its count in `AssetTraps`.
+			let step = db.reads_writes(5, 5);
+
+			let expiry = T::AssetTrapExpiry::get();
+			let count = AssetTrapExpiryCount::<T>::get();
+			let mut cursor = AssetTrapExpiryCursor::<T>::get();
+			while cursor < count && used.saturating_add(step) <= limit {
+				match AssetTrapExpiries::<T>::get(cursor) {
+					Some((trapped_at, origin, hash))
+						if trapped_at.saturating_add(expiry) <= now =>
+					{
+						used += step;
+						AssetTrapExpiries::<T>::remove(cursor);
+						Self::expire_asset_trap(trapped_at, origin, hash);
+					},
+					// entries are added in order of expiry, so none of the later ones expired.
+					Some(_) => break,
+					None => {},
+				}
+				cursor += 1;
+			}
+			AssetTrapExpiryCursor::<T>::put(cursor);
+			used
+		}
+
+		/// Expire the trapping of the assets of `origin` under `hash` at `trapped_at`, unless it was
+		/// claimed since.
+		fn expire_asset_trap(
+			trapped_at: T::BlockNumber,
+			origin: VersionedMultiLocation,
+			hash: H256,
+		) {
+			if !Self::take_trapping(hash, trapped_at) {
+				return
+			}
+			AssetTraps::<T>::mutate_exists(hash, |count| {
+				*count = count.map(|n| n.saturating_sub(1)).filter(|n| *n > 0);
+			});
+			Self::unlist_trapped_assets(&origin, hash);
+			Self::deposit_event(Event::AssetTrapExpired(hash, origin));
+		}
+
 		fn do_reserve_transfer_assets(
 			origin: OriginFor<T>,
 			dest: Box<VersionedMultiLocation>,
@@ -1211,6 +2029,149 @@ pub mod pallet {
 				}
 			});
 		}
+
+		/// Dry-run `call` dispatched from `origin` and return its effects.
+		///
+		/// The state changes are not reverted, so this must only be called from a runtime API.
+		pub fn dry_run_call(
+			origin: <T as Config>::Origin,
+			call: <T as Config>::Call,
+		) -> CallDryRunEffects<<T as SysConfig>::Event> {
+			frame_system::Pallet::<T>::reset_events();
+			let (execution_result, recording) = Self::record_effects(|| call.dispatch(origin));
+
+			CallDryRunEffects {
+				execution_result,
+				emitted_events: Self::emitted_events(),
+				forwarded_xcms: recording.forwarded_xcms,
+				asset_changes: recording.asset_changes,
+				trace: recording.trace,
+			}
+		}
+
+		/// Dry-run `xcm` as if it was received from `origin_location` and return its effects.
+		///
+		/// The state changes are not reverted, so this must only be called from a runtime API.
+		pub fn dry_run_xcm(
+			origin_location: VersionedMultiLocation,
+			xcm: VersionedXcm<<T as SysConfig>::Call>,
+		) -> Result<XcmDryRunEffects<<T as SysConfig>::Event>, XcmDryRunApiError> {
+			let origin_location = MultiLocation::try_from(origin_location)
+				.map_err(|()| XcmDryRunApiError::VersionedConversionFailed)?;
+			let xcm = Xcm::<<T as SysConfig>::Call>::try_from(xcm)
+				.map_err(|()| XcmDryRunApiError::VersionedConversionFailed)?;
+			let weight = T::Weigher::weight(&mut xcm.clone())
+				.map_err(|()| XcmDryRunApiError::UnweighableMessage)?;
+
+			frame_system::Pallet::<T>::reset_events();
+			let (execution_result, recording) =
+				Self::record_effects(|| T::XcmExecutor::execute_xcm(origin_location, xcm, weight));
+
+			Ok(XcmDryRunEffects {
+				execution_result,
+				emitted_events: Self::emitted_events(),
+				forwarded_xcms: recording.forwarded_xcms,
+				asset_changes: recording.asset_changes,
+				trace: recording.trace,
+			})
+		}
+
+		/// Run `f` while recording the effects of the [`RecordingRouter`] and
+		/// [`RecordingTransactor`].
+		fn record_effects<R>(f: impl FnOnce() -> R) -> (R, XcmRecording) {
+			let mut recording = XcmRecording::default();
+			let result = dry_run_recording::using(&mut recording, f);
+			(result, recording)
+		}
+
+		/// Whether a dry-run is in progress.
+		///
+		/// This does not touch storage, so it can be called on every send and asset transfer.
+		pub(crate) fn is_dry_running() -> bool {
+			dry_run_recording::with(|_| ()).is_some()
+		}
+
+		/// Add to the recording of the ongoing dry-run, if any.
+		pub(crate) fn record(f: impl FnOnce(&mut XcmRecording)) {
+			dry_run_recording::with(f);
+		}
+
+		fn emitted_events() -> Vec<<T as SysConfig>::Event> {
+			frame_system::Pallet::<T>::read_events_no_consensus()
+				.into_iter()
+				.map(|record| record.event)
+				.collect()
+		}
+
+		/// Express the `assets` accepted as payment for execution in `version`.
+		pub fn query_acceptable_payment_assets(
+			version: XcmVersion,
+			assets: Vec<MultiLocation>,
+		) -> Result<Vec<VersionedMultiLocation>, XcmPaymentApiError> {
+			if version > XCM_VERSION {
+				return Err(XcmPaymentApiError::UnhandledXcmVersion)
+			}
+			assets
+				.into_iter()
+				.map(|asset| {
+					VersionedMultiLocation::from(asset)
+						.into_version(version)
+						.map_err(|()| XcmPaymentApiError::VersionedConversionFailed)
+				})
+				.collect()
+		}
+
+		/// The weight of executing `message`, as computed by the configured `Weigher`.
+		pub fn query_xcm_weight(message: VersionedXcm<()>) -> Result<Weight, XcmPaymentApiError> {
+			let message = Xcm::<()>::try_from(message)
+				.map_err(|()| XcmPaymentApiError::VersionedConversionFailed)?;
+			T::Weigher::weight(&mut message.into::<<T as SysConfig>::Call>())
+				.map_err(|()| XcmPaymentApiError::WeightNotComputable)
+		}
+
+		/// The fee, in units of the fungible `asset`, that `Trader` charges for `weight`.
+		///
+		/// The trader is offered all of `asset` it could ask for and the fee is whatever it keeps.
+		pub fn query_weight_to_asset_fee<Trader: WeightTrader>(
+			weight: Weight,
+			asset: VersionedMultiLocation,
+		) -> Result<u128, XcmPaymentApiError> {
+			let asset = MultiLocation::try_from(asset)
+				.map_err(|()| XcmPaymentApiError::VersionedConversionFailed)?;
+			let id = AssetId::Concrete(asset);
+			let payment: MultiAsset = (id.clone(), u128::MAX).into();
+			let unused = Trader::new()
+				.buy_weight(weight, payment.into())
+				.map_err(|_| XcmPaymentApiError::AssetNotFound)?;
+			let unused = unused.fungible.get(&id).copied().unwrap_or(0);
+			Ok(u128::MAX - unused)
+		}
+
+		/// The fees charged to a signed account for sending `message` to `destination`.
+		///
+		/// The routers of this version of XCM do not charge for delivery, so this is only the
+		/// delivery fee of `send`, in the native asset.
+		pub fn query_delivery_fees(
+			destination: VersionedMultiLocation,
+			message: VersionedXcm<()>,
+		) -> Result<VersionedMultiAssets, XcmPaymentApiError> {
+			let message = Xcm::<()>::try_from(message)
+				.map_err(|()| XcmPaymentApiError::VersionedConversionFailed)?;
+			let fee = Self::delivery_fee(&message);
+			let fees = if fee.is_zero() {
+				MultiAssets::new()
+			} else {
+				(Here, fee.saturated_into::<u128>()).into()
+			};
+			// Answer in the version the destination was given in.
+			let version = match destination {
+				VersionedMultiLocation::V0(_) => 0,
+				VersionedMultiLocation::V1(_) => 1,
+			};
+			VersionedMultiAssets::from(fees)
+				.into_version(version)
+				.map_err(|()| XcmPaymentApiError::VersionedConversionFailed)
+		}
 	}
 
 	impl<T: Config> WrapVersion for Pallet<T> {
@@ -1281,9 +2242,12 @@ pub mod pallet {
 			let versioned = VersionedMultiAssets::from(MultiAssets::from(assets));
 			let hash = BlakeTwo256::hash_of(&(&origin, &versioned));
 			AssetTraps::<T>::mutate(hash, |n| *n += 1);
+			Self::note_trapped_assets(origin, hash, &versioned);
 			Self::deposit_event(Event::AssetsTrapped(hash, origin.clone(), versioned));
-			// TODO #3735: Put the real weight in there.
-			0
+			// `AssetTraps`, the trappings of the block, the listing of the trap, the number of
+			// listed traps of the origin and `AssetTrapExpiryCount` are read and written, and the
+			// expiry entry is inserted.
+			T::DbWeight::get().reads_writes(5, 6)
 		}
 	}
 
@@ -1303,12 +2267,14 @@ pub mod pallet {
 				(0, Here) => (),
 				_ => return false,
 			};
-			let hash = BlakeTwo256::hash_of(&(origin, versioned));
+			let hash = BlakeTwo256::hash_of(&(origin, &versioned));
 			match AssetTraps::<T>::get(hash) {
 				0 => return false,
 				1 => AssetTraps::<T>::remove(hash),
 				n => AssetTraps::<T>::insert(hash, n - 1),
 			}
+			Self::note_claimed_assets(origin, hash);
+			Self::deposit_event(Event::AssetsClaimed(hash, origin.clone(), versioned));
 			return true
 		}
 	}
@@ -1552,17 +2518,139 @@ where
 }
 
 /// A simple passthrough where we reuse the `MultiLocation`-typed XCM origin as the inner value of
-/// this crate's `Origin::Xcm` value.
-pub struct XcmPassthrough<Origin>(PhantomData<Origin>);
-impl<Origin: From<crate::Origin>> ConvertOrigin<Origin> for XcmPassthrough<Origin> {
+/// this crate's `Origin::Xcm` value, for the origins matching `Filter`.
+pub struct XcmPassthrough<Origin, Filter = Everything>(PhantomData<(Origin, Filter)>);
+impl<Origin: From<crate::Origin>, Filter: Contains<MultiLocation>> ConvertOrigin<Origin>
+	for XcmPassthrough<Origin, Filter>
+{
 	fn convert_origin(
 		origin: impl Into<MultiLocation>,
 		kind: OriginKind,
 	) -> Result<Origin, MultiLocation> {
 		let origin = origin.into();
 		match kind {
-			OriginKind::Xcm => Ok(crate::Origin::Xcm(origin).into()),
+			OriginKind::Xcm if Filter::contains(&origin) => Ok(crate::Origin::Xcm(origin).into()),
 			_ => Err(origin),
 		}
 	}
 }
+
+/// A change of assets recorded during a dry-run.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum AssetChange {
+	/// `asset` was deposited into the account of `who`.
+	Deposited { who: VersionedMultiLocation, asset: VersionedMultiAsset },
+	/// `asset` was withdrawn from the account of `who`.
+	Withdrawn { who: VersionedMultiLocation, asset: VersionedMultiAsset },
+}
+
+/// The effects recorded during a dry-run.
+#[derive(Clone, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct XcmRecording {
+	/// The messages that were sent, grouped by destination.
+	pub forwarded_xcms: Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>,
+	/// The assets that were deposited or withdrawn, in order.
+	pub asset_changes: Vec<AssetChange>,
+	/// The instructions that were executed, in order.
+	pub trace: XcmTrace,
+}
+
+// The effects recorded by [`RecordingRouter`] and [`RecordingTransactor`], only set while a
+// dry-run is in progress.
+environmental::environmental!(dry_run_recording: XcmRecording);
+
+/// Records the state of the executor after every instruction while a dry-run of the pallet is in
+/// progress.
+impl<T: Config> TraceXcm for Pallet<T> {
+	fn is_enabled() -> bool {
+		Self::is_dry_running()
+	}
+
+	fn on_instruction(trace: &InstructionTrace) {
+		Self::record(|recording| recording.trace.push(trace.clone()))
+	}
+}
+
+/// Router that records the messages sent through `Router` while a dry-run of the pallet is in
+/// progress.
+///
+/// Use it to wrap both the `XcmRouter` of the pallet and the `XcmSender` of the executor.
+pub struct RecordingRouter<T, Router>(PhantomData<(T, Router)>);
+impl<T: Config, Router: SendXcm> SendXcm for RecordingRouter<T, Router> {
+	fn send_xcm(dest: impl Into<MultiLocation>, message: Xcm<()>) -> SendResult {
+		let dest = dest.into();
+		if !Pallet::<T>::is_dry_running() {
+			return Router::send_xcm(dest, message)
+		}
+
+		Router::send_xcm(dest.clone(), message.clone())?;
+		Pallet::<T>::record(|recording| {
+			let dest = VersionedMultiLocation::from(dest);
+			match recording.forwarded_xcms.iter_mut().find(|(d, _)| d == &dest) {
+				Some((_, messages)) => messages.push(message.into()),
+				None => recording.forwarded_xcms.push((dest, vec![message.into()])),
+			}
+		});
+		Ok(())
+	}
+}
+
+/// Asset transactor that records the assets deposited and withdrawn through `Transactor` while a
+/// dry-run of the pallet is in progress.
+pub struct RecordingTransactor<T, Transactor>(PhantomData<(T, Transactor)>);
+impl<T: Config, Transactor: TransactAsset> RecordingTransactor<T, Transactor> {
+	fn record_deposit(what: &MultiAsset, who: &MultiLocation) {
+		Pallet::<T>::record(|recording| {
+			recording.asset_changes.push(AssetChange::Deposited {
+				who: who.clone().into(),
+				asset: what.clone().into(),
+			})
+		})
+	}
+
+	fn record_withdrawal(what: &MultiAsset, who: &MultiLocation) {
+		Pallet::<T>::record(|recording| {
+			recording.asset_changes.push(AssetChange::Withdrawn {
+				who: who.clone().into(),
+				asset: what.clone().into(),
+			})
+		})
+	}
+}
+
+impl<T: Config, Transactor: TransactAsset> TransactAsset for RecordingTransactor<T, Transactor> {
+	fn can_check_in(origin: &MultiLocation, what: &MultiAsset) -> XcmResult {
+		Transactor::can_check_in(origin, what)
+	}
+
+	fn check_in(origin: &MultiLocation, what: &MultiAsset) {
+		Transactor::check_in(origin, what)
+	}
+
+	fn check_out(dest: &MultiLocation, what: &MultiAsset) {
+		Transactor::check_out(dest, what)
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> XcmResult {
+		Transactor::deposit_asset(what, who)?;
+		Self::record_deposit(what, who);
+		Ok(())
+	}
+
+	fn withdraw_asset(what: &MultiAsset, who: &MultiLocation) -> Result<Assets, XcmError> {
+		let assets = Transactor::withdraw_asset(what, who)?;
+		Self::record_withdrawal(what, who);
+		Ok(assets)
+	}
+
+	fn transfer_asset(
+		asset: &MultiAsset,
+		from: &MultiLocation,
+		to: &MultiLocation,
+	) -> Result<Assets, XcmError> {
+		let assets = Transactor::transfer_asset(asset, from, to)?;
+		Self::record_withdrawal(asset, from);
+		Self::record_deposit(asset, to);
+		Ok(assets)
+	}
+}
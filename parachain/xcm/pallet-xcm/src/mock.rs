@@ -14,7 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use frame_support::{construct_runtime, parameter_types, traits::Everything, weights::Weight};
+use frame_support::{
+	construct_runtime, match_types, parameter_types, traits::Everything, weights::Weight,
+};
 use selendra_parachain::primitives::Id as ParaId;
 use selendra_runtime_parachains::origin;
 use sp_core::H256;
@@ -215,6 +217,11 @@ impl pallet_balances::Config for Test {
 	type ReserveIdentifier = [u8; 8];
 }
 
+/// A parachain trusted to teleport the native asset.
+pub const TELEPORTER_PARA_ID: u32 = 1000;
+/// A parachain trusted as the reserve of its own assets.
+pub const RESERVE_PARA_ID: u32 = 1001;
+
 parameter_types! {
 	pub const RelayLocation: MultiLocation = Here.into();
 	pub const AnyNetwork: NetworkId = NetworkId::Any;
@@ -228,17 +235,32 @@ pub type SovereignAccountOf =
 pub type LocalAssetTransactor =
 	XcmCurrencyAdapter<Balances, IsConcrete<RelayLocation>, SovereignAccountOf, AccountId, ()>;
 
+match_types! {
+	pub type RemoteLockUnlockers: impl Contains<MultiLocation> = {
+		MultiLocation { parents: 0, interior: X2(Parachain(_), PalletInstance(_)) }
+	};
+}
+
 type LocalOriginConverter = (
 	SovereignSignedViaLocation<SovereignAccountOf, Origin>,
 	ChildParachainAsNative<origin::Origin, Origin>,
 	SignedAccountId32AsNative<AnyNetwork, Origin>,
 	ChildSystemParachainAsSuperuser<ParaId, Origin>,
+	pallet_xcm::XcmPassthrough<Origin, RemoteLockUnlockers>,
 );
 
 parameter_types! {
 	pub const BaseXcmWeight: Weight = 1_000;
 	pub CurrencyPerSecond: (AssetId, u128) = (Concrete(RelayLocation::get()), 1);
 	pub TrustedAssets: (MultiAssetFilter, MultiLocation) = (All.into(), Here.into());
+	pub TrustedTeleporter: (MultiAssetFilter, MultiLocation) = (
+		Wild(AllOf { id: Concrete(Here.into()), fun: WildFungible }),
+		Parachain(TELEPORTER_PARA_ID).into(),
+	);
+	pub TrustedReserve: (MultiAssetFilter, MultiLocation) = (
+		Wild(AllOf { id: Concrete(Parachain(RESERVE_PARA_ID).into()), fun: WildFungible }),
+		Parachain(RESERVE_PARA_ID).into(),
+	);
 	pub const MaxInstructions: u32 = 100;
 }
 
@@ -252,11 +274,11 @@ pub type Barrier = (
 pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
 	type Call = Call;
-	type XcmSender = TestSendXcm;
-	type AssetTransactor = LocalAssetTransactor;
+	type XcmSender = pallet_xcm::RecordingRouter<Test, TestSendXcm>;
+	type AssetTransactor = pallet_xcm::RecordingTransactor<Test, LocalAssetTransactor>;
 	type OriginConverter = LocalOriginConverter;
-	type IsReserve = ();
-	type IsTeleporter = Case<TrustedAssets>;
+	type IsReserve = Case<TrustedReserve>;
+	type IsTeleporter = (Case<TrustedAssets>, Case<TrustedTeleporter>);
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
 	type Weigher = FixedWeightBounds<BaseXcmWeight, Call, MaxInstructions>;
@@ -265,20 +287,29 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = XcmPallet;
 	type AssetClaims = XcmPallet;
 	type SubscriptionService = XcmPallet;
+	type Tracer = XcmPallet;
+	type SafeCallFilter = Everything;
 }
 
 pub type LocalOriginToLocation = SignedToAccountId32<Origin, AccountId, AnyNetwork>;
 
 parameter_types! {
 	pub static AdvertisedXcmVersion: pallet_xcm::XcmVersion = 2;
+	pub const MaxAssetTrapsPerOrigin: u32 = 2;
+	pub const AssetTrapExpiry: u64 = 10;
+	pub const MaxRemoteLockConsumers: u32 = 2;
+	pub const NoteUnlockableWeight: Weight = 1_000_000;
+	pub static DeliveryFeeBase: Balance = 0;
+	pub static DeliveryFeePerByte: Balance = 0;
 }
 
 impl pallet_xcm::Config for Test {
 	type Event = Event;
 	type SendXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
-	type XcmRouter = (TestSendXcmErrX8, TestSendXcm);
+	type XcmRouter = pallet_xcm::RecordingRouter<Test, (TestSendXcmErrX8, TestSendXcm)>;
 	type ExecuteXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
 	type XcmExecuteFilter = Everything;
+	type XcmSendFilter = Everything;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = Everything;
 	type XcmReserveTransferFilter = Everything;
@@ -288,6 +319,28 @@ impl pallet_xcm::Config for Test {
 	type Call = Call;
 	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
 	type AdvertisedXcmVersion = AdvertisedXcmVersion;
+	type MaxAssetTrapsPerOrigin = MaxAssetTrapsPerOrigin;
+	type AssetTrapExpiry = AssetTrapExpiry;
+	type Currency = Balances;
+	type UnlockOrigin = pallet_xcm::EnsureXcm<RemoteLockUnlockers>;
+	type NoteUnlockableOrigin = pallet_xcm::EnsureXcm<Everything>;
+	type MaxRemoteLockConsumers = MaxRemoteLockConsumers;
+	type NoteUnlockableWeight = NoteUnlockableWeight;
+	type DeliveryFeeBase = DeliveryFeeBase;
+	type DeliveryFeePerByte = DeliveryFeePerByte;
+	type OnDeliveryFee = ();
+	type WeightInfo = ();
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_xcm::benchmarking::Config for Test {
+	fn remote_lock_unlocker() -> Result<MultiLocation, frame_benchmarking::BenchmarkError> {
+		Ok(X2(Parachain(2000), PalletInstance(31)).into())
+	}
+
+	fn remote_lock_owner() -> Result<MultiLocation, frame_benchmarking::BenchmarkError> {
+		Ok(MultiLocation::new(1, X1(Junction::AccountId32 { network: Any, id: [1u8; 32] })))
+	}
 }
 
 impl origin::Config for Test {}
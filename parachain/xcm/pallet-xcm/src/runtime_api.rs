@@ -0,0 +1,144 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Runtime APIs for dry-running extrinsics and XCM programs, for estimating XCM fees and for
+//! discovering trapped assets.
+//!
+//! Dry-runs execute against the state of the block they are called at, record their effects and
+//! never commit them.
+
+use crate::{AssetChange, TrappedAssetsRecord};
+use codec::{Codec, Decode, Encode};
+use frame_support::dispatch::DispatchResultWithPostInfo;
+use sp_core::H256;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+use xcm::{
+	latest::{Outcome, Weight},
+	VersionedMultiAssets, VersionedMultiLocation, VersionedXcm,
+};
+use xcm_executor::traits::XcmTrace;
+
+/// The effects of dry-running an extrinsic.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug)]
+pub struct CallDryRunEffects<Event> {
+	/// The result of dispatching the call.
+	pub execution_result: DispatchResultWithPostInfo,
+	/// The events emitted while dispatching the call.
+	pub emitted_events: Vec<Event>,
+	/// The messages that would have been sent, grouped by destination.
+	pub forwarded_xcms: Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>,
+	/// The assets that were deposited or withdrawn through XCM, in order.
+	pub asset_changes: Vec<AssetChange>,
+	/// The XCM instructions that were executed, in order.
+	pub trace: XcmTrace,
+}
+
+/// The effects of dry-running an XCM program.
+#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug)]
+pub struct XcmDryRunEffects<Event> {
+	/// The outcome of executing the program.
+	pub execution_result: Outcome,
+	/// The events emitted while executing the program.
+	pub emitted_events: Vec<Event>,
+	/// The messages that would have been sent, grouped by destination.
+	pub forwarded_xcms: Vec<(VersionedMultiLocation, Vec<VersionedXcm<()>>)>,
+	/// The assets that were deposited or withdrawn, in order.
+	pub asset_changes: Vec<AssetChange>,
+	/// The instructions that were executed, in order.
+	pub trace: XcmTrace,
+}
+
+/// Why a dry-run could not be performed.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub enum XcmDryRunApiError {
+	/// A versioned argument could not be converted into the latest version.
+	VersionedConversionFailed,
+	/// The weight of the XCM program could not be determined.
+	UnweighableMessage,
+}
+
+/// Why an XCM fee or weight could not be estimated.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
+pub enum XcmPaymentApiError {
+	/// The runtime does not support fee estimation.
+	Unimplemented,
+	/// A versioned argument could not be converted into the latest version.
+	VersionedConversionFailed,
+	/// The weight of the XCM program could not be determined.
+	WeightNotComputable,
+	/// The given asset is not accepted as payment for execution.
+	AssetNotFound,
+	/// The requested XCM version is not supported.
+	UnhandledXcmVersion,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for dry-running extrinsics and XCM programs without committing their effects.
+	pub trait XcmDryRunApi<Call: Codec, Event: Codec, OriginCaller: Codec> {
+		/// Dry-run `call` dispatched from `origin`.
+		fn dry_run_call(
+			origin: OriginCaller,
+			call: Call,
+		) -> Result<CallDryRunEffects<Event>, XcmDryRunApiError>;
+
+		/// Dry-run `xcm` as if it was received from `origin_location`.
+		fn dry_run_xcm(
+			origin_location: VersionedMultiLocation,
+			xcm: VersionedXcm<Call>,
+		) -> Result<XcmDryRunEffects<Event>, XcmDryRunApiError>;
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for estimating the weight and fees of XCM programs.
+	///
+	/// The weight of a program is computed by the configured `Weigher` and converted into a fee by
+	/// the configured `Trader`, exactly as the executor does when it pays for execution.
+	pub trait XcmPaymentApi {
+		/// The assets that can be used to pay for execution, expressed in `xcm_version`.
+		fn query_acceptable_payment_assets(
+			xcm_version: u32,
+		) -> Result<Vec<VersionedMultiLocation>, XcmPaymentApiError>;
+
+		/// The weight of executing `message` on this chain.
+		fn query_xcm_weight(message: VersionedXcm<()>) -> Result<Weight, XcmPaymentApiError>;
+
+		/// The fee, in units of the fungible `asset`, of buying `weight` for execution.
+		fn query_weight_to_asset_fee(
+			weight: Weight,
+			asset: VersionedMultiLocation,
+		) -> Result<u128, XcmPaymentApiError>;
+
+		/// The fees charged to a signed account for sending `message` to `destination`.
+		fn query_delivery_fees(
+			destination: VersionedMultiLocation,
+			message: VersionedXcm<()>,
+		) -> Result<VersionedMultiAssets, XcmPaymentApiError>;
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API for discovering the assets trapped for an origin.
+	pub trait TrappedAssetsApi<BlockNumber: Codec> {
+		/// The assets trapped for `origin`, with the hash of each trap.
+		///
+		/// Each record holds the assets exactly as they must be passed to `claim_assets`.
+		fn trapped_assets(
+			origin: VersionedMultiLocation,
+		) -> Vec<(H256, TrappedAssetsRecord<BlockNumber>)>;
+	}
+}
@@ -15,18 +15,27 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	mock::*, AssetTraps, CurrentMigration, Error, LatestVersionedMultiLocation, Queries,
-	QueryStatus, VersionDiscoveryQueue, VersionNotifiers, VersionNotifyTargets,
+	mock::*,
+	runtime_api::{XcmDryRunEffects, XcmPaymentApiError},
+	AssetChange, AssetTrapBlocks, AssetTrapExpiries, AssetTrapExpiryCursor, AssetTraps,
+	CurrentMigration, Error, LatestVersionedMultiLocation, Queries, QueryStatus,
+	RemoteLockedFungibles, TrappedAssetsRecord, VersionDiscoveryQueue, VersionNotifiers,
+	VersionNotifyTargets,
 };
+use codec::Encode;
 use frame_support::{
 	assert_noop, assert_ok,
 	traits::{Currency, Hooks},
+	weights::{constants::WEIGHT_PER_SECOND, Weight},
 };
 use selendra_parachain::primitives::Id as ParaId;
-use sp_runtime::traits::{AccountIdConversion, BlakeTwo256, Hash};
+use sp_runtime::traits::{AccountIdConversion, BadOrigin, BlakeTwo256, Hash};
 use xcm::prelude::*;
-use xcm_builder::AllowKnownQueryResponses;
-use xcm_executor::{traits::ShouldExecute, XcmExecutor};
+use xcm_builder::{AllowKnownQueryResponses, FixedRateOfFungible};
+use xcm_executor::{
+	traits::{ShouldExecute, TraceXcm, TransferType},
+	XcmExecutor,
+};
 
 const ALICE: AccountId = AccountId::new([0u8; 32]);
 const BOB: AccountId = AccountId::new([1u8; 32]);
@@ -185,6 +194,68 @@ fn send_works() {
 	});
 }
 
+/// Test that a sent `XCM` message cannot be attributed to another origin
+///
+/// Asserts that the message is prefixed with the sender's location even if it tries to descend
+/// into another account, so it can only ever narrow the sender's origin.
+#[test]
+fn send_cannot_spoof_origin() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let sender: MultiLocation =
+			AccountId32 { network: AnyNetwork::get(), id: ALICE.into() }.into();
+		let other: MultiLocation =
+			AccountId32 { network: AnyNetwork::get(), id: BOB.into() }.into();
+		let message = Xcm(vec![
+			DescendOrigin(other.clone().try_into().unwrap()),
+			buy_execution((Parent, SEND_AMOUNT)),
+			DepositAsset { assets: All.into(), max_assets: 1, beneficiary: other },
+		]);
+		assert_ok!(XcmPallet::send(
+			Origin::signed(ALICE),
+			Box::new(RelayLocation::get().into()),
+			Box::new(VersionedXcm::from(message.clone())),
+		));
+		let (_, sent) = sent_xcm().pop().unwrap();
+		assert_eq!(sent.0[0], DescendOrigin(sender.try_into().unwrap()));
+		assert_eq!(sent.0[1..], message.0[..]);
+	});
+}
+
+/// Test the delivery fee of `send`
+///
+/// Asserts that a signed sender is charged the fee reported by `query_delivery_fees` up front,
+/// and cannot send a message it cannot pay for.
+#[test]
+fn send_charges_delivery_fee() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		DeliveryFeeBase::set(3);
+		DeliveryFeePerByte::set(1);
+		let message = Xcm(vec![ClearOrigin]);
+		let fee = 3 + message.encoded_size() as u128;
+		let fees: MultiAssets = (Here, fee).into();
+		assert_eq!(
+			XcmPallet::query_delivery_fees(Parent.into(), message.clone().into()),
+			Ok(fees.into())
+		);
+		assert_ok!(XcmPallet::send(
+			Origin::signed(ALICE),
+			Box::new(RelayLocation::get().into()),
+			Box::new(VersionedXcm::from(message.clone())),
+		));
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - fee);
+		assert_noop!(
+			XcmPallet::send(
+				Origin::signed(BOB),
+				Box::new(RelayLocation::get().into()),
+				Box::new(VersionedXcm::from(message)),
+			),
+			Error::<Test>::FeesNotMet
+		);
+	});
+}
+
 /// Test that sending an `XCM` message fails when the `XcmRouter` blocks the
 /// matching message format
 ///
@@ -520,6 +591,519 @@ fn execute_withdraw_to_deposit_works() {
 	});
 }
 
+/// Test that executing XCM which sends messages charges their delivery fees
+///
+/// Asserts that a signed account is charged up front for a message sent by an instruction of the
+/// executed XCM, including one nested in an appendix, and cannot execute it if it cannot pay.
+#[test]
+fn execute_charges_delivery_fee() {
+	let balances = vec![
+		(ALICE, INITIAL_BALANCE),
+		(ParaId::from(PARA_ID).into_account_truncating(), INITIAL_BALANCE),
+	];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		DeliveryFeeBase::set(3);
+		DeliveryFeePerByte::set(1);
+		let dest: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		let transfer = TransferReserveAsset {
+			assets: (Here, SEND_AMOUNT).into(),
+			dest: Parachain(PARA_ID).into(),
+			xcm: Xcm(vec![DepositAsset { assets: All.into(), max_assets: 1, beneficiary: dest }]),
+		};
+		let fee = 3 + Xcm::<()>(vec![transfer.clone()]).encoded_size() as u128;
+		let message = Xcm(vec![SetAppendix(Xcm(vec![transfer.clone().into()]))]);
+		assert_ok!(XcmPallet::execute(
+			Origin::signed(ALICE),
+			Box::new(VersionedXcm::from(message)),
+			2 * BaseXcmWeight::get(),
+		));
+		assert!(matches!(
+			last_event(),
+			Event::XcmPallet(crate::Event::Attempted(Outcome::Complete(_)))
+		));
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - SEND_AMOUNT - fee);
+		assert_noop!(
+			XcmPallet::execute(
+				Origin::signed(BOB),
+				Box::new(VersionedXcm::from(Xcm(vec![transfer.into()]))),
+				BaseXcmWeight::get(),
+			),
+			Error::<Test>::FeesNotMet
+		);
+	});
+}
+
+/// Test `transfer_assets` with this chain as the reserve
+///
+/// Asserts that the assets are reserve-transferred since the destination is not a teleporter, and
+/// that the same message as `limited_reserve_transfer_assets` is sent.
+#[test]
+fn transfer_assets_picks_local_reserve() {
+	let balances = vec![
+		(ALICE, INITIAL_BALANCE),
+		(ParaId::from(PARA_ID).into_account_truncating(), INITIAL_BALANCE),
+	];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let dest: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		assert_ok!(XcmPallet::transfer_assets(
+			Origin::signed(ALICE),
+			Box::new(Parachain(PARA_ID).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, SEND_AMOUNT).into()),
+			0,
+			WeightLimit::Limited(5000),
+		));
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - SEND_AMOUNT);
+		let para_acc: AccountId = ParaId::from(PARA_ID).into_account_truncating();
+		assert_eq!(Balances::free_balance(para_acc), INITIAL_BALANCE + SEND_AMOUNT);
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				Parachain(PARA_ID).into(),
+				Xcm(vec![
+					ReserveAssetDeposited((Parent, SEND_AMOUNT).into()),
+					ClearOrigin,
+					buy_limited_execution((Parent, SEND_AMOUNT), 5000),
+					DepositAsset { assets: All.into(), max_assets: 1, beneficiary: dest },
+				]),
+			)]
+		);
+		assert_eq!(
+			last_event(),
+			Event::XcmPallet(crate::Event::Attempted(Outcome::Complete(BaseXcmWeight::get())))
+		);
+	});
+}
+
+/// Test `transfer_assets` when no route exists
+///
+/// Asserts that assets of an untrusted reserve are rejected before anything is executed.
+#[test]
+fn transfer_assets_fails_without_route() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let dest: MultiLocation = Parachain(PARA_ID).into();
+		let beneficiary: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		let foreign: MultiLocation = Parachain(PARA_ID + 1).into();
+		assert_noop!(
+			XcmPallet::transfer_assets(
+				Origin::signed(ALICE),
+				Box::new(dest.clone().into()),
+				Box::new(beneficiary.clone().into()),
+				Box::new((foreign, SEND_AMOUNT).into()),
+				0,
+				WeightLimit::Unlimited,
+			),
+			Error::<Test>::InvalidAssetUnknownReserve
+		);
+	});
+}
+
+/// Test the message of `transfer_assets` for assets transferred in different ways
+///
+/// Asserts that the assets are grouped by the way they are transferred, with one program per
+/// group, and that the group without the fee asset pays with its first asset.
+#[test]
+fn transfer_assets_groups_assets_by_transfer_type() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let dest: MultiLocation = Parachain(PARA_ID).into();
+		let beneficiary: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		let native: MultiAsset = (Here, SEND_AMOUNT).into();
+		let foreign: MultiAsset = (dest.clone(), SEND_AMOUNT).into();
+		let assets: MultiAssets = vec![native.clone(), foreign.clone()].into();
+
+		let groups = XcmPallet::transfer_groups(&assets, &dest, 1).unwrap();
+		assert_eq!(
+			groups,
+			vec![
+				(TransferType::LocalReserve, native.clone().into(), 0),
+				(TransferType::DestinationReserve, foreign.clone().into(), 0),
+			]
+		);
+		let message = XcmPallet::transfer_groups_message::<()>(
+			groups,
+			dest.clone(),
+			beneficiary.clone(),
+			Unlimited,
+		);
+		let xcm = |fees: MultiAsset| {
+			Xcm(vec![
+				buy_execution(fees),
+				DepositAsset { assets: Wild(All), max_assets: 1, beneficiary: beneficiary.clone() },
+			])
+		};
+		assert_eq!(
+			message,
+			Ok(Xcm(vec![
+				TransferReserveAsset {
+					assets: native.into(),
+					dest: dest.clone(),
+					xcm: xcm((Parent, SEND_AMOUNT).into()),
+				},
+				WithdrawAsset(foreign.into()),
+				InitiateReserveWithdraw {
+					assets: Wild(All),
+					reserve: dest,
+					xcm: xcm((Here, SEND_AMOUNT).into()),
+				},
+			]))
+		);
+	});
+}
+
+/// Test `transfer_assets` to a trusted teleporter
+///
+/// Asserts that the native asset is teleported, as the destination is a trusted teleporter of it.
+#[test]
+fn transfer_assets_picks_teleport() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let dest: MultiLocation = Parachain(TELEPORTER_PARA_ID).into();
+		let beneficiary: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		assert_ok!(XcmPallet::transfer_assets(
+			Origin::signed(ALICE),
+			Box::new(dest.clone().into()),
+			Box::new(beneficiary.clone().into()),
+			Box::new((Here, SEND_AMOUNT).into()),
+			0,
+			WeightLimit::Limited(5000),
+		));
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - SEND_AMOUNT);
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				dest,
+				Xcm(vec![
+					ReceiveTeleportedAsset((Parent, SEND_AMOUNT).into()),
+					ClearOrigin,
+					buy_limited_execution((Parent, SEND_AMOUNT), 5000),
+					DepositAsset { assets: All.into(), max_assets: 1, beneficiary },
+				]),
+			)]
+		);
+		assert_eq!(
+			last_event(),
+			Event::XcmPallet(crate::Event::Attempted(Outcome::Complete(2 * BaseXcmWeight::get())))
+		);
+	});
+}
+
+/// Test the message of `transfer_assets` when the destination is the reserve
+///
+/// Asserts that the assets are withdrawn on the destination, which gets all of the fees.
+#[test]
+fn transfer_assets_through_destination_reserve() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let dest: MultiLocation = Parachain(PARA_ID).into();
+		let beneficiary: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		let assets: MultiAssets = (dest.clone(), SEND_AMOUNT).into();
+
+		let transfer_type = XcmPallet::transfer_groups(&assets, &dest, 0).unwrap().remove(0).0;
+		assert_eq!(transfer_type, TransferType::DestinationReserve);
+		let message = XcmPallet::transfer_message::<()>(
+			transfer_type,
+			dest.clone(),
+			beneficiary.clone(),
+			assets.clone(),
+			0,
+			Unlimited,
+		);
+		assert_eq!(
+			message,
+			Ok(Xcm(vec![
+				WithdrawAsset(assets),
+				InitiateReserveWithdraw {
+					assets: Wild(All),
+					reserve: dest,
+					xcm: Xcm(vec![
+						buy_execution((Here, SEND_AMOUNT)),
+						DepositAsset { assets: Wild(All), max_assets: 1, beneficiary },
+					]),
+				},
+			]))
+		);
+	});
+}
+
+/// Test the message of `transfer_assets` through a trusted third reserve
+///
+/// Asserts that the fees are split between the reserve and the destination, and that fees which
+/// cannot be split are rejected.
+#[test]
+fn transfer_assets_through_remote_reserve() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let dest: MultiLocation = Parachain(PARA_ID).into();
+		let reserve: MultiLocation = Parachain(RESERVE_PARA_ID).into();
+		let beneficiary: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		let assets: MultiAssets = (reserve.clone(), SEND_AMOUNT).into();
+
+		let transfer_type = XcmPallet::transfer_groups(&assets, &dest, 0).unwrap().remove(0).0;
+		assert_eq!(transfer_type, TransferType::RemoteReserve(reserve.clone()));
+		let message = XcmPallet::transfer_message::<()>(
+			transfer_type.clone(),
+			dest,
+			beneficiary.clone(),
+			assets.clone(),
+			0,
+			Limited(5000),
+		);
+		let half = SEND_AMOUNT / 2;
+		assert_eq!(
+			message,
+			Ok(Xcm(vec![
+				WithdrawAsset(assets),
+				InitiateReserveWithdraw {
+					assets: Wild(All),
+					reserve: reserve.clone(),
+					xcm: Xcm(vec![
+						buy_limited_execution((Here, half), 5000),
+						DepositReserveAsset {
+							assets: Wild(All),
+							max_assets: 1,
+							dest: (Parent, Parachain(PARA_ID)).into(),
+							xcm: Xcm(vec![
+								buy_limited_execution(
+									((Parent, Parachain(RESERVE_PARA_ID)), half),
+									5000
+								),
+								DepositAsset { assets: Wild(All), max_assets: 1, beneficiary },
+							]),
+						},
+					]),
+				},
+			]))
+		);
+
+		let nft: MultiAssets = (reserve, NonFungible(Index(0))).into();
+		assert_eq!(
+			XcmPallet::transfer_message::<()>(
+				transfer_type,
+				Parachain(PARA_ID).into(),
+				Here.into(),
+				nft,
+				0,
+				Unlimited,
+			),
+			Err(Error::<Test>::UnsplittableFees)
+		);
+	});
+}
+
+/// Test `lock_asset` and `unlock_asset`
+///
+/// Asserts that the lock is notified to the unlocker with a `Transact` from the owner, whose
+/// delivery the owner pays for here and whose execution it pays for on the unlocker's chain, that
+/// the locked amount cannot be spent and that the lock can only be released by the unlocker.
+#[test]
+fn remote_lock_can_only_be_released_by_unlocker() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		DeliveryFeeBase::set(3);
+		let unlocker: MultiLocation = X2(Parachain(PARA_ID), PalletInstance(31)).into();
+		let fees: MultiAsset = (Here, SEND_AMOUNT).into();
+		assert_ok!(XcmPallet::lock_asset(
+			Origin::signed(ALICE),
+			*b"lock0001",
+			SEND_AMOUNT,
+			Box::new(unlocker.clone().into()),
+			Box::new(fees.clone().into()),
+		));
+		let owner = AccountId32 { network: AnyNetwork::get(), id: ALICE.into() };
+		let notification = crate::Call::<Test>::note_unlockable {
+			id: *b"lock0001",
+			asset: Box::new(MultiAsset::from((Parent, SEND_AMOUNT)).into()),
+		};
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				Parachain(PARA_ID).into(),
+				Xcm(vec![
+					DescendOrigin(owner.clone().into()),
+					WithdrawAsset(fees.clone().into()),
+					buy_execution(fees),
+					Transact {
+						origin_type: OriginKind::Xcm,
+						require_weight_at_most: NoteUnlockableWeight::get(),
+						call: (31u8, notification).encode().into(),
+					},
+					RefundSurplus,
+					DepositAsset {
+						assets: Wild(All),
+						max_assets: 1,
+						beneficiary: MultiLocation::new(1, X1(owner)),
+					},
+				]),
+			)]
+		);
+		assert_eq!(
+			last_event(),
+			Event::XcmPallet(crate::Event::AssetLocked(
+				ALICE,
+				*b"lock0001",
+				SEND_AMOUNT,
+				unlocker.clone()
+			))
+		);
+		assert_eq!(Balances::usable_balance(ALICE), INITIAL_BALANCE - 3 - SEND_AMOUNT);
+
+		// Neither the owner, the unlocker's chain nor another pallet may release the lock.
+		assert_noop!(
+			XcmPallet::unlock_asset(Origin::signed(ALICE), *b"lock0001", ALICE),
+			BadOrigin
+		);
+		let chain = Origin::from(crate::Origin::Xcm(Parachain(PARA_ID).into()));
+		assert_noop!(XcmPallet::unlock_asset(chain, *b"lock0001", ALICE), BadOrigin);
+		let other = X2(Parachain(PARA_ID), PalletInstance(32)).into();
+		let other = Origin::from(crate::Origin::Xcm(other));
+		assert_noop!(
+			XcmPallet::unlock_asset(other, *b"lock0001", ALICE),
+			Error::<Test>::LockNotFound
+		);
+
+		let origin = Origin::from(crate::Origin::Xcm(unlocker.clone()));
+		assert_ok!(XcmPallet::unlock_asset(origin, *b"lock0001", ALICE));
+		assert_eq!(
+			last_event(),
+			Event::XcmPallet(crate::Event::AssetUnlocked(
+				ALICE,
+				*b"lock0001",
+				SEND_AMOUNT,
+				unlocker
+			))
+		);
+		assert_eq!(Balances::usable_balance(ALICE), INITIAL_BALANCE - 3);
+		assert!(RemoteLockedFungibles::<Test>::get(ALICE).is_empty());
+	});
+}
+
+/// Test releasing a remote lock through XCM
+///
+/// Asserts that the unlocker releases a lock by sending a `Transact` of `unlock_asset` with the
+/// `Xcm` origin kind, which is only passed through for the pallets of parachains.
+#[test]
+fn remote_lock_is_released_by_unlocker_message() {
+	let balances = vec![
+		(ALICE, INITIAL_BALANCE),
+		(ParaId::from(PARA_ID).into_account_truncating(), INITIAL_BALANCE),
+	];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let unlocker: MultiLocation = X2(Parachain(PARA_ID), PalletInstance(31)).into();
+		assert_ok!(XcmPallet::lock_asset(
+			Origin::signed(ALICE),
+			*b"lock0001",
+			SEND_AMOUNT,
+			Box::new(unlocker.into()),
+			Box::new(MultiAsset::from((Here, SEND_AMOUNT)).into()),
+		));
+		let unlock = |descend: Junction| {
+			let call =
+				Call::XcmPallet(crate::Call::unlock_asset { id: *b"lock0001", owner: ALICE });
+			let message = Xcm(vec![
+				WithdrawAsset((Here, SEND_AMOUNT).into()),
+				buy_execution((Here, SEND_AMOUNT)),
+				DescendOrigin(descend.into()),
+				Transact {
+					origin_type: OriginKind::Xcm,
+					require_weight_at_most: 1_000_000_000,
+					call: call.encode().into(),
+				},
+			]);
+			XcmExecutor::<XcmConfig>::execute_xcm(Parachain(PARA_ID), message, 2_000_000_000)
+		};
+
+		// An account of the parachain cannot pass for the unlocker.
+		let account = AccountId32 { network: AnyNetwork::get(), id: ALICE.into() };
+		assert!(matches!(unlock(account), Outcome::Incomplete(_, XcmError::BadOrigin)));
+		assert_eq!(RemoteLockedFungibles::<Test>::get(ALICE).len(), 1);
+
+		assert!(matches!(unlock(PalletInstance(31)), Outcome::Complete(_)));
+		assert!(RemoteLockedFungibles::<Test>::get(ALICE).is_empty());
+		assert_eq!(Balances::usable_balance(ALICE), INITIAL_BALANCE);
+	});
+}
+
+/// Test `lock_asset` bounds
+///
+/// Asserts that an account cannot lock more than its free balance, nor hold more than
+/// `MaxRemoteLockConsumers` locks, nor lower a lock, that overlapping locks are covered by the
+/// greatest and that the unlocker must be a pallet of another chain.
+#[test]
+fn remote_locks_are_bounded() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let lock = |id: [u8; 8], amount, unlocker: MultiLocation| {
+			let fees = MultiAsset::from((Here, SEND_AMOUNT));
+			XcmPallet::lock_asset(
+				Origin::signed(ALICE),
+				id,
+				amount,
+				Box::new(unlocker.into()),
+				Box::new(fees.into()),
+			)
+		};
+		let unlocker: MultiLocation = X2(Parachain(PARA_ID), PalletInstance(31)).into();
+		for bad_unlocker in [Parachain(PARA_ID).into(), PalletInstance(31).into()] {
+			assert_noop!(lock(*b"lock0001", SEND_AMOUNT, bad_unlocker), Error::<Test>::BadUnlocker);
+		}
+		assert_noop!(
+			lock(*b"lock0001", INITIAL_BALANCE + 1, unlocker.clone()),
+			Error::<Test>::LowBalance
+		);
+		assert_ok!(lock(*b"lock0001", SEND_AMOUNT, unlocker.clone()));
+		assert_ok!(lock(*b"lock0002", 2 * SEND_AMOUNT, unlocker.clone()));
+		assert_eq!(Balances::usable_balance(ALICE), INITIAL_BALANCE - 2 * SEND_AMOUNT);
+		assert_noop!(
+			lock(*b"lock0003", SEND_AMOUNT, unlocker.clone()),
+			Error::<Test>::TooManyLocks
+		);
+		// The owner may raise a lock, but not lower it.
+		assert_noop!(
+			lock(*b"lock0002", SEND_AMOUNT, unlocker.clone()),
+			Error::<Test>::CannotLowerLock
+		);
+		assert_ok!(lock(*b"lock0002", 3 * SEND_AMOUNT, unlocker));
+		assert_eq!(Balances::usable_balance(ALICE), INITIAL_BALANCE - 3 * SEND_AMOUNT);
+		assert_eq!(RemoteLockedFungibles::<Test>::get(ALICE).len(), 2);
+	});
+}
+
+/// Test `note_unlockable`
+///
+/// Asserts that the notification of a remote lock is only accepted from the `Xcm` origin of the
+/// owner.
+#[test]
+fn unlockable_assets_are_noted() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let owner =
+			MultiLocation::new(1, X1(AccountId32 { network: AnyNetwork::get(), id: ALICE.into() }));
+		let asset: MultiAsset = (Parent, SEND_AMOUNT).into();
+		assert_noop!(
+			XcmPallet::note_unlockable(
+				Origin::signed(ALICE),
+				*b"lock0001",
+				Box::new(asset.clone().into())
+			),
+			BadOrigin
+		);
+		assert_ok!(XcmPallet::note_unlockable(
+			Origin::from(crate::Origin::Xcm(owner.clone())),
+			*b"lock0001",
+			Box::new(asset.clone().into()),
+		));
+		assert_eq!(
+			last_event(),
+			Event::XcmPallet(crate::Event::AssetUnlockable(owner, *b"lock0001", asset))
+		);
+	});
+}
+
 /// Test drop/claim assets.
 #[test]
 fn trapped_assets_can_be_claimed() {
@@ -598,6 +1182,155 @@ fn trapped_assets_can_be_claimed() {
 	});
 }
 
+/// Trap `amount` of the native asset for ALICE.
+fn trap_assets_of_alice(amount: u128) {
+	assert_ok!(XcmPallet::execute(
+		Origin::signed(ALICE),
+		Box::new(VersionedXcm::from(Xcm(vec![
+			WithdrawAsset((Here, amount).into()),
+			buy_execution((Here, amount)),
+			SetErrorHandler(Xcm(vec![ClearError])),
+			Trap(0),
+		]))),
+		5 * BaseXcmWeight::get()
+	));
+}
+
+/// Test listing trapped assets and claiming them with `claim_assets`.
+///
+/// Asserts that the trap is listed for its origin until it is claimed, and that only trapped
+/// assets can be claimed.
+#[test]
+fn trapped_assets_can_be_listed_and_claimed() {
+	let balances = vec![(ALICE, INITIAL_BALANCE), (BOB, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		trap_assets_of_alice(SEND_AMOUNT);
+
+		let source: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		let dest: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: BOB.into() }.into();
+		let vma = VersionedMultiAssets::from(MultiAssets::from((Here, SEND_AMOUNT)));
+		let hash = BlakeTwo256::hash_of(&(source.clone(), vma.clone()));
+		let expires_at = 1 + AssetTrapExpiry::get();
+		assert_eq!(
+			XcmPallet::trapped_assets(source.clone().into()),
+			vec![(hash, TrappedAssetsRecord { assets: vma.clone(), count: 1, expires_at })]
+		);
+		assert!(XcmPallet::trapped_assets(dest.clone().into()).is_empty());
+
+		assert_ok!(XcmPallet::claim_assets(
+			Origin::signed(ALICE),
+			Box::new(vma.clone()),
+			Box::new(dest.clone().into()),
+		));
+		let claimed =
+			Event::XcmPallet(crate::Event::AssetsClaimed(hash, source.clone(), vma.clone()));
+		assert!(System::events().iter().any(|record| record.event == claimed));
+		assert_eq!(
+			last_event(),
+			Event::XcmPallet(crate::Event::Attempted(Outcome::Complete(2 * BaseXcmWeight::get())))
+		);
+		assert_eq!(Balances::total_balance(&BOB), INITIAL_BALANCE + SEND_AMOUNT);
+		assert!(XcmPallet::trapped_assets(source.into()).is_empty());
+		assert_eq!(AssetTraps::<Test>::iter().count(), 0);
+
+		assert_noop!(
+			XcmPallet::claim_assets(Origin::signed(ALICE), Box::new(vma), Box::new(dest.into())),
+			Error::<Test>::NotTrapped
+		);
+	});
+}
+
+/// Test the expiry and the bound of asset traps.
+///
+/// Asserts that traps beyond the bound are not listed but expire like the listed ones, and that
+/// each trapping of the same assets expires on its own.
+#[test]
+fn trapped_assets_expire_and_are_bounded() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		for amount in 1..=3 {
+			trap_assets_of_alice(amount);
+		}
+		let source: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		assert_eq!(AssetTraps::<Test>::iter().count(), 3);
+		assert_eq!(
+			XcmPallet::trapped_assets(source.clone().into()).len() as u32,
+			MaxAssetTrapsPerOrigin::get()
+		);
+
+		// Trapping the first assets again keeps them claimable after the first trapping expired.
+		System::set_block_number(2);
+		trap_assets_of_alice(1);
+		let hash_of = |amount: u128| {
+			let vma = VersionedMultiAssets::from(MultiAssets::from((Here, amount)));
+			BlakeTwo256::hash_of(&(source.clone(), vma))
+		};
+
+		let expires_at = 1 + AssetTrapExpiry::get();
+		XcmPallet::on_idle(expires_at - 1, Weight::max_value());
+		assert_eq!(AssetTrapExpiryCursor::<Test>::get(), 0);
+
+		XcmPallet::on_idle(expires_at, Weight::max_value());
+		assert_eq!(AssetTrapExpiryCursor::<Test>::get(), 3);
+		assert_eq!(AssetTrapExpiries::<Test>::iter().count(), 1);
+		assert_eq!(AssetTraps::<Test>::iter().collect::<Vec<_>>(), vec![(hash_of(1), 1)]);
+		assert_eq!(XcmPallet::trapped_assets(source.clone().into())[0].1.count, 1);
+		for amount in 1..=3 {
+			let expired = Event::XcmPallet(crate::Event::AssetTrapExpired(
+				hash_of(amount),
+				source.clone().into(),
+			));
+			assert!(System::events().iter().any(|record| record.event == expired));
+		}
+
+		XcmPallet::on_idle(expires_at + 1, Weight::max_value());
+		assert_eq!(AssetTrapExpiryCursor::<Test>::get(), 4);
+		assert!(XcmPallet::trapped_assets(source.into()).is_empty());
+		assert_eq!(AssetTraps::<Test>::iter().count(), 0);
+		assert_eq!(AssetTrapBlocks::<Test>::iter().count(), 0);
+		assert_eq!(AssetTrapExpiries::<Test>::iter().count(), 0);
+	});
+}
+
+/// Test that claiming assets takes their oldest trapping.
+///
+/// Asserts that the expiry of a claimed trapping leaves a later trapping of the same assets
+/// claimable until it expires itself.
+#[test]
+fn claimed_trapping_does_not_expire_later_trapping() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let source: MultiLocation =
+			Junction::AccountId32 { network: NetworkId::Any, id: ALICE.into() }.into();
+		let vma = VersionedMultiAssets::from(MultiAssets::from((Here, SEND_AMOUNT)));
+		let hash = BlakeTwo256::hash_of(&(source.clone(), vma.clone()));
+
+		trap_assets_of_alice(SEND_AMOUNT);
+		System::set_block_number(2);
+		trap_assets_of_alice(SEND_AMOUNT);
+		assert_ok!(XcmPallet::claim_assets(
+			Origin::signed(ALICE),
+			Box::new(vma),
+			Box::new(source.clone().into()),
+		));
+		assert_eq!(AssetTrapBlocks::<Test>::iter().collect::<Vec<_>>(), vec![(hash, 2, 1)]);
+
+		XcmPallet::on_idle(1 + AssetTrapExpiry::get(), Weight::max_value());
+		assert_eq!(AssetTrapExpiryCursor::<Test>::get(), 1);
+		assert_eq!(AssetTraps::<Test>::get(hash), 1);
+		assert_eq!(XcmPallet::trapped_assets(source.clone().into())[0].1.count, 1);
+
+		XcmPallet::on_idle(2 + AssetTrapExpiry::get(), Weight::max_value());
+		assert_eq!(AssetTraps::<Test>::get(hash), 0);
+		assert!(XcmPallet::trapped_assets(source.clone().into()).is_empty());
+		let expired = Event::XcmPallet(crate::Event::AssetTrapExpired(hash, source.into()));
+		assert_eq!(System::events().iter().filter(|record| record.event == expired).count(), 1);
+	});
+}
+
 #[test]
 fn fake_latest_versioned_multilocation_works() {
 	use codec::Encode;
@@ -1017,3 +1750,194 @@ fn subscription_side_upgrades_work_with_multistage_notify() {
 		);
 	});
 }
+
+/// Test `dry_run_call`
+///
+/// Asserts that the effects of the call are recorded and that recording stops afterwards.
+#[test]
+fn dry_run_call_records_effects() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let alice: MultiLocation = AccountId32 { network: Any, id: ALICE.into() }.into();
+		let dest: MultiLocation = AccountId32 { network: Any, id: BOB.into() }.into();
+		let call = Call::XcmPallet(crate::Call::teleport_assets {
+			dest: Box::new(RelayLocation::get().into()),
+			beneficiary: Box::new(dest.clone().into()),
+			assets: Box::new((Here, SEND_AMOUNT).into()),
+			fee_asset_item: 0,
+		});
+
+		let effects = XcmPallet::dry_run_call(Origin::signed(ALICE), call);
+
+		assert_ok!(effects.execution_result);
+		assert_eq!(
+			effects.forwarded_xcms,
+			vec![(
+				RelayLocation::get().into(),
+				vec![VersionedXcm::from(Xcm(vec![
+					ReceiveTeleportedAsset((Here, SEND_AMOUNT).into()),
+					ClearOrigin,
+					buy_limited_execution((Here, SEND_AMOUNT), 4000),
+					DepositAsset { assets: All.into(), max_assets: 1, beneficiary: dest },
+				]))],
+			)]
+		);
+		assert_eq!(
+			effects.asset_changes,
+			vec![AssetChange::Withdrawn {
+				who: alice.into(),
+				asset: MultiAsset::from((Here, SEND_AMOUNT)).into(),
+			}]
+		);
+		assert_eq!(
+			effects.emitted_events.last(),
+			Some(&Event::XcmPallet(crate::Event::Attempted(Outcome::Complete(
+				2 * BaseXcmWeight::get()
+			))))
+		);
+		assert!(!XcmPallet::is_dry_running());
+	});
+}
+
+/// Test `dry_run_xcm`
+///
+/// Asserts that the outcome and the asset changes of the message are returned.
+#[test]
+fn dry_run_xcm_records_effects() {
+	let balances = vec![(ParaId::from(PARA_ID).into_account_truncating(), INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let dest: MultiLocation = AccountId32 { network: Any, id: BOB.into() }.into();
+		let message = Xcm::<Call>(vec![
+			WithdrawAsset((Here, SEND_AMOUNT).into()),
+			buy_execution((Here, SEND_AMOUNT)),
+			DepositAsset { assets: All.into(), max_assets: 1, beneficiary: dest.clone() },
+		]);
+
+		let XcmDryRunEffects { execution_result, forwarded_xcms, asset_changes, .. } =
+			XcmPallet::dry_run_xcm(
+				MultiLocation::from(Parachain(PARA_ID)).into(),
+				VersionedXcm::from(message),
+			)
+			.unwrap();
+
+		assert_eq!(execution_result, Outcome::Complete(3 * BaseXcmWeight::get()));
+		assert!(forwarded_xcms.is_empty());
+		assert_eq!(
+			asset_changes,
+			vec![
+				AssetChange::Withdrawn {
+					who: MultiLocation::from(Parachain(PARA_ID)).into(),
+					asset: MultiAsset::from((Here, SEND_AMOUNT)).into(),
+				},
+				AssetChange::Deposited {
+					who: dest.into(),
+					asset: MultiAsset::from((Here, SEND_AMOUNT)).into(),
+				},
+			]
+		);
+		assert_eq!(Balances::total_balance(&BOB), SEND_AMOUNT);
+	});
+}
+
+/// Test `dry_run_xcm` tracing
+///
+/// Asserts that every executed instruction is traced with the origin and holding after it and
+/// with its error, and that nothing is traced outside of a dry-run.
+#[test]
+fn dry_run_xcm_traces_instructions() {
+	let balances = vec![(ParaId::from(PARA_ID).into_account_truncating(), INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let origin: MultiLocation = Parachain(PARA_ID).into();
+		let message = Xcm::<Call>(vec![
+			WithdrawAsset((Here, SEND_AMOUNT).into()),
+			buy_execution((Here, SEND_AMOUNT)),
+			ClearOrigin,
+			TransferAsset {
+				assets: (Here, SEND_AMOUNT).into(),
+				beneficiary: AccountId32 { network: Any, id: BOB.into() }.into(),
+			},
+		]);
+
+		let XcmDryRunEffects { execution_result, trace, .. } =
+			XcmPallet::dry_run_xcm(origin.clone().into(), VersionedXcm::from(message)).unwrap();
+
+		assert_eq!(
+			execution_result,
+			Outcome::Incomplete(4 * BaseXcmWeight::get(), XcmError::BadOrigin)
+		);
+		assert_eq!(
+			trace.iter().map(|t| (t.index, t.opcode)).collect::<Vec<_>>(),
+			vec![(0, 0), (1, 19), (2, 10), (3, 4)]
+		);
+		assert_eq!(trace[0].origin, Some(origin));
+		assert_eq!(trace[0].holding, (Here, SEND_AMOUNT).into());
+		assert_eq!(trace[0].weight, BaseXcmWeight::get());
+		assert_eq!(trace[2].origin, None);
+		assert!(trace[..3].iter().all(|t| t.error.is_none()));
+		assert_eq!(trace[3].error, Some(XcmError::BadOrigin));
+		assert!(!XcmPallet::is_dry_running());
+		assert!(!<XcmPallet as TraceXcm>::is_enabled());
+	});
+}
+
+/// Test `query_xcm_weight` and `query_weight_to_asset_fee`
+///
+/// Asserts that the weight comes from the configured `Weigher` and that the fee is what the
+/// `Trader` charges for it, in the asset it accepts only.
+#[test]
+fn xcm_weight_and_fee_estimation_works() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let message = Xcm::<()>(vec![
+			WithdrawAsset((Here, SEND_AMOUNT).into()),
+			ClearOrigin,
+			DepositAsset { assets: All.into(), max_assets: 1, beneficiary: Here.into() },
+		]);
+		assert_eq!(
+			XcmPallet::query_xcm_weight(VersionedXcm::from(message)),
+			Ok(3 * BaseXcmWeight::get())
+		);
+
+		type Trader = FixedRateOfFungible<CurrencyPerSecond, ()>;
+		let relay: VersionedMultiLocation = RelayLocation::get().into();
+		assert_eq!(XcmPallet::query_weight_to_asset_fee::<Trader>(0, relay.clone()), Ok(0));
+		assert_eq!(
+			XcmPallet::query_weight_to_asset_fee::<Trader>(3 * WEIGHT_PER_SECOND, relay),
+			Ok(3)
+		);
+		assert_eq!(
+			XcmPallet::query_weight_to_asset_fee::<Trader>(
+				WEIGHT_PER_SECOND,
+				MultiLocation::from(Parachain(PARA_ID)).into()
+			),
+			Err(XcmPaymentApiError::AssetNotFound)
+		);
+	});
+}
+
+/// Test `query_acceptable_payment_assets` and `query_delivery_fees`
+///
+/// Asserts that the assets are expressed in the requested version and that delivery is free.
+#[test]
+fn payment_assets_and_delivery_fees_queries_work() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let assets = vec![RelayLocation::get()];
+		assert_eq!(
+			XcmPallet::query_acceptable_payment_assets(0, assets.clone()),
+			Ok(vec![VersionedMultiLocation::V0(xcm::v0::MultiLocation::Null)])
+		);
+		assert_eq!(
+			XcmPallet::query_acceptable_payment_assets(2, assets.clone()),
+			Ok(vec![RelayLocation::get().into()])
+		);
+		assert_eq!(
+			XcmPallet::query_acceptable_payment_assets(XCM_VERSION + 1, assets),
+			Err(XcmPaymentApiError::UnhandledXcmVersion)
+		);
+
+		let message = VersionedXcm::from(Xcm::<()>(vec![ClearOrigin]));
+		assert_eq!(
+			XcmPallet::query_delivery_fees(Parent.into(), message),
+			Ok(MultiAssets::new().into())
+		);
+	});
+}
@@ -0,0 +1,104 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_xcm`
+//!
+//! Until the benchmarks in `benchmarking.rs` are run against the reference machine, these weights
+//! are composed from benchmarked weights of similar operations: `lock_asset` from
+//! `pallet_balances::transfer_keep_alive` (paying the delivery fee and setting the lock) and
+//! `runtime_parachains::hrmp::hrmp_init_open_channel` (queueing a downward message), and
+//! `unlock_asset` from `pallet_balances::force_unreserve`, plus the reads and writes the pallet
+//! does on top of them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+// Implemented by autogenerated benchmarking code.
+pub trait WeightInfo {
+	fn lock_asset() -> Weight;
+	fn unlock_asset() -> Weight;
+	fn note_unlockable() -> Weight;
+}
+
+pub struct SubstrateWeight<T>(PhantomData<T>);
+
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: System Account (r:1 w:1)
+	// Storage: XcmPallet RemoteLockedFungibles (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: XcmPallet SupportedVersion (r:1 w:0)
+	// Storage: XcmPallet VersionDiscoveryQueue (r:1 w:1)
+	// Storage: XcmPallet SafeXcmVersion (r:1 w:0)
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: Dmp DownwardMessageQueueHeads (r:1 w:1)
+	// Storage: Dmp DownwardMessageQueues (r:1 w:1)
+	fn lock_asset() -> Weight {
+		(149_524_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+
+	// Storage: XcmPallet RemoteLockedFungibles (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn unlock_asset() -> Weight {
+		(46_957_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+
+	// No storage is accessed, the call only deposits an event.
+	fn note_unlockable() -> Weight {
+		(10_000_000 as Weight)
+	}
+}
+
+impl WeightInfo for () {
+	// Storage: System Account (r:1 w:1)
+	// Storage: XcmPallet RemoteLockedFungibles (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: XcmPallet SupportedVersion (r:1 w:0)
+	// Storage: XcmPallet VersionDiscoveryQueue (r:1 w:1)
+	// Storage: XcmPallet SafeXcmVersion (r:1 w:0)
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: Dmp DownwardMessageQueueHeads (r:1 w:1)
+	// Storage: Dmp DownwardMessageQueues (r:1 w:1)
+	fn lock_asset() -> Weight {
+		(149_524_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+
+	// Storage: XcmPallet RemoteLockedFungibles (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn unlock_asset() -> Weight {
+		(46_957_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+
+	// No storage is accessed, the call only deposits an event.
+	fn note_unlockable() -> Weight {
+		(10_000_000 as Weight)
+	}
+}
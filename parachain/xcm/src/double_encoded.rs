@@ -49,6 +49,21 @@ impl<T> core::fmt::Debug for DoubleEncoded<T> {
 	}
 }
 
+#[cfg(feature = "std")]
+impl<T> serde::Serialize for DoubleEncoded<T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		crate::serde_utils::serialize(&self.encoded, serializer)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de, T> serde::Deserialize<'de> for DoubleEncoded<T> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		crate::serde_utils::bytes::deserialize(deserializer)
+			.map(|encoded| Self { encoded, decoded: None })
+	}
+}
+
 impl<T> From<Vec<u8>> for DoubleEncoded<T> {
 	fn from(encoded: Vec<u8>) -> Self {
 		Self { encoded, decoded: None }
@@ -38,6 +38,8 @@ pub mod latest {
 }
 
 mod double_encoded;
+#[cfg(feature = "std")]
+mod serde_utils;
 pub use double_encoded::DoubleEncoded;
 
 /// Maximum nesting level for XCM decoding.
@@ -68,6 +70,7 @@ pub trait IntoVersion: Sized {
 
 /// A single `MultiLocation` value, together with its version code.
 #[derive(Derivative, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Clone(bound = ""), Eq(bound = ""), PartialEq(bound = ""), Debug(bound = ""))]
 #[codec(encode_bound())]
 #[codec(decode_bound())]
@@ -122,6 +125,7 @@ impl TryFrom<VersionedMultiLocation> for v1::MultiLocation {
 
 /// A single `Response` value, together with its version code.
 #[derive(Derivative, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Clone(bound = ""), Eq(bound = ""), PartialEq(bound = ""), Debug(bound = ""))]
 #[codec(encode_bound())]
 #[codec(decode_bound())]
@@ -198,6 +202,7 @@ impl TryFrom<VersionedResponse> for v2::Response {
 
 /// A single `MultiAsset` value, together with its version code.
 #[derive(Derivative, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Clone(bound = ""), Eq(bound = ""), PartialEq(bound = ""), Debug(bound = ""))]
 #[codec(encode_bound())]
 #[codec(decode_bound())]
@@ -252,6 +257,7 @@ impl TryFrom<VersionedMultiAsset> for v1::MultiAsset {
 
 /// A single `MultiAssets` value, together with its version code.
 #[derive(Derivative, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derivative(Clone(bound = ""), Eq(bound = ""), PartialEq(bound = ""), Debug(bound = ""))]
 #[codec(encode_bound())]
 #[codec(decode_bound())]
@@ -306,6 +312,8 @@ impl TryFrom<VersionedMultiAssets> for v1::MultiAssets {
 
 /// A single XCM message, together with its version code.
 #[derive(Derivative, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
 #[derivative(Clone(bound = ""), Eq(bound = ""), PartialEq(bound = ""), Debug(bound = ""))]
 #[codec(encode_bound())]
 #[codec(decode_bound())]
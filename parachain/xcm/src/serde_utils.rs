@@ -0,0 +1,119 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Serde helpers for the byte fields of XCM types, which are represented as `0x`-prefixed hex.
+
+use alloc::vec::Vec;
+use serde::{de::Error, Deserializer, Serializer};
+use sp_runtime::{traits::Get, WeakBoundedVec};
+
+/// Serialize any byte sequence as hex.
+pub fn serialize<S: Serializer>(bytes: impl AsRef<[u8]>, serializer: S) -> Result<S::Ok, S::Error> {
+	impl_serde::serialize::serialize(bytes.as_ref(), serializer)
+}
+
+/// Hex representation of `Vec<u8>`.
+pub mod bytes {
+	use super::*;
+
+	pub fn serialize<S: Serializer>(bytes: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+		super::serialize(bytes, serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		impl_serde::serialize::deserialize(deserializer)
+	}
+}
+
+/// Hex representation of `[u8; N]`.
+pub mod array {
+	use super::*;
+	use impl_serde::serialize::ExpectedLen;
+
+	pub fn serialize<S: Serializer, const N: usize>(
+		bytes: &[u8; N],
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		super::serialize(bytes, serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+		deserializer: D,
+	) -> Result<[u8; N], D::Error> {
+		let mut array = [0u8; N];
+		impl_serde::serialize::deserialize_check_len(deserializer, ExpectedLen::Exact(&mut array))?;
+		Ok(array)
+	}
+}
+
+/// Hex representation of `WeakBoundedVec<u8, _>`.
+pub mod weak_bounded {
+	use super::*;
+
+	pub fn serialize<S: Serializer, B>(
+		bytes: &WeakBoundedVec<u8, B>,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		super::serialize(&bytes[..], serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>, B: Get<u32>>(
+		deserializer: D,
+	) -> Result<WeakBoundedVec<u8, B>, D::Error> {
+		let bytes = impl_serde::serialize::deserialize(deserializer)?;
+		WeakBoundedVec::try_from(bytes).map_err(|_| D::Error::custom("too many bytes"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{latest::prelude::*, VersionedMultiLocation, VersionedXcm};
+	use alloc::vec;
+
+	#[test]
+	fn byte_fields_are_hex() {
+		let location: VersionedMultiLocation = MultiLocation::new(
+			1,
+			X2(Parachain(2000), AccountKey20 { network: Any, key: [0xab; 20] }),
+		)
+		.into();
+		let json = serde_json::to_string(&location).unwrap();
+		assert!(json.contains("\"0xabababababababababababababababababababab\""));
+		assert_eq!(serde_json::from_str::<VersionedMultiLocation>(&json).unwrap(), location);
+	}
+
+	#[test]
+	fn message_json_round_trip() {
+		let message: VersionedXcm<()> = Xcm(vec![
+			WithdrawAsset((Here, 100u128).into()),
+			BuyExecution { fees: (Here, 100u128).into(), weight_limit: Limited(1_000) },
+			Transact {
+				origin_type: OriginKind::SovereignAccount,
+				require_weight_at_most: 1_000,
+				call: vec![1u8, 2, 3].into(),
+			},
+			DepositAsset {
+				assets: All.into(),
+				max_assets: 1,
+				beneficiary: GeneralKey(vec![0xde, 0xad].try_into().unwrap()).into(),
+			},
+		])
+		.into();
+		let json = serde_json::to_string(&message).unwrap();
+		assert!(json.contains("\"0x010203\""));
+		assert_eq!(serde_json::from_str::<VersionedXcm<()>>(&json).unwrap(), message);
+	}
+}
@@ -22,22 +22,30 @@ use sp_runtime::{traits::ConstU32, WeakBoundedVec};
 
 /// A global identifier of an account-bearing consensus system.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum NetworkId {
 	/// Unidentified/any.
 	Any,
 	/// Some named network.
-	Named(WeakBoundedVec<u8, ConstU32<32>>),
+	Named(
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::weak_bounded"))]
+		WeakBoundedVec<u8, ConstU32<32>>,
+	),
 	/// The Selendra Relay chain
 	Selendra,
 }
 
 /// An identifier of a pluralistic body.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum BodyId {
 	/// The only body in its context.
 	Unit,
 	/// A named body.
-	Named(WeakBoundedVec<u8, ConstU32<32>>),
+	Named(
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::weak_bounded"))]
+		WeakBoundedVec<u8, ConstU32<32>>,
+	),
 	/// An indexed body.
 	Index(#[codec(compact)] u32),
 	/// The unambiguous executive body (for Selendra, this would be the Selendra council).
@@ -54,6 +62,7 @@ pub enum BodyId {
 
 /// A part of a pluralistic body.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum BodyPart {
 	/// The body's declaration, under whatever means it decides.
 	Voice,
@@ -101,6 +110,7 @@ impl BodyPart {
 ///
 /// Each item assumes a pre-existing location as its context and is defined in terms of it.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Junction {
 	/// The consensus system of which the context is a member and state-wise super-set.
 	///
@@ -115,7 +125,11 @@ pub enum Junction {
 	/// the context.
 	///
 	/// Generally used when the context is a Substrate-based chain.
-	AccountId32 { network: NetworkId, id: [u8; 32] },
+	AccountId32 {
+		network: NetworkId,
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))]
+		id: [u8; 32],
+	},
 	/// An 8-byte index for an account of a specific network that is respected as a sovereign endpoint within
 	/// the context.
 	///
@@ -129,7 +143,11 @@ pub enum Junction {
 	/// the context.
 	///
 	/// May be used when the context is an Ethereum or Bitcoin chain or smart-contract.
-	AccountKey20 { network: NetworkId, key: [u8; 20] },
+	AccountKey20 {
+		network: NetworkId,
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))]
+		key: [u8; 20],
+	},
 	/// An instanced, indexed pallet that forms a constituent part of the context.
 	///
 	/// Generally used when the context is a Frame-based chain.
@@ -145,7 +163,10 @@ pub enum Junction {
 	/// Usage will vary widely owing to its generality.
 	///
 	/// NOTE: Try to avoid using this and instead use a more specific item.
-	GeneralKey(WeakBoundedVec<u8, ConstU32<32>>),
+	GeneralKey(
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::weak_bounded"))]
+		WeakBoundedVec<u8, ConstU32<32>>,
+	),
 	/// The unambiguous child.
 	///
 	/// Not currently used except as a fallback when deriving ancestry.
@@ -81,6 +81,7 @@ pub use crate::v1::AssetInstance;
 /// - `<chain>/AccountKey20` for an ERC-20-style single-asset smart-contract on an Ethereum-like chain.
 ///
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum MultiAsset {
 	/// No assets. Rarely used.
 	None,
@@ -98,10 +99,16 @@ pub enum MultiAsset {
 	AllNonFungible,
 
 	/// All fungible assets of a given abstract asset `id`entifier.
-	AllAbstractFungible { id: Vec<u8> },
+	AllAbstractFungible {
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::bytes"))]
+		id: Vec<u8>,
+	},
 
 	/// All non-fungible assets of a given abstract asset `class`.
-	AllAbstractNonFungible { class: Vec<u8> },
+	AllAbstractNonFungible {
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::bytes"))]
+		class: Vec<u8>,
+	},
 
 	/// All fungible assets of a given concrete asset `id`entifier.
 	AllConcreteFungible { id: MultiLocation },
@@ -111,13 +118,18 @@ pub enum MultiAsset {
 
 	/// Some specific `amount` of the fungible asset identified by an abstract `id`.
 	AbstractFungible {
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::bytes"))]
 		id: Vec<u8>,
 		#[codec(compact)]
 		amount: u128,
 	},
 
 	/// Some specific `instance` of the non-fungible asset whose `class` is identified abstractly.
-	AbstractNonFungible { class: Vec<u8>, instance: AssetInstance },
+	AbstractNonFungible {
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::bytes"))]
+		class: Vec<u8>,
+		instance: AssetInstance,
+	},
 
 	/// Some specific `amount` of the fungible asset identified by an concrete `id`.
 	ConcreteFungible {
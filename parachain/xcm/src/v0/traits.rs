@@ -22,6 +22,7 @@ use parity_scale_codec::{Decode, Encode};
 use super::{MultiLocation, Xcm};
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
 	Undefined,
 	/// An arithmetic overflow happened.
@@ -104,6 +105,7 @@ pub type Weight = u64;
 
 /// Outcome of an XCM execution.
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, scale_info::TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Outcome {
 	/// Execution completed successfully; given weight was used.
 	Complete(Weight),
@@ -26,6 +26,7 @@ use sp_runtime::{traits::ConstU32, WeakBoundedVec};
 ///
 /// Each item assumes a pre-existing location as its context and is defined in terms of it.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Junction {
 	/// An indexed parachain belonging to and operated by the context.
 	///
@@ -35,7 +36,11 @@ pub enum Junction {
 	/// the context.
 	///
 	/// Generally used when the context is a Substrate-based chain.
-	AccountId32 { network: NetworkId, id: [u8; 32] },
+	AccountId32 {
+		network: NetworkId,
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))]
+		id: [u8; 32],
+	},
 	/// An 8-byte index for an account of a specific network that is respected as a sovereign endpoint within
 	/// the context.
 	///
@@ -49,7 +54,11 @@ pub enum Junction {
 	/// the context.
 	///
 	/// May be used when the context is an Ethereum or Bitcoin chain or smart-contract.
-	AccountKey20 { network: NetworkId, key: [u8; 20] },
+	AccountKey20 {
+		network: NetworkId,
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))]
+		key: [u8; 20],
+	},
 	/// An instanced, indexed pallet that forms a constituent part of the context.
 	///
 	/// Generally used when the context is a Frame-based chain.
@@ -65,7 +74,10 @@ pub enum Junction {
 	/// Usage will vary widely owing to its generality.
 	///
 	/// NOTE: Try to avoid using this and instead use a more specific item.
-	GeneralKey(WeakBoundedVec<u8, ConstU32<32>>),
+	GeneralKey(
+		#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::weak_bounded"))]
+		WeakBoundedVec<u8, ConstU32<32>>,
+	),
 	/// The unambiguous child.
 	///
 	/// Not currently used except as a fallback when deriving ancestry.
@@ -73,6 +73,7 @@ mod junction;
 mod multiasset;
 mod multilocation;
 mod order;
+mod text;
 mod traits; // the new multiasset.
 
 pub use junction::Junction;
@@ -84,6 +85,7 @@ pub use multilocation::{
 	Ancestor, AncestorThen, InteriorMultiLocation, Junctions, MultiLocation, Parent, ParentThen,
 };
 pub use order::Order;
+pub use text::ParseLocationError;
 pub use traits::{Error, ExecuteXcm, Outcome, Result, SendXcm};
 
 // These parts of XCM v0 have been unchanged in XCM v1, and are re-imported here.
@@ -115,6 +117,7 @@ pub mod prelude {
 
 /// Response data to a query.
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Response {
 	/// Some assets.
 	Assets(MultiAssets),
@@ -131,6 +134,8 @@ pub enum Response {
 /// This is the inner XCM format and is version-sensitive. Messages are typically passed using the outer
 /// XCM format, known as `VersionedXcm`.
 #[derive(Derivative, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "std", serde(bound = ""))]
 #[derivative(Clone(bound = ""), Eq(bound = ""), PartialEq(bound = ""), Debug(bound = ""))]
 #[codec(encode_bound())]
 #[codec(decode_bound())]
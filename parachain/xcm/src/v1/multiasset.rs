@@ -31,6 +31,7 @@ use scale_info::TypeInfo;
 
 /// A general identifier for an instance of a non-fungible asset class.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum AssetInstance {
 	/// Undefined - used if the non-fungible asset class has only one instance.
 	Undefined,
@@ -40,19 +41,19 @@ pub enum AssetInstance {
 	Index(#[codec(compact)] u128),
 
 	/// A 4-byte fixed-length datum.
-	Array4([u8; 4]),
+	Array4(#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))] [u8; 4]),
 
 	/// An 8-byte fixed-length datum.
-	Array8([u8; 8]),
+	Array8(#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))] [u8; 8]),
 
 	/// A 16-byte fixed-length datum.
-	Array16([u8; 16]),
+	Array16(#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))] [u8; 16]),
 
 	/// A 32-byte fixed-length datum.
-	Array32([u8; 32]),
+	Array32(#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::array"))] [u8; 32]),
 
 	/// An arbitrary piece of data. Use only when necessary.
-	Blob(Vec<u8>),
+	Blob(#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::bytes"))] Vec<u8>),
 }
 
 impl From<()> for AssetInstance {
@@ -93,9 +94,10 @@ impl From<Vec<u8>> for AssetInstance {
 
 /// Classification of an asset being concrete or abstract.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum AssetId {
 	Concrete(MultiLocation),
-	Abstract(Vec<u8>),
+	Abstract(#[cfg_attr(feature = "std", serde(with = "crate::serde_utils::bytes"))] Vec<u8>),
 }
 
 impl<T: Into<MultiLocation>> From<T> for AssetId {
@@ -142,6 +144,7 @@ impl AssetId {
 
 /// Classification of whether an asset is fungible or not, along with a mandatory amount or instance.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Fungibility {
 	Fungible(#[codec(compact)] u128),
 	NonFungible(AssetInstance),
@@ -169,6 +172,7 @@ impl<T: Into<AssetInstance>> From<T> for Fungibility {
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct MultiAsset {
 	pub id: AssetId,
 	pub fun: Fungibility,
@@ -284,8 +288,17 @@ impl TryFrom<Vec<super::super::v0::MultiAsset>> for MultiAsset {
 
 /// A `Vec` of `MultiAsset`s. There may be no duplicate fungible items in here and when decoding, they must be sorted.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize), serde(transparent))]
 pub struct MultiAssets(Vec<MultiAsset>);
 
+#[cfg(feature = "std")]
+impl<'de> serde::Deserialize<'de> for MultiAssets {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		// Accept any order and duplicates, just like `From<Vec<MultiAsset>>`.
+		<Vec<MultiAsset> as serde::Deserialize>::deserialize(deserializer).map(Self::from)
+	}
+}
+
 impl Decode for MultiAssets {
 	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
 		Self::from_sorted_and_deduplicated(Vec::<MultiAsset>::decode(input)?)
@@ -447,6 +460,7 @@ impl MultiAssets {
 }
 /// Classification of whether an asset is fungible or not.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum WildFungibility {
 	Fungible,
 	NonFungible,
@@ -454,6 +468,7 @@ pub enum WildFungibility {
 
 /// A wildcard representing a set of assets.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum WildMultiAsset {
 	/// All assets in the holding register, up to `usize` individual assets (different instances of non-fungibles could
 	/// be separate assets).
@@ -526,6 +541,7 @@ impl<A: Into<AssetId>, B: Into<WildFungibility>> From<(A, B)> for WildMultiAsset
 /// Note: Vectors of wildcards whose encoding is supported in XCM v0 are unsupported
 /// in this implementation and will result in a decode error.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum MultiAssetFilter {
 	Definite(MultiAssets),
 	Wild(WildMultiAsset),
@@ -0,0 +1,438 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Human-readable textual representation of `MultiLocation`s.
+//!
+//! A location is written as a `/`-separated path: each parent is written as `..` and comes
+//! first, followed by the interior junctions, e.g. `../Parachain(2000)/AccountId32(0x..)`. The
+//! location `Here` is written as `.`. Network identifiers are only written when they are not
+//! `Any`, in which case they appear as the first argument of the junction, e.g.
+//! `AccountId32(Selendra, 0x..)`.
+
+use super::{BodyId, BodyPart, Junction, Junctions, MultiLocation, NetworkId};
+use alloc::vec::Vec;
+use core::{
+	fmt::{self, Write},
+	str::FromStr,
+};
+use sp_runtime::WeakBoundedVec;
+
+/// An error encountered while parsing the textual form of a `MultiLocation`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ParseLocationError {
+	/// The input is not well-formed, e.g. unbalanced parentheses or an empty segment.
+	Malformed,
+	/// A `..` segment appeared after an interior junction.
+	ParentAfterJunction,
+	/// There are more parents or interior junctions than a `MultiLocation` can hold.
+	TooLong,
+	/// The named junction, network, body or body part is not known.
+	UnknownItem,
+	/// An argument has the wrong format or the wrong number of arguments was given.
+	BadArgument,
+}
+
+impl fmt::Display for ParseLocationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let msg = match self {
+			Self::Malformed => "malformed location",
+			Self::ParentAfterJunction => "`..` may only appear before any junction",
+			Self::TooLong => "location has too many parents or junctions",
+			Self::UnknownItem => "unknown junction, network, body or body part",
+			Self::BadArgument => "bad junction argument",
+		};
+		f.write_str(msg)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseLocationError {}
+
+fn write_hex(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+	f.write_str("0x")?;
+	bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, ParseLocationError> {
+	let digits = s.strip_prefix("0x").ok_or(ParseLocationError::BadArgument)?;
+	if digits.len() % 2 != 0 {
+		return Err(ParseLocationError::BadArgument)
+	}
+	(0..digits.len())
+		.step_by(2)
+		.map(|i| {
+			digits
+				.get(i..i + 2)
+				.and_then(|pair| u8::from_str_radix(pair, 16).ok())
+				.ok_or(ParseLocationError::BadArgument)
+		})
+		.collect()
+}
+
+fn parse_array<const N: usize>(s: &str) -> Result<[u8; N], ParseLocationError> {
+	parse_hex(s)?.try_into().map_err(|_| ParseLocationError::BadArgument)
+}
+
+fn parse_bounded<B: sp_runtime::traits::Get<u32>>(
+	s: &str,
+) -> Result<WeakBoundedVec<u8, B>, ParseLocationError> {
+	parse_hex(s)?.try_into().map_err(|_| ParseLocationError::BadArgument)
+}
+
+fn parse_number<N: FromStr>(s: &str) -> Result<N, ParseLocationError> {
+	s.parse().map_err(|_| ParseLocationError::BadArgument)
+}
+
+/// Split `s` on every `sep` which is not enclosed in parentheses.
+fn split_top_level(s: &str, sep: char) -> Result<Vec<&str>, ParseLocationError> {
+	let mut parts = Vec::new();
+	let mut depth = 0usize;
+	let mut start = 0;
+	for (i, c) in s.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth = depth.checked_sub(1).ok_or(ParseLocationError::Malformed)?,
+			c if c == sep && depth == 0 => {
+				parts.push(s[start..i].trim());
+				start = i + c.len_utf8();
+			},
+			_ => {},
+		}
+	}
+	if depth != 0 {
+		return Err(ParseLocationError::Malformed)
+	}
+	parts.push(s[start..].trim());
+	Ok(parts)
+}
+
+/// Split a term of the form `Name` or `Name(arg, ...)` into its name and arguments.
+fn split_term(s: &str) -> Result<(&str, Vec<&str>), ParseLocationError> {
+	match s.find('(') {
+		None => Ok((s.trim(), Vec::new())),
+		Some(open) => {
+			let inner = s[open + 1..].strip_suffix(')').ok_or(ParseLocationError::Malformed)?;
+			Ok((s[..open].trim(), split_top_level(inner, ',')?))
+		},
+	}
+}
+
+/// Split off the optional leading network argument of an account junction.
+fn split_network<'a>(args: &[&'a str]) -> Result<(NetworkId, &'a str), ParseLocationError> {
+	match args {
+		[value] => Ok((NetworkId::Any, value)),
+		[network, value] => Ok((network.parse()?, value)),
+		_ => Err(ParseLocationError::BadArgument),
+	}
+}
+
+fn single<'a>(args: &[&'a str]) -> Result<&'a str, ParseLocationError> {
+	match args {
+		[value] => Ok(value),
+		_ => Err(ParseLocationError::BadArgument),
+	}
+}
+
+fn pair<'a>(args: &[&'a str]) -> Result<(&'a str, &'a str), ParseLocationError> {
+	match args {
+		[a, b] => Ok((a, b)),
+		_ => Err(ParseLocationError::BadArgument),
+	}
+}
+
+impl fmt::Display for NetworkId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			NetworkId::Any => f.write_str("Any"),
+			NetworkId::Selendra => f.write_str("Selendra"),
+			NetworkId::Named(name) => {
+				f.write_str("Named(")?;
+				write_hex(f, name)?;
+				f.write_char(')')
+			},
+		}
+	}
+}
+
+impl FromStr for NetworkId {
+	type Err = ParseLocationError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match split_term(s)? {
+			("Any", args) if args.is_empty() => Ok(NetworkId::Any),
+			("Selendra", args) if args.is_empty() => Ok(NetworkId::Selendra),
+			("Named", args) => Ok(NetworkId::Named(parse_bounded(single(&args)?)?)),
+			_ => Err(ParseLocationError::UnknownItem),
+		}
+	}
+}
+
+impl fmt::Display for BodyId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			BodyId::Unit => f.write_str("Unit"),
+			BodyId::Named(name) => {
+				f.write_str("Named(")?;
+				write_hex(f, name)?;
+				f.write_char(')')
+			},
+			BodyId::Index(index) => write!(f, "Index({})", index),
+			BodyId::Executive => f.write_str("Executive"),
+			BodyId::Technical => f.write_str("Technical"),
+			BodyId::Legislative => f.write_str("Legislative"),
+			BodyId::Judicial => f.write_str("Judicial"),
+		}
+	}
+}
+
+impl FromStr for BodyId {
+	type Err = ParseLocationError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (name, args) = split_term(s)?;
+		let body = match name {
+			"Named" => return Ok(BodyId::Named(parse_bounded(single(&args)?)?)),
+			"Index" => return Ok(BodyId::Index(parse_number(single(&args)?)?)),
+			"Unit" => BodyId::Unit,
+			"Executive" => BodyId::Executive,
+			"Technical" => BodyId::Technical,
+			"Legislative" => BodyId::Legislative,
+			"Judicial" => BodyId::Judicial,
+			_ => return Err(ParseLocationError::UnknownItem),
+		};
+		if !args.is_empty() {
+			return Err(ParseLocationError::BadArgument)
+		}
+		Ok(body)
+	}
+}
+
+impl fmt::Display for BodyPart {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			BodyPart::Voice => f.write_str("Voice"),
+			BodyPart::Members { count } => write!(f, "Members({})", count),
+			BodyPart::Fraction { nom, denom } => write!(f, "Fraction({}, {})", nom, denom),
+			BodyPart::AtLeastProportion { nom, denom } =>
+				write!(f, "AtLeastProportion({}, {})", nom, denom),
+			BodyPart::MoreThanProportion { nom, denom } =>
+				write!(f, "MoreThanProportion({}, {})", nom, denom),
+		}
+	}
+}
+
+impl FromStr for BodyPart {
+	type Err = ParseLocationError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (name, args) = split_term(s)?;
+		let ratio = |args: &[&str]| -> Result<(u32, u32), ParseLocationError> {
+			let (nom, denom) = pair(args)?;
+			Ok((parse_number(nom)?, parse_number(denom)?))
+		};
+		match name {
+			"Voice" if args.is_empty() => Ok(BodyPart::Voice),
+			"Members" => Ok(BodyPart::Members { count: parse_number(single(&args)?)? }),
+			"Fraction" => ratio(&args).map(|(nom, denom)| BodyPart::Fraction { nom, denom }),
+			"AtLeastProportion" =>
+				ratio(&args).map(|(nom, denom)| BodyPart::AtLeastProportion { nom, denom }),
+			"MoreThanProportion" =>
+				ratio(&args).map(|(nom, denom)| BodyPart::MoreThanProportion { nom, denom }),
+			_ => Err(ParseLocationError::UnknownItem),
+		}
+	}
+}
+
+impl fmt::Display for Junction {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let network = |f: &mut fmt::Formatter, network: &NetworkId| match network {
+			NetworkId::Any => Ok(()),
+			network => write!(f, "{}, ", network),
+		};
+		match self {
+			Junction::Parachain(id) => write!(f, "Parachain({})", id),
+			Junction::AccountId32 { network: n, id } => {
+				f.write_str("AccountId32(")?;
+				network(f, n)?;
+				write_hex(f, id)?;
+				f.write_char(')')
+			},
+			Junction::AccountIndex64 { network: n, index } => {
+				f.write_str("AccountIndex64(")?;
+				network(f, n)?;
+				write!(f, "{})", index)
+			},
+			Junction::AccountKey20 { network: n, key } => {
+				f.write_str("AccountKey20(")?;
+				network(f, n)?;
+				write_hex(f, key)?;
+				f.write_char(')')
+			},
+			Junction::PalletInstance(index) => write!(f, "PalletInstance({})", index),
+			Junction::GeneralIndex(index) => write!(f, "GeneralIndex({})", index),
+			Junction::GeneralKey(key) => {
+				f.write_str("GeneralKey(")?;
+				write_hex(f, key)?;
+				f.write_char(')')
+			},
+			Junction::OnlyChild => f.write_str("OnlyChild"),
+			Junction::Plurality { id, part } => write!(f, "Plurality({}, {})", id, part),
+		}
+	}
+}
+
+impl FromStr for Junction {
+	type Err = ParseLocationError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (name, args) = split_term(s)?;
+		match name {
+			"Parachain" => Ok(Junction::Parachain(parse_number(single(&args)?)?)),
+			"AccountId32" => {
+				let (network, id) = split_network(&args)?;
+				Ok(Junction::AccountId32 { network, id: parse_array(id)? })
+			},
+			"AccountIndex64" => {
+				let (network, index) = split_network(&args)?;
+				Ok(Junction::AccountIndex64 { network, index: parse_number(index)? })
+			},
+			"AccountKey20" => {
+				let (network, key) = split_network(&args)?;
+				Ok(Junction::AccountKey20 { network, key: parse_array(key)? })
+			},
+			"PalletInstance" => Ok(Junction::PalletInstance(parse_number(single(&args)?)?)),
+			"GeneralIndex" => Ok(Junction::GeneralIndex(parse_number(single(&args)?)?)),
+			"GeneralKey" => Ok(Junction::GeneralKey(parse_bounded(single(&args)?)?)),
+			"OnlyChild" if args.is_empty() => Ok(Junction::OnlyChild),
+			"Plurality" => {
+				let (id, part) = pair(&args)?;
+				Ok(Junction::Plurality { id: id.parse()?, part: part.parse()? })
+			},
+			_ => Err(ParseLocationError::UnknownItem),
+		}
+	}
+}
+
+impl fmt::Display for MultiLocation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.parents == 0 && self.interior == Junctions::Here {
+			return f.write_char('.')
+		}
+		for i in 0..self.parents {
+			if i > 0 {
+				f.write_char('/')?;
+			}
+			f.write_str("..")?;
+		}
+		for (i, junction) in self.interior.iter().enumerate() {
+			if i > 0 || self.parents > 0 {
+				f.write_char('/')?;
+			}
+			write!(f, "{}", junction)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for MultiLocation {
+	type Err = ParseLocationError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.trim();
+		if s == "." {
+			return Ok(MultiLocation::here())
+		}
+		let mut location = MultiLocation::here();
+		for segment in split_top_level(s, '/')? {
+			if segment == ".." {
+				if location.interior != Junctions::Here {
+					return Err(ParseLocationError::ParentAfterJunction)
+				}
+				location.parents =
+					location.parents.checked_add(1).ok_or(ParseLocationError::TooLong)?;
+			} else if segment.is_empty() {
+				return Err(ParseLocationError::Malformed)
+			} else {
+				location
+					.push_interior(segment.parse()?)
+					.map_err(|_| ParseLocationError::TooLong)?;
+			}
+		}
+		Ok(location)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::v1::prelude::*;
+	use alloc::string::ToString;
+
+	#[test]
+	fn display_and_parse_round_trip() {
+		let cases = [
+			(MultiLocation::here(), "."),
+			(MultiLocation::parent(), ".."),
+			(MultiLocation::new(1, X1(Parachain(2000))), "../Parachain(2000)"),
+			(
+				MultiLocation::new(0, X2(PalletInstance(50), GeneralIndex(1984))),
+				"PalletInstance(50)/GeneralIndex(1984)",
+			),
+			(
+				MultiLocation::new(
+					1,
+					X2(Parachain(2000), AccountId32 { network: Any, id: [0xab; 32] }),
+				),
+				"../Parachain(2000)/AccountId32(0xabababababababababababababababababababababababababababababababab)",
+			),
+			(
+				MultiLocation::new(0, X1(AccountKey20 { network: Selendra, key: [1; 20] })),
+				"AccountKey20(Selendra, 0x0101010101010101010101010101010101010101)",
+			),
+			(
+				MultiLocation::new(
+					2,
+					X1(Plurality { id: BodyId::Index(3), part: BodyPart::Fraction { nom: 2, denom: 3 } }),
+				),
+				"../../Plurality(Index(3), Fraction(2, 3))",
+			),
+			(
+				MultiLocation::new(0, X2(OnlyChild, GeneralKey(alloc::vec![0xde, 0xad].try_into().unwrap()))),
+				"OnlyChild/GeneralKey(0xdead)",
+			),
+		];
+		for (location, text) in cases {
+			assert_eq!(location.to_string(), text);
+			assert_eq!(text.parse::<MultiLocation>(), Ok(location));
+		}
+	}
+
+	#[test]
+	fn parse_rejects_bad_input() {
+		use ParseLocationError::*;
+		assert_eq!("Parachain(1)/..".parse::<MultiLocation>(), Err(ParentAfterJunction));
+		assert_eq!("Parachain(1".parse::<MultiLocation>(), Err(Malformed));
+		assert_eq!("Parachain(1)//OnlyChild".parse::<MultiLocation>(), Err(Malformed));
+		assert_eq!("Nowhere(1)".parse::<MultiLocation>(), Err(UnknownItem));
+		assert_eq!("Parachain(x)".parse::<MultiLocation>(), Err(BadArgument));
+		assert_eq!("AccountId32(0x00)".parse::<MultiLocation>(), Err(BadArgument));
+		assert_eq!(
+			"OnlyChild/OnlyChild/OnlyChild/OnlyChild/OnlyChild/OnlyChild/OnlyChild/OnlyChild/OnlyChild"
+				.parse::<MultiLocation>(),
+			Err(TooLong)
+		);
+	}
+}
@@ -23,6 +23,7 @@ use scale_info::TypeInfo;
 use super::{MultiLocation, Xcm};
 
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
 	Undefined,
 	/// An arithmetic overflow happened.
@@ -107,6 +108,7 @@ pub type Weight = u64;
 
 /// Outcome of an XCM execution.
 #[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum Outcome {
 	/// Execution completed successfully; given weight was used.
 	Complete(Weight),
@@ -16,7 +16,11 @@
 
 //! Various implementations for `ShouldExecute`.
 
-use frame_support::{ensure, traits::Contains, weights::Weight};
+use frame_support::{
+	ensure,
+	traits::{Contains, Get},
+	weights::Weight,
+};
 use selendra_parachain::primitives::IsSystem;
 use sp_std::{marker::PhantomData, result::Result};
 use xcm::latest::{Instruction::*, Junction, Junctions, MultiLocation, WeightLimit::*, Xcm};
@@ -91,6 +95,45 @@ impl<T: Contains<MultiLocation>> ShouldExecute for AllowTopLevelPaidExecutionFro
 	}
 }
 
+/// Applies the leading `DescendOrigin` instructions of the message to its origin and then lets
+/// `InnerBarrier` decide on the rest of the message as if it came from the computed origin.
+///
+/// At most `MaxPrefixes` leading instructions are applied. This allows e.g. an account on a
+/// parachain, whose messages start with `DescendOrigin(AccountId32 { .. })`, to pay for its own
+/// execution with `AllowTopLevelPaidExecutionFrom`.
+pub struct WithComputedOrigin<InnerBarrier, MaxPrefixes>(PhantomData<(InnerBarrier, MaxPrefixes)>);
+impl<InnerBarrier: ShouldExecute, MaxPrefixes: Get<u32>> ShouldExecute
+	for WithComputedOrigin<InnerBarrier, MaxPrefixes>
+{
+	fn should_execute<Call>(
+		origin: &MultiLocation,
+		message: &mut Xcm<Call>,
+		max_weight: Weight,
+		weight_credit: &mut Weight,
+	) -> Result<(), ()> {
+		log::trace!(
+			target: "xcm::barriers",
+			"WithComputedOrigin origin: {:?}, message: {:?}, max_weight: {:?}, weight_credit: {:?}",
+			origin, message, max_weight, weight_credit,
+		);
+		let mut actual_origin = origin.clone();
+		let mut skipped = 0;
+		for instruction in message.0.iter().take(MaxPrefixes::get() as usize) {
+			match instruction {
+				DescendOrigin(interior) =>
+					actual_origin.append_with(interior.clone()).map_err(|_| ())?,
+				_ => break,
+			}
+			skipped += 1;
+		}
+		let mut rest = Xcm(message.0.split_off(skipped));
+		let result =
+			InnerBarrier::should_execute(&actual_origin, &mut rest, max_weight, weight_credit);
+		message.0.append(&mut rest.0);
+		result
+	}
+}
+
 /// Allows execution from any origin that is contained in `T` (i.e. `T::Contains(origin)`) without any payments.
 /// Use only for executions from trusted origin groups.
 pub struct AllowUnpaidExecutionFrom<T>(PhantomData<T>);
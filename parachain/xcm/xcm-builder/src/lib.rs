@@ -31,6 +31,8 @@ pub mod test_utils;
 mod location_conversion;
 pub use location_conversion::{
 	Account32Hash, AccountId32Aliases, AccountKey20Aliases, ChildParachainConvertsVia,
+	DescribeAccountId32Terminal, DescribeAccountKey20Terminal, DescribeAllTerminal, DescribeFamily,
+	DescribeLocation, DescribePalletTerminal, DescribeTerminus, HashedDescription,
 	LocationInverter, ParentIsPreset, SiblingParachainConvertsVia,
 };
 
@@ -45,7 +47,7 @@ pub use origin_conversion::{
 mod barriers;
 pub use barriers::{
 	AllowKnownQueryResponses, AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom,
-	AllowUnpaidExecutionFrom, IsChildSystemParachain, TakeWeightCredit,
+	AllowUnpaidExecutionFrom, IsChildSystemParachain, TakeWeightCredit, WithComputedOrigin,
 };
 
 mod currency_adapter;
@@ -69,3 +71,6 @@ pub use matches_fungible::{IsAbstract, IsConcrete};
 
 mod filter_asset_location;
 pub use filter_asset_location::{Case, NativeAsset};
+
+mod transact_filter;
+pub use transact_filter::{AllowCallsFromPallets, AllowNamedCalls};
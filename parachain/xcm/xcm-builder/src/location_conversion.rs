@@ -15,10 +15,10 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use frame_support::traits::Get;
-use parity_scale_codec::{Decode, Encode};
+use parity_scale_codec::{Compact, Decode, Encode};
 use sp_io::hashing::blake2_256;
 use sp_runtime::traits::{AccountIdConversion, TrailingZeroInput};
-use sp_std::{borrow::Borrow, marker::PhantomData};
+use sp_std::{borrow::Borrow, marker::PhantomData, prelude::*};
 use xcm::latest::{Junction::*, Junctions::*, MultiLocation, NetworkId, Parent};
 use xcm_executor::traits::{Convert, InvertLocation};
 
@@ -35,6 +35,126 @@ impl<Network: Get<NetworkId>, AccountId: From<[u8; 32]> + Into<[u8; 32]> + Clone
 	}
 }
 
+/// Means of converting a location into a stable and unique descriptive identifier.
+pub trait DescribeLocation {
+	/// Create a description of the given `location` if possible. No two locations should have the
+	/// same descriptor.
+	fn describe_location(location: &MultiLocation) -> Option<Vec<u8>>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl DescribeLocation for Tuple {
+	fn describe_location(location: &MultiLocation) -> Option<Vec<u8>> {
+		for_tuples!( #(
+			if let Some(description) = Tuple::describe_location(location) {
+				return Some(description)
+			}
+		)* );
+		None
+	}
+}
+
+/// Describes the location `Here` as the empty description.
+pub struct DescribeTerminus;
+impl DescribeLocation for DescribeTerminus {
+	fn describe_location(location: &MultiLocation) -> Option<Vec<u8>> {
+		match (location.parents, &location.interior) {
+			(0, Here) => Some(Vec::new()),
+			_ => None,
+		}
+	}
+}
+
+/// Describes a single `PalletInstance` junction.
+pub struct DescribePalletTerminal;
+impl DescribeLocation for DescribePalletTerminal {
+	fn describe_location(location: &MultiLocation) -> Option<Vec<u8>> {
+		match (location.parents, &location.interior) {
+			(0, X1(PalletInstance(index))) =>
+				Some((b"Pallet", Compact::<u32>::from(*index as u32)).encode()),
+			_ => None,
+		}
+	}
+}
+
+/// Describes a single `AccountId32` junction, regardless of its network.
+pub struct DescribeAccountId32Terminal;
+impl DescribeLocation for DescribeAccountId32Terminal {
+	fn describe_location(location: &MultiLocation) -> Option<Vec<u8>> {
+		match (location.parents, &location.interior) {
+			(0, X1(AccountId32 { id, .. })) => Some((b"AccountId32", id).encode()),
+			_ => None,
+		}
+	}
+}
+
+/// Describes a single `AccountKey20` junction, regardless of its network.
+pub struct DescribeAccountKey20Terminal;
+impl DescribeLocation for DescribeAccountKey20Terminal {
+	fn describe_location(location: &MultiLocation) -> Option<Vec<u8>> {
+		match (location.parents, &location.interior) {
+			(0, X1(AccountKey20 { key, .. })) => Some((b"AccountKey20", key).encode()),
+			_ => None,
+		}
+	}
+}
+
+/// Describes the location itself and the pallets and accounts directly within it.
+pub type DescribeAllTerminal = (
+	DescribeTerminus,
+	DescribePalletTerminal,
+	DescribeAccountId32Terminal,
+	DescribeAccountKey20Terminal,
+);
+
+/// Describes the locations within a child, sibling or parent chain, using `DescribeInterior` for
+/// the part of the location within that chain.
+///
+/// E.g. seen from a parachain, `../Parachain(2000)/AccountId32(x)` is described as the account
+/// `x` within the sibling chain 2000.
+pub struct DescribeFamily<DescribeInterior>(PhantomData<DescribeInterior>);
+impl<DescribeInterior: DescribeLocation> DescribeLocation for DescribeFamily<DescribeInterior> {
+	fn describe_location(location: &MultiLocation) -> Option<Vec<u8>> {
+		match (location.parents, location.interior.first()) {
+			(0, Some(Parachain(index))) => {
+				let tail = location.interior.clone().split_first().0;
+				let interior = DescribeInterior::describe_location(&tail.into())?;
+				Some((b"ChildChain", Compact::<u32>::from(*index), interior).encode())
+			},
+			(1, Some(Parachain(index))) => {
+				let tail = location.interior.clone().split_first().0;
+				let interior = DescribeInterior::describe_location(&tail.into())?;
+				Some((b"SiblingChain", Compact::<u32>::from(*index), interior).encode())
+			},
+			(1, _) => {
+				let tail: MultiLocation = location.interior.clone().into();
+				let interior = DescribeInterior::describe_location(&tail)?;
+				Some((b"ParentChain", interior).encode())
+			},
+			_ => None,
+		}
+	}
+}
+
+/// Converts any location which `Describe` can describe into an account, by hashing the
+/// description.
+///
+/// Unlike [`Account32Hash`], the account does not depend on the encoding of the location, so it
+/// remains stable across XCM versions.
+pub struct HashedDescription<AccountId, Describe>(PhantomData<(AccountId, Describe)>);
+impl<AccountId: From<[u8; 32]> + Clone, Describe: DescribeLocation>
+	Convert<MultiLocation, AccountId> for HashedDescription<AccountId, Describe>
+{
+	fn convert_ref(location: impl Borrow<MultiLocation>) -> Result<AccountId, ()> {
+		let description = Describe::describe_location(location.borrow()).ok_or(())?;
+		Ok(blake2_256(&description).into())
+	}
+
+	fn reverse_ref(_: impl Borrow<AccountId>) -> Result<MultiLocation, ()> {
+		Err(())
+	}
+}
+
 /// A [`MultiLocation`] consisting of a single `Parent` [`Junction`] will be converted to the
 /// parent `AccountId`.
 pub struct ParentIsPreset<AccountId>(PhantomData<AccountId>);
@@ -281,4 +401,50 @@ mod tests {
 		let inverted = LocationInverter::<Ancestry>::invert_location(&input);
 		assert_eq!(inverted, Err(()));
 	}
+
+	#[test]
+	fn hashed_description_describes_remote_accounts() {
+		type ToAccount = HashedDescription<[u8; 32], DescribeFamily<DescribeAllTerminal>>;
+		let account = |location: MultiLocation| ToAccount::convert_ref(location);
+
+		let alice = AccountId32 { network: Any, id: [1; 32] };
+		let sibling_alice = MultiLocation::new(1, X2(Parachain(2000), alice.clone()));
+		assert_eq!(
+			account(sibling_alice.clone()),
+			Ok(blake2_256(
+				&(
+					b"SiblingChain",
+					Compact::<u32>::from(2000),
+					(b"AccountId32", [1u8; 32]).encode()
+				)
+					.encode()
+			))
+		);
+		// The network of the account does not matter...
+		let named_alice = AccountId32 { network: NetworkId::Selendra, id: [1; 32] };
+		assert_eq!(
+			account(MultiLocation::new(1, X2(Parachain(2000), named_alice))),
+			account(sibling_alice.clone())
+		);
+		// ...but the chain and its relation to us do.
+		assert_ne!(
+			account(MultiLocation::new(1, X2(Parachain(2001), alice.clone()))),
+			account(sibling_alice.clone())
+		);
+		assert_ne!(
+			account(MultiLocation::new(0, X2(Parachain(2000), alice.clone()))),
+			account(sibling_alice)
+		);
+		assert!(account(MultiLocation::new(1, X1(Parachain(2000)))).is_ok());
+		assert!(account(MultiLocation::new(1, X1(alice.clone()))).is_ok());
+		assert!(account(MultiLocation::new(1, X2(Parachain(2000), PalletInstance(3)))).is_ok());
+
+		// Locals, grandparents and locations too deep to describe are not converted.
+		assert_eq!(account(MultiLocation::new(0, X1(alice.clone()))), Err(()));
+		assert_eq!(account(MultiLocation::new(2, X1(Parachain(2000)))), Err(()));
+		assert_eq!(
+			account(MultiLocation::new(1, X3(Parachain(2000), PalletInstance(3), alice))),
+			Err(())
+		);
+	}
 }
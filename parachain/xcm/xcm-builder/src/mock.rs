@@ -16,8 +16,9 @@
 
 use crate::{barriers::AllowSubscriptionsFrom, test_utils::*};
 pub use crate::{
-	AllowKnownQueryResponses, AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom,
-	FixedRateOfFungible, FixedWeightBounds, LocationInverter, TakeWeightCredit,
+	AllowKnownQueryResponses, AllowNamedCalls, AllowTopLevelPaidExecutionFrom,
+	AllowUnpaidExecutionFrom, FixedRateOfFungible, FixedWeightBounds, LocationInverter,
+	TakeWeightCredit,
 };
 pub use frame_support::{
 	dispatch::{
@@ -25,7 +26,7 @@ pub use frame_support::{
 	},
 	ensure, parameter_types,
 	sp_runtime::DispatchErrorWithPostInfo,
-	traits::{Contains, Get, IsInVec},
+	traits::{CallMetadata, Contains, Get, GetCallMetadata, IsInVec},
 	weights::{GetDispatchInfo, PostDispatchInfo},
 };
 pub use parity_scale_codec::{Decode, Encode};
@@ -100,6 +101,26 @@ impl GetDispatchInfo for TestCall {
 	}
 }
 
+impl GetCallMetadata for TestCall {
+	fn get_module_names() -> &'static [&'static str] {
+		&["Test"]
+	}
+
+	fn get_call_names(_module: &str) -> &'static [&'static str] {
+		&["only_root", "only_parachain", "only_signed", "any"]
+	}
+
+	fn get_call_metadata(&self) -> CallMetadata {
+		let function_name = match self {
+			TestCall::OnlyRoot(..) => "only_root",
+			TestCall::OnlyParachain(..) => "only_parachain",
+			TestCall::OnlySigned(..) => "only_signed",
+			TestCall::Any(..) => "any",
+		};
+		CallMetadata { function_name, pallet_name: "Test" }
+	}
+}
+
 thread_local! {
 	pub static SENT_XCM: RefCell<Vec<(MultiLocation, opaque::Xcm)>> = RefCell::new(Vec::new());
 }
@@ -262,6 +283,13 @@ parameter_types! {
 	// 1_000_000_000_000 => 1 unit of asset for 1 unit of Weight.
 	pub static WeightPrice: (AssetId, u128) = (From::from(Here), 1_000_000_000_000);
 	pub static MaxInstructions: u32 = 100;
+	// Every call is safe to `Transact` by default.
+	pub static SafeCalls: &'static [(&'static str, &'static str)] = &[
+		("Test", "only_root"),
+		("Test", "only_parachain"),
+		("Test", "only_signed"),
+		("Test", "any"),
+	];
 }
 
 pub type TestBarrier = (
@@ -288,4 +316,6 @@ impl Config for TestConfig {
 	type AssetTrap = TestAssetTrap;
 	type AssetClaims = TestAssetTrap;
 	type SubscriptionService = TestSubscriptionService;
+	type Tracer = ();
+	type SafeCallFilter = AllowNamedCalls<SafeCalls>;
 }
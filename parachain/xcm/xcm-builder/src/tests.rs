@@ -15,7 +15,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::{mock::*, test_utils::*, *};
-use frame_support::{assert_err, weights::constants::WEIGHT_PER_SECOND};
+use frame_support::{assert_err, traits::ConstU32, weights::constants::WEIGHT_PER_SECOND};
 use xcm::latest::prelude::*;
 use xcm_executor::{traits::*, Config, XcmExecutor};
 
@@ -144,6 +144,45 @@ fn allow_paid_should_work() {
 	assert_eq!(r, Ok(()));
 }
 
+#[test]
+fn computed_origin_should_work() {
+	let account: MultiLocation =
+		MultiLocation::new(1, X1(AccountIndex64 { network: Any, index: 1 }));
+	AllowPaidFrom::set(vec![account.clone()]);
+
+	let fees = (Parent, 30).into();
+	let mut message = Xcm::<()>(vec![
+		DescendOrigin(X1(AccountIndex64 { network: Any, index: 1 })),
+		WithdrawAsset((Parent, 100).into()),
+		BuyExecution { fees, weight_limit: Unlimited },
+		DepositAsset { assets: All.into(), max_assets: 1, beneficiary: Here.into() },
+	]);
+
+	let r = AllowTopLevelPaidExecutionFrom::<IsInVec<AllowPaidFrom>>::should_execute(
+		&Parent.into(),
+		&mut message,
+		40,
+		&mut 0,
+	);
+	assert_eq!(r, Err(()));
+
+	let r = WithComputedOrigin::<
+		AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
+		ConstU32<0>,
+	>::should_execute(&Parent.into(), &mut message, 40, &mut 0);
+	assert_eq!(r, Err(()));
+
+	let r = WithComputedOrigin::<
+		AllowTopLevelPaidExecutionFrom<IsInVec<AllowPaidFrom>>,
+		ConstU32<8>,
+	>::should_execute(&Parent.into(), &mut message, 40, &mut 0);
+	assert_eq!(r, Ok(()));
+	// The prefix is left in place and the inner barrier still limits the weight bought.
+	assert_eq!(message.0.len(), 4);
+	assert!(matches!(message.0[0], DescendOrigin(..)));
+	assert!(matches!(message.0[2], BuyExecution { weight_limit: Limited(40), .. }));
+}
+
 #[test]
 fn paying_reserve_deposit_should_work() {
 	AllowPaidFrom::set(vec![Parent.into()]);
@@ -615,6 +654,29 @@ fn transacting_should_refund_weight() {
 	assert_eq!(r, Outcome::Complete(40));
 }
 
+#[test]
+fn transacting_should_respect_safe_call_filter() {
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	SafeCalls::set(&[("Test", "only_root")]);
+
+	let message = Xcm::<TestCall>(vec![Transact {
+		origin_type: OriginKind::Native,
+		require_weight_at_most: 50,
+		call: TestCall::Any(50, None).encode().into(),
+	}]);
+	let weight_limit = 60;
+	let r = XcmExecutor::<TestConfig>::execute_xcm(Parent, message, weight_limit);
+	assert_eq!(r, Outcome::Incomplete(60, XcmError::BadOrigin));
+
+	let message = Xcm::<TestCall>(vec![Transact {
+		origin_type: OriginKind::Superuser,
+		require_weight_at_most: 50,
+		call: TestCall::OnlyRoot(50, None).encode().into(),
+	}]);
+	let r = XcmExecutor::<TestConfig>::execute_xcm(Parent, message, weight_limit);
+	assert_eq!(r, Outcome::Complete(60));
+}
+
 #[test]
 fn paid_transacting_should_refund_payment_for_unused_weight() {
 	let one: MultiLocation = X1(AccountIndex64 { index: 1, network: Any }).into();
@@ -0,0 +1,49 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Various implementations of `Contains<Call>` for use as the `SafeCallFilter` of the executor.
+
+use frame_support::traits::{Contains, Get, GetCallMetadata};
+use sp_std::marker::PhantomData;
+
+/// Allows any call of the pallets named in `Pallets`.
+pub struct AllowCallsFromPallets<Pallets>(PhantomData<Pallets>);
+impl<Call: GetCallMetadata, Pallets: Get<&'static [&'static str]>> Contains<Call>
+	for AllowCallsFromPallets<Pallets>
+{
+	fn contains(call: &Call) -> bool {
+		let pallet_name = call.get_call_metadata().pallet_name;
+		log::trace!(target: "xcm::safe_call_filter", "AllowCallsFromPallets pallet: {:?}", pallet_name);
+		Pallets::get().contains(&pallet_name)
+	}
+}
+
+/// Allows only the calls named in `Calls`, as `(pallet_name, function_name)` pairs.
+pub struct AllowNamedCalls<Calls>(PhantomData<Calls>);
+impl<Call: GetCallMetadata, Calls: Get<&'static [(&'static str, &'static str)]>> Contains<Call>
+	for AllowNamedCalls<Calls>
+{
+	fn contains(call: &Call) -> bool {
+		let metadata = call.get_call_metadata();
+		log::trace!(
+			target: "xcm::safe_call_filter",
+			"AllowNamedCalls pallet: {:?}, function: {:?}",
+			metadata.pallet_name,
+			metadata.function_name,
+		);
+		Calls::get().contains(&(metadata.pallet_name, metadata.function_name))
+	}
+}
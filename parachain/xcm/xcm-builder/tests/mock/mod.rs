@@ -16,7 +16,7 @@
 
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{Everything, Nothing},
+	traits::{ConstU128, ConstU32, ConstU64, Everything, Nothing},
 	weights::Weight,
 };
 use sp_core::H256;
@@ -175,6 +175,8 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = XcmPallet;
 	type AssetClaims = XcmPallet;
 	type SubscriptionService = XcmPallet;
+	type Tracer = XcmPallet;
+	type SafeCallFilter = Everything;
 }
 
 pub type LocalOriginToLocation = SignedToAccountId32<Origin, AccountId, SelendraNetwork>;
@@ -187,6 +189,7 @@ impl pallet_xcm::Config for Runtime {
 	// Anyone can execute XCM messages locally...
 	type ExecuteXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
 	type XcmExecuteFilter = Nothing;
+	type XcmSendFilter = Everything;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = Everything;
 	type XcmReserveTransferFilter = Everything;
@@ -195,6 +198,17 @@ impl pallet_xcm::Config for Runtime {
 	type Origin = Origin;
 	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
 	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+	type MaxAssetTrapsPerOrigin = ConstU32<16>;
+	type AssetTrapExpiry = ConstU64<100>;
+	type Currency = Balances;
+	type UnlockOrigin = pallet_xcm::EnsureXcm<Everything>;
+	type NoteUnlockableOrigin = pallet_xcm::EnsureXcm<Everything>;
+	type WeightInfo = ();
+	type MaxRemoteLockConsumers = ConstU32<16>;
+	type NoteUnlockableWeight = ConstU64<1_000_000_000>;
+	type DeliveryFeeBase = ConstU128<0>;
+	type DeliveryFeePerByte = ConstU128<0>;
+	type OnDeliveryFee = ();
 }
 
 impl origin::Config for Runtime {}
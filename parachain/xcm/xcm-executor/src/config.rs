@@ -16,10 +16,11 @@
 
 use crate::traits::{
 	ClaimAssets, ConvertOrigin, DropAssets, FilterAssetLocation, InvertLocation, OnResponse,
-	ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
+	ShouldExecute, TraceXcm, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
 };
 use frame_support::{
 	dispatch::{Dispatchable, Parameter},
+	traits::Contains,
 	weights::{GetDispatchInfo, PostDispatchInfo},
 };
 use xcm::latest::SendXcm;
@@ -68,4 +69,13 @@ pub trait Config {
 
 	/// How we handle version subscription requests.
 	type SubscriptionService: VersionChangeNotifier;
+
+	/// The hook which is given the state of the executor after every instruction.
+	type Tracer: TraceXcm;
+
+	/// The calls which may be dispatched by `Transact`.
+	///
+	/// Calls which are not contained are rejected with `BadOrigin` before their origin is even
+	/// converted, regardless of what the converted origin would be allowed to do.
+	type SafeCallFilter: Contains<Self::Call>;
 }
@@ -19,6 +19,7 @@
 use frame_support::{
 	dispatch::{Dispatchable, Weight},
 	ensure,
+	traits::Contains,
 	weights::GetDispatchInfo,
 };
 use sp_runtime::traits::Saturating;
@@ -31,8 +32,9 @@ use xcm::latest::{
 
 pub mod traits;
 use traits::{
-	ClaimAssets, ConvertOrigin, DropAssets, FilterAssetLocation, InvertLocation, OnResponse,
-	ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
+	ClaimAssets, ConvertOrigin, DropAssets, FilterAssetLocation, InstructionTrace, InvertLocation,
+	OnResponse, ShouldExecute, TraceXcm, TransactAsset, VersionChangeNotifier, WeightBounds,
+	WeightTrader, XcmAssetTransfers,
 };
 
 mod assets;
@@ -163,6 +165,11 @@ impl From<ExecutorError> for frame_benchmarking::BenchmarkError {
 	}
 }
 
+impl<Config: config::Config> XcmAssetTransfers for XcmExecutor<Config> {
+	type IsReserve = Config::IsReserve;
+	type IsTeleporter = Config::IsTeleporter;
+}
+
 impl<Config: config::Config> XcmExecutor<Config> {
 	pub fn new(origin: impl Into<MultiLocation>) -> Self {
 		let origin = origin.into();
@@ -193,13 +200,24 @@ impl<Config: config::Config> XcmExecutor<Config> {
 			self.total_refunded,
 			self.error_handler_weight,
 		);
+		let tracing = Self::tracing_enabled();
 		let mut result = Ok(());
 		for (i, instr) in xcm.0.into_iter().enumerate() {
 			match &mut result {
-				r @ Ok(()) =>
-					if let Err(e) = self.process_instruction(instr) {
+				r @ Ok(()) => {
+					let traced = tracing.then(|| {
+						let opcode = Self::opcode(&instr);
+						let weight = Config::Weigher::instr_weight(&instr).unwrap_or(0);
+						(opcode, weight, self.total_surplus)
+					});
+					let outcome = self.process_instruction(instr);
+					if let Some((opcode, weight, surplus_before)) = traced {
+						self.trace_instruction(i as u32, opcode, weight, surplus_before, &outcome);
+					}
+					if let Err(e) = outcome {
 						*r = Err(ExecutorError { index: i as u32, xcm_error: e, weight: 0 });
-					},
+					}
+				},
 				Err(ref mut error) =>
 					if let Ok(x) = Config::Weigher::instr_weight(&instr) {
 						error.weight.saturating_accrue(x)
@@ -209,6 +227,78 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		result
 	}
 
+	/// The SCALE discriminant of `instr`, without encoding it.
+	fn opcode(instr: &Instruction<Config::Call>) -> u8 {
+		match instr {
+			WithdrawAsset(..) => 0,
+			ReserveAssetDeposited(..) => 1,
+			ReceiveTeleportedAsset(..) => 2,
+			QueryResponse { .. } => 3,
+			TransferAsset { .. } => 4,
+			TransferReserveAsset { .. } => 5,
+			Transact { .. } => 6,
+			HrmpNewChannelOpenRequest { .. } => 7,
+			HrmpChannelAccepted { .. } => 8,
+			HrmpChannelClosing { .. } => 9,
+			ClearOrigin => 10,
+			DescendOrigin(..) => 11,
+			ReportError { .. } => 12,
+			DepositAsset { .. } => 13,
+			DepositReserveAsset { .. } => 14,
+			ExchangeAsset { .. } => 15,
+			InitiateReserveWithdraw { .. } => 16,
+			InitiateTeleport { .. } => 17,
+			QueryHolding { .. } => 18,
+			BuyExecution { .. } => 19,
+			RefundSurplus => 20,
+			SetErrorHandler(..) => 21,
+			SetAppendix(..) => 22,
+			ClearError => 23,
+			ClaimAsset { .. } => 24,
+			Trap(..) => 25,
+			SubscribeVersion { .. } => 26,
+			UnsubscribeVersion => 27,
+		}
+	}
+
+	/// Whether instructions should be traced, either for the configured `Tracer` or for the
+	/// `xcm::trace` log target.
+	fn tracing_enabled() -> bool {
+		Config::Tracer::is_enabled() || log::log_enabled!(target: "xcm::trace", log::Level::Trace)
+	}
+
+	/// Report the state of the executor after processing the instruction at `index`, which was
+	/// estimated to weigh `weight` and returned `outcome`.
+	fn trace_instruction(
+		&self,
+		index: u32,
+		opcode: u8,
+		weight: Weight,
+		surplus_before: u64,
+		outcome: &Result<(), XcmError>,
+	) {
+		let surplus = self.total_surplus.saturating_sub(surplus_before);
+		let trace = InstructionTrace {
+			index,
+			opcode,
+			origin: self.origin.clone(),
+			holding: self.holding.clone().into(),
+			weight: weight.saturating_sub(surplus),
+			error: outcome.clone().err(),
+		};
+		log::trace!(
+			target: "xcm::trace",
+			"index: {}, opcode: {}, origin: {:?}, holding: {:?}, weight: {}, error: {:?}",
+			trace.index,
+			trace.opcode,
+			trace.origin,
+			trace.holding,
+			trace.weight,
+			trace.error,
+		);
+		Config::Tracer::on_instruction(&trace);
+	}
+
 	/// Execute any final operations after having executed the XCM message.
 	/// This includes refunding surplus weight, trapping extra holding funds, and returning any errors during execution.
 	pub fn post_execute(mut self, xcm_weight: Weight) -> Outcome {
@@ -338,8 +428,10 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				// We assume that the Relay-chain is allowed to use transact on this parachain.
 				let origin = self.origin.clone().ok_or(XcmError::BadOrigin)?;
 
-				// TODO: #2841 #TRANSACTFILTER allow the trait to issue filters for the relay-chain
+				// The call is only decoded once we know there is an origin to dispatch it with, and
+				// only dispatched if it is deemed safe for XCM.
 				let message_call = call.take_decoded().map_err(|_| XcmError::FailedToDecode)?;
+				ensure!(Config::SafeCallFilter::contains(&message_call), XcmError::BadOrigin);
 				let dispatch_origin = Config::OriginConverter::convert_origin(origin, origin_type)
 					.map_err(|_| XcmError::BadOrigin)?;
 				let weight = message_call.get_dispatch_info().weight;
@@ -0,0 +1,85 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::traits::FilterAssetLocation;
+use xcm::latest::{AssetId, Junction, Junctions, MultiAsset, MultiLocation};
+
+/// The ways an asset can be transferred to another chain.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum TransferType {
+	/// The asset is teleported.
+	Teleport,
+	/// This chain is the reserve of the asset.
+	LocalReserve,
+	/// The destination is the reserve of the asset.
+	DestinationReserve,
+	/// The given third chain is the reserve of the asset.
+	RemoteReserve(MultiLocation),
+}
+
+/// Why the transfer type of an asset could not be determined.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TransferTypeError {
+	/// The asset is not concrete, so its reserve is unknown.
+	NotConcrete,
+	/// The reserve of the asset is not trusted.
+	UnknownReserve,
+}
+
+/// The trust configuration used to decide how assets are transferred to other chains.
+pub trait XcmAssetTransfers {
+	/// Combinations of (Asset, Location) pairs which we trust as reserves.
+	type IsReserve: FilterAssetLocation;
+
+	/// Combinations of (Asset, Location) pairs which we trust as teleporters.
+	type IsTeleporter: FilterAssetLocation;
+
+	/// Determine how `asset` is transferred to `dest`.
+	///
+	/// Teleporting is preferred whenever `dest` is a trusted teleporter of the asset. Otherwise the
+	/// asset is reserve-transferred through the chain its location belongs to, which must be
+	/// trusted as a reserve unless it is this chain or `dest`.
+	fn determine_for(
+		asset: &MultiAsset,
+		dest: &MultiLocation,
+	) -> Result<TransferType, TransferTypeError> {
+		if Self::IsTeleporter::filter_asset_location(asset, dest) {
+			return Ok(TransferType::Teleport)
+		}
+		let reserve = match &asset.id {
+			AssetId::Concrete(location) => chain_location(location),
+			AssetId::Abstract(_) => return Err(TransferTypeError::NotConcrete),
+		};
+		if reserve == MultiLocation::here() {
+			Ok(TransferType::LocalReserve)
+		} else if &reserve == dest {
+			Ok(TransferType::DestinationReserve)
+		} else if Self::IsReserve::filter_asset_location(asset, &reserve) {
+			Ok(TransferType::RemoteReserve(reserve))
+		} else {
+			Err(TransferTypeError::UnknownReserve)
+		}
+	}
+}
+
+/// The location of the chain `location` belongs to.
+fn chain_location(location: &MultiLocation) -> MultiLocation {
+	match location.first_interior() {
+		Some(Junction::Parachain(id)) =>
+			MultiLocation::new(location.parents, Junctions::X1(Junction::Parachain(*id))),
+		_ => MultiLocation::new(location.parents, Junctions::Here),
+	}
+}
@@ -15,6 +15,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 //! Various traits used in configuring the executor.
 
+mod asset_transfer;
+pub use asset_transfer::{TransferType, TransferTypeError, XcmAssetTransfers};
 mod conversion;
 pub use conversion::{Convert, ConvertOrigin, Decoded, Encoded, Identity, InvertLocation, JustTry};
 mod drop_assets;
@@ -29,6 +31,8 @@ mod on_response;
 pub use on_response::{OnResponse, VersionChangeNotifier};
 mod should_execute;
 pub use should_execute::ShouldExecute;
+mod trace;
+pub use trace::{InstructionTrace, TraceXcm, XcmTrace};
 mod transact_asset;
 pub use transact_asset::TransactAsset;
 mod weight;
@@ -0,0 +1,63 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use frame_support::weights::Weight;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::vec::Vec;
+use xcm::latest::{Error as XcmError, MultiAssets, MultiLocation};
+
+/// The state of the executor after processing a single instruction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct InstructionTrace {
+	/// The index of the instruction within the program fragment (the message itself, or the
+	/// error handler or appendix) being executed. It restarts from zero with every fragment.
+	pub index: u32,
+	/// The SCALE discriminant of the instruction.
+	pub opcode: u8,
+	/// The origin after the instruction was processed.
+	pub origin: Option<MultiLocation>,
+	/// The holding register after the instruction was processed.
+	pub holding: MultiAssets,
+	/// The weight consumed by the instruction, accounting for any surplus it reported.
+	pub weight: Weight,
+	/// The error returned by the instruction, if any.
+	pub error: Option<XcmError>,
+}
+
+/// The instructions processed in an execution, in order.
+pub type XcmTrace = Vec<InstructionTrace>;
+
+/// A hook which is given the state of the executor after every instruction.
+///
+/// Building an `InstructionTrace` clones the holding register, so the executor only does so when
+/// `is_enabled` returns `true` (or `xcm::trace` logging is enabled).
+pub trait TraceXcm {
+	/// Whether instructions should be traced at the moment.
+	fn is_enabled() -> bool;
+
+	/// Note that an instruction was processed.
+	fn on_instruction(trace: &InstructionTrace);
+}
+
+impl TraceXcm for () {
+	fn is_enabled() -> bool {
+		false
+	}
+
+	fn on_instruction(_trace: &InstructionTrace) {}
+}
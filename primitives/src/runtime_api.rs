@@ -53,7 +53,7 @@ use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 sp_api::decl_runtime_apis! {
 	/// The API for querying the state of parachains on-chain.
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait ParachainHost<H: Encode + Decode = pcp::v2::Hash, N: Encode + Decode = pcp::v2::BlockNumber> {
 		/// Get the current validators.
 		fn validators() -> Vec<v2::ValidatorId>;
@@ -143,6 +143,25 @@ sp_api::decl_runtime_apis! {
 		fn validation_code_hash(para_id: ppp::Id, assumption: v2::OccupiedCoreAssumption)
 			-> Option<ppp::ValidationCodeHash>;
 
+		/***** Added in v3 *****/
+
+		/// Returns all para lifecycle transitions that are scheduled to be applied at upcoming
+		/// session boundaries, ordered by session.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn scheduled_para_transitions() -> Vec<v2::ScheduledParaTransition>;
+
+		/// Returns the state of the validation code upgrade process of the given para.
+		///
+		/// Returns `None` if the para is not registered.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn para_upgrade_status(para_id: ppp::Id) -> Option<v2::ParaUpgradeStatus<N>>;
+
+		/// Returns the asynchronous backing parameters of the active configuration.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn async_backing_params() -> v2::AsyncBackingParams;
 
 		/***** Replaced in v2 *****/
 
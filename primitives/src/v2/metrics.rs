@@ -176,4 +176,18 @@ pub mod metric_definitions {
 			description: "Counts the number of bitfields signature checked in `enter_inner`.",
 			labels: &["validity"],
 		};
+
+	/// Counts the inbound XCMP channels skipped by the xcmp-queue pallet of a parachain because
+	/// they were `suspended` or had their weight quota `exhausted`.
+	pub const XCMP_QUEUE_INBOUND_CHANNELS_SKIPPED: CounterVecDefinition = CounterVecDefinition {
+		name: "selendra_xcmp_queue_inbound_channels_skipped",
+		description: "Counts the inbound XCMP channels skipped when servicing the queue.",
+		labels: &["reason"],
+	};
+
+	/// Counts the weight used executing inbound XCMP messages.
+	pub const XCMP_QUEUE_INBOUND_WEIGHT_USED: CounterDefinition = CounterDefinition {
+		name: "selendra_xcmp_queue_inbound_weight_used",
+		description: "Counts the weight used executing inbound XCMP messages.",
+	};
 }
@@ -300,6 +300,23 @@ pub mod well_known_keys {
 		})
 	}
 
+	/// The head data of the given para as of the latest included candidate.
+	///
+	/// The storage entry stores a value of `HeadData` type.
+	pub fn para_head(para_id: Id) -> Vec<u8> {
+		let prefix = hex!["cd710b30bd2eab0352ddcc26417aa1941b3c252fcb29d88eff4f3de5de4476c3"];
+
+		para_id.using_encoded(|para_id: &[u8]| {
+			prefix
+				.as_ref()
+				.iter()
+				.chain(twox_64(para_id).iter())
+				.chain(para_id.iter())
+				.cloned()
+				.collect()
+		})
+	}
+
 	/// The signal that indicates whether the parachain should go-ahead with the proposed validation
 	/// code upgrade.
 	///
@@ -1157,6 +1174,87 @@ pub enum UpgradeGoAhead {
 	GoAhead,
 }
 
+/// A lifecycle transition of a para that is queued to be applied at a session boundary.
+#[derive(Copy, Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum ParaLifecycleTransition {
+	/// The para is onboarding as a parathread or a parachain.
+	#[codec(index = 0)]
+	Onboarding,
+	/// The parathread is upgrading to a parachain.
+	#[codec(index = 1)]
+	UpgradeToParachain,
+	/// The parachain is downgrading to a parathread.
+	#[codec(index = 2)]
+	DowngradeToParathread,
+	/// The para is being offboarded.
+	#[codec(index = 3)]
+	Offboarding,
+}
+
+/// A para lifecycle transition together with the session at the start of which it is applied.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ScheduledParaTransition {
+	/// The para undergoing the transition.
+	pub para_id: Id,
+	/// The session at the start of which the transition is applied.
+	pub session: SessionIndex,
+	/// The kind of the transition.
+	pub transition: ParaLifecycleTransition,
+}
+
+/// A tally of the PVF pre-checking votes cast so far for a validation code.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PvfCheckVoteTally<N = BlockNumber> {
+	/// The hash of the validation code being pre-checked.
+	pub code_hash: ValidationCodeHash,
+	/// The number of validators that voted to accept the code.
+	pub accept: u32,
+	/// The number of validators that voted to reject the code.
+	pub reject: u32,
+	/// The number of validators in the active set that are eligible to vote.
+	pub n_validators: u32,
+	/// The number of session changes the vote has observed so far.
+	pub age: SessionIndex,
+	/// The relay-chain block number at which the vote was started.
+	pub created_at: N,
+}
+
+/// The state of the validation code upgrade process of a para.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ParaUpgradeStatus<N = BlockNumber> {
+	/// The hash of the validation code the para currently uses.
+	pub current_code_hash: Option<ValidationCodeHash>,
+	/// The hash of the validation code the para is upgrading to, if any.
+	pub future_code_hash: Option<ValidationCodeHash>,
+	/// The relay-chain block number at which the pending upgrade is expected to be applied.
+	pub expected_upgrade_at: Option<N>,
+	/// The go-ahead signal the relay-chain currently communicates to the para.
+	pub go_ahead: Option<UpgradeGoAhead>,
+	/// The upgrade restriction the relay-chain currently communicates to the para.
+	pub restriction: Option<UpgradeRestriction>,
+	/// The relay-chain block number at which the upgrade cooldown of the para ends.
+	pub cooldown_until: Option<N>,
+	/// The tally of the PVF pre-checking vote the para is waiting on, if any.
+	pub pvf_check: Option<PvfCheckVoteTally<N>>,
+}
+
+/// Candidate's acceptance limitations for asynchronous backing per relay parent.
+#[derive(Clone, Copy, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AsyncBackingParams {
+	/// The maximum number of para blocks between the para head in a relay parent
+	/// and a new candidate. Restricts nodes from building arbitrary long chains
+	/// and spamming other validators.
+	///
+	/// When async backing is disabled, the only valid value is 0.
+	pub max_candidate_depth: u32,
+	/// How many ancestors of a relay parent are allowed to build candidates on top
+	/// of.
+	///
+	/// When async backing is disabled, the only valid value is 0.
+	pub allowed_ancestry_len: u32,
+}
+
 /// Consensus engine id for selendra v1 consensus engine.
 pub const SELENDRA_ENGINE_ID: sp_runtime::ConsensusEngineId = *b"POL1";
 
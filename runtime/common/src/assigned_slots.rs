@@ -23,9 +23,8 @@
 //! This pallet should not be used on a production relay chain,
 
 use crate::{
-	slots::{self, Pallet as Slots, WeightInfo},
+	slots::{self, Pallet as Slots, WeightInfo as SlotsWeightInfo},
 	traits::{LeaseError, Leaser, Registrar},
-	MAXIMUM_BLOCK_WEIGHT,
 };
 use frame_support::{pallet_prelude::*, traits::Currency};
 use frame_system::pallet_prelude::*;
@@ -69,6 +68,33 @@ type BalanceOf<T> = <<<T as Config>::Leaser as Leaser<<T as frame_system::Config
 type LeasePeriodOf<T> =
 	<<T as Config>::Leaser as Leaser<<T as frame_system::Config>::BlockNumber>>::LeasePeriod;
 
+pub trait WeightInfo {
+	fn assign_perm_parachain_slot() -> Weight;
+	fn assign_temp_parachain_slot() -> Weight;
+	fn unassign_parachain_slot() -> Weight;
+	fn set_max_permanent_slots() -> Weight;
+	fn set_max_temporary_slots() -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn assign_perm_parachain_slot() -> Weight {
+		0
+	}
+	fn assign_temp_parachain_slot() -> Weight {
+		0
+	}
+	fn unassign_parachain_slot() -> Weight {
+		0
+	}
+	fn set_max_permanent_slots() -> Weight {
+		0
+	}
+	fn set_max_temporary_slots() -> Weight {
+		0
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -101,17 +127,12 @@ pub mod pallet {
 		#[pallet::constant]
 		type TemporarySlotLeasePeriodLength: Get<u32>;
 
-		/// The max number of permanent slots that can be assigned.
-		#[pallet::constant]
-		type MaxPermanentSlots: Get<u32>;
-
-		/// The max number of temporary slots that can be assigned.
-		#[pallet::constant]
-		type MaxTemporarySlots: Get<u32>;
-
 		/// The max number of temporary slots to be scheduled per lease periods.
 		#[pallet::constant]
 		type MaxTemporarySlotPerLeasePeriod: Get<u32>;
+
+		/// Weight Information for the Extrinsics in the Pallet
+		type WeightInfo: WeightInfo;
 	}
 
 	/// Assigned permanent slots, with their start lease period, and duration.
@@ -146,6 +167,37 @@ pub mod pallet {
 	#[pallet::getter(fn active_temporary_slot_count)]
 	pub type ActiveTemporarySlotCount<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+	/// The max number of permanent slots that can be assigned.
+	#[pallet::storage]
+	#[pallet::getter(fn max_permanent_slots)]
+	pub type MaxPermanentSlots<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The max number of temporary slots that can be assigned.
+	#[pallet::storage]
+	#[pallet::getter(fn max_temporary_slots)]
+	pub type MaxTemporarySlots<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		pub max_permanent_slots: u32,
+		pub max_temporary_slots: u32,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			GenesisConfig { max_permanent_slots: 0, max_temporary_slots: 0 }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			MaxPermanentSlots::<T>::put(self.max_permanent_slots);
+			MaxTemporarySlots::<T>::put(self.max_temporary_slots);
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -153,6 +205,10 @@ pub mod pallet {
 		PermanentSlotAssigned(ParaId),
 		/// A para was assigned a temporary parachain slot
 		TemporarySlotAssigned(ParaId),
+		/// The maximum number of permanent slots has been changed
+		MaxPermanentSlotsChanged { slots: u32 },
+		/// The maximum number of temporary slots has been changed
+		MaxTemporarySlotsChanged { slots: u32 },
 	}
 
 	#[pallet::error]
@@ -194,9 +250,8 @@ pub mod pallet {
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		// TODO: Benchmark this
 		/// Assign a permanent parachain slot and immediately create a lease for it.
-		#[pallet::weight(((MAXIMUM_BLOCK_WEIGHT / 10) as Weight, DispatchClass::Operational))]
+		#[pallet::weight((<T as Config>::WeightInfo::assign_perm_parachain_slot(), DispatchClass::Operational))]
 		pub fn assign_perm_parachain_slot(origin: OriginFor<T>, id: ParaId) -> DispatchResult {
 			T::AssignSlotOrigin::ensure_origin(origin)?;
 
@@ -224,7 +279,7 @@ pub mod pallet {
 			);
 
 			ensure!(
-				PermanentSlotCount::<T>::get() < T::MaxPermanentSlots::get(),
+				PermanentSlotCount::<T>::get() < MaxPermanentSlots::<T>::get(),
 				Error::<T>::MaxPermanentSlotsExceeded
 			);
 
@@ -250,11 +305,10 @@ pub mod pallet {
 			Ok(())
 		}
 
-		// TODO: Benchmark this
 		/// Assign a temporary parachain slot. The function tries to create a lease for it
 		/// immediately if `SlotLeasePeriodStart::Current` is specified, and if the number
 		/// of currently active temporary slots is below `MaxTemporarySlotPerLeasePeriod`.
-		#[pallet::weight(((MAXIMUM_BLOCK_WEIGHT / 10) as Weight, DispatchClass::Operational))]
+		#[pallet::weight((<T as Config>::WeightInfo::assign_temp_parachain_slot(), DispatchClass::Operational))]
 		pub fn assign_temp_parachain_slot(
 			origin: OriginFor<T>,
 			id: ParaId,
@@ -286,7 +340,7 @@ pub mod pallet {
 			);
 
 			ensure!(
-				TemporarySlotCount::<T>::get() < T::MaxTemporarySlots::get(),
+				TemporarySlotCount::<T>::get() < MaxTemporarySlots::<T>::get(),
 				Error::<T>::MaxTemporarySlotsExceeded
 			);
 
@@ -335,9 +389,8 @@ pub mod pallet {
 			Ok(())
 		}
 
-		// TODO: Benchmark this
 		/// Unassign a permanent or temporary parachain slot
-		#[pallet::weight(((MAXIMUM_BLOCK_WEIGHT / 10) as Weight, DispatchClass::Operational))]
+		#[pallet::weight((<T as Config>::WeightInfo::unassign_parachain_slot(), DispatchClass::Operational))]
 		pub fn unassign_parachain_slot(origin: OriginFor<T>, id: ParaId) -> DispatchResult {
 			T::AssignSlotOrigin::ensure_origin(origin.clone())?;
 
@@ -380,6 +433,28 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Set the maximum number of permanent slots that can be assigned.
+		#[pallet::weight((<T as Config>::WeightInfo::set_max_permanent_slots(), DispatchClass::Operational))]
+		pub fn set_max_permanent_slots(origin: OriginFor<T>, slots: u32) -> DispatchResult {
+			T::AssignSlotOrigin::ensure_origin(origin)?;
+
+			MaxPermanentSlots::<T>::put(slots);
+
+			Self::deposit_event(Event::<T>::MaxPermanentSlotsChanged { slots });
+			Ok(())
+		}
+
+		/// Set the maximum number of temporary slots that can be assigned.
+		#[pallet::weight((<T as Config>::WeightInfo::set_max_temporary_slots(), DispatchClass::Operational))]
+		pub fn set_max_temporary_slots(origin: OriginFor<T>, slots: u32) -> DispatchResult {
+			T::AssignSlotOrigin::ensure_origin(origin)?;
+
+			MaxTemporarySlots::<T>::put(slots);
+
+			Self::deposit_event(Event::<T>::MaxTemporarySlotsChanged { slots });
+			Ok(())
+		}
 	}
 }
 
@@ -539,7 +614,7 @@ mod tests {
 
 	use crate::{assigned_slots, mock::TestRegistrar, slots};
 	use ::test_helpers::{dummy_head_data, dummy_validation_code};
-	use frame_support::{assert_noop, assert_ok, parameter_types};
+	use frame_support::{assert_noop, assert_ok, parameter_types, traits::GenesisBuild};
 	use frame_system::EnsureRoot;
 	use pallet_balances;
 	use primitives::v2::{BlockNumber, Header};
@@ -569,7 +644,7 @@ mod tests {
 			ParasShared: parachains_shared::{Pallet, Call, Storage},
 			Parachains: parachains_paras::{Pallet, Call, Storage, Config, Event},
 			Slots: slots::{Pallet, Call, Storage, Event<T>},
-			AssignedSlots: assigned_slots::{Pallet, Call, Storage, Event<T>},
+			AssignedSlots: assigned_slots::{Pallet, Call, Storage, Event<T>, Config},
 		}
 	);
 
@@ -663,8 +738,6 @@ mod tests {
 	parameter_types! {
 		pub const PermanentSlotLeasePeriodLength: u32 = 3;
 		pub const TemporarySlotLeasePeriodLength: u32 = 2;
-		pub const MaxPermanentSlots: u32 = 2;
-		pub const MaxTemporarySlots: u32 = 6;
 		pub const MaxTemporarySlotPerLeasePeriod: u32 = 2;
 	}
 
@@ -674,9 +747,8 @@ mod tests {
 		type Leaser = Slots;
 		type PermanentSlotLeasePeriodLength = PermanentSlotLeasePeriodLength;
 		type TemporarySlotLeasePeriodLength = TemporarySlotLeasePeriodLength;
-		type MaxPermanentSlots = MaxPermanentSlots;
-		type MaxTemporarySlots = MaxTemporarySlots;
 		type MaxTemporarySlotPerLeasePeriod = MaxTemporarySlotPerLeasePeriod;
+		type WeightInfo = crate::assigned_slots::TestWeightInfo;
 	}
 
 	// This function basically just builds a genesis storage key/value store according to
@@ -688,6 +760,16 @@ mod tests {
 		}
 		.assimilate_storage(&mut t)
 		.unwrap();
+
+		GenesisBuild::<Test>::assimilate_storage(
+			&crate::assigned_slots::GenesisConfig {
+				max_temporary_slots: 6,
+				max_permanent_slots: 2,
+			},
+			&mut t,
+		)
+		.unwrap();
+
 		t.into()
 	}
 
@@ -1291,4 +1373,159 @@ mod tests {
 			assert_eq!(Slots::already_leased(ParaId::from(1_u32), 0, 1), false);
 		});
 	}
+
+	#[test]
+	fn set_max_permanent_slots_works() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			assert_noop!(AssignedSlots::set_max_permanent_slots(Origin::signed(1), 5), BadOrigin);
+
+			assert_eq!(AssignedSlots::max_permanent_slots(), 2);
+			assert_ok!(AssignedSlots::set_max_permanent_slots(Origin::root(), 0));
+			assert_eq!(AssignedSlots::max_permanent_slots(), 0);
+
+			assert_ok!(TestRegistrar::<Test>::register(
+				1,
+				ParaId::from(1_u32),
+				dummy_head_data(),
+				dummy_validation_code(),
+			));
+			assert_noop!(
+				AssignedSlots::assign_perm_parachain_slot(Origin::root(), ParaId::from(1_u32)),
+				Error::<Test>::MaxPermanentSlotsExceeded
+			);
+		});
+	}
+
+	#[test]
+	fn set_max_temporary_slots_works() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			assert_noop!(AssignedSlots::set_max_temporary_slots(Origin::signed(1), 5), BadOrigin);
+
+			assert_eq!(AssignedSlots::max_temporary_slots(), 6);
+			assert_ok!(AssignedSlots::set_max_temporary_slots(Origin::root(), 0));
+			assert_eq!(AssignedSlots::max_temporary_slots(), 0);
+
+			assert_ok!(TestRegistrar::<Test>::register(
+				1,
+				ParaId::from(1_u32),
+				dummy_head_data(),
+				dummy_validation_code(),
+			));
+			assert_noop!(
+				AssignedSlots::assign_temp_parachain_slot(
+					Origin::root(),
+					ParaId::from(1_u32),
+					SlotLeasePeriodStart::Current
+				),
+				Error::<Test>::MaxTemporarySlotsExceeded
+			);
+		});
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::*;
+	use frame_support::{assert_ok, traits::Currency as CurrencyT};
+	use frame_system::RawOrigin;
+	use sp_runtime::traits::Bounded;
+
+	use frame_benchmarking::{account, benchmarks};
+
+	use crate::assigned_slots::Pallet as AssignedSlots;
+
+	type SlotsBalanceOf<T> = <<T as slots::Config>::Currency as CurrencyT<
+		<T as frame_system::Config>::AccountId,
+	>>::Balance;
+
+	fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+		let events = frame_system::Pallet::<T>::events();
+		let system_event: <T as frame_system::Config>::Event = generic_event.into();
+		// compare to the last event record
+		let frame_system::EventRecord { event, .. } = &events[events.len() - 1];
+		assert_eq!(event, &system_event);
+	}
+
+	fn register_a_parathread<T: Config>(i: u32) -> ParaId {
+		let para = ParaId::from(i);
+		let manager: T::AccountId = account("manager", i, 0);
+		<T as slots::Config>::Currency::make_free_balance_be(
+			&manager,
+			SlotsBalanceOf::<T>::max_value(),
+		);
+		let worst_head_data = T::Registrar::worst_head_data();
+		let worst_validation_code = T::Registrar::worst_validation_code();
+
+		assert_ok!(T::Registrar::register(manager, para, worst_head_data, worst_validation_code));
+		T::Registrar::execute_pending_transitions();
+
+		para
+	}
+
+	// If there is an offset, we need to be on that block to be able to do lease things.
+	fn set_block_after_lease_offset<T: Config>() {
+		frame_system::Pallet::<T>::set_block_number(
+			<T as slots::Config>::LeaseOffset::get() + One::one(),
+		);
+	}
+
+	benchmarks! {
+		assign_perm_parachain_slot {
+			set_block_after_lease_offset::<T>();
+			MaxPermanentSlots::<T>::put(1);
+			let para = register_a_parathread::<T>(1_u32);
+			let origin = T::AssignSlotOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, para)
+		verify {
+			assert_last_event::<T>(Event::<T>::PermanentSlotAssigned(para).into());
+		}
+
+		assign_temp_parachain_slot {
+			set_block_after_lease_offset::<T>();
+			MaxTemporarySlots::<T>::put(1);
+			let para = register_a_parathread::<T>(1_u32);
+			let origin = T::AssignSlotOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, para, SlotLeasePeriodStart::Current)
+		verify {
+			assert_last_event::<T>(Event::<T>::TemporarySlotAssigned(para).into());
+		}
+
+		unassign_parachain_slot {
+			set_block_after_lease_offset::<T>();
+			MaxPermanentSlots::<T>::put(1);
+			let para = register_a_parathread::<T>(1_u32);
+			assert_ok!(AssignedSlots::<T>::assign_perm_parachain_slot(
+				RawOrigin::Root.into(),
+				para,
+			));
+			let origin = T::AssignSlotOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, para)
+		verify {
+			assert!(!PermanentSlots::<T>::contains_key(para));
+		}
+
+		set_max_permanent_slots {
+			let origin = T::AssignSlotOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, 100)
+		verify {
+			assert_eq!(MaxPermanentSlots::<T>::get(), 100);
+		}
+
+		set_max_temporary_slots {
+			let origin = T::AssignSlotOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, 100)
+		verify {
+			assert_eq!(MaxTemporarySlots::<T>::get(), 100);
+		}
+
+		impl_benchmark_test_suite!(
+			AssignedSlots,
+			crate::assigned_slots::tests::new_test_ext(),
+			crate::assigned_slots::tests::Test,
+		);
+	}
 }
@@ -19,6 +19,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod assigned_slots;
+pub mod paras_governance;
 pub mod paras_registrar;
 pub mod paras_sudo_wrapper;
 pub mod slot_range;
@@ -0,0 +1,923 @@
+// Copyright (C) 2021-2022 Selendra.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Pallet allowing governance to manage the lifecycle of paras in bulk.
+//!
+//! Unlike the registrar and the slots pallet, which act on a single para and only schedule
+//! transitions for the next possible session, this pallet lets a privileged origin:
+//!
+//! - schedule a parathread upgrade or a parachain downgrade at a specific future session,
+//! - onboard many paras at once, taking their genesis head and validation code from preimages.
+
+use crate::traits::Registrar;
+use frame_support::{
+	pallet_prelude::*,
+	traits::{PreimageProvider, PreimageRecipient},
+};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use parity_scale_codec::{Decode, Encode};
+use primitives::v2::{
+	HeadData, Id as ParaId, ParaLifecycleTransition, ScheduledParaTransition, SessionIndex,
+	ValidationCode, LOWEST_PUBLIC_ID,
+};
+use runtime_parachains::{configuration, paras, shared};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// Onboarding parameters of a single para, referring to its data by preimage hash.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ParaOnboarding<AccountId, Hash> {
+	/// The para ID to register. Public IDs must have been reserved by `manager`.
+	pub id: ParaId,
+	/// The account managing the para, which pays the registration deposit.
+	pub manager: AccountId,
+	/// Hash of the preimage holding the genesis head data.
+	pub genesis_head_hash: Hash,
+	/// Hash of the preimage holding the initial validation code.
+	pub validation_code_hash: Hash,
+	/// Whether the para should be upgraded to a parachain once it is onboarded.
+	pub parachain: bool,
+}
+
+/// A lifecycle transition of a para scheduled by governance.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum ParaTransition {
+	/// Upgrade a parathread to a parachain.
+	Upgrade,
+	/// Downgrade a parachain to a parathread.
+	Downgrade,
+}
+
+pub trait WeightInfo {
+	fn schedule_parathread_upgrade() -> Weight;
+	fn schedule_parachain_downgrade() -> Weight;
+	fn register_paras(n: u32) -> Weight;
+	fn enact_transitions(n: u32) -> Weight;
+}
+
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn schedule_parathread_upgrade() -> Weight {
+		0
+	}
+	fn schedule_parachain_downgrade() -> Weight {
+		0
+	}
+	fn register_paras(_n: u32) -> Weight {
+		0
+	}
+	fn enact_transitions(_n: u32) -> Weight {
+		0
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	#[pallet::disable_frame_system_supertrait_check]
+	pub trait Config: configuration::Config + paras::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may schedule lifecycle transitions and onboard paras.
+		type GovernanceOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
+		/// The registrar through which paras are registered, upgraded and downgraded.
+		type Registrar: Registrar<AccountId = Self::AccountId>;
+
+		/// Provider of the genesis head and validation code preimages.
+		type Preimages: PreimageRecipient<Self::Hash>;
+
+		/// The maximum number of paras that can be onboarded in a single call.
+		#[pallet::constant]
+		type MaxParasPerBatch: Get<u32>;
+
+		/// The maximum number of transitions that can be scheduled for a single session.
+		#[pallet::constant]
+		type MaxTransitionsPerSession: Get<u32>;
+
+		/// Weight Information for the Extrinsics in the Pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A parathread is scheduled to be upgraded to a parachain at the start of `session`.
+		ParathreadUpgradeScheduled { para_id: ParaId, session: SessionIndex },
+		/// A parachain is scheduled to be downgraded to a parathread at the start of `session`.
+		ParachainDowngradeScheduled { para_id: ParaId, session: SessionIndex },
+		/// A para is scheduled to be onboarded.
+		OnboardingScheduled { para_id: ParaId, parachain: bool },
+		/// A transition scheduled for `session` could not be handed over to the registrar, e.g.
+		/// because the para changed its lifecycle in the meantime.
+		TransitionFailed { para_id: ParaId, session: SessionIndex },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The para is not a parathread.
+		NotParathread,
+		/// The para is not a parachain.
+		NotParachain,
+		/// The session is earlier than the next session at which transitions can be applied.
+		SessionTooEarly,
+		/// The para could not be upgraded to a parachain.
+		CannotUpgrade,
+		/// The para could not be downgraded to a parathread.
+		CannotDowngrade,
+		/// Too many paras were given for a single batch.
+		TooManyParas,
+		/// Too many transitions are already scheduled for the session.
+		TooManyTransitions,
+		/// A transition is already scheduled for the para at the session.
+		AlreadyScheduled,
+		/// The genesis head or validation code preimage is not available.
+		PreimageNotFound,
+		/// The validation code is empty.
+		EmptyCode,
+		/// The validation code exceeds the maximum allowed size.
+		CodeTooLarge,
+		/// The genesis head data exceeds the maximum allowed size.
+		HeadDataTooLarge,
+		/// The para is already registered.
+		AlreadyRegistered,
+		/// The public para ID has not been reserved by the manager.
+		NotReserved,
+	}
+
+	/// The transitions scheduled by governance, keyed by the session at which they are enacted.
+	///
+	/// They are handed over to the registrar once that session is the next one at which lifecycle
+	/// changes can be applied, so that paras keep their current lifecycle until then.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_transitions)]
+	pub type PendingTransitions<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		SessionIndex,
+		BoundedVec<(ParaId, ParaTransition), T::MaxTransitionsPerSession>,
+		ValueQuery,
+	>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+			let session = shared::Pallet::<T>::scheduled_session();
+			let transitions = PendingTransitions::<T>::take(session);
+			for (para_id, transition) in transitions.iter() {
+				if Self::hand_over(*para_id, *transition).is_err() {
+					Self::deposit_event(Event::<T>::TransitionFailed {
+						para_id: *para_id,
+						session,
+					});
+				}
+			}
+			<T as Config>::WeightInfo::enact_transitions(transitions.len() as u32)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Schedule a parathread to be upgraded to a parachain at the start of `session`.
+		///
+		/// The origin must be `GovernanceOrigin`. The session must not be earlier than the next
+		/// session at which lifecycle changes can be applied. The para stays a parathread until
+		/// `session` is that next session.
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_parathread_upgrade())]
+		pub fn schedule_parathread_upgrade(
+			origin: OriginFor<T>,
+			id: ParaId,
+			session: SessionIndex,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(T::Registrar::is_parathread(id), Error::<T>::NotParathread);
+			Self::schedule(id, ParaTransition::Upgrade, session)?;
+			Self::deposit_event(Event::<T>::ParathreadUpgradeScheduled { para_id: id, session });
+			Ok(())
+		}
+
+		/// Schedule a parachain to be downgraded to a parathread at the start of `session`.
+		///
+		/// The origin must be `GovernanceOrigin`. The session must not be earlier than the next
+		/// session at which lifecycle changes can be applied. The para stays a parachain until
+		/// `session` is that next session.
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_parachain_downgrade())]
+		pub fn schedule_parachain_downgrade(
+			origin: OriginFor<T>,
+			id: ParaId,
+			session: SessionIndex,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(T::Registrar::is_parachain(id), Error::<T>::NotParachain);
+			Self::schedule(id, ParaTransition::Downgrade, session)?;
+			Self::deposit_event(Event::<T>::ParachainDowngradeScheduled { para_id: id, session });
+			Ok(())
+		}
+
+		/// Onboard a batch of paras through the registrar, reading their genesis head and
+		/// validation code from the preimages referenced by `paras`.
+		///
+		/// The origin must be `GovernanceOrigin`. Each para is registered as a parathread under
+		/// its `manager`, who pays the usual deposit, and is upgraded to a parachain in the
+		/// session after its onboarding if requested. Either all paras are scheduled for
+		/// onboarding or, if any of them fails the checks, none of them are.
+		#[pallet::weight(<T as Config>::WeightInfo::register_paras(paras.len() as u32))]
+		pub fn register_paras(
+			origin: OriginFor<T>,
+			paras: Vec<ParaOnboarding<T::AccountId, T::Hash>>,
+		) -> DispatchResult {
+			T::GovernanceOrigin::ensure_origin(origin)?;
+			ensure!(paras.len() as u32 <= T::MaxParasPerBatch::get(), Error::<T>::TooManyParas);
+
+			let current_session = shared::Pallet::<T>::session_index();
+			let onboarding_session = shared::Pallet::<T>::scheduled_session();
+			// The first session at which a para onboarded now is a parathread that can be
+			// upgraded.
+			let upgrade_session =
+				onboarding_session.saturating_add(onboarding_session - current_session);
+			for onboarding in paras {
+				let (genesis_head, validation_code) = Self::onboarding_data(&onboarding)?;
+				T::Registrar::register(
+					onboarding.manager,
+					onboarding.id,
+					genesis_head,
+					validation_code,
+				)?;
+				if onboarding.parachain {
+					Self::schedule(onboarding.id, ParaTransition::Upgrade, upgrade_session)?;
+				}
+				Self::deposit_event(Event::<T>::OnboardingScheduled {
+					para_id: onboarding.id,
+					parachain: onboarding.parachain,
+				});
+			}
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The transitions held back for later sessions, ordered by session.
+	///
+	/// These are not yet known to `paras`, so runtimes add them to the transitions reported by
+	/// the `scheduled_para_transitions` runtime API.
+	pub fn scheduled_transitions() -> Vec<ScheduledParaTransition> {
+		let mut scheduled: Vec<_> = PendingTransitions::<T>::iter()
+			.flat_map(|(session, transitions)| {
+				transitions
+					.into_iter()
+					.map(move |(para_id, transition)| ScheduledParaTransition {
+						para_id,
+						session,
+						transition: match transition {
+							ParaTransition::Upgrade => ParaLifecycleTransition::UpgradeToParachain,
+							ParaTransition::Downgrade =>
+								ParaLifecycleTransition::DowngradeToParathread,
+						},
+					})
+			})
+			.collect();
+		scheduled.sort_by_key(|t| t.session);
+		scheduled
+	}
+
+	/// Schedule `transition` of `id` to be enacted at the start of `session`, handing it over to
+	/// the registrar right away if `session` is the next session at which it can be enacted.
+	fn schedule(id: ParaId, transition: ParaTransition, session: SessionIndex) -> DispatchResult {
+		let scheduled_session = shared::Pallet::<T>::scheduled_session();
+		ensure!(session >= scheduled_session, Error::<T>::SessionTooEarly);
+		if session == scheduled_session {
+			return Self::hand_over(id, transition)
+		}
+		PendingTransitions::<T>::try_mutate(session, |transitions| {
+			ensure!(!transitions.iter().any(|(para, _)| *para == id), Error::<T>::AlreadyScheduled);
+			transitions
+				.try_push((id, transition))
+				.map_err(|_| Error::<T>::TooManyTransitions)?;
+			Ok(())
+		})
+	}
+
+	/// Have the registrar enact `transition` of `id` at the next possible session.
+	fn hand_over(id: ParaId, transition: ParaTransition) -> DispatchResult {
+		match transition {
+			ParaTransition::Upgrade =>
+				T::Registrar::make_parachain(id).map_err(|_| Error::<T>::CannotUpgrade.into()),
+			ParaTransition::Downgrade =>
+				T::Registrar::make_parathread(id).map_err(|_| Error::<T>::CannotDowngrade.into()),
+		}
+	}
+
+	/// Fetch and validate the onboarding data of a para from the preimages.
+	fn onboarding_data(
+		onboarding: &ParaOnboarding<T::AccountId, T::Hash>,
+	) -> Result<(HeadData, ValidationCode), DispatchError> {
+		ensure!(
+			paras::Pallet::<T>::lifecycle(onboarding.id).is_none(),
+			Error::<T>::AlreadyRegistered
+		);
+		// Public IDs are handed out by the registrar, so they must have been reserved to not
+		// collide with the IDs it hands out later.
+		if onboarding.id >= LOWEST_PUBLIC_ID {
+			ensure!(
+				T::Registrar::manager_of(onboarding.id).as_ref() == Some(&onboarding.manager),
+				Error::<T>::NotReserved
+			);
+		}
+		let genesis_head = T::Preimages::get_preimage(&onboarding.genesis_head_hash)
+			.map(HeadData)
+			.ok_or(Error::<T>::PreimageNotFound)?;
+		let validation_code = T::Preimages::get_preimage(&onboarding.validation_code_hash)
+			.map(ValidationCode)
+			.ok_or(Error::<T>::PreimageNotFound)?;
+
+		let config = configuration::Pallet::<T>::config();
+		ensure!(!validation_code.0.is_empty(), Error::<T>::EmptyCode);
+		ensure!(validation_code.0.len() <= config.max_code_size as usize, Error::<T>::CodeTooLarge);
+		ensure!(
+			genesis_head.0.len() <= config.max_head_data_size as usize,
+			Error::<T>::HeadDataTooLarge
+		);
+
+		Ok((genesis_head, validation_code))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{paras_governance, paras_registrar, traits::Registrar as _};
+	use frame_support::{
+		assert_noop, assert_ok,
+		error::BadOrigin,
+		parameter_types,
+		traits::{GenesisBuild, OnFinalize, OnInitialize},
+		BoundedVec,
+	};
+	use frame_system::{limits, EnsureRoot};
+	use primitives::v2::{Balance, BlockNumber, Header};
+	use runtime_parachains::{origin, ParaLifecycle};
+	use sp_core::H256;
+	use sp_io::TestExternalities;
+	use sp_runtime::{
+		traits::{BlakeTwo256, Hash as HashT, IdentityLookup},
+		transaction_validity::TransactionPriority,
+		Perbill,
+	};
+	use sp_std::collections::btree_map::BTreeMap;
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Configuration: configuration::{Pallet, Call, Storage, Config<T>},
+			Parachains: paras::{Pallet, Call, Storage, Config, Event},
+			ParasShared: shared::{Pallet, Call, Storage},
+			Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>},
+			ParachainsOrigin: origin::{Pallet, Origin},
+			ParasGovernance: paras_governance::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+	where
+		Call: From<C>,
+	{
+		type Extrinsic = UncheckedExtrinsic;
+		type OverarchingCall = Call;
+	}
+
+	const NORMAL_RATIO: Perbill = Perbill::from_percent(75);
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+		pub BlockWeights: limits::BlockWeights =
+			frame_system::limits::BlockWeights::simple_max(1024);
+		pub BlockLength: limits::BlockLength =
+			limits::BlockLength::max_with_normal_ratio(4 * 1024 * 1024, NORMAL_RATIO);
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = BlockNumber;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type DbWeight = ();
+		type BlockWeights = BlockWeights;
+		type BlockLength = BlockLength;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u128>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: Balance = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u128;
+		type DustRemoval = ();
+		type Event = Event;
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	impl shared::Config for Test {}
+
+	impl origin::Config for Test {}
+
+	parameter_types! {
+		pub const ParasUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+	}
+
+	impl paras::Config for Test {
+		type Event = Event;
+		type WeightInfo = paras::TestWeightInfo;
+		type UnsignedPriority = ParasUnsignedPriority;
+		type NextSessionRotation = crate::mock::TestNextSessionRotation;
+	}
+
+	impl configuration::Config for Test {
+		type WeightInfo = configuration::TestWeightInfo;
+	}
+
+	pub struct NoSwap;
+	impl crate::traits::OnSwap for NoSwap {
+		fn on_swap(_: ParaId, _: ParaId) {}
+	}
+
+	parameter_types! {
+		pub const ParaDeposit: Balance = 10;
+		pub const DataDepositPerByte: Balance = 1;
+	}
+
+	impl paras_registrar::Config for Test {
+		type Event = Event;
+		type Origin = Origin;
+		type Currency = Balances;
+		type OnSwap = NoSwap;
+		type ParaDeposit = ParaDeposit;
+		type DataDepositPerByte = DataDepositPerByte;
+		type WeightInfo = paras_registrar::TestWeightInfo;
+	}
+
+	parameter_types! {
+		pub static Preimages: BTreeMap<H256, Vec<u8>> = BTreeMap::new();
+		pub const MaxParasPerBatch: u32 = 3;
+		pub const MaxTransitionsPerSession: u32 = 4;
+	}
+
+	pub struct TestPreimages;
+	impl PreimageProvider<H256> for TestPreimages {
+		fn have_preimage(hash: &H256) -> bool {
+			Preimages::get().contains_key(hash)
+		}
+		fn get_preimage(hash: &H256) -> Option<Vec<u8>> {
+			Preimages::get().get(hash).cloned()
+		}
+		fn preimage_requested(_: &H256) -> bool {
+			false
+		}
+		fn request_preimage(_: &H256) {}
+		fn unrequest_preimage(_: &H256) {}
+	}
+
+	impl PreimageRecipient<H256> for TestPreimages {
+		type MaxSize = frame_support::traits::ConstU32<1024>;
+		fn note_preimage(bytes: BoundedVec<u8, Self::MaxSize>) {
+			let bytes = bytes.into_inner();
+			let mut preimages = Preimages::get();
+			preimages.insert(BlakeTwo256::hash(&bytes), bytes);
+			Preimages::set(preimages);
+		}
+		fn unnote_preimage(hash: &H256) {
+			let mut preimages = Preimages::get();
+			preimages.remove(hash);
+			Preimages::set(preimages);
+		}
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type GovernanceOrigin = EnsureRoot<u64>;
+		type Registrar = Registrar;
+		type Preimages = TestPreimages;
+		type MaxParasPerBatch = MaxParasPerBatch;
+		type MaxTransitionsPerSession = MaxTransitionsPerSession;
+		type WeightInfo = TestWeightInfo;
+	}
+
+	pub fn new_test_ext() -> TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+		GenesisBuild::<Test>::assimilate_storage(
+			&configuration::GenesisConfig {
+				config: configuration::HostConfiguration {
+					max_code_size: 1024,
+					max_head_data_size: 32,
+					..Default::default()
+				},
+			},
+			&mut t,
+		)
+		.unwrap();
+
+		GenesisBuild::<Test>::assimilate_storage(
+			&paras_registrar::GenesisConfig { next_free_para_id: LOWEST_PUBLIC_ID },
+			&mut t,
+		)
+		.unwrap();
+
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 10_000), (2, 10_000)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+
+		t.into()
+	}
+
+	const BLOCKS_PER_SESSION: u32 = 3;
+
+	fn run_to_block(n: BlockNumber) {
+		// NOTE that this function only simulates modules of interest. Depending on new pallet may
+		// require adding it here.
+		assert!(System::block_number() < n);
+		while System::block_number() < n {
+			let b = System::block_number();
+
+			if System::block_number() > 1 {
+				System::on_finalize(System::block_number());
+			}
+			// Session change every 3 blocks.
+			if (b + 1) % BLOCKS_PER_SESSION == 0 {
+				shared::Pallet::<Test>::set_session_index(
+					shared::Pallet::<Test>::session_index() + 1,
+				);
+				Parachains::test_on_new_session();
+			}
+			System::set_block_number(b + 1);
+			System::on_initialize(System::block_number());
+			ParasGovernance::on_initialize(System::block_number());
+		}
+	}
+
+	fn run_to_session(n: BlockNumber) {
+		let block_number = n * BLOCKS_PER_SESSION;
+		run_to_block(block_number);
+	}
+
+	fn note_preimage(data: Vec<u8>) -> H256 {
+		let hash = BlakeTwo256::hash(&data);
+		TestPreimages::note_preimage(data.try_into().unwrap());
+		hash
+	}
+
+	fn onboarding(id: u32, parachain: bool) -> ParaOnboarding<u64, H256> {
+		ParaOnboarding {
+			id: id.into(),
+			manager: 1,
+			genesis_head_hash: note_preimage(vec![id as u8; 8]),
+			validation_code_hash: note_preimage(vec![id as u8; 64]),
+			parachain,
+		}
+	}
+
+	#[test]
+	fn register_paras_works() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			assert_noop!(
+				ParasGovernance::register_paras(Origin::signed(1), vec![onboarding(1, false)]),
+				BadOrigin
+			);
+			assert_ok!(ParasGovernance::register_paras(
+				Origin::root(),
+				vec![onboarding(1, false), onboarding(2, true)]
+			));
+			assert_eq!(Parachains::lifecycle(ParaId::from(1)), Some(ParaLifecycle::Onboarding));
+			assert_eq!(Parachains::lifecycle(ParaId::from(2)), Some(ParaLifecycle::Onboarding));
+			// The paras are registered with the registrar, under their manager.
+			assert_eq!(Registrar::manager_of(1.into()), Some(1));
+			assert_eq!(Registrar::manager_of(2.into()), Some(1));
+			assert!(Balances::reserved_balance(1) > 0);
+			assert_eq!(
+				ParasGovernance::pending_transitions(4).into_inner(),
+				vec![(ParaId::from(2), ParaTransition::Upgrade)]
+			);
+
+			run_to_session(2);
+			assert!(Parachains::is_parathread(1.into()));
+			assert!(Parachains::is_parathread(2.into()));
+			assert_eq!(Parachains::para_head(ParaId::from(2)), Some(HeadData(vec![2u8; 8])));
+
+			run_to_session(4);
+			assert!(Parachains::is_parathread(1.into()));
+			assert!(Parachains::is_parachain(2.into()));
+		});
+	}
+
+	#[test]
+	fn register_paras_requires_reserved_public_ids() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			let id = u32::from(LOWEST_PUBLIC_ID);
+			assert_noop!(
+				ParasGovernance::register_paras(Origin::root(), vec![onboarding(id, false)]),
+				Error::<Test>::NotReserved
+			);
+			assert_ok!(Registrar::reserve(Origin::signed(2)));
+			assert_noop!(
+				ParasGovernance::register_paras(Origin::root(), vec![onboarding(id, false)]),
+				Error::<Test>::NotReserved
+			);
+			let mut reserved = onboarding(id, false);
+			reserved.manager = 2;
+			assert_ok!(ParasGovernance::register_paras(Origin::root(), vec![reserved]));
+			assert_eq!(Registrar::manager_of(LOWEST_PUBLIC_ID), Some(2));
+
+			// The registrar keeps handing out fresh IDs.
+			assert_ok!(Registrar::reserve(Origin::signed(1)));
+			assert_eq!(Registrar::manager_of(LOWEST_PUBLIC_ID + 1), Some(1));
+		});
+	}
+
+	#[test]
+	fn register_paras_is_atomic() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			let mut missing_code = onboarding(2, false);
+			missing_code.validation_code_hash = H256::repeat_byte(42);
+			assert_noop!(
+				ParasGovernance::register_paras(
+					Origin::root(),
+					vec![onboarding(1, false), missing_code]
+				),
+				Error::<Test>::PreimageNotFound
+			);
+
+			let mut too_large_head = onboarding(3, false);
+			too_large_head.genesis_head_hash = note_preimage(vec![0u8; 33]);
+			assert_noop!(
+				ParasGovernance::register_paras(Origin::root(), vec![too_large_head]),
+				Error::<Test>::HeadDataTooLarge
+			);
+
+			assert_noop!(
+				ParasGovernance::register_paras(
+					Origin::root(),
+					(1..=4).map(|id| onboarding(id, false)).collect()
+				),
+				Error::<Test>::TooManyParas
+			);
+
+			assert_noop!(
+				ParasGovernance::register_paras(
+					Origin::root(),
+					vec![onboarding(1, false), onboarding(1, true)]
+				),
+				Error::<Test>::AlreadyRegistered
+			);
+		});
+	}
+
+	#[test]
+	fn lifecycle_transitions_happen_at_requested_session() {
+		new_test_ext().execute_with(|| {
+			run_to_block(1);
+
+			assert_ok!(ParasGovernance::register_paras(
+				Origin::root(),
+				vec![onboarding(1, false), onboarding(2, true)]
+			));
+			run_to_session(4);
+			assert!(Parachains::is_parathread(1.into()));
+			assert!(Parachains::is_parachain(2.into()));
+
+			let current = shared::Pallet::<Test>::session_index();
+			assert_noop!(
+				ParasGovernance::schedule_parathread_upgrade(Origin::root(), 1.into(), current + 1),
+				Error::<Test>::SessionTooEarly
+			);
+			assert_noop!(
+				ParasGovernance::schedule_parathread_upgrade(Origin::root(), 2.into(), current + 5),
+				Error::<Test>::NotParathread
+			);
+			assert_noop!(
+				ParasGovernance::schedule_parachain_downgrade(
+					Origin::root(),
+					1.into(),
+					current + 5
+				),
+				Error::<Test>::NotParachain
+			);
+
+			assert_ok!(ParasGovernance::schedule_parathread_upgrade(
+				Origin::root(),
+				1.into(),
+				current + 3
+			));
+			assert_noop!(
+				ParasGovernance::schedule_parathread_upgrade(Origin::root(), 1.into(), current + 3),
+				Error::<Test>::AlreadyScheduled
+			);
+			// The next possible session is handed over to the registrar right away.
+			assert_ok!(ParasGovernance::schedule_parachain_downgrade(
+				Origin::root(),
+				2.into(),
+				current + 2
+			));
+			assert_eq!(
+				Parachains::lifecycle(ParaId::from(2)),
+				Some(ParaLifecycle::DowngradingParachain)
+			);
+			// A later session leaves the para untouched until then.
+			assert_eq!(Parachains::lifecycle(ParaId::from(1)), Some(ParaLifecycle::Parathread));
+			assert_eq!(
+				ParasGovernance::pending_transitions(current + 3).into_inner(),
+				vec![(ParaId::from(1), ParaTransition::Upgrade)]
+			);
+			assert_eq!(
+				ParasGovernance::scheduled_transitions(),
+				vec![ScheduledParaTransition {
+					para_id: 1.into(),
+					session: current + 3,
+					transition: ParaLifecycleTransition::UpgradeToParachain,
+				}]
+			);
+
+			run_to_session(current + 1);
+			assert_eq!(
+				Parachains::lifecycle(ParaId::from(1)),
+				Some(ParaLifecycle::UpgradingParathread)
+			);
+			assert!(ParasGovernance::pending_transitions(current + 3).is_empty());
+
+			run_to_session(current + 2);
+			assert!(Parachains::is_parathread(2.into()));
+			assert!(Parachains::is_parathread(1.into()));
+
+			run_to_session(current + 3);
+			assert!(Parachains::is_parachain(1.into()));
+		});
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking {
+	use super::{Pallet as ParasGovernance, *};
+	use crate::paras_registrar;
+	use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+	use frame_support::{assert_ok, traits::Currency};
+	use frame_system::RawOrigin;
+	use runtime_parachains::ParaLifecycle;
+	use sp_runtime::traits::{Bounded, Hash, Zero};
+
+	fn funded_manager<T: paras_registrar::Config>(index: u32) -> T::AccountId {
+		let manager: T::AccountId = account("manager", index, 0);
+		<T as paras_registrar::Config>::Currency::make_free_balance_be(
+			&manager,
+			Bounded::max_value(),
+		);
+		manager
+	}
+
+	// Reserve the next public para ID for a fresh manager.
+	fn reserve_para<T: paras_registrar::Config>(index: u32) -> (T::AccountId, ParaId) {
+		let manager = funded_manager::<T>(index);
+		let para = paras_registrar::NextFreeParaId::<T>::get().max(LOWEST_PUBLIC_ID);
+		assert_ok!(paras_registrar::Pallet::<T>::reserve(
+			RawOrigin::Signed(manager.clone()).into()
+		));
+		(manager, para)
+	}
+
+	fn register_para<T: Config + paras_registrar::Config>(index: u32) -> ParaId {
+		let (manager, para) = reserve_para::<T>(index);
+		assert_ok!(T::Registrar::register(
+			manager,
+			para,
+			T::Registrar::worst_head_data(),
+			T::Registrar::worst_validation_code(),
+		));
+		para
+	}
+
+	benchmarks! {
+		where_clause { where T: paras_registrar::Config }
+
+		schedule_parathread_upgrade {
+			let para = register_para::<T>(0);
+			T::Registrar::execute_pending_transitions();
+			let session = shared::Pallet::<T>::scheduled_session();
+			let origin = T::GovernanceOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, para, session)
+		verify {
+			assert_eq!(paras::Pallet::<T>::lifecycle(para), Some(ParaLifecycle::UpgradingParathread));
+		}
+
+		schedule_parachain_downgrade {
+			let para = register_para::<T>(0);
+			T::Registrar::execute_pending_transitions();
+			assert_ok!(T::Registrar::make_parachain(para));
+			T::Registrar::execute_pending_transitions();
+			let session = shared::Pallet::<T>::scheduled_session();
+			let origin = T::GovernanceOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, para, session)
+		verify {
+			assert_eq!(paras::Pallet::<T>::lifecycle(para), Some(ParaLifecycle::DowngradingParachain));
+		}
+
+		register_paras {
+			let n in 1 .. T::MaxParasPerBatch::get();
+			let head = T::Registrar::worst_head_data().0;
+			let paras = (0..n).map(|i| {
+				let (manager, id) = reserve_para::<T>(i);
+				// Every para gets a distinct code, so that each one kicks off its own PVF check.
+				let mut code = T::Registrar::worst_validation_code().0;
+				code[..4].copy_from_slice(&i.to_le_bytes());
+				let genesis_head_hash = T::Hashing::hash(&head);
+				let validation_code_hash = T::Hashing::hash(&code);
+				T::Preimages::note_preimage(head.clone().try_into().unwrap());
+				T::Preimages::note_preimage(code.try_into().unwrap());
+				ParaOnboarding { id, manager, genesis_head_hash, validation_code_hash, parachain: true }
+			}).collect::<Vec<_>>();
+			let first = paras[0].id;
+			let origin = T::GovernanceOrigin::successful_origin();
+		}: _<<T as frame_system::Config>::Origin>(origin, paras)
+		verify {
+			assert_eq!(paras::Pallet::<T>::lifecycle(first), Some(ParaLifecycle::Onboarding));
+		}
+
+		enact_transitions {
+			let n in 0 .. T::MaxTransitionsPerSession::get();
+			let paras = (0..n).map(|i| register_para::<T>(i)).collect::<Vec<_>>();
+			T::Registrar::execute_pending_transitions();
+			let transitions = paras.iter().map(|para| (*para, ParaTransition::Upgrade)).collect::<Vec<_>>();
+			PendingTransitions::<T>::insert(
+				shared::Pallet::<T>::scheduled_session(),
+				BoundedVec::try_from(transitions).unwrap(),
+			);
+		}: {
+			ParasGovernance::<T>::on_initialize(Zero::zero());
+		}
+		verify {
+			for para in paras {
+				assert_eq!(paras::Pallet::<T>::lifecycle(para), Some(ParaLifecycle::UpgradingParathread));
+			}
+		}
+
+		impl_benchmark_test_suite!(
+			ParasGovernance,
+			crate::paras_governance::tests::new_test_ext(),
+			crate::paras_governance::tests::Test,
+		);
+	}
+}
@@ -65,8 +65,11 @@ impl Contains<Call> for BaseFilter {
 			Call::Hrmp(_) |
 			Call::Slots(_) |
 			Call::Registrar(_) |
+			Call::ParasGovernance(_) |
 			Call::XcmPallet(_) |
 			Call::VoterList(_) => true,
+			#[cfg(feature = "indranet")]
+			Call::ParasSudoWrapper(_) | Call::AssignedSlots(_) => true,
 		}
 	}
 }
@@ -135,6 +138,10 @@ impl InstanceFilter<Call> for ProxyType {
 	fn filter(&self, c: &Call) -> bool {
 		match self {
 			ProxyType::Any => true,
+			// Testnet-only pallets can't be listed in the `matches!` below, as an `or` pattern
+			// can't be feature-gated.
+			#[cfg(feature = "indranet")]
+			ProxyType::NonTransfer if matches!(c, Call::AssignedSlots(..)) => true,
 			ProxyType::NonTransfer => matches!(
 				c,
 				Call::System(..) |
@@ -187,6 +194,7 @@ impl InstanceFilter<Call> for ProxyType {
 					Call::PhragmenElection(..) |
 					Call::Treasury(..) | Call::Bounties(..) |
 					Call::Tips(..) | Call::Utility(..) |
+					Call::ParasGovernance(..) |
 					Call::Sudo(..)
 			),
 			ProxyType::Staking => {
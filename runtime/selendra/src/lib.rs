@@ -70,9 +70,11 @@ pub use pallet_staking::StakerStatus;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{CurrencyAdapter, FeeDetails, RuntimeDispatchInfo};
 
+#[cfg(feature = "indranet")]
+use runtime_common::{assigned_slots, paras_sudo_wrapper};
 use runtime_common::{
-	impl_runtime_weights, impls::DealWithFees, paras_registrar, prod_or_fast, slots,
-	BlockHashCount, BlockLength, CouncilInstance, CouncilMembershipInstance, CurrencyToVote,
+	impl_runtime_weights, impls::DealWithFees, paras_governance, paras_registrar, prod_or_fast,
+	slots, BlockHashCount, BlockLength, CouncilInstance, CouncilMembershipInstance, CurrencyToVote,
 	EnsureRootOrAllCouncil, EnsureRootOrAllTechnicalCommittee, EnsureRootOrHalfCouncil,
 	EnsureRootOrThreeFourthsCouncil, EnsureRootOrTwoThirdsCouncil,
 	EnsureRootOrTwoThirdsTechnicalCommittee, SlowAdjustingFeeUpdate, TechnicalCommitteeInstance,
@@ -92,8 +94,8 @@ use primitives::v2::{
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CandidateHash,
 	CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash, Id as ParaId,
 	InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption,
-	PersistedValidationData, ScrapedOnChainVotes, SessionInfo, Signature, ValidationCode,
-	ValidationCodeHash, ValidatorId, ValidatorIndex,
+	ParaUpgradeStatus, PersistedValidationData, ScheduledParaTransition, ScrapedOnChainVotes,
+	SessionInfo, Signature, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
 };
 
 /// Constant values used within the runtime.
@@ -1054,79 +1056,98 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
-construct_runtime! {
-	pub enum Runtime where
-		Block = Block,
-		NodeBlock = primitives::v2::Block,
-		UncheckedExtrinsic = UncheckedExtrinsic
-	{
-		// Basic stuff; balances is uncallable initially.
-		System: frame_system::{Pallet, Call, Storage, Config, Event<T>} = 0,
-		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 1,
-		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>} = 2,
-		Preimage: pallet_preimage::{Pallet, Call, Storage, Event<T>} = 4,
-
-		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 10,
-		TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>} = 14,
-
-		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 20,
-		Bounties: pallet_bounties::{Pallet, Call, Storage, Event<T>} = 21,
-		Tips: pallet_tips::{Pallet, Call, Storage, Event<T>} = 22,
-
-		Utility: pallet_utility::{Pallet, Call, Event} = 30,
-		Multisig: pallet_multisig::{Pallet, Call, Storage, Event<T>} = 31,
-		Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>} = 32,
-		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 33,
-		Indices: pallet_indices::{Pallet, Call, Storage, Config<T>, Event<T>} = 36,
-		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>} = 37,
-		Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>} = 38,
-
-		// Babe must be before session.
-		Authorship: pallet_authorship::{Pallet, Call, Storage} = 40,
-		Babe: pallet_babe::{Pallet, Call, Storage, Config, ValidateUnsigned} = 41,
-		Staking: pallet_staking::{Pallet, Call, Storage, Config<T>, Event<T>} = 42,
-		Offences: pallet_offences::{Pallet, Storage, Event} = 43,
-		Historical: session_historical::{Pallet} = 44,
-		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>} = 45,
-		Grandpa: pallet_grandpa::{Pallet, Call, Storage, Config, Event, ValidateUnsigned} = 46,
-		ImOnline: pallet_im_online::{Pallet, Call, Storage, Event<T>, ValidateUnsigned, Config<T>} = 47,
-		AuthorityDiscovery: pallet_authority_discovery::{Pallet, Config} = 48,
-		ElectionProviderMultiPhase: pallet_election_provider_multi_phase::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 49,
-		VoterList: pallet_bags_list::{Pallet, Call, Storage, Event<T>} = 50,
-		NominationPools: pallet_nomination_pools::{Pallet, Call, Storage, Event<T>, Config<T>} = 51,
-
-		// Governance stuff.
-		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 61,
-		CouncilMembership: pallet_membership::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 62,
-		TechnicalCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 65,
-		TechnicalMembership: pallet_membership::<Instance2>::{Pallet, Call, Storage, Event<T>, Config<T>} = 66,
-		PhragmenElection: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>, Config<T>} = 67,
-		Democracy: pallet_democracy::{Pallet, Call, Storage, Config<T>, Event<T>} = 68,
-
-		// Parachains pallets. Start indices at 70 to leave room.
-		ParachainsOrigin: parachains_origin::{Pallet, Origin} = 70,
-		Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>} = 71,
-		ParasShared: parachains_shared::{Pallet, Call, Storage} = 72,
-		ParaInclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>} = 73,
-		ParaInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent} = 74,
-		Parascheduler: parachains_scheduler::{Pallet, Storage} = 75,
-		Paras: parachains_paras::{Pallet, Call, Storage, Event, Config} = 76,
-		Initializer: parachains_initializer::{Pallet, Call, Storage} = 77,
-		Dmp: parachains_dmp::{Pallet, Call, Storage} = 78,
-		Ump: parachains_ump::{Pallet, Call, Storage, Event} = 79,
-		Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>, Config} = 80,
-		ParasessionInfo: parachains_session_info::{Pallet, Storage} = 81,
-		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 82,
-
-		// Parachain Onboarding Pallets. Start indices at 90 to leave room.
-		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>} = 90,
-		Slots: slots::{Pallet, Call, Storage, Event<T>} = 91,
-
-		// Pallet for sending XCM.
-		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 99,
-
-		Sudo: pallet_sudo::{Pallet, Call, Storage, Event<T>, Config<T>} = 200,
-	}
+/// Builds the runtime from the production pallets followed by `$testnet` pallets, so that
+/// testnet-only pallets are not part of the production runtime at all.
+macro_rules! construct_selendra_runtime {
+	($($testnet:tt)*) => {
+		construct_runtime! {
+			pub enum Runtime where
+				Block = Block,
+				NodeBlock = primitives::v2::Block,
+				UncheckedExtrinsic = UncheckedExtrinsic
+			{
+				// Basic stuff; balances is uncallable initially.
+				System: frame_system::{Pallet, Call, Storage, Config, Event<T>} = 0,
+				Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 1,
+				Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>} = 2,
+				Preimage: pallet_preimage::{Pallet, Call, Storage, Event<T>} = 4,
+
+				Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 10,
+				TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>} = 14,
+
+				Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 20,
+				Bounties: pallet_bounties::{Pallet, Call, Storage, Event<T>} = 21,
+				Tips: pallet_tips::{Pallet, Call, Storage, Event<T>} = 22,
+
+				Utility: pallet_utility::{Pallet, Call, Event} = 30,
+				Multisig: pallet_multisig::{Pallet, Call, Storage, Event<T>} = 31,
+				Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>} = 32,
+				Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 33,
+				Indices: pallet_indices::{Pallet, Call, Storage, Config<T>, Event<T>} = 36,
+				Identity: pallet_identity::{Pallet, Call, Storage, Event<T>} = 37,
+				Vesting: pallet_vesting::{Pallet, Call, Storage, Event<T>, Config<T>} = 38,
+
+				// Babe must be before session.
+				Authorship: pallet_authorship::{Pallet, Call, Storage} = 40,
+				Babe: pallet_babe::{Pallet, Call, Storage, Config, ValidateUnsigned} = 41,
+				Staking: pallet_staking::{Pallet, Call, Storage, Config<T>, Event<T>} = 42,
+				Offences: pallet_offences::{Pallet, Storage, Event} = 43,
+				Historical: session_historical::{Pallet} = 44,
+				Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>} = 45,
+				Grandpa: pallet_grandpa::{Pallet, Call, Storage, Config, Event, ValidateUnsigned} = 46,
+				ImOnline: pallet_im_online::{Pallet, Call, Storage, Event<T>, ValidateUnsigned, Config<T>} = 47,
+				AuthorityDiscovery: pallet_authority_discovery::{Pallet, Config} = 48,
+				ElectionProviderMultiPhase: pallet_election_provider_multi_phase::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 49,
+				VoterList: pallet_bags_list::{Pallet, Call, Storage, Event<T>} = 50,
+				NominationPools: pallet_nomination_pools::{Pallet, Call, Storage, Event<T>, Config<T>} = 51,
+
+				// Governance stuff.
+				Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 61,
+				CouncilMembership: pallet_membership::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 62,
+				TechnicalCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 65,
+				TechnicalMembership: pallet_membership::<Instance2>::{Pallet, Call, Storage, Event<T>, Config<T>} = 66,
+				PhragmenElection: pallet_elections_phragmen::{Pallet, Call, Storage, Event<T>, Config<T>} = 67,
+				Democracy: pallet_democracy::{Pallet, Call, Storage, Config<T>, Event<T>} = 68,
+
+				// Parachains pallets. Start indices at 70 to leave room.
+				ParachainsOrigin: parachains_origin::{Pallet, Origin} = 70,
+				Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>} = 71,
+				ParasShared: parachains_shared::{Pallet, Call, Storage} = 72,
+				ParaInclusion: parachains_inclusion::{Pallet, Call, Storage, Event<T>} = 73,
+				ParaInherent: parachains_paras_inherent::{Pallet, Call, Storage, Inherent} = 74,
+				Parascheduler: parachains_scheduler::{Pallet, Storage} = 75,
+				Paras: parachains_paras::{Pallet, Call, Storage, Event, Config} = 76,
+				Initializer: parachains_initializer::{Pallet, Call, Storage} = 77,
+				Dmp: parachains_dmp::{Pallet, Call, Storage} = 78,
+				Ump: parachains_ump::{Pallet, Call, Storage, Event} = 79,
+				Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>, Config} = 80,
+				ParasessionInfo: parachains_session_info::{Pallet, Storage} = 81,
+				ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 82,
+
+				// Parachain Onboarding Pallets. Start indices at 90 to leave room.
+				Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>} = 90,
+				Slots: slots::{Pallet, Call, Storage, Event<T>} = 91,
+				ParasGovernance: paras_governance::{Pallet, Call, Storage, Event<T>} = 92,
+
+				// Pallet for sending XCM.
+				XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 99,
+
+				Sudo: pallet_sudo::{Pallet, Call, Storage, Event<T>, Config<T>} = 200,
+
+				$($testnet)*
+			}
+		}
+	};
+}
+
+#[cfg(not(feature = "indranet"))]
+construct_selendra_runtime! {}
+
+// Testnet pallets, only part of the runtime with the `indranet` feature.
+#[cfg(feature = "indranet")]
+construct_selendra_runtime! {
+	ParasSudoWrapper: paras_sudo_wrapper::{Pallet, Call} = 250,
+	AssignedSlots: assigned_slots::{Pallet, Call, Storage, Event<T>, Config} = 251,
 }
 
 /// The address format for describing accounts.
@@ -1159,8 +1180,11 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
-	(),
+	Migrations,
 >;
+
+/// All migrations that will run on the next runtime upgrade.
+pub type Migrations = (parachains_configuration::migration::v3::MigrateToV3<Runtime>,);
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 
@@ -1170,47 +1194,61 @@ extern crate frame_benchmarking;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benches {
-	define_benchmarks!(
-		// Selendra
-		// NOTE: Make sure to prefix these with `runtime_common::` so
-		// the that path resolves correctly in the generated file.
-		[runtime_common::slots, Slots]
-		[runtime_common::paras_registrar, Registrar]
-		[runtime_parachains::configuration, Configuration]
-		[runtime_parachains::disputes, ParasDisputes]
-		[runtime_parachains::initializer, Initializer]
-		[runtime_parachains::paras, Paras]
-		[runtime_parachains::paras_inherent, ParaInherent]
-		[runtime_parachains::ump, Ump]
-		// Substrate
-		[pallet_bags_list, VoterList]
-		[pallet_balances, Balances]
-		[frame_benchmarking::baseline, Baseline::<Runtime>]
-		[pallet_bounties, Bounties]
-		[pallet_collective, Council]
-		[pallet_collective, TechnicalCommittee]
-		[pallet_democracy, Democracy]
-		[pallet_elections_phragmen, PhragmenElection]
-		[pallet_election_provider_multi_phase, ElectionProviderMultiPhase]
-		[frame_election_provider_support, ElectionProviderBench::<Runtime>]
-		[pallet_identity, Identity]
-		[pallet_im_online, ImOnline]
-		[pallet_indices, Indices]
-		[pallet_membership, TechnicalMembership]
-		[pallet_multisig, Multisig]
-		[pallet_offences, OffencesBench::<Runtime>]
-		[pallet_preimage, Preimage]
-		[pallet_proxy, Proxy]
-		[pallet_scheduler, Scheduler]
-		[pallet_session, SessionBench::<Runtime>]
-		[pallet_staking, Staking]
-		[frame_system, SystemBench::<Runtime>]
-		[pallet_timestamp, Timestamp]
-		[pallet_tips, Tips]
-		[pallet_treasury, Treasury]
-		[pallet_utility, Utility]
-		[pallet_vesting, Vesting]
-	);
+	/// Defines the benchmarks of the production pallets followed by the `$testnet` ones.
+	macro_rules! define_selendra_benchmarks {
+		($($testnet:tt)*) => {
+			define_benchmarks!(
+				// Selendra
+				// NOTE: Make sure to prefix these with `runtime_common::` so
+				// the that path resolves correctly in the generated file.
+				[runtime_common::slots, Slots]
+				[runtime_common::paras_registrar, Registrar]
+				[runtime_common::paras_governance, ParasGovernance]
+				[runtime_parachains::configuration, Configuration]
+				[runtime_parachains::disputes, ParasDisputes]
+				[runtime_parachains::initializer, Initializer]
+				[runtime_parachains::paras, Paras]
+				[runtime_parachains::paras_inherent, ParaInherent]
+				[runtime_parachains::ump, Ump]
+				// Substrate
+				[pallet_bags_list, VoterList]
+				[pallet_balances, Balances]
+				[frame_benchmarking::baseline, Baseline::<Runtime>]
+				[pallet_bounties, Bounties]
+				[pallet_collective, Council]
+				[pallet_collective, TechnicalCommittee]
+				[pallet_democracy, Democracy]
+				[pallet_elections_phragmen, PhragmenElection]
+				[pallet_election_provider_multi_phase, ElectionProviderMultiPhase]
+				[frame_election_provider_support, ElectionProviderBench::<Runtime>]
+				[pallet_identity, Identity]
+				[pallet_im_online, ImOnline]
+				[pallet_indices, Indices]
+				[pallet_membership, TechnicalMembership]
+				[pallet_multisig, Multisig]
+				[pallet_offences, OffencesBench::<Runtime>]
+				[pallet_preimage, Preimage]
+				[pallet_proxy, Proxy]
+				[pallet_scheduler, Scheduler]
+				[pallet_session, SessionBench::<Runtime>]
+				[pallet_staking, Staking]
+				[frame_system, SystemBench::<Runtime>]
+				[pallet_timestamp, Timestamp]
+				[pallet_tips, Tips]
+				[pallet_treasury, Treasury]
+				[pallet_utility, Utility]
+				[pallet_vesting, Vesting]
+				[pallet_xcm, XcmPalletBench::<Runtime>]
+				$($testnet)*
+			);
+		};
+	}
+
+	#[cfg(not(feature = "indranet"))]
+	define_selendra_benchmarks!();
+
+	#[cfg(feature = "indranet")]
+	define_selendra_benchmarks!([runtime_common::assigned_slots, AssignedSlots]);
 }
 
 #[cfg(not(feature = "disable-runtime-api"))]
@@ -1370,11 +1408,40 @@ sp_api::impl_runtime_apis! {
 			parachains_runtime_api_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
 
+		fn scheduled_para_transitions() -> Vec<ScheduledParaTransition> {
+			let mut transitions = parachains_runtime_api_impl::scheduled_para_transitions::<Runtime>();
+			transitions.extend(ParasGovernance::scheduled_transitions());
+			transitions.sort_by_key(|t| t.session);
+			transitions
+		}
+
+		fn para_upgrade_status(para_id: ParaId) -> Option<ParaUpgradeStatus<BlockNumber>> {
+			parachains_runtime_api_impl::para_upgrade_status::<Runtime>(para_id)
+		}
+
+		fn async_backing_params() -> AsyncBackingParams {
+			parachains_runtime_api_impl::async_backing_params::<Runtime>()
+		}
+
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			unimplemented!()
 		}
 	}
 
+	impl parachains_configuration::runtime_api::HostConfigurationApi<Block, AccountId, BlockNumber> for Runtime {
+		fn active_config() -> parachains_configuration::HostConfiguration<BlockNumber> {
+			Configuration::config()
+		}
+
+		fn pending_configs() -> Vec<(SessionIndex, parachains_configuration::HostConfiguration<BlockNumber>)> {
+			Configuration::pending_configs()
+		}
+
+		fn config_change_history() -> Vec<parachains_configuration::ConfigChangeRecord<AccountId, BlockNumber>> {
+			Configuration::config_change_history().into_inner()
+		}
+	}
+
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
 		fn validator_set() -> Option<beefy_primitives::ValidatorSet<BeefyId>> {
 			// dummy implementation due to lack of BEEFY pallet.
@@ -1562,6 +1629,65 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_xcm::runtime_api::XcmDryRunApi<Block, Call, Event, OriginCaller> for Runtime {
+		fn dry_run_call(
+			origin: OriginCaller,
+			call: Call,
+		) -> Result<
+			pallet_xcm::runtime_api::CallDryRunEffects<Event>,
+			pallet_xcm::runtime_api::XcmDryRunApiError,
+		> {
+			Ok(XcmPallet::dry_run_call(origin.into(), call))
+		}
+
+		fn dry_run_xcm(
+			origin_location: xcm::VersionedMultiLocation,
+			xcm: xcm::VersionedXcm<Call>,
+		) -> Result<
+			pallet_xcm::runtime_api::XcmDryRunEffects<Event>,
+			pallet_xcm::runtime_api::XcmDryRunApiError,
+		> {
+			XcmPallet::dry_run_xcm(origin_location, xcm)
+		}
+	}
+
+	impl pallet_xcm::runtime_api::XcmPaymentApi<Block> for Runtime {
+		fn query_acceptable_payment_assets(
+			xcm_version: xcm::Version,
+		) -> Result<Vec<xcm::VersionedMultiLocation>, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			XcmPallet::query_acceptable_payment_assets(xcm_version, vec![xcm_config::SelLocation::get()])
+		}
+
+		fn query_xcm_weight(
+			message: xcm::VersionedXcm<()>,
+		) -> Result<xcm::latest::Weight, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			XcmPallet::query_xcm_weight(message)
+		}
+
+		fn query_weight_to_asset_fee(
+			weight: xcm::latest::Weight,
+			asset: xcm::VersionedMultiLocation,
+		) -> Result<u128, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			type Trader = <xcm_config::XcmConfig as xcm_executor::Config>::Trader;
+			XcmPallet::query_weight_to_asset_fee::<Trader>(weight, asset)
+		}
+
+		fn query_delivery_fees(
+			destination: xcm::VersionedMultiLocation,
+			message: xcm::VersionedXcm<()>,
+		) -> Result<xcm::VersionedMultiAssets, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			XcmPallet::query_delivery_fees(destination, message)
+		}
+	}
+
+	impl pallet_xcm::runtime_api::TrappedAssetsApi<Block, BlockNumber> for Runtime {
+		fn trapped_assets(
+			origin: xcm::VersionedMultiLocation,
+		) -> Vec<(Hash, pallet_xcm::TrappedAssetsRecord<BlockNumber>)> {
+			XcmPallet::trapped_assets(origin)
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade() -> (Weight, Weight) {
@@ -1589,6 +1715,7 @@ sp_api::impl_runtime_apis! {
 			use pallet_election_provider_support_benchmarking::Pallet as ElectionProviderBench;
 			use frame_system_benchmarking::Pallet as SystemBench;
 			use frame_benchmarking::baseline::Pallet as Baseline;
+			use pallet_xcm::benchmarking::Pallet as XcmPalletBench;
 
 			let mut list = Vec::<BenchmarkList>::new();
 			list_benchmarks!(list, extra);
@@ -1603,7 +1730,8 @@ sp_api::impl_runtime_apis! {
 			Vec<frame_benchmarking::BenchmarkBatch>,
 			sp_runtime::RuntimeString,
 		> {
-			use frame_benchmarking::{Benchmarking, BenchmarkBatch, TrackedStorageKey};
+			use frame_benchmarking::{Benchmarking, BenchmarkBatch, BenchmarkError, TrackedStorageKey};
+			use xcm::latest::prelude::*;
 			// Trying to add benchmarks directly to some pallets caused cyclic dependency issues.
 			// To get around that, we separated the benchmarks into its own crate.
 			use pallet_session_benchmarking::Pallet as SessionBench;
@@ -1611,12 +1739,23 @@ sp_api::impl_runtime_apis! {
 			use pallet_election_provider_support_benchmarking::Pallet as ElectionProviderBench;
 			use frame_system_benchmarking::Pallet as SystemBench;
 			use frame_benchmarking::baseline::Pallet as Baseline;
+			use pallet_xcm::benchmarking::Pallet as XcmPalletBench;
 
 			impl pallet_session_benchmarking::Config for Runtime {}
 			impl pallet_offences_benchmarking::Config for Runtime {}
 			impl pallet_election_provider_support_benchmarking::Config for Runtime {}
 			impl frame_system_benchmarking::Config for Runtime {}
 			impl frame_benchmarking::baseline::Config for Runtime {}
+			impl pallet_xcm::benchmarking::Config for Runtime {
+				fn remote_lock_unlocker() -> Result<MultiLocation, BenchmarkError> {
+					Ok(X2(Parachain(1000), PalletInstance(31)).into())
+				}
+
+				fn remote_lock_owner() -> Result<MultiLocation, BenchmarkError> {
+					// Locks are only ever placed on the relay chain's asset from other chains.
+					Err(BenchmarkError::Skip)
+				}
+			}
 
 			let whitelist: Vec<TrackedStorageKey> = vec![
 				// Block Number
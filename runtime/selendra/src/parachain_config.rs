@@ -17,11 +17,18 @@
 ///! Parachain configuration for Selendra.
 use super::{
 	deposit, parameter_types, weights, xcm_config, Babe, Balances, EnsureRoot, Event, Historical,
-	Origin, ParasDisputes, Registrar, Runtime, Slots, TransactionPriority, DOLLARS, WEEKS,
+	Origin, ParasDisputes, Preimage, Registrar, Runtime, Slots, TransactionPriority, DOLLARS,
+	WEEKS,
 };
 
+#[cfg(feature = "indranet")]
+use frame_support::weights::Weight;
 use primitives::v2::{AccountId, Balance, BlockNumber};
-use runtime_common::{paras_registrar, prod_or_fast, slots, EnsureRootOrThreeFourthsCouncil};
+#[cfg(feature = "indranet")]
+use runtime_common::{assigned_slots, paras_sudo_wrapper, MAXIMUM_BLOCK_WEIGHT};
+use runtime_common::{
+	paras_governance, paras_registrar, prod_or_fast, slots, EnsureRootOrThreeFourthsCouncil,
+};
 
 use runtime_parachains::{
 	configuration as parachains_configuration, disputes as parachains_disputes,
@@ -71,7 +78,7 @@ impl parachains_ump::Config for Runtime {
 		crate::parachains_ump::XcmSink<xcm_executor::XcmExecutor<xcm_config::XcmConfig>, Runtime>;
 	type FirstMessageFactorPercent = FirstMessageFactorPercent;
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
-	type WeightInfo = parachains_ump::TestWeightInfo;
+	type WeightInfo = weights::runtime_parachains_ump::WeightInfo<Runtime>;
 }
 
 impl parachains_dmp::Config for Runtime {}
@@ -134,3 +141,62 @@ impl slots::Config for Runtime {
 	type ForceOrigin = EnsureRootOrThreeFourthsCouncil;
 	type WeightInfo = weights::runtime_common_slots::WeightInfo<Runtime>;
 }
+
+parameter_types! {
+	pub const MaxParasPerBatch: u32 = 10;
+	pub const MaxTransitionsPerSession: u32 = 32;
+}
+
+impl paras_governance::Config for Runtime {
+	type Event = Event;
+	type GovernanceOrigin = EnsureRootOrThreeFourthsCouncil;
+	type Registrar = Registrar;
+	type Preimages = Preimage;
+	type MaxParasPerBatch = MaxParasPerBatch;
+	type MaxTransitionsPerSession = MaxTransitionsPerSession;
+	type WeightInfo = weights::runtime_common_paras_governance::WeightInfo<Runtime>;
+}
+
+#[cfg(feature = "indranet")]
+impl paras_sudo_wrapper::Config for Runtime {}
+
+#[cfg(feature = "indranet")]
+parameter_types! {
+	pub const PermanentSlotLeasePeriodLength: u32 = 8;
+	pub const TemporarySlotLeasePeriodLength: u32 = 1;
+	pub const MaxTemporarySlotPerLeasePeriod: u32 = 5;
+}
+
+#[cfg(feature = "indranet")]
+impl assigned_slots::Config for Runtime {
+	type Event = Event;
+	type AssignSlotOrigin = EnsureRoot<AccountId>;
+	type Leaser = Slots;
+	type PermanentSlotLeasePeriodLength = PermanentSlotLeasePeriodLength;
+	type TemporarySlotLeasePeriodLength = TemporarySlotLeasePeriodLength;
+	type MaxTemporarySlotPerLeasePeriod = MaxTemporarySlotPerLeasePeriod;
+	type WeightInfo = AssignedSlotsWeightInfo;
+}
+
+/// Upper-bound weights for the assigned slots calls until the pallet benchmarks have been run
+/// against an `indranet` build and their output added under `weights`.
+#[cfg(feature = "indranet")]
+pub struct AssignedSlotsWeightInfo;
+#[cfg(feature = "indranet")]
+impl assigned_slots::WeightInfo for AssignedSlotsWeightInfo {
+	fn assign_perm_parachain_slot() -> Weight {
+		MAXIMUM_BLOCK_WEIGHT / 10
+	}
+	fn assign_temp_parachain_slot() -> Weight {
+		MAXIMUM_BLOCK_WEIGHT / 10
+	}
+	fn unassign_parachain_slot() -> Weight {
+		MAXIMUM_BLOCK_WEIGHT / 10
+	}
+	fn set_max_permanent_slots() -> Weight {
+		MAXIMUM_BLOCK_WEIGHT / 10
+	}
+	fn set_max_temporary_slots() -> Weight {
+		MAXIMUM_BLOCK_WEIGHT / 10
+	}
+}
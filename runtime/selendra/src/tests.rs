@@ -183,6 +183,324 @@ mod test_fees {
 	}
 }
 
+#[cfg(test)]
+mod test_ump {
+	use crate::{parachain_config::FirstMessageFactorPercent, *};
+	use frame_support::{
+		assert_ok,
+		dispatch::GetDispatchInfo,
+		traits::{GenesisBuild, OnFinalize},
+		weights::DispatchClass,
+	};
+	use parachains_configuration::HostConfiguration;
+	use primitives::v2::InherentData as ParachainsInherentData;
+	use runtime_parachains::ump::{WeightInfo as _, MAX_UPWARD_MESSAGE_SIZE_BOUND};
+	use sp_runtime::traits::Header as _;
+	use xcm::latest::prelude::*;
+
+	fn new_test_ext(config: HostConfiguration<BlockNumber>) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		GenesisBuild::<Runtime>::assimilate_storage(
+			&parachains_configuration::GenesisConfig { config },
+			&mut t,
+		)
+		.unwrap();
+		t.into()
+	}
+
+	/// An upward message with an encoded length of exactly `size` bytes.
+	fn transact_message(size: u32) -> Vec<u8> {
+		let message = |remark_len: usize| {
+			let call = Call::System(frame_system::Call::remark { remark: vec![0u8; remark_len] });
+			xcm::VersionedXcm::<Call>::from(Xcm::<Call>(vec![Transact {
+				origin_type: OriginKind::SovereignAccount,
+				require_weight_at_most: Weight::MAX,
+				call: call.encode().into(),
+			}]))
+			.encode()
+		};
+		// The length prefixes grow with the remark, so shrink it until the message fits.
+		let mut remark_len = size as usize;
+		loop {
+			let msg = message(remark_len);
+			if msg.len() <= size as usize {
+				assert_eq!(msg.len(), size as usize);
+				return msg
+			}
+			remark_len -= msg.len() - size as usize;
+		}
+	}
+
+	/// Fill the upward queue of `para` with copies of `msg` up to the configured limits, the way
+	/// accepted candidates would. Returns the number of queued messages.
+	fn fill_upward_queue(config: &HostConfiguration<BlockNumber>, para: ParaId, msg: &[u8]) -> u32 {
+		let count =
+			(config.max_upward_queue_size / msg.len() as u32).min(config.max_upward_queue_count);
+		parachains_ump::RelayDispatchQueues::<Runtime>::insert(
+			para,
+			vec![msg.to_vec(); count as usize],
+		);
+		parachains_ump::RelayDispatchQueueSize::<Runtime>::insert(
+			para,
+			(count, count * msg.len() as u32),
+		);
+		parachains_ump::NeedsDispatch::<Runtime>::mutate(|paras| {
+			if let Err(i) = paras.binary_search(&para) {
+				paras.insert(i, para);
+			}
+		});
+		count
+	}
+
+	fn queued_messages() -> u32 {
+		parachains_ump::RelayDispatchQueueSize::<Runtime>::iter_values()
+			.map(|(count, _)| count)
+			.sum()
+	}
+
+	/// Author a block on top of `parent` that only contains the paras inherent, which gives the
+	/// upward queues their share of the block. The inherent is applied as an extrinsic, so the
+	/// block weight includes both its pre-dispatch weight and the dispatched upward messages.
+	/// Returns the header of the new block and the weight charged for the inherent extrinsic.
+	fn author_block(parent: &Header) -> (Header, Weight) {
+		let number = parent.number() + 1;
+		System::initialize(&number, &parent.hash(), &Default::default());
+		let call = Call::ParaInherent(parachains_paras_inherent::Call::enter {
+			data: ParachainsInherentData {
+				bitfields: Vec::new(),
+				backed_candidates: Vec::new(),
+				disputes: Vec::new(),
+				parent_header: parent.clone(),
+			},
+		});
+		let inherent_weight = call.get_dispatch_info().weight +
+			BlockWeights::get().get(DispatchClass::Mandatory).base_extrinsic;
+		assert_ok!(Executive::apply_extrinsic(UncheckedExtrinsic::new_unsigned(call)).unwrap());
+		ParaInherent::on_finalize(number);
+		let header = Header::new(
+			number,
+			Default::default(),
+			Default::default(),
+			parent.hash(),
+			Default::default(),
+		);
+		(header, inherent_weight)
+	}
+
+	#[test]
+	fn full_upward_queues_never_overflow_block_weight() {
+		let config = HostConfiguration {
+			max_upward_queue_count: 64,
+			max_upward_queue_size: 1024 * 1024,
+			max_upward_message_size: MAX_UPWARD_MESSAGE_SIZE_BOUND,
+			max_upward_message_num_per_candidate: 64,
+			ump_service_total_weight: 100_000_000_000,
+			ump_max_individual_weight: 20_000_000_000,
+			..Default::default()
+		};
+		let max_block = BlockWeights::get().max_block;
+		let max_dispatch_weight =
+			config.ump_service_total_weight * FirstMessageFactorPercent::get().max(100) / 100;
+
+		new_test_ext(config.clone()).execute_with(|| {
+			let msg = transact_message(config.max_upward_message_size);
+			let queued: u32 =
+				(2000..2010).map(|id| fill_upward_queue(&config, ParaId::from(id), &msg)).sum();
+			assert!(queued > 0);
+			assert_eq!(queued_messages(), queued);
+
+			// Every block has to make progress, and the dispatched messages have to be charged
+			// to the block without overflowing it.
+			let mut parent = Header::new(
+				0,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			);
+			let mut blocks = 0;
+			while !parachains_ump::NeedsDispatch::<Runtime>::get().is_empty() {
+				let before = queued_messages();
+				let (header, inherent_weight) = author_block(&parent);
+				parent = header;
+				let dispatched = before - queued_messages();
+				assert!(dispatched > 0);
+
+				let round_weight =
+					<Runtime as parachains_ump::Config>::WeightInfo::process_pending_upward_messages(
+						dispatched,
+					);
+				let block_weight = System::block_weight();
+				let ump_weight =
+					block_weight.get(DispatchClass::Mandatory).saturating_sub(inherent_weight);
+				assert!(ump_weight >= round_weight);
+				assert!(ump_weight <= max_dispatch_weight + round_weight);
+				assert!(block_weight.total() <= max_block);
+
+				blocks += 1;
+				assert!(blocks <= queued);
+			}
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_xcm {
+	use crate::{xcm_config::SafeUserXcm, *};
+	use codec::Encode;
+	use frame_support::{assert_noop, assert_ok, traits::Contains};
+	use keyring::Sr25519Keyring::Alice;
+	use xcm::latest::prelude::*;
+
+	fn account(who: &AccountId) -> MultiLocation {
+		Junction::AccountId32 { network: NetworkId::Any, id: who.clone().into() }.into()
+	}
+
+	fn transact() -> Instruction<Call> {
+		let call = Call::System(frame_system::Call::remark { remark: vec![] });
+		Transact {
+			origin_type: OriginKind::Superuser,
+			require_weight_at_most: 1_000_000_000,
+			call: call.encode().into(),
+		}
+	}
+
+	fn transfer() -> Xcm<Call> {
+		Xcm(vec![
+			WithdrawAsset((Here, 100u128).into()),
+			BuyExecution { fees: (Here, 100u128).into(), weight_limit: Unlimited },
+			DepositReserveAsset {
+				assets: All.into(),
+				max_assets: 1,
+				dest: Parachain(2000).into(),
+				xcm: Xcm(vec![ClearOrigin]),
+			},
+		])
+	}
+
+	#[test]
+	fn accounts_can_only_move_assets() {
+		let alice = account(&Alice.to_account_id());
+		assert!(SafeUserXcm::contains(&(alice.clone(), transfer())));
+
+		let spoofs: Vec<Xcm<Call>> = vec![
+			Xcm(vec![transact()]),
+			Xcm(vec![SetAppendix(Xcm(vec![transact()]))]),
+			Xcm(vec![ReportError {
+				query_id: 0,
+				dest: Parachain(2000).into(),
+				max_response_weight: 0,
+			}]),
+			Xcm(vec![QueryResponse { query_id: 0, response: Response::Null, max_weight: 0 }]),
+			Xcm(vec![HrmpChannelAccepted { recipient: 2000 }]),
+			Xcm(vec![DepositReserveAsset {
+				assets: All.into(),
+				max_assets: 1,
+				dest: Parachain(2000).into(),
+				xcm: Xcm(vec![QueryHolding {
+					query_id: 0,
+					dest: Parent.into(),
+					assets: All.into(),
+					max_response_weight: 0,
+				}]),
+			}]),
+		];
+		for message in spoofs {
+			assert!(!SafeUserXcm::contains(&(alice.clone(), message)));
+		}
+
+		// The council is not restricted.
+		let council: MultiLocation =
+			Plurality { id: BodyId::Executive, part: BodyPart::Voice }.into();
+		assert!(SafeUserXcm::contains(&(council, Xcm(vec![transact()]))));
+	}
+
+	#[test]
+	fn accounts_cannot_send_or_execute_unsafe_xcm() {
+		let mut t: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into();
+		t.execute_with(|| {
+			System::set_block_number(1);
+			let origin = Origin::signed(Alice.to_account_id());
+			assert_noop!(
+				XcmPallet::execute(
+					origin.clone(),
+					Box::new(VersionedXcm::from(Xcm(vec![transact()]))),
+					1_000_000_000,
+				),
+				pallet_xcm::Error::<Runtime>::Filtered
+			);
+			let report = Xcm::<()>(vec![ReportError {
+				query_id: 0,
+				dest: Parent.into(),
+				max_response_weight: 0,
+			}]);
+			assert_noop!(
+				XcmPallet::send(
+					origin.clone(),
+					Box::new(Parachain(2000).into().into()),
+					Box::new(VersionedXcm::from(report)),
+				),
+				pallet_xcm::Error::<Runtime>::Filtered
+			);
+			// A permitted message is still charged for before it is sent.
+			assert_noop!(
+				XcmPallet::send(
+					origin,
+					Box::new(Parachain(2000).into().into()),
+					Box::new(VersionedXcm::from(Xcm::<()>(vec![ClearOrigin]))),
+				),
+				pallet_xcm::Error::<Runtime>::FeesNotMet
+			);
+			// So is a permitted message sent by executing a transfer.
+			assert_noop!(
+				XcmPallet::execute(
+					origin.clone(),
+					Box::new(VersionedXcm::from(transfer())),
+					1_000_000_000,
+				),
+				pallet_xcm::Error::<Runtime>::FeesNotMet
+			);
+			// A permitted message which sends nothing is free.
+			assert_ok!(XcmPallet::execute(
+				origin,
+				Box::new(VersionedXcm::from(Xcm(vec![ClearOrigin]))),
+				1_000_000_000,
+			));
+			assert!(matches!(
+				System::events().last().map(|r| &r.event),
+				Some(Event::XcmPallet(pallet_xcm::Event::Attempted(Outcome::Complete(_))))
+			));
+		});
+	}
+
+	#[test]
+	fn only_safe_calls_can_be_transacted() {
+		use crate::xcm_config::SafeCallFilter;
+
+		let para = ParaId::from(2000);
+		let safe = vec![
+			Call::Hrmp(parachains_hrmp::Call::hrmp_accept_open_channel { sender: para }),
+			Call::Registrar(paras_registrar::Call::deregister { id: para }),
+			Call::Registrar(paras_registrar::Call::swap { id: para, other: 2001.into() }),
+		];
+		for call in safe {
+			assert!(SafeCallFilter::contains(&call));
+		}
+
+		let unsafe_calls = vec![
+			Call::System(frame_system::Call::remark { remark: vec![] }),
+			Call::Registrar(paras_registrar::Call::reserve {}),
+			Call::Registrar(paras_registrar::Call::force_remove_lock { para }),
+		];
+		for call in unsafe_calls {
+			assert!(!SafeCallFilter::contains(&call));
+		}
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use crate::*;
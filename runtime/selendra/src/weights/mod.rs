@@ -42,7 +42,9 @@ pub mod pallet_tips;
 pub mod pallet_treasury;
 pub mod pallet_utility;
 pub mod pallet_vesting;
+pub mod pallet_xcm;
 
+pub mod runtime_common_paras_governance;
 pub mod runtime_common_paras_registrar;
 pub mod runtime_common_slots;
 pub mod runtime_parachains_configuration;
@@ -51,3 +53,4 @@ pub mod runtime_parachains_hrmp;
 pub mod runtime_parachains_initializer;
 pub mod runtime_parachains_paras;
 pub mod runtime_parachains_paras_inherent;
+pub mod runtime_parachains_ump;
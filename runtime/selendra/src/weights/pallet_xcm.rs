@@ -0,0 +1,48 @@
+//! Weights for `pallet_xcm`
+//!
+//! Until the pallet's benchmarks are run against the production machine, these weights are
+//! composed from the benchmarked weights of similar operations: `lock_asset` from
+//! `pallet_balances::transfer_keep_alive` (paying the delivery fee and setting the lock) and
+//! `runtime_parachains::hrmp::hrmp_init_open_channel` (queueing a downward message), and
+//! `unlock_asset` from `pallet_balances::force_unreserve`, plus the reads and writes the pallet
+//! does on top of them.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `pallet_xcm`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_xcm::WeightInfo for WeightInfo<T> {
+	// Storage: System Account (r:3 w:3)
+	// Storage: Authorship Author (r:1 w:0)
+	// Storage: XcmPallet RemoteLockedFungibles (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: XcmPallet SupportedVersion (r:1 w:0)
+	// Storage: XcmPallet VersionDiscoveryQueue (r:1 w:1)
+	// Storage: XcmPallet SafeXcmVersion (r:1 w:0)
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: Dmp DownwardMessageQueueHeads (r:1 w:1)
+	// Storage: Dmp DownwardMessageQueues (r:1 w:1)
+	fn lock_asset() -> Weight {
+		(149_524_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(12 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+	// Storage: XcmPallet RemoteLockedFungibles (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn unlock_asset() -> Weight {
+		(46_957_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// No storage is accessed, the call only deposits an event. It is rejected by the
+	// `NoteUnlockableOrigin` of this chain.
+	fn note_unlockable() -> Weight {
+		(10_000_000 as Weight)
+	}
+}
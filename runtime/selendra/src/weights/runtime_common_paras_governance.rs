@@ -0,0 +1,69 @@
+//! Weights for `runtime_common::paras_governance`
+//!
+//! The pallet hands every registration and lifecycle transition over to the registrar, so until
+//! its own benchmarks are run against the production machine these weights are composed from the
+//! benchmarked weights of `runtime_common::paras_registrar`, plus the reads and writes the pallet
+//! does on top of them.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for `runtime_common::paras_governance`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> runtime_common::paras_governance::WeightInfo for WeightInfo<T> {
+	// Storage: Paras ParaLifecycles (r:1 w:1)
+	// Storage: ParasShared CurrentSessionIndex (r:1 w:0)
+	// Storage: Paras ActionsQueue (r:1 w:1)
+	// Storage: Registrar Paras (r:1 w:1)
+	// Storage: ParasGovernance PendingTransitions (r:1 w:1)
+	fn schedule_parathread_upgrade() -> Weight {
+		(150_196_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: Paras ParaLifecycles (r:1 w:1)
+	// Storage: ParasShared CurrentSessionIndex (r:1 w:0)
+	// Storage: Paras ActionsQueue (r:1 w:1)
+	// Storage: ParasGovernance PendingTransitions (r:1 w:1)
+	fn schedule_parachain_downgrade() -> Weight {
+		(150_196_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	// Storage: ParasShared CurrentSessionIndex (r:1 w:0)
+	// Storage: Preimage PreimageFor (r:2 w:0)
+	// Storage: Registrar Paras (r:2 w:1)
+	// Storage: Paras ParaLifecycles (r:2 w:1)
+	// Storage: Configuration ActiveConfig (r:2 w:0)
+	// Storage: Paras PvfActiveVoteMap (r:1 w:0)
+	// Storage: Paras CodeByHash (r:1 w:1)
+	// Storage: Paras ActionsQueue (r:1 w:1)
+	// Storage: Paras CodeByHashRefs (r:1 w:1)
+	// Storage: Paras CurrentCodeHash (r:0 w:1)
+	// Storage: Paras UpcomingParasGenesis (r:0 w:1)
+	// Storage: ParasGovernance PendingTransitions (r:1 w:1)
+	fn register_paras(n: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((16_079_555_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((13 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((8 as Weight).saturating_mul(n as Weight)))
+	}
+	// Storage: ParasShared CurrentSessionIndex (r:1 w:0)
+	// Storage: ParasGovernance PendingTransitions (r:1 w:1)
+	// Storage: Paras ParaLifecycles (r:1 w:1)
+	// Storage: Paras ActionsQueue (r:1 w:1)
+	// Storage: Registrar Paras (r:1 w:1)
+	fn enact_transitions(n: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((150_196_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+}
@@ -36,6 +36,21 @@ impl<T: frame_system::Config> runtime_parachains::ump::WeightInfo for WeightInfo
 			// Standard Error: 0
 			.saturating_add((4_000 as Weight).saturating_mul(s as Weight))
 	}
+	// NOTE: Not yet generated by the benchmark CLI. Composed from `clean_ump_after_outgoing` for
+	// the round and `process_upward_message` for each served queue until the next run.
+	// Storage: Configuration ActiveConfig (r:1 w:0)
+	// Storage: Ump NeedsDispatch (r:1 w:1)
+	// Storage: Ump NextDispatchRoundStartWith (r:1 w:1)
+	// Storage: Ump RelayDispatchQueues (r:1 w:1)
+	// Storage: Ump RelayDispatchQueueSize (r:1 w:1)
+	fn process_pending_upward_messages(n: u32, ) -> Weight {
+		(17_998_000 as Weight)
+			.saturating_add((14_561_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(n as Weight)))
+	}
 	// Storage: Ump NeedsDispatch (r:1 w:1)
 	// Storage: Ump NextDispatchRoundStartWith (r:1 w:1)
 	// Storage: Ump RelayDispatchQueues (r:0 w:1)
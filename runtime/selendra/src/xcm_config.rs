@@ -17,22 +17,26 @@
 //! XCM configuration for Selendra.
 
 use super::{
-	parachains_origin, AccountId, Balances, Call, CouncilInstance, Event, Origin, ParaId, Runtime,
-	WeightToFee, XcmPallet,
+	parachains_origin, weights, AccountId, Balance, Balances, BlockNumber, Call, CouncilInstance,
+	Event, Origin, ParaId, Runtime, WeightToFee, XcmPallet, CENTS, DAYS, MILLICENTS,
 };
 use frame_support::{
 	match_types, parameter_types,
-	traits::{Everything, Nothing},
+	traits::{Contains, Everything, Nothing},
 	weights::Weight,
 };
-use runtime_common::{impls::ToAuthor, xcm_sender};
+use runtime_common::{
+	impls::{DealWithFees, ToAuthor},
+	xcm_sender,
+};
 use xcm::latest::prelude::*;
 use xcm_builder::{
-	AccountId32Aliases, AllowKnownQueryResponses, AllowSubscriptionsFrom,
-	AllowTopLevelPaidExecutionFrom, BackingToPlurality, ChildParachainAsNative,
-	ChildParachainConvertsVia, CurrencyAdapter as XcmCurrencyAdapter, FixedWeightBounds,
-	IsConcrete, LocationInverter, SignedAccountId32AsNative, SignedToAccountId32,
-	SovereignSignedViaLocation, TakeWeightCredit, UsingComponents,
+	AccountId32Aliases, AllowCallsFromPallets, AllowKnownQueryResponses, AllowNamedCalls,
+	AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom, BackingToPlurality,
+	ChildParachainAsNative, ChildParachainConvertsVia, CurrencyAdapter as XcmCurrencyAdapter,
+	DescribeAllTerminal, DescribeFamily, FixedWeightBounds, HashedDescription, IsConcrete,
+	LocationInverter, SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation,
+	TakeWeightCredit, UsingComponents, WithComputedOrigin,
 };
 
 parameter_types! {
@@ -56,6 +60,8 @@ pub type SovereignAccountOf = (
 	ChildParachainConvertsVia<ParaId, AccountId>,
 	// We can directly alias an `AccountId32` into a local account.
 	AccountId32Aliases<SelendraNetwork, AccountId>,
+	// Accounts and pallets within other chains get a derived account of their own.
+	HashedDescription<AccountId, DescribeFamily<DescribeAllTerminal>>,
 );
 
 /// Our asset transactor. This is what allows us to interact with the runtime assets from the point of
@@ -88,6 +94,9 @@ type LocalOriginConverter = (
 	// If the origin kind is `Native` and the XCM origin is the `AccountId32` location, then it can
 	// be expressed using the `Signed` origin variant.
 	SignedAccountId32AsNative<SelendraNetwork, Origin>,
+	// If the origin kind is `Xcm` and the XCM origin is a pallet of a child parachain, then it is
+	// passed through as a `pallet_xcm::Origin::Xcm`, to release the locks held on its behalf.
+	pallet_xcm::XcmPassthrough<Origin, RemoteLockUnlockers>,
 );
 
 parameter_types! {
@@ -96,14 +105,19 @@ parameter_types! {
 	/// Maximum number of instructions in a single XCM fragment. A sanity check against weight
 	/// calculations getting too crazy.
 	pub const MaxInstructions: u32 = 100;
+	/// The maximum number of `DescendOrigin` instructions a message may start with.
+	pub const MaxOriginPrefixes: u32 = 8;
 }
 
 /// The XCM router. When we want to send an XCM message, we use this type. It amalgamates all of our
-/// individual routers.
-pub type XcmRouter = (
-	// Only one router so far - use DMP to communicate with child parachains.
-	xcm_sender::ChildParachainRouter<Runtime, XcmPallet>,
-);
+/// individual routers. The messages sent while dry-running are recorded by `XcmPallet`.
+pub type XcmRouter = pallet_xcm::RecordingRouter<
+	Runtime,
+	(
+		// Only one router so far - use DMP to communicate with child parachains.
+		xcm_sender::ChildParachainRouter<Runtime, XcmPallet>,
+	),
+>;
 
 parameter_types! {
 	pub const Selendra: MultiAssetFilter = Wild(AllOf { fun: WildFungible, id: Concrete(SelLocation::get()) });
@@ -117,25 +131,46 @@ match_types! {
 	pub type OnlyParachains: impl Contains<MultiLocation> = {
 		MultiLocation { parents: 0, interior: X1(Parachain(_)) }
 	};
+	pub type RemoteLockUnlockers: impl Contains<MultiLocation> = {
+		MultiLocation { parents: 0, interior: X2(Parachain(_), PalletInstance(_)) }
+	};
 }
 
 /// The barriers one of which must be passed for an XCM message to be executed.
 pub type Barrier = (
 	// Weight that is paid for may be consumed.
 	TakeWeightCredit,
-	// If the message is one that immediately attemps to pay for execution, then allow it.
-	AllowTopLevelPaidExecutionFrom<Everything>,
+	// If the message is one that immediately attemps to pay for execution, then allow it. The
+	// origin may first descend into e.g. an account of the sending chain.
+	WithComputedOrigin<AllowTopLevelPaidExecutionFrom<Everything>, MaxOriginPrefixes>,
 	// Expected responses are OK.
 	AllowKnownQueryResponses<XcmPallet>,
 	// Subscriptions for version tracking are OK.
 	AllowSubscriptionsFrom<OnlyParachains>,
 );
 
+parameter_types! {
+	/// The pallets all calls of which may be dispatched through XCM `Transact`.
+	pub const SafeCallPallets: &'static [&'static str] = &["Staking", "Hrmp"];
+	/// The other calls which may be dispatched through XCM `Transact`: a parachain managing its own
+	/// registration, and remote asset locking.
+	pub const SafeNamedCalls: &'static [(&'static str, &'static str)] = &[
+		("Registrar", "deregister"),
+		("Registrar", "swap"),
+		("XcmPallet", "lock_asset"),
+		("XcmPallet", "unlock_asset"),
+	];
+}
+
+/// The calls which may be dispatched through XCM `Transact`.
+pub type SafeCallFilter = (AllowCallsFromPallets<SafeCallPallets>, AllowNamedCalls<SafeNamedCalls>);
+
 pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
 	type Call = Call;
 	type XcmSender = XcmRouter;
-	type AssetTransactor = LocalAssetTransactor;
+	// The asset changes made while dry-running are recorded by `XcmPallet`.
+	type AssetTransactor = pallet_xcm::RecordingTransactor<Runtime, LocalAssetTransactor>;
 	type OriginConverter = LocalOriginConverter;
 	// Selendra Relay recognises no chains which act as reserves.
 	type IsReserve = ();
@@ -149,12 +184,21 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = XcmPallet;
 	type AssetClaims = XcmPallet;
 	type SubscriptionService = XcmPallet;
+	type Tracer = XcmPallet;
+	type SafeCallFilter = SafeCallFilter;
 }
 
 parameter_types! {
 	pub const CouncilBodyId: BodyId = BodyId::Executive;
 	// We are conservative with the XCM version we advertize.
 	pub const AdvertisedXcmVersion: u32 = 2;
+	pub const MaxAssetTrapsPerOrigin: u32 = 64;
+	pub const AssetTrapExpiry: BlockNumber = 90 * DAYS;
+	pub const MaxRemoteLockConsumers: u32 = 16;
+	// The weight of `note_unlockable` on the chains of the unlockers. This is a safe overestimate.
+	pub const NoteUnlockableWeight: Weight = 1_000_000_000;
+	pub const DeliveryFeeBase: Balance = 3 * CENTS;
+	pub const DeliveryFeePerByte: Balance = 10 * MILLICENTS;
 }
 
 /// Type to convert a council origin to a Plurality `MultiLocation` value.
@@ -171,15 +215,53 @@ pub type LocalOriginToLocation = (
 	SignedToAccountId32<Origin, AccountId, SelendraNetwork>,
 );
 
+/// The XCM programs which may be sent or executed by local origins.
+///
+/// Accounts may only move assets, pay for execution and handle errors. In particular they may not
+/// `Transact`, nor have the executing chain report a query response or an HRMP or version
+/// notification in its own name. Other local origins, i.e. the council, are not restricted.
+pub struct SafeUserXcm;
+impl<RuntimeCall> Contains<(MultiLocation, Xcm<RuntimeCall>)> for SafeUserXcm {
+	fn contains((origin, message): &(MultiLocation, Xcm<RuntimeCall>)) -> bool {
+		match origin {
+			MultiLocation { parents: 0, interior: X1(Junction::AccountId32 { .. }) } =>
+				is_safe_user_xcm(message),
+			_ => true,
+		}
+	}
+}
+
+fn is_safe_user_xcm<RuntimeCall>(message: &Xcm<RuntimeCall>) -> bool {
+	message.0.iter().all(|instruction| match instruction {
+		WithdrawAsset(..) |
+		TransferAsset { .. } |
+		BuyExecution { .. } |
+		RefundSurplus |
+		ClearOrigin |
+		DescendOrigin(..) |
+		DepositAsset { .. } |
+		ExchangeAsset { .. } |
+		ClaimAsset { .. } |
+		Trap(..) => true,
+		TransferReserveAsset { xcm, .. } |
+		DepositReserveAsset { xcm, .. } |
+		InitiateReserveWithdraw { xcm, .. } |
+		InitiateTeleport { xcm, .. } => is_safe_user_xcm(xcm),
+		SetErrorHandler(xcm) | SetAppendix(xcm) => is_safe_user_xcm(xcm),
+		_ => false,
+	})
+}
+
 impl pallet_xcm::Config for Runtime {
 	type Event = Event;
-	// Only allow the council to send messages.
-	type SendXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, CouncilToPlurality>;
+	// The council and signed accounts can send messages, which are always attributed to them.
+	type SendXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
 	type XcmRouter = XcmRouter;
 	// Anyone can execute XCM messages locally...
 	type ExecuteXcmOrigin = xcm_builder::EnsureXcmOrigin<Origin, LocalOriginToLocation>;
-	// ...but they must match our filter, which rejects all.
-	type XcmExecuteFilter = Nothing; // == Deny All
+	// ...but the messages of accounts must only use safe instructions.
+	type XcmExecuteFilter = SafeUserXcm;
+	type XcmSendFilter = SafeUserXcm;
 	type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = Everything; // == Allow All
 	type XcmReserveTransferFilter = Everything; // == Allow All
@@ -189,4 +271,17 @@ impl pallet_xcm::Config for Runtime {
 	type Call = Call;
 	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
 	type AdvertisedXcmVersion = AdvertisedXcmVersion;
+	type MaxAssetTrapsPerOrigin = MaxAssetTrapsPerOrigin;
+	type AssetTrapExpiry = AssetTrapExpiry;
+	type Currency = Balances;
+	// Only the pallets of child parachains may hold locks here, and none are held elsewhere on
+	// behalf of this chain.
+	type UnlockOrigin = pallet_xcm::EnsureXcm<RemoteLockUnlockers>;
+	type NoteUnlockableOrigin = pallet_xcm::EnsureXcm<Nothing>;
+	type MaxRemoteLockConsumers = MaxRemoteLockConsumers;
+	type NoteUnlockableWeight = NoteUnlockableWeight;
+	type DeliveryFeeBase = DeliveryFeeBase;
+	type DeliveryFeePerByte = DeliveryFeePerByte;
+	type OnDeliveryFee = DealWithFees<Runtime>;
+	type WeightInfo = weights::pallet_xcm::WeightInfo<Runtime>;
 }
@@ -44,6 +44,9 @@ pub struct RelayStateSproofBuilder {
 	pub hrmp_channels: BTreeMap<relay_chain::v2::HrmpChannelId, AbridgedHrmpChannel>,
 	pub current_slot: relay_chain::v2::Slot,
 	pub current_epoch: u64,
+	pub included_para_head: Option<relay_chain::v2::HeadData>,
+	/// Arbitrary relay chain storage entries to include in the proof.
+	pub additional_key_values: Vec<(Vec<u8>, Vec<u8>)>,
 }
 
 impl Default for RelayStateSproofBuilder {
@@ -69,6 +72,8 @@ impl Default for RelayStateSproofBuilder {
 			hrmp_channels: BTreeMap::new(),
 			current_slot: 0.into(),
 			current_epoch: 0u64,
+			included_para_head: None,
+			additional_key_values: Vec::new(),
 		}
 	}
 }
@@ -155,8 +160,18 @@ impl RelayStateSproofBuilder {
 			for (channel, metadata) in self.hrmp_channels {
 				insert(relay_chain::well_known_keys::hrmp_channels(channel), metadata.encode());
 			}
+			if let Some(included_para_head) = self.included_para_head {
+				insert(
+					relay_chain::well_known_keys::para_head(self.para_id),
+					included_para_head.encode(),
+				);
+			}
 			insert(relay_chain::well_known_keys::EPOCH_INDEX.to_vec(), self.current_epoch.encode());
 			insert(relay_chain::well_known_keys::CURRENT_SLOT.to_vec(), self.current_slot.encode());
+
+			for (key, value) in self.additional_key_values {
+				insert(key, value);
+			}
 		}
 
 		let root = backend.root().clone();
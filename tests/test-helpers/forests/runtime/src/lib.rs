@@ -267,6 +267,8 @@ impl forests_pallet_parachain_system::Config for Runtime {
 	type XcmpMessageHandler = ();
 	type ReservedXcmpWeight = ();
 	type CheckAssociatedRelayNumber = forests_pallet_parachain_system::AnyRelayNumber;
+	type ConsensusHook = forests_pallet_parachain_system::ExpectParentIncluded;
+	type RelayStateKeys = ();
 }
 
 parameter_types! {
@@ -41,7 +41,7 @@ pub struct TestCollatorCli {
 	pub parachain_id: u32,
 
 	/// Relay chain arguments
-	#[clap(raw = true, conflicts_with = "relay-chain-rpc-url")]
+	#[clap(raw = true, conflicts_with = "relay-chain-rpc-urls")]
 	pub relaychain_args: Vec<String>,
 
 	#[clap(long)]
@@ -182,8 +182,10 @@ async fn build_relay_chain_interface(
 	collator_options: CollatorOptions,
 	task_manager: &mut TaskManager,
 ) -> RelayChainResult<Arc<dyn RelayChainInterface + 'static>> {
-	if let Some(relay_chain_url) = collator_options.relay_chain_rpc_url {
-		let client = create_client_and_start_worker(relay_chain_url, task_manager).await?;
+	if !collator_options.relay_chain_rpc_urls.is_empty() {
+		let client =
+			create_client_and_start_worker(collator_options.relay_chain_rpc_urls, task_manager)
+				.await?;
 		return Ok(Arc::new(RelayChainRpcInterface::new(client)) as Arc<_>)
 	}
 
@@ -351,6 +353,8 @@ where
 			collator_key,
 			import_queue,
 			relay_chain_slot_duration: Duration::from_secs(6),
+			relay_parent_offset: 0,
+			prometheus_registry: prometheus_registry.as_ref(),
 		};
 
 		start_collator(params).await?;
@@ -367,6 +371,7 @@ where
 			// long on the full node to recover, so we reduce this time here.
 			relay_chain_slot_duration: Duration::from_millis(6),
 			collator_options,
+			prometheus_registry: prometheus_registry.as_ref(),
 		};
 
 		start_full_node(params)?;
@@ -562,8 +567,10 @@ impl TestNodeBuilder {
 			false,
 		);
 
-		let collator_options =
-			CollatorOptions { relay_chain_rpc_url: self.relay_chain_full_node_url };
+		let collator_options = CollatorOptions {
+			relay_chain_rpc_urls: self.relay_chain_full_node_url.into_iter().collect(),
+			relay_chain_minimal_node: false,
+		};
 
 		relay_chain_config.network.node_name =
 			format!("{} (relay chain)", relay_chain_config.network.node_name);
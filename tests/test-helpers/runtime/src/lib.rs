@@ -44,12 +44,12 @@ use pallet_grandpa::{fg_primitives, AuthorityId as GrandpaId};
 use pallet_session::historical as session_historical;
 use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
 use primitives::v2::{
-	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CandidateHash,
-	CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Hash as HashT,
-	Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce,
-	OccupiedCoreAssumption, PersistedValidationData, ScrapedOnChainVotes,
-	SessionInfo as SessionInfoData, Signature, ValidationCode, ValidationCodeHash, ValidatorId,
-	ValidatorIndex,
+	AccountId, AccountIndex, AsyncBackingParams, Balance, BlockNumber, CandidateEvent,
+	CandidateHash, CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo,
+	Hash as HashT, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, Moment, Nonce,
+	OccupiedCoreAssumption, ParaUpgradeStatus, PersistedValidationData, ScheduledParaTransition,
+	ScrapedOnChainVotes, SessionInfo as SessionInfoData, Signature, ValidationCode,
+	ValidationCodeHash, ValidatorId, ValidatorIndex,
 };
 use runtime_common::{
 	impl_runtime_weights, paras_sudo_wrapper, BlockHashCount, BlockLength, SlowAdjustingFeeUpdate,
@@ -534,6 +534,7 @@ impl pallet_xcm::Config for Runtime {
 	type Weigher = xcm_builder::FixedWeightBounds<BaseXcmWeight, Call, MaxInstructions>;
 	type XcmRouter = xcm_config::DoNothingRouter;
 	type XcmExecuteFilter = Everything;
+	type XcmSendFilter = Everything;
 	type XcmExecutor = xcm_executor::XcmExecutor<xcm_config::XcmConfig>;
 	type XcmTeleportFilter = Everything;
 	type XcmReserveTransferFilter = Everything;
@@ -541,6 +542,17 @@ impl pallet_xcm::Config for Runtime {
 	type Call = Call;
 	const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
 	type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+	type MaxAssetTrapsPerOrigin = frame_support::traits::ConstU32<16>;
+	type AssetTrapExpiry = frame_support::traits::ConstU32<100>;
+	type Currency = Balances;
+	type UnlockOrigin = pallet_xcm::EnsureXcm<Everything>;
+	type NoteUnlockableOrigin = pallet_xcm::EnsureXcm<Everything>;
+	type WeightInfo = ();
+	type MaxRemoteLockConsumers = frame_support::traits::ConstU32<16>;
+	type NoteUnlockableWeight = frame_support::traits::ConstU64<1_000>;
+	type DeliveryFeeBase = frame_support::traits::ConstU128<0>;
+	type DeliveryFeePerByte = frame_support::traits::ConstU128<0>;
+	type OnDeliveryFee = ();
 }
 
 impl parachains_hrmp::Config for Runtime {
@@ -890,9 +902,22 @@ sp_api::impl_runtime_apis! {
 			runtime_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
 
+		fn scheduled_para_transitions() -> Vec<ScheduledParaTransition> {
+			runtime_impl::scheduled_para_transitions::<Runtime>()
+		}
+
+		fn para_upgrade_status(para_id: ParaId) -> Option<ParaUpgradeStatus<BlockNumber>> {
+			runtime_impl::para_upgrade_status::<Runtime>(para_id)
+		}
+
+		fn async_backing_params() -> AsyncBackingParams {
+			runtime_impl::async_backing_params::<Runtime>()
+		}
+
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			selendra_runtime_parachains::runtime_api_impl::vstaging::get_session_disputes::<Runtime>()
 		}
+
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
@@ -1049,6 +1074,34 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_xcm::runtime_api::XcmPaymentApi<Block> for Runtime {
+		fn query_acceptable_payment_assets(
+			xcm_version: xcm::Version,
+		) -> Result<Vec<xcm::VersionedMultiLocation>, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			Xcm::query_acceptable_payment_assets(xcm_version, vec![xcm::latest::Junctions::Here.into()])
+		}
+
+		fn query_xcm_weight(
+			message: xcm::VersionedXcm<()>,
+		) -> Result<xcm::latest::Weight, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			Xcm::query_xcm_weight(message)
+		}
+
+		fn query_weight_to_asset_fee(
+			weight: xcm::latest::Weight,
+			asset: xcm::VersionedMultiLocation,
+		) -> Result<u128, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			Xcm::query_weight_to_asset_fee::<xcm_config::DummyWeightTrader>(weight, asset)
+		}
+
+		fn query_delivery_fees(
+			destination: xcm::VersionedMultiLocation,
+			message: xcm::VersionedXcm<()>,
+		) -> Result<xcm::VersionedMultiAssets, pallet_xcm::runtime_api::XcmPaymentApiError> {
+			Xcm::query_delivery_fees(destination, message)
+		}
+	}
+
 	impl crate::GetLastTimestamp<Block> for Runtime {
 		fn get_last_timestamp() -> u64 {
 			Timestamp::now()
@@ -92,4 +92,6 @@ impl xcm_executor::Config for XcmConfig {
 	type AssetTrap = super::Xcm;
 	type AssetClaims = super::Xcm;
 	type SubscriptionService = super::Xcm;
+	type Tracer = super::Xcm;
+	type SafeCallFilter = Everything;
 }
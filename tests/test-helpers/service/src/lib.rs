@@ -331,7 +331,12 @@ impl SelendraTestNode {
 		para_id: ParaId,
 		collator: CollatorFn,
 	) {
-		let config = CollationGenerationConfig { key: collator_key, collator, para_id };
+		let config = CollationGenerationConfig {
+			key: collator_key,
+			collator,
+			para_id,
+			relay_parent_offset: 0,
+		};
 
 		self.overseer_handle
 			.send_msg(CollationGenerationMessage::Initialize(config), "Collator")